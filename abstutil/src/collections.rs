@@ -213,6 +213,14 @@ impl Tags {
         Tags(map)
     }
 
+    pub fn from_pairs(kv: Vec<(&str, &str)>) -> Tags {
+        Tags(
+            kv.into_iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+    }
+
     pub fn get(&self, k: &str) -> Option<&String> {
         self.0.get(k)
     }
@@ -17,7 +17,9 @@ static SYSTEM_DATA: include_dir::Dir = include_dir::include_dir!(
     "cities/",
     "fonts/",
     "maps/montlake.bin",
-    "proposals/"
+    "proposals/",
+    "themes/",
+    "translations/"
 );
 
 // For file_exists and list_dir only, also check if the file is in the Manifest. The caller has to
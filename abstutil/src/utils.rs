@@ -45,6 +45,15 @@ pub fn prettyprint_usize(x: usize) -> String {
     result
 }
 
+/// Quotes a value for a CSV file, if it contains anything special.
+pub fn csv_escape(value: &str) -> String {
+    if value.contains(',') || value.contains('"') || value.contains('\n') {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
 pub fn basename(path: &str) -> String {
     std::path::Path::new(path)
         .file_stem()
@@ -3,7 +3,7 @@
 //! 1) Reads a large .osm file
 //! 2) Finds all boundary relations representing cities
 //! 3) Calculates the polygon covering that city
-//! 4) Uses osmconvert to clip the large .osm to a smaller one with just the city
+//! 4) Clips the large .osm to a smaller one for each city, reading through it just once
 //!
 //! This tool writes all output files (.poly boundaries and .osm extracts) in the current
 //! directory!
@@ -11,7 +11,6 @@
 use abstutil::{CmdArgs, Timer};
 use geom::{Circle, Distance, GPSBounds, LonLat, Polygon};
 use map_model::osm::OsmID;
-use std::process::Command;
 
 fn main() {
     let mut args = CmdArgs::new();
@@ -24,7 +23,8 @@ fn main() {
     let mut timer = Timer::new(format!("extract cities from {}", input));
 
     // Infer the boundary of the input from the <bounds> tag
-    let doc = convert_osm::reader::read(&input, &GPSBounds::new(), &mut timer).unwrap();
+    let doc = convert_osm::reader::read(&input, &GPSBounds::new(), None, &mut timer).unwrap();
+    let mut clips = Vec::new();
     for (id, rel) in &doc.relations {
         if !rel.tags.is("border_type", "city") && !rel.tags.is("place", "city") {
             continue;
@@ -73,19 +73,11 @@ fn main() {
             Polygon::convex_hull(polygons)
         };
 
-        let clipping_polygon = format!("{}.poly", name);
-        LonLat::write_osmosis_polygon(
-            &clipping_polygon,
-            &doc.gps_bounds.convert_back(clip.points()),
-        )
-        .unwrap();
-
-        abstutil::must_run_cmd(
-            Command::new("osmconvert")
-                .arg(&input)
-                .arg(format!("-B={}", clipping_polygon))
-                .arg("--complete-ways")
-                .arg(format!("-o={}.osm", name)),
-        );
+        let boundary = doc.gps_bounds.convert_back(clip.points());
+        LonLat::write_osmosis_polygon(&format!("{}.poly", name), &boundary).unwrap();
+        clips.push((boundary, format!("{}.osm", name)));
     }
+    drop(doc);
+
+    convert_osm::reader::clip_to_osm(&input, clips, &mut timer).unwrap();
 }
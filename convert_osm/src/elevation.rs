@@ -0,0 +1,733 @@
+//! Loads digital elevation models (DEMs) and samples them at arbitrary points. Three raster formats
+//! are supported, but only in WGS84 longitude/latitude (EPSG:4326). Projected rasters, like UTM or
+//! state plane, are rejected with an error; reproject them first.
+//!
+//! - GeoTIFF (`.tif` or `.tiff`), uncompressed, with one band. Run
+//!   `gdalwarp -t_srs EPSG:4326 -co COMPRESS=NONE in.tif out.tif` to convert anything else.
+//! - ESRI ASCII grid (`.asc`)
+//! - SRTM tiles (`.hgt`), at 1 or 3 arc-seconds. The tile's corner comes from the filename, like
+//!   `N47W123.hgt`.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+
+use geom::{Distance, LonLat};
+
+/// A raster of elevation samples. Rows go from north to south, and columns from west to east.
+pub struct Elevation {
+    /// The longitude of the center of the westmost column
+    west: f64,
+    /// The latitude of the center of the northmost row
+    north: f64,
+    /// Degrees between the centers of adjacent columns
+    lon_step: f64,
+    /// Degrees between the centers of adjacent rows
+    lat_step: f64,
+    width: usize,
+    height: usize,
+    /// Meters. NaN where the DEM has no data.
+    data: Vec<f32>,
+}
+
+impl Elevation {
+    /// Picks the format based on the file extension.
+    pub fn load(path: &str) -> Result<Elevation, String> {
+        println!("Reading elevation data from {}", path);
+        let extension = Path::new(path)
+            .extension()
+            .and_then(|x| x.to_str())
+            .map(|x| x.to_lowercase());
+        match extension.as_deref() {
+            Some("tif") | Some("tiff") => load_geotiff(&read_file(path)?),
+            Some("asc") => load_ascii_grid(path),
+            Some("hgt") => load_srtm(path),
+            _ => Err(format!(
+                "{} isn't a GeoTIFF (.tif), ASCII grid (.asc), or SRTM tile (.hgt)",
+                path
+            )),
+        }
+    }
+
+    /// Bilinearly interpolates the 4 samples surrounding the point, skipping any without data.
+    /// Returns None outside of the raster or where there's no data at all.
+    pub fn get(&self, pt: LonLat) -> Option<Distance> {
+        // Points within half a cell of the outermost samples still fall in the raster
+        let x = (pt.x() - self.west) / self.lon_step;
+        let y = (self.north - pt.y()) / self.lat_step;
+        let max_x = (self.width - 1) as f64;
+        let max_y = (self.height - 1) as f64;
+        if x < -0.5 || y < -0.5 || x > max_x + 0.5 || y > max_y + 0.5 {
+            return None;
+        }
+        let x = x.max(0.0).min(max_x);
+        let y = y.max(0.0).min(max_y);
+
+        let x0 = x.floor() as usize;
+        let y0 = y.floor() as usize;
+        let x1 = (x0 + 1).min(self.width - 1);
+        let y1 = (y0 + 1).min(self.height - 1);
+        let fx = x - (x0 as f64);
+        let fy = y - (y0 as f64);
+
+        let mut sum = 0.0;
+        let mut total_weight = 0.0;
+        for &(col, row, weight) in &[
+            (x0, y0, (1.0 - fx) * (1.0 - fy)),
+            (x1, y0, fx * (1.0 - fy)),
+            (x0, y1, (1.0 - fx) * fy),
+            (x1, y1, fx * fy),
+        ] {
+            let value = self.data[row * self.width + col];
+            if !value.is_nan() && weight > 0.0 {
+                sum += weight * f64::from(value);
+                total_weight += weight;
+            }
+        }
+        if total_weight == 0.0 {
+            return None;
+        }
+        Some(Distance::meters(sum / total_weight))
+    }
+
+    fn validate(self) -> Result<Elevation, String> {
+        if self.width < 2 || self.height < 2 {
+            return Err(format!(
+                "DEM must be at least 2x2, but it's {}x{}",
+                self.width, self.height
+            ));
+        }
+        if self.data.len() != self.width * self.height {
+            return Err(format!(
+                "DEM is {}x{}, but has {} samples",
+                self.width,
+                self.height,
+                self.data.len()
+            ));
+        }
+        if self.lon_step <= 0.0 || self.lat_step <= 0.0 {
+            return Err(format!(
+                "DEM has a bad cell size of {} by {} degrees",
+                self.lon_step, self.lat_step
+            ));
+        }
+        // Catch projected rasters that don't declare their coordinate system
+        let east = self.west + self.lon_step * ((self.width - 1) as f64);
+        let south = self.north - self.lat_step * ((self.height - 1) as f64);
+        if self.west < -180.0 || east > 180.0 || south < -90.0 || self.north > 90.0 {
+            return Err(format!(
+                "DEM covers ({}, {}) to ({}, {}), which isn't longitude/latitude. Reproject it to \
+                 EPSG:4326.",
+                self.west, self.north, east, south
+            ));
+        }
+        Ok(self)
+    }
+}
+
+fn read_file(path: &str) -> Result<Vec<u8>, String> {
+    let mut bytes = Vec::new();
+    File::open(path)
+        .and_then(|mut f| f.read_to_end(&mut bytes))
+        .map_err(|err| format!("Couldn't read {}: {}", path, err))?;
+    Ok(bytes)
+}
+
+/// SRTM tiles are big-endian 16-bit samples covering exactly one degree, with samples on both
+/// edges.
+fn load_srtm(path: &str) -> Result<Elevation, String> {
+    let bytes = read_file(path)?;
+    let dim = match bytes.len() {
+        2_884_802 => 1201,
+        25_934_402 => 3601,
+        n => {
+            return Err(format!(
+                "{} has {} bytes, which isn't a 1 or 3 arc-second SRTM tile",
+                path, n
+            ));
+        }
+    };
+
+    let name = Path::new(path)
+        .file_stem()
+        .and_then(|x| x.to_str())
+        .unwrap_or("");
+    let (lon, lat) = srtm_corner(name).ok_or_else(|| {
+        format!(
+            "Can't tell where {} is; SRTM tiles should be named like N47W123.hgt",
+            path
+        )
+    })?;
+
+    Elevation {
+        west: lon,
+        north: lat + 1.0,
+        lon_step: 1.0 / ((dim - 1) as f64),
+        lat_step: 1.0 / ((dim - 1) as f64),
+        width: dim,
+        height: dim,
+        data: bytes
+            .chunks_exact(2)
+            .map(|pair| match BigEndian::read_i16(pair) {
+                -32768 => std::f32::NAN,
+                x => f32::from(x),
+            })
+            .collect(),
+    }
+    .validate()
+}
+
+/// The (longitude, latitude) of the southwest corner of an SRTM tile, from a name like N47W123.
+fn srtm_corner(name: &str) -> Option<(f64, f64)> {
+    let name = name.to_uppercase();
+    let lat_sign = match name.get(0..1)? {
+        "N" => 1.0,
+        "S" => -1.0,
+        _ => return None,
+    };
+    let lon_sign = match name.get(3..4)? {
+        "E" => 1.0,
+        "W" => -1.0,
+        _ => return None,
+    };
+    let lat = name.get(1..3)?.parse::<f64>().ok()?;
+    let lon = name.get(4..7)?.parse::<f64>().ok()?;
+    Some((lon_sign * lon, lat_sign * lat))
+}
+
+fn load_ascii_grid(path: &str) -> Result<Elevation, String> {
+    let f = File::open(path).map_err(|err| format!("Couldn't read {}: {}", path, err))?;
+    parse_ascii_grid(BufReader::new(f), path)
+}
+
+/// A header of keys like ncols, nrows, xllcorner, yllcorner, cellsize, and NODATA_value, then
+/// rows of samples, starting from the north.
+fn parse_ascii_grid<R: BufRead>(input: R, path: &str) -> Result<Elevation, String> {
+    let mut ncols = None;
+    let mut nrows = None;
+    let mut x_corner = None;
+    let mut y_corner = None;
+    let mut x_center = None;
+    let mut y_center = None;
+    let mut cellsize = None;
+    let mut nodata = None;
+    let mut data = Vec::new();
+
+    for line in input.lines() {
+        let line = line.map_err(|err| format!("Couldn't read {}: {}", path, err))?;
+        let mut parts = line.split_whitespace();
+        let first = match parts.next() {
+            Some(x) => x,
+            None => {
+                continue;
+            }
+        };
+        if data.is_empty() && first.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            let value = parts
+                .next()
+                .and_then(|x| x.parse::<f64>().ok())
+                .ok_or_else(|| format!("Bad header line in {}: {}", path, line))?;
+            match first.to_lowercase().as_ref() {
+                "ncols" => ncols = Some(value as usize),
+                "nrows" => nrows = Some(value as usize),
+                "xllcorner" => x_corner = Some(value),
+                "yllcorner" => y_corner = Some(value),
+                "xllcenter" => x_center = Some(value),
+                "yllcenter" => y_center = Some(value),
+                "cellsize" => cellsize = Some(value),
+                "nodata_value" => nodata = Some(value as f32),
+                _ => {
+                    return Err(format!("Unknown header {} in {}", first, path));
+                }
+            }
+            continue;
+        }
+
+        for x in std::iter::once(first).chain(parts) {
+            let value = x
+                .parse::<f32>()
+                .map_err(|_| format!("Bad sample {} in {}", x, path))?;
+            data.push(if Some(value) == nodata {
+                std::f32::NAN
+            } else {
+                value
+            });
+        }
+    }
+
+    let missing = |key| format!("{} is missing {}", path, key);
+    let width = ncols.ok_or_else(|| missing("ncols"))?;
+    let height = nrows.ok_or_else(|| missing("nrows"))?;
+    let cellsize = cellsize.ok_or_else(|| missing("cellsize"))?;
+    // The corner is the outer edge of the southwest cell
+    let west = x_center
+        .or_else(|| x_corner.map(|x| x + cellsize / 2.0))
+        .ok_or_else(|| missing("xllcorner"))?;
+    let south = y_center
+        .or_else(|| y_corner.map(|y| y + cellsize / 2.0))
+        .ok_or_else(|| missing("yllcorner"))?;
+
+    Elevation {
+        west,
+        north: south + cellsize * (height.max(1) - 1) as f64,
+        lon_step: cellsize,
+        lat_step: cellsize,
+        width,
+        height,
+        data,
+    }
+    .validate()
+}
+
+const IMAGE_WIDTH: u16 = 256;
+const IMAGE_LENGTH: u16 = 257;
+const BITS_PER_SAMPLE: u16 = 258;
+const COMPRESSION: u16 = 259;
+const STRIP_OFFSETS: u16 = 273;
+const SAMPLES_PER_PIXEL: u16 = 277;
+const ROWS_PER_STRIP: u16 = 278;
+const TILE_WIDTH: u16 = 322;
+const TILE_LENGTH: u16 = 323;
+const TILE_OFFSETS: u16 = 324;
+const SAMPLE_FORMAT: u16 = 339;
+const MODEL_PIXEL_SCALE: u16 = 33550;
+const MODEL_TIEPOINT: u16 = 33922;
+const MODEL_TRANSFORMATION: u16 = 34264;
+const GEO_KEY_DIRECTORY: u16 = 34735;
+const GDAL_NODATA: u16 = 42113;
+
+// GeoKeys
+const MODEL_TYPE: f64 = 1024.0;
+const MODEL_TYPE_GEOGRAPHIC: f64 = 2.0;
+const RASTER_TYPE: f64 = 1025.0;
+const RASTER_PIXEL_IS_POINT: f64 = 2.0;
+
+/// The first image of a classic (not BigTIFF) GeoTIFF.
+struct Tiff<'a> {
+    bytes: &'a [u8],
+    little_endian: bool,
+    /// Numeric tags are widened to f64, ASCII tags are left as bytes
+    tags: Vec<(u16, Vec<f64>, &'a [u8])>,
+}
+
+impl<'a> Tiff<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Tiff<'a>, String> {
+        let mut tiff = Tiff {
+            bytes,
+            little_endian: match bytes.get(0..2) {
+                Some(b"II") => true,
+                Some(b"MM") => false,
+                _ => {
+                    return Err("Not a TIFF file".to_string());
+                }
+            },
+            tags: Vec::new(),
+        };
+        match tiff.u16(2)? {
+            42 => {}
+            43 => {
+                return Err("BigTIFF isn't supported".to_string());
+            }
+            x => {
+                return Err(format!("Not a TIFF file (version {})", x));
+            }
+        }
+
+        let ifd = tiff.u32(4)? as usize;
+        for idx in 0..tiff.u16(ifd)? as usize {
+            let entry = ifd + 2 + 12 * idx;
+            let tag = tiff.u16(entry)?;
+            let field_type = tiff.u16(entry + 2)?;
+            let count = tiff.u32(entry + 4)? as usize;
+            let size = match field_type {
+                1 | 2 | 6 | 7 => 1,
+                3 | 8 => 2,
+                4 | 9 | 11 => 4,
+                5 | 10 | 12 => 8,
+                // Skip types we'll never need
+                _ => {
+                    continue;
+                }
+            };
+            let start = if size * count <= 4 {
+                entry + 8
+            } else {
+                tiff.u32(entry + 8)? as usize
+            };
+            let raw = tiff.slice(start, size * count)?;
+            let mut values = Vec::new();
+            if field_type != 2 && field_type != 7 {
+                for i in 0..count {
+                    let at = start + size * i;
+                    values.push(match field_type {
+                        1 => f64::from(raw[i]),
+                        6 => f64::from(raw[i] as i8),
+                        3 => f64::from(tiff.u16(at)?),
+                        8 => f64::from(tiff.u16(at)? as i16),
+                        4 => f64::from(tiff.u32(at)?),
+                        9 => f64::from(tiff.u32(at)? as i32),
+                        11 => f64::from(f32::from_bits(tiff.u32(at)?)),
+                        12 => f64::from_bits(tiff.u64(at)?),
+                        5 => f64::from(tiff.u32(at)?) / f64::from(tiff.u32(at + 4)?),
+                        10 => f64::from(tiff.u32(at)? as i32) / f64::from(tiff.u32(at + 4)? as i32),
+                        _ => unreachable!(),
+                    });
+                }
+            }
+            tiff.tags.push((tag, values, raw));
+        }
+        Ok(tiff)
+    }
+
+    fn get(&self, tag: u16) -> Option<&Vec<f64>> {
+        self.tags
+            .iter()
+            .find(|(t, _, _)| *t == tag)
+            .map(|(_, values, _)| values)
+    }
+
+    fn get_one(&self, tag: u16) -> Option<f64> {
+        self.get(tag).and_then(|values| values.get(0).cloned())
+    }
+
+    fn require(&self, tag: u16) -> Result<&Vec<f64>, String> {
+        self.get(tag)
+            .filter(|values| !values.is_empty())
+            .ok_or_else(|| format!("GeoTIFF is missing tag {}", tag))
+    }
+
+    fn get_ascii(&self, tag: u16) -> Option<String> {
+        let (_, _, raw) = self.tags.iter().find(|(t, _, _)| *t == tag)?;
+        Some(
+            String::from_utf8_lossy(raw)
+                .trim_matches(char::from(0))
+                .trim()
+                .to_string(),
+        )
+    }
+
+    fn slice(&self, start: usize, len: usize) -> Result<&'a [u8], String> {
+        self.bytes.get(start..start + len).ok_or_else(|| {
+            format!(
+                "GeoTIFF is truncated; wanted bytes {}..{}",
+                start,
+                start + len
+            )
+        })
+    }
+
+    fn u16(&self, at: usize) -> Result<u16, String> {
+        let b = self.slice(at, 2)?;
+        Ok(if self.little_endian {
+            LittleEndian::read_u16(b)
+        } else {
+            BigEndian::read_u16(b)
+        })
+    }
+
+    fn u32(&self, at: usize) -> Result<u32, String> {
+        let b = self.slice(at, 4)?;
+        Ok(if self.little_endian {
+            LittleEndian::read_u32(b)
+        } else {
+            BigEndian::read_u32(b)
+        })
+    }
+
+    fn u64(&self, at: usize) -> Result<u64, String> {
+        let b = self.slice(at, 8)?;
+        Ok(if self.little_endian {
+            LittleEndian::read_u64(b)
+        } else {
+            BigEndian::read_u64(b)
+        })
+    }
+
+    fn sample(&self, at: usize, bits: usize, format: usize) -> Result<f32, String> {
+        Ok(match (format, bits) {
+            (1, 8) => f32::from(self.slice(at, 1)?[0]),
+            (2, 8) => f32::from(self.slice(at, 1)?[0] as i8),
+            (1, 16) => f32::from(self.u16(at)?),
+            (2, 16) => f32::from(self.u16(at)? as i16),
+            (1, 32) => self.u32(at)? as f32,
+            (2, 32) => (self.u32(at)? as i32) as f32,
+            (3, 32) => f32::from_bits(self.u32(at)?),
+            (3, 64) => f64::from_bits(self.u64(at)?) as f32,
+            _ => {
+                return Err(format!(
+                    "GeoTIFF samples with format {} and {} bits aren't supported",
+                    format, bits
+                ));
+            }
+        })
+    }
+}
+
+fn load_geotiff(bytes: &[u8]) -> Result<Elevation, String> {
+    let tiff = Tiff::parse(bytes)?;
+
+    let width = tiff.require(IMAGE_WIDTH)?[0] as usize;
+    let height = tiff.require(IMAGE_LENGTH)?[0] as usize;
+    if tiff.get_one(COMPRESSION).unwrap_or(1.0) != 1.0 {
+        return Err(
+            "Compressed GeoTIFFs aren't supported; use gdal_translate -co COMPRESS=NONE"
+                .to_string(),
+        );
+    }
+    if tiff.get_one(SAMPLES_PER_PIXEL).unwrap_or(1.0) != 1.0 {
+        return Err("GeoTIFF must have exactly one band".to_string());
+    }
+    let bits = tiff.get_one(BITS_PER_SAMPLE).unwrap_or(1.0) as usize;
+    let format = tiff.get_one(SAMPLE_FORMAT).unwrap_or(1.0) as usize;
+    let bytes_per_sample = bits / 8;
+
+    // GeoKeys are (key, location, count, value), after a 4-number header
+    let mut pixel_is_point = false;
+    if let Some(keys) = tiff.get(GEO_KEY_DIRECTORY) {
+        for key in keys.chunks_exact(4).skip(1) {
+            if key[0] == MODEL_TYPE && key[1] == 0.0 && key[3] != MODEL_TYPE_GEOGRAPHIC {
+                return Err(
+                    "GeoTIFF isn't in longitude/latitude; use gdalwarp -t_srs EPSG:4326"
+                        .to_string(),
+                );
+            }
+            if key[0] == RASTER_TYPE && key[1] == 0.0 {
+                pixel_is_point = key[3] == RASTER_PIXEL_IS_POINT;
+            }
+        }
+    }
+
+    // Where pixel (0, 0) is, and how big each pixel is
+    let (corner_lon, corner_lat, lon_step, lat_step) =
+        if let Some(m) = tiff.get(MODEL_TRANSFORMATION).filter(|m| m.len() == 16) {
+            if m[1] != 0.0 || m[4] != 0.0 {
+                return Err("Rotated GeoTIFFs aren't supported".to_string());
+            }
+            (m[3], m[7], m[0], -m[5])
+        } else {
+            let scale = tiff.require(MODEL_PIXEL_SCALE)?;
+            let tiepoint = tiff.require(MODEL_TIEPOINT)?;
+            if scale.len() < 2 || tiepoint.len() < 6 {
+                return Err("GeoTIFF has a malformed tiepoint or pixel scale".to_string());
+            }
+            (
+                tiepoint[3] - tiepoint[0] * scale[0],
+                tiepoint[4] + tiepoint[1] * scale[1],
+                scale[0],
+                scale[1],
+            )
+        };
+    // Unless the raster says otherwise, that corner is the outer edge of the pixel
+    let (west, north) = if pixel_is_point {
+        (corner_lon, corner_lat)
+    } else {
+        (corner_lon + lon_step / 2.0, corner_lat - lat_step / 2.0)
+    };
+
+    let nodata = tiff
+        .get_ascii(GDAL_NODATA)
+        .and_then(|x| x.parse::<f64>().ok())
+        .map(|x| x as f32);
+
+    // Images are split into strips of rows or into rectangular tiles. A strip is just a tile as
+    // wide as the image.
+    let (tile_width, tile_height, offsets) = if let Some(offsets) = tiff.get(TILE_OFFSETS) {
+        (
+            tiff.require(TILE_WIDTH)?[0] as usize,
+            tiff.require(TILE_LENGTH)?[0] as usize,
+            offsets,
+        )
+    } else {
+        (
+            width,
+            tiff.get_one(ROWS_PER_STRIP)
+                .map(|x| (x as usize).min(height))
+                .unwrap_or(height),
+            tiff.require(STRIP_OFFSETS)?,
+        )
+    };
+    if tile_width == 0 || tile_height == 0 {
+        return Err("GeoTIFF has empty tiles".to_string());
+    }
+    let tiles_across = (width + tile_width - 1) / tile_width;
+
+    let mut data = Vec::with_capacity(width * height);
+    for row in 0..height {
+        for col in 0..width {
+            let tile = (row / tile_height) * tiles_across + col / tile_width;
+            let offset = *offsets
+                .get(tile)
+                .ok_or_else(|| format!("GeoTIFF is missing tile {}", tile))?
+                as usize;
+            let idx_in_tile = (row % tile_height) * tile_width + col % tile_width;
+            let value = tiff.sample(offset + idx_in_tile * bytes_per_sample, bits, format)?;
+            data.push(if Some(value) == nodata {
+                std::f32::NAN
+            } else {
+                value
+            });
+        }
+    }
+
+    Elevation {
+        west,
+        north,
+        lon_step,
+        lat_step,
+        width,
+        height,
+        data,
+    }
+    .validate()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_meters(actual: Option<Distance>, expected: f64) {
+        let actual = actual.unwrap().inner_meters();
+        assert!(
+            (actual - expected).abs() < 1e-6,
+            "expected {}m, got {}m",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn srtm_names() {
+        assert_eq!(srtm_corner("N47W123"), Some((-123.0, 47.0)));
+        assert_eq!(srtm_corner("s33e151"), Some((151.0, -33.0)));
+        assert_eq!(srtm_corner("N47X123"), None);
+        assert_eq!(srtm_corner("N47W12"), None);
+        assert_eq!(srtm_corner("seattle"), None);
+    }
+
+    #[test]
+    fn ascii_grid() {
+        let input = "ncols 3\nnrows 2\nxllcorner -122.5\nyllcorner 47.5\ncellsize 0.1\n\
+                     NODATA_value -9999\n10 20 30\n40 -9999 60\n";
+        let dem = parse_ascii_grid(input.as_bytes(), "test.asc").unwrap();
+        // The corner is the outer edge of the southwest cell, so samples are at cell centers
+        assert_meters(dem.get(LonLat::new(-122.45, 47.65)), 10.0);
+        assert_meters(dem.get(LonLat::new(-122.25, 47.65)), 30.0);
+        assert_meters(dem.get(LonLat::new(-122.45, 47.55)), 40.0);
+        assert!(dem.data[4].is_nan());
+        // Halfway between 10, 20, 40, and the missing sample
+        assert_meters(
+            dem.get(LonLat::new(-122.4, 47.6)),
+            (10.0 + 20.0 + 40.0) / 3.0,
+        );
+        assert_eq!(dem.get(LonLat::new(-121.0, 47.6)), None);
+
+        assert!(parse_ascii_grid("ncols 3\n1 2 3\n".as_bytes(), "test.asc").is_err());
+    }
+
+    /// A little-endian TIFF with one strip of 16-bit signed samples. Each tag is (tag, field
+    /// type, count, raw little-endian value).
+    fn make_tiff(mut tags: Vec<(u16, u16, u32, Vec<u8>)>, samples: &[i16]) -> Vec<u8> {
+        let num_tags = tags.len() + 1;
+        let ifd_end = 8 + 2 + 12 * num_tags + 4;
+        let mut ifd = Vec::new();
+        let mut values = Vec::new();
+        let data_offset = (ifd_end
+            + tags
+                .iter()
+                .map(|(_, _, _, raw)| raw.len())
+                .filter(|n| *n > 4)
+                .sum::<usize>()) as u32;
+        tags.push((STRIP_OFFSETS, 4, 1, data_offset.to_le_bytes().to_vec()));
+        for (tag, field_type, count, mut raw) in tags {
+            ifd.extend_from_slice(&tag.to_le_bytes());
+            ifd.extend_from_slice(&field_type.to_le_bytes());
+            ifd.extend_from_slice(&count.to_le_bytes());
+            if raw.len() <= 4 {
+                raw.resize(4, 0);
+                ifd.extend(raw);
+            } else {
+                ifd.extend_from_slice(&((ifd_end + values.len()) as u32).to_le_bytes());
+                values.extend(raw);
+            }
+        }
+
+        let mut bytes = b"II".to_vec();
+        bytes.extend_from_slice(&42_u16.to_le_bytes());
+        bytes.extend_from_slice(&8_u32.to_le_bytes());
+        bytes.extend_from_slice(&(num_tags as u16).to_le_bytes());
+        bytes.extend(ifd);
+        bytes.extend_from_slice(&0_u32.to_le_bytes());
+        bytes.extend(values);
+        for x in samples {
+            bytes.extend_from_slice(&x.to_le_bytes());
+        }
+        bytes
+    }
+
+    fn shorts(values: &[u16]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|x| x.to_le_bytes().to_vec())
+            .collect()
+    }
+
+    fn doubles(values: &[f64]) -> Vec<u8> {
+        values
+            .iter()
+            .flat_map(|x| x.to_le_bytes().to_vec())
+            .collect()
+    }
+
+    /// A 2x2 raster starting at (-122.5, 47.7) with 0.1 degree pixels
+    fn geotiff_tags() -> Vec<(u16, u16, u32, Vec<u8>)> {
+        vec![
+            (IMAGE_WIDTH, 3, 1, shorts(&[2])),
+            (IMAGE_LENGTH, 3, 1, shorts(&[2])),
+            (BITS_PER_SAMPLE, 3, 1, shorts(&[16])),
+            (SAMPLE_FORMAT, 3, 1, shorts(&[2])),
+            (MODEL_PIXEL_SCALE, 12, 3, doubles(&[0.1, 0.1, 0.0])),
+            (
+                MODEL_TIEPOINT,
+                12,
+                6,
+                doubles(&[0.0, 0.0, 0.0, -122.5, 47.7, 0.0]),
+            ),
+            (GDAL_NODATA, 2, 4, b"-99\0".to_vec()),
+        ]
+    }
+
+    #[test]
+    fn geotiff() {
+        let dem = load_geotiff(&make_tiff(geotiff_tags(), &[10, 20, 30, -99])).unwrap();
+        // The tiepoint is the outer corner of the first pixel
+        assert_meters(dem.get(LonLat::new(-122.45, 47.65)), 10.0);
+        assert_meters(dem.get(LonLat::new(-122.35, 47.65)), 20.0);
+        assert_meters(dem.get(LonLat::new(-122.45, 47.55)), 30.0);
+        assert!(dem.data[3].is_nan());
+        assert_meters(dem.get(LonLat::new(-122.4, 47.6)), 20.0);
+        assert_eq!(dem.get(LonLat::new(-122.0, 47.6)), None);
+
+        // Unless the pixels are points
+        let mut tags = geotiff_tags();
+        tags.push((
+            GEO_KEY_DIRECTORY,
+            3,
+            8,
+            shorts(&[1, 1, 0, 1, RASTER_TYPE as u16, 0, 1, 2]),
+        ));
+        let dem = load_geotiff(&make_tiff(tags, &[10, 20, 30, -99])).unwrap();
+        assert_meters(dem.get(LonLat::new(-122.5, 47.7)), 10.0);
+
+        // Projected rasters aren't supported
+        let mut tags = geotiff_tags();
+        tags.push((
+            GEO_KEY_DIRECTORY,
+            3,
+            8,
+            shorts(&[1, 1, 0, 1, MODEL_TYPE as u16, 0, 1, 1]),
+        ));
+        assert!(load_geotiff(&make_tiff(tags, &[10, 20, 30, -99])).is_err());
+    }
+}
@@ -6,7 +6,7 @@ use abstutil::{retain_btreemap, Tags, Timer};
 use geom::{HashablePt2D, Polygon, Pt2D, Ring};
 use kml::{ExtraShape, ExtraShapes};
 use map_model::raw::{RawArea, RawBuilding, RawMap, RawParkingLot, RawRoad, RestrictionType};
-use map_model::{osm, Amenity, AreaType, NamePerLanguage};
+use map_model::{osm, Amenity, AreaType, CrossingType, NamePerLanguage};
 
 use crate::osm_geom::{get_multipolygon_members, glue_multipolygon, multipoly_geometry};
 use crate::transit;
@@ -17,6 +17,8 @@ pub struct OsmExtract {
     pub roads: Vec<(WayID, RawRoad)>,
     /// Traffic signals to the direction they apply (or just true if unspecified)
     pub traffic_signals: HashMap<HashablePt2D, bool>,
+    /// Crossing nodes, which might be in the middle of a road
+    pub crossings: HashMap<HashablePt2D, CrossingType>,
     pub osm_node_ids: HashMap<HashablePt2D, NodeID>,
     /// (ID, restriction type, from way ID, via node ID, to way ID)
     pub simple_turn_restrictions: Vec<(RestrictionType, WayID, NodeID, WayID)>,
@@ -27,7 +29,12 @@ pub struct OsmExtract {
 }
 
 pub fn extract_osm(map: &mut RawMap, opts: &Options, timer: &mut Timer) -> OsmExtract {
-    let mut doc = crate::reader::read(&opts.osm_input, &map.gps_bounds, timer).unwrap();
+    let clip = if opts.clip.is_some() {
+        Some(&map.boundary_polygon)
+    } else {
+        None
+    };
+    let mut doc = crate::reader::read(&opts.osm_input, &map.gps_bounds, clip, timer).unwrap();
     if opts.clip.is_none() {
         // Use the boundary from .osm.
         map.gps_bounds = doc.gps_bounds.clone();
@@ -37,6 +44,7 @@ pub fn extract_osm(map: &mut RawMap, opts: &Options, timer: &mut Timer) -> OsmEx
     let mut out = OsmExtract {
         roads: Vec::new(),
         traffic_signals: HashMap::new(),
+        crossings: HashMap::new(),
         osm_node_ids: HashMap::new(),
         simple_turn_restrictions: Vec::new(),
         complicated_turn_restrictions: Vec::new(),
@@ -48,11 +56,22 @@ pub fn extract_osm(map: &mut RawMap, opts: &Options, timer: &mut Timer) -> OsmEx
         timer.next();
         out.osm_node_ids.insert(node.pt.to_hashable(), *id);
 
-        if node.tags.is(osm::HIGHWAY, "traffic_signals") {
+        if node.tags.is(osm::HIGHWAY, "traffic_signals")
+            || (opts.include_footways && node.tags.is("crossing", "traffic_signals"))
+        {
             let backwards = node.tags.is("traffic_signals:direction", "backward");
             out.traffic_signals
                 .insert(node.pt.to_hashable(), !backwards);
         }
+        if opts.split_midblock_crossings
+            && node.tags.is(osm::HIGHWAY, "crossing")
+            && !node.tags.is("crossing", "no")
+        {
+            out.crossings.insert(
+                node.pt.to_hashable(),
+                CrossingType::from_osm_tags(&node.tags),
+            );
+        }
         for amenity in get_bldg_amenities(&node.tags) {
             out.amenities.push((node.pt, amenity));
         }
@@ -130,7 +149,7 @@ pub fn extract_osm(map: &mut RawMap, opts: &Options, timer: &mut Timer) -> OsmEx
                     osm_tags: way.tags.clone(),
                 },
             );
-        } else if let Some(at) = get_area_type(&way.tags) {
+        } else if let Some(at) = get_area_type(&way.tags, opts) {
             map.areas.push(RawArea {
                 area_type: at,
                 osm_id: OsmID::Way(id),
@@ -179,7 +198,7 @@ pub fn extract_osm(map: &mut RawMap, opts: &Options, timer: &mut Timer) -> OsmEx
         timer.next();
         let id = *id;
 
-        if let Some(area_type) = get_area_type(&rel.tags) {
+        if let Some(area_type) = get_area_type(&rel.tags, opts) {
             if rel.tags.is("type", "multipolygon") {
                 for polygon in glue_multipolygon(
                     id,
@@ -393,6 +412,16 @@ fn is_road(tags: &mut Tags, opts: &Options) -> bool {
         return false;
     }
 
+    if opts.include_footways
+        && tags.is_any(osm::HIGHWAY, vec!["footway", "path", "pedestrian", "steps"])
+        && !tags.is_any("foot", vec!["no", "private"])
+    {
+        // Footways are just one sidewalk, so nothing else to infer
+        tags.insert(osm::SIDEWALK, "none");
+        tags.insert(osm::PARKING_BOTH, "no_parking");
+        return true;
+    }
+
     let highway = if let Some(x) = tags.get(osm::HIGHWAY) {
         if x == "construction" {
             // What exactly is under construction?
@@ -501,7 +530,7 @@ fn get_bldg_amenities(tags: &Tags) -> Vec<Amenity> {
     amenities
 }
 
-fn get_area_type(tags: &Tags) -> Option<AreaType> {
+fn get_area_type(tags: &Tags, opts: &Options) -> Option<AreaType> {
     if tags.is_any("leisure", vec!["park", "golf_course"]) {
         return Some(AreaType::Park);
     }
@@ -522,15 +551,71 @@ fn get_area_type(tags: &Tags) -> Option<AreaType> {
         return Some(AreaType::Island);
     }
 
+    // Pedestrians can't cut across plazas yet, but at least draw them, instead of leaving a gap
+    // between the footways that lead to them
+    if opts.include_footways
+        && tags.is(osm::HIGHWAY, "pedestrian")
+        && tags.is("area", "yes")
+        && !tags.is_any("foot", vec!["no", "private"])
+    {
+        return Some(AreaType::PedestrianPlaza);
+    }
+
     // TODO These just cover up poorly inferred road geometry now. Figure out how to use these.
     if false {
         if tags.is("traffic_calming", "island") {
             return Some(AreaType::PedestrianIsland);
         }
-        if tags.is(osm::HIGHWAY, "pedestrian") && tags.is("area", "yes") {
-            return Some(AreaType::PedestrianIsland);
-        }
     }
 
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{OnstreetParking, PrivateOffstreetParking, PublicOffstreetParking};
+
+    fn opts(include_footways: bool) -> Options {
+        Options {
+            osm_input: String::new(),
+            city_name: String::new(),
+            name: String::new(),
+            clip: None,
+            map_config: map_model::MapConfig {
+                driving_side: map_model::DrivingSide::Right,
+                bikes_can_use_bus_lanes: true,
+            },
+            onstreet_parking: OnstreetParking::JustOSM,
+            public_offstreet_parking: PublicOffstreetParking::None,
+            private_offstreet_parking: PrivateOffstreetParking::FixedPerBldg(0),
+            elevation: None,
+            include_railroads: false,
+            include_footways,
+            split_midblock_crossings: false,
+        }
+    }
+
+    #[test]
+    fn pedestrian_plazas() {
+        let plaza = Tags::from_pairs(vec![("highway", "pedestrian"), ("area", "yes")]);
+        assert!(!is_road(&mut plaza.clone(), &opts(true)));
+        assert_eq!(
+            get_area_type(&plaza, &opts(true)),
+            Some(AreaType::PedestrianPlaza)
+        );
+        assert_eq!(get_area_type(&plaza, &opts(false)), None);
+
+        let private = Tags::from_pairs(vec![
+            ("highway", "pedestrian"),
+            ("area", "yes"),
+            ("foot", "private"),
+        ]);
+        assert_eq!(get_area_type(&private, &opts(true)), None);
+
+        // Pedestrian streets that aren't areas are still footways
+        let street = Tags::from_pairs(vec![("highway", "pedestrian")]);
+        assert!(is_road(&mut street.clone(), &opts(true)));
+        assert_eq!(get_area_type(&street, &opts(true)), None);
+    }
+}
@@ -1,35 +1,47 @@
-use abstutil::Timer;
+use std::collections::{BTreeMap, BTreeSet};
+
+use abstutil::{Tags, Timer};
 use geom::{Distance, FindClosest, GPSBounds, LonLat, Pt2D, Ring};
 use map_model::raw::RawMap;
 use map_model::{osm, Amenity, MapConfig};
 
 mod clip;
+mod elevation;
 mod extract;
 pub mod osm_geom;
 mod parking;
+mod pbf;
 pub mod reader;
 mod snappy;
 mod split_ways;
-mod srtm;
 mod transit;
 
 pub struct Options {
+    /// An .osm XML file, or a .osm.pbf, which can be a large regional extract if `clip` is set.
     pub osm_input: String,
     pub city_name: String,
     pub name: String,
 
-    /// The path to an osmosis boundary polygon. Highly recommended.
+    /// The path to an osmosis boundary polygon. Highly recommended, and required for .pbf input.
     pub clip: Option<String>,
     pub map_config: MapConfig,
 
     pub onstreet_parking: OnstreetParking,
     pub public_offstreet_parking: PublicOffstreetParking,
     pub private_offstreet_parking: PrivateOffstreetParking,
-    /// If provided, pull elevation data from this SRTM file. The SRTM parser is incorrect, so the
-    /// results will be nonsense.
+    /// If provided, pull elevation data from this digital elevation model: a GeoTIFF, ESRI ASCII
+    /// grid, or SRTM tile in longitude/latitude. Projected rasters must be reprojected to EPSG:4326
+    /// first.
     pub elevation: Option<String>,
     /// OSM railway=rail will be included as light rail if so. Cosmetic only.
     pub include_railroads: bool,
+    /// Import separately mapped sidewalks, crossings, pedestrian streets, trails, and steps as
+    /// walkable footways. Otherwise, pedestrians only use sidewalks inferred along roads.
+    pub include_footways: bool,
+    /// Split roads at crossing nodes in the middle of a block, so pedestrians can cross there,
+    /// controlled by a stop sign or pedestrian signal. Otherwise, pedestrians only cross at
+    /// intersections.
+    pub split_midblock_crossings: bool,
 }
 
 /// What roads will have on-street parking lanes? Data from
@@ -130,14 +142,91 @@ fn use_amenities(map: &mut RawMap, amenities: Vec<(Pt2D, Amenity)>, timer: &mut
 
 fn use_elevation(map: &mut RawMap, path: &str, timer: &mut Timer) {
     timer.start("apply elevation data to intersections");
-    let elevation = srtm::Elevation::load(path).unwrap();
+    let dem = elevation::Elevation::load(path)
+        .unwrap_or_else(|err| panic!("Couldn't load elevation data from {}: {}", path, err));
+    let mut missing = 0;
     for i in map.intersections.values_mut() {
         // TODO Not sure why, but I've seen nodes from South Carolina wind up in the updated
         // Seattle extract. And I think there's a bug with clipping, because they survive to this
         // point. O_O
         if map.boundary_polygon.contains_pt(i.point) {
-            i.elevation = elevation.get(i.point.to_gps(&map.gps_bounds));
+            if let Some(e) = dem.get(i.point.to_gps(&map.gps_bounds)) {
+                i.elevation = e;
+            } else {
+                missing += 1;
+            }
         }
     }
+    if missing > 0 {
+        timer.warn(format!(
+            "{} intersections have no elevation data in {}",
+            missing, path
+        ));
+    }
     timer.stop("apply elevation data to intersections");
+
+    fix_bridges_and_tunnels(map);
+}
+
+/// A DEM describes the ground, not roads on bridges or in tunnels. Intersections only touching
+/// bridges and tunnels get their elevation interpolated between the ends of the structure instead,
+/// weighted by distance.
+fn fix_bridges_and_tunnels(map: &mut RawMap) {
+    let mut neighbors: BTreeMap<osm::NodeID, Vec<(osm::NodeID, Distance)>> = BTreeMap::new();
+    let mut at_grade: BTreeSet<osm::NodeID> = BTreeSet::new();
+    for (id, r) in &map.roads {
+        if is_bridge_or_tunnel(&r.osm_tags) {
+            let length = r
+                .center_points
+                .windows(2)
+                .map(|pair| pair[0].dist_to(pair[1]))
+                .sum::<Distance>();
+            neighbors
+                .entry(id.i1)
+                .or_insert_with(Vec::new)
+                .push((id.i2, length));
+            neighbors
+                .entry(id.i2)
+                .or_insert_with(Vec::new)
+                .push((id.i1, length));
+        } else {
+            at_grade.insert(id.i1);
+            at_grade.insert(id.i2);
+        }
+    }
+    abstutil::retain_btreemap(&mut neighbors, |i, _| {
+        !at_grade.contains(i) && map.intersections.contains_key(i)
+    });
+
+    // Repeatedly set each intersection to the distance-weighted average of its neighbors. Along a
+    // chain of bridge segments, this converges to a straight line between the two ends.
+    for _ in 0..1000 {
+        let mut max_change = Distance::ZERO;
+        for (i, adjacent) in &neighbors {
+            let mut sum = 0.0;
+            let mut total_weight = 0.0;
+            for (other, length) in adjacent {
+                if let Some(other) = map.intersections.get(other) {
+                    let weight = 1.0 / length.inner_meters().max(1.0);
+                    sum += weight * other.elevation.inner_meters();
+                    total_weight += weight;
+                }
+            }
+            if total_weight == 0.0 {
+                continue;
+            }
+            let e = Distance::meters(sum / total_weight);
+            let old = std::mem::replace(&mut map.intersections.get_mut(i).unwrap().elevation, e);
+            max_change = max_change.max((e - old).abs());
+        }
+        if max_change < Distance::meters(0.01) {
+            break;
+        }
+    }
+}
+
+fn is_bridge_or_tunnel(tags: &Tags) -> bool {
+    (tags.contains_key("bridge") && !tags.is("bridge", "no"))
+        || (tags.contains_key("tunnel") && !tags.is_any("tunnel", vec!["no", "building_passage"]))
+        || tags.get("layer").map(|x| x != "0").unwrap_or(false)
 }
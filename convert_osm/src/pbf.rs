@@ -0,0 +1,430 @@
+//! A small reader for the .osm.pbf format, described at
+//! <https://wiki.openstreetmap.org/wiki/PBF_Format>. Only the fields the importer uses are decoded,
+//! and only one block (usually 8,000 objects) is held in memory at a time.
+
+use std::error::Error;
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+
+use geom::LonLat;
+use map_model::osm::{NodeID, OsmID, RelationID, WayID};
+
+use crate::reader::Element;
+
+/// Calls `f` on every object in the file, in order.
+pub fn read_elements<R: Read>(
+    mut input: R,
+    f: &mut dyn FnMut(&Element),
+) -> Result<(), Box<dyn Error>> {
+    loop {
+        let mut header_len = [0; 4];
+        match input.read_exact(&mut header_len) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::UnexpectedEof => {
+                return Ok(());
+            }
+            Err(err) => {
+                return Err(err.into());
+            }
+        }
+        let mut header = vec![0; u32::from_be_bytes(header_len) as usize];
+        input.read_exact(&mut header)?;
+        let mut blob_type = "";
+        let mut blob_size = 0;
+        for field in Fields::new(&header) {
+            match field? {
+                (1, Value::Bytes(x)) => {
+                    blob_type = std::str::from_utf8(x)?;
+                }
+                (3, Value::Varint(x)) => {
+                    blob_size = x as usize;
+                }
+                _ => {}
+            }
+        }
+
+        let mut blob = vec![0; blob_size];
+        input.read_exact(&mut blob)?;
+        match blob_type {
+            "OSMHeader" => check_header(&decompress(&blob)?)?,
+            "OSMData" => read_block(&decompress(&blob)?, f)?,
+            // The spec says to skip unknown blobs
+            _ => {}
+        }
+    }
+}
+
+fn decompress(blob: &[u8]) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut raw_size = 0;
+    let mut zlib_data = None;
+    for field in Fields::new(blob) {
+        match field? {
+            (1, Value::Bytes(x)) => {
+                return Ok(x.to_vec());
+            }
+            (2, Value::Varint(x)) => {
+                raw_size = x as usize;
+            }
+            (3, Value::Bytes(x)) => {
+                zlib_data = Some(x);
+            }
+            _ => {}
+        }
+    }
+    let zlib_data = zlib_data.ok_or("Only uncompressed and zlib-compressed blobs are supported")?;
+    let mut data = Vec::with_capacity(raw_size);
+    ZlibDecoder::new(zlib_data).read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn check_header(data: &[u8]) -> Result<(), Box<dyn Error>> {
+    for field in Fields::new(data) {
+        // required_features
+        if let (4, Value::Bytes(x)) = field? {
+            let feature = std::str::from_utf8(x)?;
+            if feature != "OsmSchema-V0.6" && feature != "DenseNodes" {
+                return Err(format!("Unsupported .pbf feature {}", feature).into());
+            }
+        }
+    }
+    Ok(())
+}
+
+fn read_block(data: &[u8], f: &mut dyn FnMut(&Element)) -> Result<(), Box<dyn Error>> {
+    let mut block = Block {
+        strings: Vec::new(),
+        granularity: 100,
+        lat_offset: 0,
+        lon_offset: 0,
+    };
+    let mut groups = Vec::new();
+    for field in Fields::new(data) {
+        match field? {
+            (1, Value::Bytes(x)) => {
+                for field in Fields::new(x) {
+                    if let (1, Value::Bytes(s)) = field? {
+                        block.strings.push(std::str::from_utf8(s)?);
+                    }
+                }
+            }
+            (2, Value::Bytes(x)) => {
+                groups.push(x);
+            }
+            (17, Value::Varint(x)) => {
+                block.granularity = x as i64;
+            }
+            (19, Value::Varint(x)) => {
+                block.lat_offset = x as i64;
+            }
+            (20, Value::Varint(x)) => {
+                block.lon_offset = x as i64;
+            }
+            _ => {}
+        }
+    }
+
+    for group in groups {
+        for field in Fields::new(group) {
+            match field? {
+                (1, Value::Bytes(x)) => f(&block.node(x)?),
+                (2, Value::Bytes(x)) => block.dense_nodes(x, f)?,
+                (3, Value::Bytes(x)) => f(&block.way(x)?),
+                (4, Value::Bytes(x)) => f(&block.relation(x)?),
+                // Changesets
+                _ => {}
+            }
+        }
+    }
+    Ok(())
+}
+
+struct Block<'a> {
+    strings: Vec<&'a str>,
+    granularity: i64,
+    lat_offset: i64,
+    lon_offset: i64,
+}
+
+impl<'a> Block<'a> {
+    fn node(&self, data: &[u8]) -> Result<Element<'a>, Box<dyn Error>> {
+        let mut id = 0;
+        let mut keys = Vec::new();
+        let mut vals = Vec::new();
+        let mut lat = 0;
+        let mut lon = 0;
+        for field in Fields::new(data) {
+            match field? {
+                (1, Value::Varint(x)) => {
+                    id = zigzag(x);
+                }
+                (2, x) => x.repeated(&mut keys)?,
+                (3, x) => x.repeated(&mut vals)?,
+                (8, Value::Varint(x)) => {
+                    lat = zigzag(x);
+                }
+                (9, Value::Varint(x)) => {
+                    lon = zigzag(x);
+                }
+                _ => {}
+            }
+        }
+        Ok(Element::Node {
+            id: NodeID(id),
+            pt: self.pt(lon, lat),
+            tags: self.tags(&keys, &vals)?,
+        })
+    }
+
+    fn dense_nodes(&self, data: &[u8], f: &mut dyn FnMut(&Element)) -> Result<(), Box<dyn Error>> {
+        let mut ids = Vec::new();
+        let mut lats = Vec::new();
+        let mut lons = Vec::new();
+        let mut keys_vals = Vec::new();
+        for field in Fields::new(data) {
+            match field? {
+                (1, x) => x.repeated(&mut ids)?,
+                (8, x) => x.repeated(&mut lats)?,
+                (9, x) => x.repeated(&mut lons)?,
+                (10, x) => x.repeated(&mut keys_vals)?,
+                _ => {}
+            }
+        }
+        if lats.len() != ids.len() || lons.len() != ids.len() {
+            return Err("Dense nodes have a different number of IDs and coordinates".into());
+        }
+
+        // Everything is delta-encoded
+        let mut id = 0;
+        let mut lat = 0;
+        let mut lon = 0;
+        // Each node's tags end with a 0. If no node in the block has tags, this is empty.
+        let mut keys_vals = keys_vals.into_iter();
+        for ((delta_id, delta_lat), delta_lon) in ids.into_iter().zip(lats).zip(lons) {
+            id += zigzag(delta_id);
+            lat += zigzag(delta_lat);
+            lon += zigzag(delta_lon);
+            let mut tags = Vec::new();
+            while let Some(key) = keys_vals.next() {
+                if key == 0 {
+                    break;
+                }
+                let value = keys_vals
+                    .next()
+                    .ok_or("Dense node tag is missing a value")?;
+                tags.push((self.string(key)?, self.string(value)?));
+            }
+            f(&Element::Node {
+                id: NodeID(id),
+                pt: self.pt(lon, lat),
+                tags,
+            });
+        }
+        Ok(())
+    }
+
+    fn way(&self, data: &[u8]) -> Result<Element<'a>, Box<dyn Error>> {
+        let mut id = 0;
+        let mut keys = Vec::new();
+        let mut vals = Vec::new();
+        let mut refs = Vec::new();
+        for field in Fields::new(data) {
+            match field? {
+                (1, Value::Varint(x)) => {
+                    id = x as i64;
+                }
+                (2, x) => x.repeated(&mut keys)?,
+                (3, x) => x.repeated(&mut vals)?,
+                (8, x) => x.repeated(&mut refs)?,
+                _ => {}
+            }
+        }
+        let mut node = 0;
+        let nodes = refs
+            .into_iter()
+            .map(|x| {
+                node += zigzag(x);
+                NodeID(node)
+            })
+            .collect();
+        Ok(Element::Way {
+            id: WayID(id),
+            nodes,
+            tags: self.tags(&keys, &vals)?,
+        })
+    }
+
+    fn relation(&self, data: &[u8]) -> Result<Element<'a>, Box<dyn Error>> {
+        let mut id = 0;
+        let mut keys = Vec::new();
+        let mut vals = Vec::new();
+        let mut roles = Vec::new();
+        let mut member_ids = Vec::new();
+        let mut types = Vec::new();
+        for field in Fields::new(data) {
+            match field? {
+                (1, Value::Varint(x)) => {
+                    id = x as i64;
+                }
+                (2, x) => x.repeated(&mut keys)?,
+                (3, x) => x.repeated(&mut vals)?,
+                (8, x) => x.repeated(&mut roles)?,
+                (9, x) => x.repeated(&mut member_ids)?,
+                (10, x) => x.repeated(&mut types)?,
+                _ => {}
+            }
+        }
+        if roles.len() != member_ids.len() || types.len() != member_ids.len() {
+            return Err(format!("Relation {} has mismatched member fields", id).into());
+        }
+
+        let mut members = Vec::new();
+        let mut member = 0;
+        for ((role, delta), member_type) in roles.into_iter().zip(member_ids).zip(types) {
+            member += zigzag(delta);
+            let member = match member_type {
+                0 => OsmID::Node(NodeID(member)),
+                1 => OsmID::Way(WayID(member)),
+                2 => OsmID::Relation(RelationID(member)),
+                x => {
+                    return Err(format!("Relation {} has a member of type {}", id, x).into());
+                }
+            };
+            members.push((self.string(role)?, member));
+        }
+        Ok(Element::Relation {
+            id: RelationID(id),
+            members,
+            tags: self.tags(&keys, &vals)?,
+        })
+    }
+
+    fn pt(&self, lon: i64, lat: i64) -> LonLat {
+        // In nanodegrees
+        LonLat::new(
+            1e-9 * (self.lon_offset + self.granularity * lon) as f64,
+            1e-9 * (self.lat_offset + self.granularity * lat) as f64,
+        )
+    }
+
+    fn string(&self, idx: u64) -> Result<&'a str, String> {
+        self.strings
+            .get(idx as usize)
+            .cloned()
+            .ok_or_else(|| format!("String {} isn't in the block's table", idx))
+    }
+
+    fn tags(&self, keys: &[u64], vals: &[u64]) -> Result<Vec<(&'a str, &'a str)>, String> {
+        if keys.len() != vals.len() {
+            return Err("Different number of tag keys and values".to_string());
+        }
+        keys.iter()
+            .zip(vals)
+            .map(|(k, v)| Ok((self.string(*k)?, self.string(*v)?)))
+            .collect()
+    }
+}
+
+// Protocol buffers, just enough to walk through messages. Fixed-width fields are skipped.
+
+enum Value<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+impl<'a> Value<'a> {
+    // Repeated numbers are usually packed together, but may also be listed one at a time.
+    fn repeated(self, output: &mut Vec<u64>) -> Result<(), String> {
+        match self {
+            Value::Varint(x) => {
+                output.push(x);
+            }
+            Value::Bytes(mut x) => {
+                while !x.is_empty() {
+                    output.push(varint(&mut x)?);
+                }
+            }
+            Value::Fixed => {
+                return Err("Expected a number, got a fixed-width field".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Fields<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Fields<'a> {
+    fn new(data: &'a [u8]) -> Fields<'a> {
+        Fields { data }
+    }
+
+    fn next_field(&mut self) -> Result<(u64, Value<'a>), String> {
+        let key = varint(&mut self.data)?;
+        let value = match key & 0x7 {
+            0 => Value::Varint(varint(&mut self.data)?),
+            1 => {
+                self.skip(8)?;
+                Value::Fixed
+            }
+            2 => {
+                let len = varint(&mut self.data)? as usize;
+                let bytes = self.skip(len)?;
+                Value::Bytes(bytes)
+            }
+            5 => {
+                self.skip(4)?;
+                Value::Fixed
+            }
+            x => {
+                return Err(format!("Unsupported protobuf wire type {}", x));
+            }
+        };
+        Ok((key >> 3, value))
+    }
+
+    fn skip(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if len > self.data.len() {
+            return Err("Truncated protobuf message".to_string());
+        }
+        let (bytes, rest) = self.data.split_at(len);
+        self.data = rest;
+        Ok(bytes)
+    }
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = Result<(u64, Value<'a>), String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.data.is_empty() {
+            return None;
+        }
+        let result = self.next_field();
+        if result.is_err() {
+            // Don't keep going after garbage
+            self.data = &[];
+        }
+        Some(result)
+    }
+}
+
+fn varint(data: &mut &[u8]) -> Result<u64, String> {
+    let mut result = 0;
+    for shift in (0..64).step_by(7) {
+        let (byte, rest) = data.split_first().ok_or("Truncated varint")?;
+        *data = rest;
+        result |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+    Err("Varint is too long".to_string())
+}
+
+fn zigzag(x: u64) -> i64 {
+    ((x >> 1) as i64) ^ -((x & 1) as i64)
+}
@@ -1,10 +1,14 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashSet};
 use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Write};
 
 use abstutil::{prettyprint_usize, slurp_file, Tags, Timer};
-use geom::{GPSBounds, LonLat, Pt2D};
+use geom::{GPSBounds, LonLat, Polygon, Pt2D, Ring};
 use map_model::osm::{NodeID, OsmID, RelationID, WayID};
 
+use crate::pbf;
+
 // References to missing objects are just filtered out.
 // Per https://wiki.openstreetmap.org/wiki/OSM_XML#Certainties_and_Uncertainties, we assume
 // elements come in order: nodes, ways, then relations.
@@ -39,7 +43,24 @@ pub struct Relation {
     pub members: Vec<(String, OsmID)>,
 }
 
+/// Reads .osm XML or .osm.pbf. XML is expected to already be clipped, but .pbf files are usually
+/// large regional extracts, so they need a clipping polygon (in the coordinate space of
+/// `input_gps_bounds`) to filter by while streaming.
 pub fn read(
+    path: &str,
+    input_gps_bounds: &GPSBounds,
+    clip: Option<&Polygon>,
+    timer: &mut Timer,
+) -> Result<Document, Box<dyn Error>> {
+    if path.ends_with(".pbf") {
+        let clip = clip.ok_or("Reading a .pbf file requires a clipping polygon")?;
+        read_pbf(path, input_gps_bounds, clip, timer)
+    } else {
+        read_xml(path, input_gps_bounds, timer)
+    }
+}
+
+fn read_xml(
     path: &str,
     input_gps_bounds: &GPSBounds,
     timer: &mut Timer,
@@ -181,20 +202,426 @@ pub fn read(
 }
 
 fn read_tags(obj: roxmltree::Node) -> Tags {
+    collect_tags(
+        obj.children()
+            .filter(|child| child.tag_name().name() == "tag")
+            .map(|child| (child.attribute("k").unwrap(), child.attribute("v").unwrap())),
+    )
+}
+
+fn collect_tags<'a, I: Iterator<Item = (&'a str, &'a str)>>(kv: I) -> Tags {
     let mut tags = Tags::new(BTreeMap::new());
-    for child in obj.children() {
-        if child.tag_name().name() == "tag" {
-            let key = child.attribute("k").unwrap();
-            // Filter out really useless data
-            if key.starts_with("tiger:") || key.starts_with("old_name:") {
-                continue;
-            }
-            tags.insert(key, child.attribute("v").unwrap());
+    for (key, value) in kv {
+        // Filter out really useless data
+        if key.starts_with("tiger:") || key.starts_with("old_name:") {
+            continue;
         }
+        tags.insert(key, value);
     }
     tags
 }
 
+/// One object from an .osm.pbf, or from a Document being clipped again
+pub(crate) enum Element<'a> {
+    Node {
+        id: NodeID,
+        pt: LonLat,
+        tags: Vec<(&'a str, &'a str)>,
+    },
+    Way {
+        id: WayID,
+        nodes: Vec<NodeID>,
+        tags: Vec<(&'a str, &'a str)>,
+    },
+    Relation {
+        id: RelationID,
+        members: Vec<(&'a str, OsmID)>,
+        tags: Vec<(&'a str, &'a str)>,
+    },
+}
+
+/// Streams through a .osm.pbf file, only keeping objects inside the clipping polygon.
+fn read_pbf(
+    path: &str,
+    gps_bounds: &GPSBounds,
+    clip: &Polygon,
+    timer: &mut Timer,
+) -> Result<Document, Box<dyn Error>> {
+    let mut regions = vec![Clipped::new(gps_bounds.clone(), clip.clone())];
+    clip_elements(
+        path,
+        |f| pbf::read_elements(BufReader::new(File::open(path)?), f),
+        &mut regions,
+        timer,
+    )?;
+    let doc = regions.pop().unwrap().into_document();
+    timer.note(format!(
+        "Found {} nodes, {} ways, {} relations",
+        prettyprint_usize(doc.nodes.len()),
+        prettyprint_usize(doc.ways.len()),
+        prettyprint_usize(doc.relations.len())
+    ));
+    Ok(doc)
+}
+
+/// Clips a large .osm.pbf (or .osm) to many boundaries at once, writing a smaller .osm file for
+/// each. Boundaries are closed rings, like from `LonLat::read_osmosis_polygon`. The input is read
+/// through once (twice if some ways cross a boundary), no matter how many boundaries there are.
+pub fn clip_to_osm(
+    input: &str,
+    clips: Vec<(Vec<LonLat>, String)>,
+    timer: &mut Timer,
+) -> Result<(), Box<dyn Error>> {
+    let mut regions = Vec::new();
+    let mut outputs = Vec::new();
+    for (boundary, output) in clips {
+        let gps_bounds = GPSBounds::from(boundary.clone());
+        let polygon = Ring::new(gps_bounds.convert(&boundary))?.to_polygon();
+        regions.push(Clipped::new(gps_bounds, polygon));
+        outputs.push(output);
+    }
+
+    if input.ends_with(".pbf") {
+        clip_elements(
+            input,
+            |f| pbf::read_elements(BufReader::new(File::open(input)?), f),
+            &mut regions,
+            timer,
+        )?;
+    } else {
+        let doc = read_xml(input, &GPSBounds::new(), timer)?;
+        clip_elements(
+            input,
+            |f| {
+                document_elements(&doc, f);
+                Ok(())
+            },
+            &mut regions,
+            timer,
+        )?;
+    }
+
+    for (region, output) in regions.into_iter().zip(outputs) {
+        timer.note(format!(
+            "Writing {} nodes, {} ways, {} relations to {}",
+            prettyprint_usize(region.nodes.len()),
+            prettyprint_usize(region.ways.len()),
+            prettyprint_usize(region.relations.len()),
+            output
+        ));
+        region.write_osm(&output)?;
+    }
+    Ok(())
+}
+
+/// The part of a large extract inside one boundary, still in longitude/latitude.
+struct Clipped {
+    gps_bounds: GPSBounds,
+    boundary: Polygon,
+    nodes: BTreeMap<NodeID, (LonLat, Tags)>,
+    ways: BTreeMap<WayID, (Vec<NodeID>, Tags)>,
+    relations: BTreeMap<RelationID, (Vec<(String, OsmID)>, Tags)>,
+    // Ways that cross the boundary need their nodes outside of it too, but those usually come
+    // earlier in the file, so they're read in a second pass.
+    missing_nodes: HashSet<NodeID>,
+    duplicate: Option<OsmID>,
+}
+
+impl Clipped {
+    fn new(gps_bounds: GPSBounds, boundary: Polygon) -> Clipped {
+        Clipped {
+            gps_bounds,
+            boundary,
+            nodes: BTreeMap::new(),
+            ways: BTreeMap::new(),
+            relations: BTreeMap::new(),
+            missing_nodes: HashSet::new(),
+            duplicate: None,
+        }
+    }
+
+    /// Like `osmconvert -B=clip.poly --complete-ways`, ways crossing the boundary are kept, and
+    /// relations keep the members that made it in.
+    fn keep(&mut self, element: &Element) {
+        match element {
+            Element::Node { id, pt, tags } => {
+                if !self.gps_bounds.contains(*pt)
+                    || !self
+                        .boundary
+                        .contains_pt(Pt2D::from_gps(*pt, &self.gps_bounds))
+                {
+                    return;
+                }
+                if self
+                    .nodes
+                    .insert(*id, (*pt, collect_tags(tags.iter().cloned())))
+                    .is_some()
+                {
+                    self.duplicate = Some(OsmID::Node(*id));
+                }
+            }
+            Element::Way { id, nodes, tags } => {
+                if !nodes.iter().any(|n| self.nodes.contains_key(n)) {
+                    return;
+                }
+                for n in nodes {
+                    if !self.nodes.contains_key(n) {
+                        self.missing_nodes.insert(*n);
+                    }
+                }
+                if self
+                    .ways
+                    .insert(*id, (nodes.clone(), collect_tags(tags.iter().cloned())))
+                    .is_some()
+                {
+                    self.duplicate = Some(OsmID::Way(*id));
+                }
+            }
+            Element::Relation { id, members, tags } => {
+                let keep = members.iter().any(|(_, member)| match member {
+                    OsmID::Node(n) => self.nodes.contains_key(n),
+                    OsmID::Way(w) => self.ways.contains_key(w),
+                    OsmID::Relation(r) => self.relations.contains_key(r),
+                });
+                if !keep {
+                    return;
+                }
+                let members = members
+                    .iter()
+                    .map(|(role, member)| (role.to_string(), *member))
+                    .collect();
+                if self
+                    .relations
+                    .insert(*id, (members, collect_tags(tags.iter().cloned())))
+                    .is_some()
+                {
+                    self.duplicate = Some(OsmID::Relation(*id));
+                }
+            }
+        }
+    }
+
+    fn keep_missing_node(&mut self, element: &Element) {
+        if let Element::Node { id, pt, tags } = element {
+            if self.missing_nodes.contains(id) {
+                self.nodes
+                    .insert(*id, (*pt, collect_tags(tags.iter().cloned())));
+            }
+        }
+    }
+
+    // References to missing objects are just filtered out.
+    fn remove_missing_members(&mut self) {
+        let nodes = &self.nodes;
+        for (way_nodes, _) in self.ways.values_mut() {
+            way_nodes.retain(|n| nodes.contains_key(n));
+        }
+        let ways = &self.ways;
+        let relation_ids: HashSet<RelationID> = self.relations.keys().cloned().collect();
+        for (members, _) in self.relations.values_mut() {
+            members.retain(|(_, member)| match member {
+                OsmID::Node(n) => nodes.contains_key(n),
+                OsmID::Way(w) => ways.contains_key(w),
+                OsmID::Relation(r) => relation_ids.contains(r),
+            });
+        }
+    }
+
+    fn into_document(self) -> Document {
+        let gps_bounds = self.gps_bounds;
+        let nodes: BTreeMap<NodeID, Node> = self
+            .nodes
+            .into_iter()
+            .map(|(id, (pt, tags))| {
+                let pt = Pt2D::from_gps(pt, &gps_bounds);
+                (id, Node { pt, tags })
+            })
+            .collect();
+        let ways = self
+            .ways
+            .into_iter()
+            .map(|(id, (way_nodes, tags))| {
+                let pts = way_nodes.iter().map(|n| nodes[n].pt).collect();
+                (
+                    id,
+                    Way {
+                        nodes: way_nodes,
+                        pts,
+                        tags,
+                    },
+                )
+            })
+            .collect();
+        let relations = self
+            .relations
+            .into_iter()
+            .map(|(id, (members, tags))| (id, Relation { tags, members }))
+            .collect();
+        Document {
+            gps_bounds,
+            nodes,
+            ways,
+            relations,
+        }
+    }
+
+    fn write_osm(&self, path: &str) -> Result<(), Box<dyn Error>> {
+        let mut f = BufWriter::new(File::create(path)?);
+        writeln!(f, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(f, r#"<osm version="0.6" generator="abstreet">"#)?;
+        let corner1 = Pt2D::new(0.0, 0.0).to_gps(&self.gps_bounds);
+        let corner2 = self.gps_bounds.get_max_world_pt().to_gps(&self.gps_bounds);
+        writeln!(
+            f,
+            r#"  <bounds minlon="{}" minlat="{}" maxlon="{}" maxlat="{}"/>"#,
+            corner1.x().min(corner2.x()),
+            corner1.y().min(corner2.y()),
+            corner1.x().max(corner2.x()),
+            corner1.y().max(corner2.y())
+        )?;
+
+        for (id, (pt, tags)) in &self.nodes {
+            write!(
+                f,
+                r#"  <node id="{}" lon="{}" lat="{}""#,
+                id.0,
+                pt.x(),
+                pt.y()
+            )?;
+            if tags.is_empty() {
+                writeln!(f, "/>")?;
+            } else {
+                writeln!(f, ">")?;
+                write_tags(&mut f, tags)?;
+                writeln!(f, "  </node>")?;
+            }
+        }
+        for (id, (nodes, tags)) in &self.ways {
+            writeln!(f, r#"  <way id="{}">"#, id.0)?;
+            for n in nodes {
+                writeln!(f, r#"    <nd ref="{}"/>"#, n.0)?;
+            }
+            write_tags(&mut f, tags)?;
+            writeln!(f, "  </way>")?;
+        }
+        for (id, (members, tags)) in &self.relations {
+            writeln!(f, r#"  <relation id="{}">"#, id.0)?;
+            for (role, member) in members {
+                let (member_type, member_id) = match member {
+                    OsmID::Node(n) => ("node", n.0),
+                    OsmID::Way(w) => ("way", w.0),
+                    OsmID::Relation(r) => ("relation", r.0),
+                };
+                writeln!(
+                    f,
+                    r#"    <member type="{}" ref="{}" role="{}"/>"#,
+                    member_type,
+                    member_id,
+                    escape(role)
+                )?;
+            }
+            write_tags(&mut f, tags)?;
+            writeln!(f, "  </relation>")?;
+        }
+        writeln!(f, "</osm>")?;
+        Ok(())
+    }
+}
+
+/// Reads through the input once to clip it to every region, then once more if some ways cross a
+/// boundary.
+fn clip_elements<F: Fn(&mut dyn FnMut(&Element)) -> Result<(), Box<dyn Error>>>(
+    name: &str,
+    read_elements: F,
+    regions: &mut [Clipped],
+    timer: &mut Timer,
+) -> Result<(), Box<dyn Error>> {
+    timer.start(format!("read {}, clipping", name));
+    read_elements(&mut |element| {
+        for region in regions.iter_mut() {
+            region.keep(element);
+        }
+    })?;
+    timer.stop(format!("read {}, clipping", name));
+    if let Some(id) = regions.iter().find_map(|region| region.duplicate) {
+        return Err(format!("Duplicate {}, your input is corrupt", id).into());
+    }
+
+    let missing: usize = regions
+        .iter()
+        .map(|region| region.missing_nodes.len())
+        .sum();
+    if missing > 0 {
+        let step = format!(
+            "read {} again for {} nodes of ways crossing the boundary",
+            name,
+            prettyprint_usize(missing)
+        );
+        timer.start(&step);
+        read_elements(&mut |element| {
+            for region in regions.iter_mut() {
+                region.keep_missing_node(element);
+            }
+        })?;
+        timer.stop(&step);
+    }
+
+    for region in regions {
+        region.remove_missing_members();
+    }
+    Ok(())
+}
+
+fn document_elements(doc: &Document, f: &mut dyn FnMut(&Element)) {
+    for (id, node) in &doc.nodes {
+        f(&Element::Node {
+            id: *id,
+            pt: node.pt.to_gps(&doc.gps_bounds),
+            tags: tag_pairs(&node.tags),
+        });
+    }
+    for (id, way) in &doc.ways {
+        f(&Element::Way {
+            id: *id,
+            nodes: way.nodes.clone(),
+            tags: tag_pairs(&way.tags),
+        });
+    }
+    for (id, rel) in &doc.relations {
+        f(&Element::Relation {
+            id: *id,
+            members: rel
+                .members
+                .iter()
+                .map(|(role, member)| (role.as_str(), *member))
+                .collect(),
+            tags: tag_pairs(&rel.tags),
+        });
+    }
+}
+
+fn tag_pairs(tags: &Tags) -> Vec<(&str, &str)> {
+    tags.inner()
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect()
+}
+
+fn write_tags<W: Write>(f: &mut W, tags: &Tags) -> std::io::Result<()> {
+    for (k, v) in tags.inner() {
+        writeln!(f, r#"    <tag k="{}" v="{}"/>"#, escape(k), escape(v))?;
+    }
+    Ok(())
+}
+
+fn escape(x: &str) -> String {
+    x.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\n', "&#10;")
+}
+
 fn scrape_bounds(doc: &roxmltree::Document) -> GPSBounds {
     let mut b = GPSBounds::new();
     for obj in doc.descendants() {
@@ -207,3 +634,203 @@ fn scrape_bounds(doc: &roxmltree::Document) -> GPSBounds {
     }
     b
 }
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::write::ZlibEncoder;
+    use flate2::Compression;
+
+    use super::*;
+
+    // Just enough protobuf encoding to build a .osm.pbf
+    fn varint(mut x: u64, out: &mut Vec<u8>) {
+        while x >= 0x80 {
+            out.push((x as u8 & 0x7f) | 0x80);
+            x >>= 7;
+        }
+        out.push(x as u8);
+    }
+
+    fn zigzag(x: i64) -> u64 {
+        ((x << 1) ^ (x >> 63)) as u64
+    }
+
+    fn number(field: u64, x: u64, out: &mut Vec<u8>) {
+        varint(field << 3, out);
+        varint(x, out);
+    }
+
+    fn bytes(field: u64, x: &[u8], out: &mut Vec<u8>) {
+        varint((field << 3) | 2, out);
+        varint(x.len() as u64, out);
+        out.extend_from_slice(x);
+    }
+
+    fn packed(field: u64, xs: Vec<u64>, out: &mut Vec<u8>) {
+        let mut buf = Vec::new();
+        for x in xs {
+            varint(x, &mut buf);
+        }
+        bytes(field, &buf, out);
+    }
+
+    // Deltas between consecutive numbers
+    fn deltas(xs: Vec<i64>) -> Vec<u64> {
+        let mut last = 0;
+        xs.into_iter()
+            .map(|x| {
+                let delta = zigzag(x - last);
+                last = x;
+                delta
+            })
+            .collect()
+    }
+
+    fn blob(blob_type: &str, data: Vec<u8>, out: &mut Vec<u8>) {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&data).unwrap();
+        let mut blob = Vec::new();
+        number(2, data.len() as u64, &mut blob);
+        bytes(3, &encoder.finish().unwrap(), &mut blob);
+
+        let mut header = Vec::new();
+        bytes(1, blob_type.as_bytes(), &mut header);
+        number(3, blob.len() as u64, &mut header);
+
+        out.extend_from_slice(&(header.len() as u32).to_be_bytes());
+        out.extend(header);
+        out.extend(blob);
+    }
+
+    /// Node 1 is inside a square covering longitude 10-10.2 and latitude 50-50.2, nodes 2 and 3
+    /// are east of it. Way 10 goes from 1 to 2, way 11 from 2 to 3. Relation 20 has both ways and
+    /// node 3, relation 21 just has way 11.
+    fn fixture() -> Vec<u8> {
+        let mut file = Vec::new();
+
+        let mut header = Vec::new();
+        bytes(4, b"OsmSchema-V0.6", &mut header);
+        bytes(4, b"DenseNodes", &mut header);
+        blob("OSMHeader", header, &mut file);
+
+        let mut strings = Vec::new();
+        for s in vec![
+            "",
+            "highway",
+            "traffic_signals",
+            "residential",
+            "outer",
+            "inner",
+        ] {
+            bytes(1, s.as_bytes(), &mut strings);
+        }
+
+        // Coordinates are in units of 100 nanodegrees
+        let mut dense = Vec::new();
+        packed(1, deltas(vec![1, 2, 3]), &mut dense);
+        packed(8, deltas(vec![501_000_000; 3]), &mut dense);
+        packed(
+            9,
+            deltas(vec![101_000_000, 103_000_000, 104_000_000]),
+            &mut dense,
+        );
+        // Only the first node has tags
+        packed(10, vec![1, 2, 0, 0, 0], &mut dense);
+        let mut nodes = Vec::new();
+        bytes(2, &dense, &mut nodes);
+
+        let mut ways = Vec::new();
+        for (id, refs) in vec![(10, vec![1, 2]), (11, vec![2, 3])] {
+            let mut way = Vec::new();
+            number(1, id, &mut way);
+            packed(2, vec![1], &mut way);
+            packed(3, vec![3], &mut way);
+            packed(8, deltas(refs), &mut way);
+            bytes(3, &way, &mut ways);
+        }
+
+        let mut relations = Vec::new();
+        for (id, members) in vec![
+            (20, vec![(4, 10, 1), (5, 11, 1), (0, 3, 0)]),
+            (21, vec![(4, 11, 1)]),
+        ] {
+            let mut rel = Vec::new();
+            number(1, id, &mut rel);
+            packed(
+                8,
+                members.iter().map(|(role, _, _)| *role).collect(),
+                &mut rel,
+            );
+            packed(
+                9,
+                deltas(members.iter().map(|(_, id, _)| *id).collect()),
+                &mut rel,
+            );
+            packed(10, members.iter().map(|(_, _, t)| *t).collect(), &mut rel);
+            bytes(4, &rel, &mut relations);
+        }
+
+        let mut block = Vec::new();
+        bytes(1, &strings, &mut block);
+        bytes(2, &nodes, &mut block);
+        bytes(2, &ways, &mut block);
+        bytes(2, &relations, &mut block);
+        blob("OSMData", block, &mut file);
+
+        file
+    }
+
+    #[test]
+    fn clip_pbf() {
+        let boundary = vec![
+            LonLat::new(10.0, 50.0),
+            LonLat::new(10.2, 50.0),
+            LonLat::new(10.2, 50.2),
+            LonLat::new(10.0, 50.2),
+            LonLat::new(10.0, 50.0),
+        ];
+        let gps_bounds = GPSBounds::from(boundary.clone());
+        let polygon = Ring::must_new(gps_bounds.convert(&boundary)).to_polygon();
+        let mut regions = vec![Clipped::new(gps_bounds.clone(), polygon)];
+
+        let file = fixture();
+        clip_elements(
+            "fixture",
+            |f| pbf::read_elements(&file[..], f),
+            &mut regions,
+            &mut Timer::throwaway(),
+        )
+        .unwrap();
+        let doc = regions.pop().unwrap().into_document();
+
+        // Node 2 is outside, but way 10 crosses the boundary, so all of it is kept
+        assert_eq!(
+            doc.nodes.keys().cloned().collect::<Vec<_>>(),
+            vec![NodeID(1), NodeID(2)]
+        );
+        assert!(doc.nodes[&NodeID(1)].tags.is("highway", "traffic_signals"));
+        assert!(doc.nodes[&NodeID(2)].tags.is_empty());
+        let gps = doc.nodes[&NodeID(2)].pt.to_gps(&gps_bounds);
+        assert!((gps.x() - 10.3).abs() < 1e-6 && (gps.y() - 50.1).abs() < 1e-6);
+
+        assert_eq!(
+            doc.ways.keys().cloned().collect::<Vec<_>>(),
+            vec![WayID(10)]
+        );
+        let way = &doc.ways[&WayID(10)];
+        assert_eq!(way.nodes, vec![NodeID(1), NodeID(2)]);
+        assert_eq!(way.pts.len(), 2);
+        assert!(way.tags.is("highway", "residential"));
+
+        assert_eq!(
+            doc.relations.keys().cloned().collect::<Vec<_>>(),
+            vec![RelationID(20)]
+        );
+        assert_eq!(
+            doc.relations[&RelationID(20)].members,
+            vec![("outer".to_string(), OsmID::Way(WayID(10)))]
+        );
+    }
+}
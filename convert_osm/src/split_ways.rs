@@ -3,10 +3,13 @@ use std::collections::HashMap;
 use abstutil::{Counter, Timer};
 use geom::{Distance, HashablePt2D, Pt2D};
 use map_model::raw::{OriginalRoad, RawIntersection, RawMap};
-use map_model::{osm, Amenity, IntersectionType};
+use map_model::{osm, Amenity, CrossingType, IntersectionType};
 
 use crate::extract::OsmExtract;
 
+/// Crossing nodes closer than this to an intersection are just part of the usual crosswalks there.
+const MIN_DIST_FROM_CROSSING_TO_INTERSECTION: Distance = Distance::const_meters(20.0);
+
 /// Returns amenities and a mapping of all points to split road. (Some internal points on roads are
 /// removed, so this mapping isn't redundant.)
 pub fn split_up_roads(
@@ -33,16 +36,55 @@ pub fn split_up_roads(
         }
     }
 
+    // Also split roads at crossings in the middle of a block, so pedestrians can cross there.
+    let mut crossings: HashMap<osm::NodeID, CrossingType> = HashMap::new();
+    for (_, r) in &input.roads {
+        if r.is_footway() || r.is_light_rail() {
+            continue;
+        }
+        let mut dist_along = vec![Distance::ZERO];
+        for pair in r.center_points.windows(2) {
+            dist_along.push(*dist_along.last().unwrap() + pair[0].dist_to(pair[1]));
+        }
+        let mut last_split = Distance::ZERO;
+        for (idx, raw_pt) in r.center_points.iter().enumerate() {
+            let pt = raw_pt.to_hashable();
+            if pt_to_intersection.contains_key(&pt) {
+                last_split = dist_along[idx];
+                continue;
+            }
+            if let Some(crossing) = input.crossings.get(&pt) {
+                // The last point is always an intersection
+                let next_split = (idx + 1..r.center_points.len())
+                    .find(|i| pt_to_intersection.contains_key(&r.center_points[*i].to_hashable()))
+                    .map(|i| dist_along[i])
+                    .unwrap();
+                if dist_along[idx] - last_split >= MIN_DIST_FROM_CROSSING_TO_INTERSECTION
+                    && next_split - dist_along[idx] >= MIN_DIST_FROM_CROSSING_TO_INTERSECTION
+                {
+                    let id = input.osm_node_ids[&pt];
+                    pt_to_intersection.insert(pt, id);
+                    crossings.insert(id, *crossing);
+                    last_split = dist_along[idx];
+                }
+            }
+        }
+    }
+
     for (pt, id) in &pt_to_intersection {
+        let crossing = crossings.get(id).cloned();
         map.intersections.insert(
             *id,
             RawIntersection {
                 point: pt.to_pt2d(),
-                intersection_type: if input.traffic_signals.remove(pt).is_some() {
+                intersection_type: if input.traffic_signals.remove(pt).is_some()
+                    || crossing == Some(CrossingType::Signalized)
+                {
                     IntersectionType::TrafficSignal
                 } else {
                     IntersectionType::StopSign
                 },
+                crossing,
                 // Filled out later
                 elevation: Distance::ZERO,
             },
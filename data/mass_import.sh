@@ -13,7 +13,7 @@ function extract {
 
 	# First extract all "cities" from the huge bbike files. If two names collide,
 	# the .osm and .poly might mix between the two arbitrarily!
-	# Don't parallelize (-j1); I think osmconvert must eat CPUs, because my system
+	# Don't parallelize (-j1); each run holds a huge .osm in memory, and my system
 	# lags heavily with -j4 here.
 	for raw_extract in `ls ~/bbike_extracts`; do
 		raw_extract=`basename -s .osm $raw_extract`
@@ -7,7 +7,7 @@ use rand::seq::SliceRandom;
 use abstutil::Timer;
 use geom::{Bounds, Circle, Distance, Duration, Pt2D, Time};
 use map_model::{IntersectionID, Map, PermanentMapEdits, Traversable};
-use sim::{Analytics, Sim, SimCallback, SimFlags};
+use sim::{Analytics, Recording, Sim, SimCallback, SimFlags};
 use widgetry::{Canvas, EventCtx, GfxCtx, Prerender, SharedAppState};
 
 use crate::challenges::HighScore;
@@ -22,9 +22,10 @@ use crate::sandbox::{GameplayMode, TutorialState};
 
 /// The top-level data that lasts through the entire game, no matter what state the game is in.
 pub struct App {
-    // Naming is from older days when there was an A/B test, "side-by-side" mode. Keeping this
-    // naming, because that mode will return someday.
     pub primary: PerMap,
+    /// Only filled out while comparing two simulations side-by-side. The primary one has the
+    /// proposal; this is the baseline.
+    pub secondary: Option<PerMap>,
     pub cs: ColorScheme,
     // TODO This is a bit weird to keep here; it's controlled almost entirely by the minimap panel.
     // It has no meaning in edit mode.
@@ -39,7 +40,7 @@ pub struct App {
 
 impl App {
     pub fn new(flags: Flags, opts: Options, ctx: &mut EventCtx, splash: bool) -> App {
-        let cs = ColorScheme::new(opts.color_scheme);
+        let cs = ColorScheme::new(opts.color_scheme.clone());
         ctx.set_style(cs.gui_style.clone());
 
         let primary = ctx.loading_screen("load map", |ctx, mut timer| {
@@ -50,6 +51,7 @@ impl App {
 
         App {
             primary,
+            secondary: None,
             unzoomed_agents: UnzoomedAgents::new(&cs),
             cs,
             opts,
@@ -512,6 +514,11 @@ pub struct PerMap {
     pub layer: Option<Box<dyn Layer>>,
     /// Only filled out in edit mode. Stored here once to avoid lots of clones. Used for preview.
     pub suspended_sim: Option<Sim>,
+    /// Agent positions captured as the simulation runs, for playback later. Kept until the player
+    /// discards it, even after recording stops.
+    pub recording: Option<Recording>,
+    /// Is `recording` currently capturing frames?
+    pub is_recording: bool,
     /// Only exists in some gameplay modes. Must be carefully reset otherwise. Has the map and
     /// scenario name too.
     // TODO Embed that in Analytics directly instead.
@@ -519,10 +526,10 @@ pub struct PerMap {
 }
 
 impl PerMap {
-    fn map_loaded(
+    /// Doesn't touch the camera, unlike loading a map normally. Used for the secondary map.
+    pub fn new(
         map: Map,
         sim: Sim,
-        splash: bool,
         flags: Flags,
         opts: &Options,
         cs: &ColorScheme,
@@ -533,7 +540,7 @@ impl PerMap {
         let (draw_map, zorder_range) = DrawMap::new(&map, opts, cs, ctx, timer);
         timer.stop("draw_map");
 
-        let per_map = PerMap {
+        PerMap {
             map,
             draw_map,
             sim,
@@ -548,8 +555,23 @@ impl PerMap {
             unedited_map: RefCell::new(None),
             layer: None,
             suspended_sim: None,
+            recording: None,
+            is_recording: false,
             prebaked: None,
-        };
+        }
+    }
+
+    fn map_loaded(
+        map: Map,
+        sim: Sim,
+        splash: bool,
+        flags: Flags,
+        opts: &Options,
+        cs: &ColorScheme,
+        ctx: &mut EventCtx,
+        timer: &mut Timer,
+    ) -> PerMap {
+        let per_map = PerMap::new(map, sim, flags, opts, cs, ctx, timer);
 
         let mut rng = per_map.current_flags.sim_flags.make_rng();
         let rand_focus_pt = per_map
@@ -1,8 +1,13 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use abstutil::Timer;
 use map_model::osm::RoadRank;
 use map_model::LaneType;
 use widgetry::{Choice, Color, Fill, Style, Texture};
 
-use crate::common::ColorScale;
+use crate::common::{heatmap_gradient, ColorScale};
 use crate::helpers::loading_tips;
 
 // I've gone back and forth how to organize color scheme code. I was previously against having one
@@ -16,7 +21,7 @@ use crate::helpers::loading_tips;
 //
 // TODO There are plenty of colors left that aren't captured here. :(
 
-#[derive(Clone, Copy, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum ColorSchemeChoice {
     Standard,
     NightMode,
@@ -30,25 +35,113 @@ pub enum ColorSchemeChoice {
     MapboxDark,
     FadedZoom,
     NegativeSpace,
+    /// Loaded from `data/system/themes/{name}.json`
+    Theme(String),
 }
 
+// Besides Standard, the built-in schemes are defined by `data/system/themes/{name}.json`. Some of
+// them have special-cased rendering elsewhere, so they keep their own variant.
+const BUILT_IN: [ColorSchemeChoice; 11] = [
+    ColorSchemeChoice::NightMode,
+    ColorSchemeChoice::SAMGreenDay,
+    ColorSchemeChoice::SAMDesertDay,
+    ColorSchemeChoice::BAP,
+    ColorSchemeChoice::OSM,
+    ColorSchemeChoice::Starcat,
+    ColorSchemeChoice::Textured,
+    ColorSchemeChoice::MapboxLight,
+    ColorSchemeChoice::MapboxDark,
+    ColorSchemeChoice::FadedZoom,
+    ColorSchemeChoice::NegativeSpace,
+];
+
 impl ColorSchemeChoice {
     pub fn choices() -> Vec<Choice<ColorSchemeChoice>> {
-        vec![
-            Choice::new("default", ColorSchemeChoice::Standard),
-            Choice::new("night mode", ColorSchemeChoice::NightMode),
-            Choice::new("sam green day", ColorSchemeChoice::SAMGreenDay),
-            Choice::new("sam desert day", ColorSchemeChoice::SAMDesertDay),
-            Choice::new("bap", ColorSchemeChoice::BAP),
-            Choice::new("osm", ColorSchemeChoice::OSM),
-            Choice::new("starcat", ColorSchemeChoice::Starcat),
-            Choice::new("textured", ColorSchemeChoice::Textured),
-            Choice::new("mapbox light", ColorSchemeChoice::MapboxLight),
-            Choice::new("mapbox dark", ColorSchemeChoice::MapboxDark),
-            Choice::new("faded zoom", ColorSchemeChoice::FadedZoom),
-            Choice::new("negative space", ColorSchemeChoice::NegativeSpace),
-        ]
+        let mut choices = vec![Choice::new("default", ColorSchemeChoice::Standard)];
+        for scheme in BUILT_IN.iter() {
+            let name = scheme.theme_name().unwrap();
+            choices.push(Choice::new(name.replace('_', " "), scheme.clone()));
+        }
+        for name in abstutil::list_all_objects(abstutil::path("system/themes")) {
+            if ColorSchemeChoice::is_built_in(&name) {
+                continue;
+            }
+            choices.push(Choice::new(
+                name.replace('_', " "),
+                ColorSchemeChoice::Theme(name),
+            ));
+        }
+        choices
+    }
+
+    /// The name of the theme file defining this scheme. Standard is the only scheme without one.
+    fn theme_name(&self) -> Option<&str> {
+        match self {
+            ColorSchemeChoice::Standard => None,
+            ColorSchemeChoice::NightMode => Some("night_mode"),
+            ColorSchemeChoice::SAMGreenDay => Some("sam_green_day"),
+            ColorSchemeChoice::SAMDesertDay => Some("sam_desert_day"),
+            ColorSchemeChoice::BAP => Some("bap"),
+            ColorSchemeChoice::OSM => Some("osm"),
+            ColorSchemeChoice::Starcat => Some("starcat"),
+            ColorSchemeChoice::Textured => Some("textured"),
+            ColorSchemeChoice::MapboxLight => Some("mapbox_light"),
+            ColorSchemeChoice::MapboxDark => Some("mapbox_dark"),
+            ColorSchemeChoice::FadedZoom => Some("faded_zoom"),
+            ColorSchemeChoice::NegativeSpace => Some("negative_space"),
+            ColorSchemeChoice::Theme(name) => Some(name),
+        }
     }
+
+    fn is_built_in(name: &str) -> bool {
+        BUILT_IN.iter().any(|x| x.theme_name() == Some(name))
+    }
+}
+
+/// A palette stored in `data/system/themes/`. Themes start from one of the built-in schemes, then
+/// override the GUI style, individual colors, and the scales used by layers and heatmaps.
+#[derive(Serialize, Deserialize)]
+struct Theme {
+    base: ColorSchemeChoice,
+    #[serde(default)]
+    gui_style: Option<Style>,
+    /// Keyed by the name of the field in `ColorScheme`
+    #[serde(default)]
+    colors: BTreeMap<String, Color>,
+    /// Keyed by the name of a `Fill` field in `ColorScheme`, with values like "grass"
+    #[serde(default)]
+    textures: BTreeMap<String, String>,
+    #[serde(default)]
+    hide_sidewalk_lines: bool,
+    #[serde(default)]
+    agent_colors: Option<Vec<Color>>,
+    #[serde(default)]
+    good_to_bad_red: Option<Vec<Color>>,
+    #[serde(default)]
+    good_to_bad_green: Option<Vec<Color>>,
+    /// The default gradient for heatmaps, like "Viridis"
+    #[serde(default)]
+    heatmap: Option<String>,
+}
+
+// Themes override colors by their field name. Fills can also be overridden with a texture.
+macro_rules! overridable_colors {
+    ($($field:ident),*) => {
+        fn set_color(&mut self, key: &str, color: Color) -> Result<(), String> {
+            match key {
+                $(stringify!($field) => {
+                    self.$field = color.into();
+                })*
+                "sidewalk_lines" => {
+                    self.sidewalk_lines = Some(color);
+                }
+                _ => {
+                    return Err(format!("unknown color {}", key));
+                }
+            }
+            Ok(())
+        }
+    };
 }
 
 pub struct ColorScheme {
@@ -126,6 +219,7 @@ pub struct ColorScheme {
     pub bus_body: Color,
     pub bus_label: Color,
     pub train_body: Color,
+    pub truck_body: Color,
     pub ped_head: Color,
     pub ped_foot: Color,
     pub ped_preparing_bike_body: Color,
@@ -136,6 +230,8 @@ pub struct ColorScheme {
     // Layers
     pub good_to_bad_red: ColorScale,
     pub good_to_bad_green: ColorScale,
+    /// The default gradient for heatmaps
+    pub heatmap: String,
     pub bus_layer: Color,
     pub edits_layer: Color,
 
@@ -149,22 +245,95 @@ pub struct ColorScheme {
 
 impl ColorScheme {
     pub fn new(scheme: ColorSchemeChoice) -> ColorScheme {
-        let mut cs = match scheme {
-            ColorSchemeChoice::Standard => ColorScheme::standard(),
-            ColorSchemeChoice::NightMode => ColorScheme::night_mode(),
-            ColorSchemeChoice::SAMGreenDay => ColorScheme::sam_green_day(),
-            ColorSchemeChoice::SAMDesertDay => ColorScheme::sam_desert_day(),
-            ColorSchemeChoice::BAP => ColorScheme::bap(),
-            ColorSchemeChoice::OSM => ColorScheme::osm(),
-            ColorSchemeChoice::Starcat => ColorScheme::starcat(),
-            ColorSchemeChoice::Textured => ColorScheme::textured(),
-            ColorSchemeChoice::MapboxLight => ColorScheme::mapbox_light(),
-            ColorSchemeChoice::MapboxDark => ColorScheme::mapbox_dark(),
-            ColorSchemeChoice::FadedZoom => ColorScheme::faded_zoom(),
-            ColorSchemeChoice::NegativeSpace => ColorScheme::negative_space(),
+        let name = match scheme.theme_name() {
+            Some(name) => name,
+            None => {
+                return ColorScheme::standard();
+            }
         };
-        cs.scheme = scheme;
-        cs
+        match ColorScheme::load_theme(name) {
+            Ok(mut cs) => {
+                // Custom themes keep their base scheme, for the special-cased rendering
+                if !matches!(scheme, ColorSchemeChoice::Theme(_)) {
+                    cs.scheme = scheme.clone();
+                }
+                cs
+            }
+            Err(err) => {
+                warn!("Couldn't load theme {}, using the default: {}", name, err);
+                ColorScheme::standard()
+            }
+        }
+    }
+
+    fn load_theme(name: &str) -> Result<ColorScheme, String> {
+        let theme: Theme = abstutil::maybe_read_json(
+            abstutil::path(format!("system/themes/{}.json", name)),
+            &mut Timer::throwaway(),
+        )
+        .map_err(|err| err.to_string())?;
+        if let ColorSchemeChoice::Theme(_) = theme.base {
+            return Err("themes can't be based on other themes".to_string());
+        }
+        if ColorSchemeChoice::is_built_in(name) && theme.base != ColorSchemeChoice::Standard {
+            return Err("built-in themes must be based on Standard".to_string());
+        }
+
+        // Map elements with special-cased rendering still follow the base scheme
+        let mut cs = ColorScheme::new(theme.base);
+        if let Some(mut style) = theme.gui_style {
+            style.loading_tips = loading_tips();
+            cs.hovering = style.hovering_color;
+            cs.panel_bg = style.panel_bg;
+            cs.gui_style = style;
+        }
+        for (key, color) in theme.colors {
+            cs.set_color(&key, color)?;
+        }
+        for (key, texture) in theme.textures {
+            let texture = match texture.as_ref() {
+                "grass" => Texture::GRASS,
+                "still_water" => Texture::STILL_WATER,
+                "running_water" => Texture::RUNNING_WATER,
+                "concrete" => Texture::CONCRETE,
+                "sand" => Texture::SAND,
+                "dirt" => Texture::DIRT,
+                "snow" => Texture::SNOW,
+                _ => {
+                    return Err(format!("unknown texture {}", texture));
+                }
+            };
+            match key.as_ref() {
+                "map_background" => cs.map_background = texture.into(),
+                "grass" => cs.grass = texture.into(),
+                "water" => cs.water = texture.into(),
+                _ => {
+                    return Err(format!("{} can't be textured", key));
+                }
+            }
+        }
+        if theme.hide_sidewalk_lines {
+            cs.sidewalk_lines = None;
+        }
+        if let Some(colors) = theme.agent_colors {
+            if colors.is_empty() {
+                return Err("agent_colors can't be empty".to_string());
+            }
+            cs.agent_colors = colors;
+        }
+        if let Some(scale) = theme.good_to_bad_red {
+            cs.good_to_bad_red = color_scale(scale)?;
+        }
+        if let Some(scale) = theme.good_to_bad_green {
+            cs.good_to_bad_green = color_scale(scale)?;
+        }
+        if let Some(gradient) = theme.heatmap {
+            if heatmap_gradient(&gradient).is_none() {
+                return Err(format!("unknown heatmap gradient {}", gradient));
+            }
+            cs.heatmap = gradient;
+        }
+        Ok(cs)
     }
 
     fn standard() -> ColorScheme {
@@ -251,6 +420,7 @@ impl ColorScheme {
             bus_body: Color::rgb(50, 133, 117),
             bus_label: Color::rgb(249, 206, 24),
             train_body: hex("#42B6E9"),
+            truck_body: hex("#8C5B3F"),
             ped_head: Color::rgb(139, 69, 19),
             ped_foot: Color::BLACK,
             ped_preparing_bike_body: Color::rgb(255, 0, 144),
@@ -261,6 +431,7 @@ impl ColorScheme {
             // Layers
             good_to_bad_red: ColorScale(vec![hex("#F19A93"), hex("#A32015")]),
             good_to_bad_green: ColorScale(vec![hex("#BEDB92"), hex("#397A4C")]),
+            heatmap: "Turbo".to_string(),
             bus_layer: hex("#4CA7E9"),
             edits_layer: hex("#12409D"),
 
@@ -319,7 +490,7 @@ impl ColorScheme {
             _ => match lane {
                 LaneType::Driving => self.driving_lane,
                 LaneType::Bus => self.bus_lane,
-                LaneType::Parking => self.parking_lane,
+                LaneType::Parking | LaneType::LoadingZone => self.parking_lane,
                 LaneType::Sidewalk | LaneType::Shoulder => self.sidewalk,
                 LaneType::Biking => self.bike_lane,
                 LaneType::SharedLeftTurn => self.driving_lane,
@@ -362,6 +533,88 @@ impl ColorScheme {
     }
 }
 
+impl ColorScheme {
+    overridable_colors!(
+        hovering,
+        panel_bg,
+        section_bg,
+        inner_panel,
+        day_time_slider,
+        night_time_slider,
+        selected,
+        current_object,
+        perma_selected_object,
+        bottom_bar_id,
+        bottom_bar_name,
+        fade_map_dark,
+        dialog_bg,
+        driving_lane,
+        bus_lane,
+        parking_lane,
+        bike_lane,
+        sidewalk,
+        general_road_marking,
+        road_center_line,
+        light_rail_track,
+        private_road,
+        unzoomed_highway,
+        unzoomed_arterial,
+        unzoomed_residential,
+        normal_intersection,
+        stop_sign,
+        stop_sign_pole,
+        signal_protected_turn,
+        signal_permitted_turn,
+        signal_banned_turn,
+        signal_box,
+        signal_spinner,
+        signal_turn_block_bg,
+        very_slow_intersection,
+        slow_intersection,
+        normal_slow_intersection,
+        void_background,
+        map_background,
+        unzoomed_interesting_intersection,
+        residential_building,
+        commerical_building,
+        building_outline,
+        parking_lot,
+        grass,
+        water,
+        unzoomed_car,
+        unzoomed_bike,
+        unzoomed_bus,
+        unzoomed_pedestrian,
+        route,
+        turn_arrow,
+        brake_light,
+        bus_body,
+        bus_label,
+        train_body,
+        truck_body,
+        ped_head,
+        ped_foot,
+        ped_preparing_bike_body,
+        ped_crowd,
+        bike_frame,
+        parked_car,
+        bus_layer,
+        edits_layer,
+        parking_trip,
+        bike_trip,
+        bus_trip,
+        before_changes,
+        after_changes
+    );
+}
+
+fn color_scale(colors: Vec<Color>) -> Result<ColorScale, String> {
+    if colors.len() < 2 {
+        return Err("color scales need at least two colors".to_string());
+    }
+    Ok(ColorScale(colors))
+}
+
 fn modulo_color(colors: &Vec<Color>, idx: usize) -> Color {
     colors[idx % colors.len()]
 }
@@ -371,180 +624,16 @@ fn hex(x: &str) -> Color {
     Color::hex(x)
 }
 
-// Alternate, in-progress schemes
-impl ColorScheme {
-    fn night_mode() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        cs.residential_building = hex("#42208B");
-        cs.sidewalk = hex("#7C55C8");
-        cs.grass = hex("#063D88").into();
-        cs.dialog_bg = hex("#063D88");
-        cs.map_background = hex("#070747").into();
-        cs.unzoomed_arterial = hex("#54247A");
-        cs.unzoomed_highway = hex("#DD1F7F");
-        cs.unzoomed_residential = hex("#4D51AC");
-        cs.water = hex("#2A43AA").into();
-        // Horrible choice, but demonstrate it can be done.
-        cs.panel_bg = Color::PURPLE;
-        cs.gui_style.panel_bg = Color::PURPLE;
-        cs
-    }
-
-    fn sam_green_day() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        cs.map_background = hex("#CFE2C4").into();
-        cs.water = hex("#B4D3E5").into();
-        cs.driving_lane = hex("#C6CDD5");
-        cs.residential_building = hex("#CCD4BD");
-        cs.sidewalk = hex("#98A1AA");
-        cs
-    }
-
-    fn sam_desert_day() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        cs.map_background = hex("#FEE4D7").into();
-        cs.grass = hex("#F6C6AF").into();
-        cs.dialog_bg = hex("#F6C6AF");
-        cs.driving_lane = hex("#BECBD3");
-        cs.residential_building = hex("#DEAA95");
-        cs.sidewalk = hex("#8B9EA8");
-        cs
-    }
-
-    fn bap() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        cs.agent_colors = vec![
-            /*hex("#DD5444"),
-            hex("#C23E46"),
-            hex("#821B38"),
-            hex("#BC3101"),*/
-            hex("#F44273"),
-            hex("#B53A7E"),
-            hex("#FF616E"),
-            hex("#FA8D37"),
-        ];
-        cs.grass = hex("#84BA3B").into(); // #2F8C2C
-        cs.dialog_bg = hex("#84BA3B");
-        cs.residential_building = hex("#367335"); // #194C18
-        cs.normal_intersection = hex("#4B5485");
-        cs.driving_lane = hex("#384173");
-        cs.parking_lane = hex("#4B5485");
-        cs.sidewalk = hex("#89ABD9");
-        cs.sidewalk_lines = Some(hex("#4B5485"));
-        cs.general_road_marking = hex("#89ABD9");
-        cs.map_background = hex("#589D54").into(); // #153F14
-        cs.ped_crowd = hex("#DD5444");
-        cs.road_center_line = hex("#BCFF00");
-        cs
-    }
-
-    fn osm() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        // TODO normal_intersection, driving_lane, parking_lane depends on osm rank
-        cs.general_road_marking = Color::BLACK;
-        cs.road_center_line = Color::rgb(202, 177, 39);
-        cs
-    }
-
-    fn starcat() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        cs.grass = hex("#3F8C0C").into();
-        cs.dialog_bg = hex("#3F8C0C");
-        cs.residential_building = hex("#8099A8"); // #5E7486
-        cs.map_background = hex("#737373").into();
-        cs.driving_lane = hex("#2A2A2A"); // TODO for arterial
-        cs.road_center_line = hex("#DB952E");
-        cs.general_road_marking = hex("#D6D6D6");
-        cs.sidewalk = cs.general_road_marking;
-        cs.sidewalk_lines = Some(hex("#707070"));
-        cs.bike_lane = hex("#72CE36");
-        cs.bus_lane = hex("#AD302D");
-        cs
-    }
-
-    fn textured() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        cs.grass = Texture::GRASS.into();
-        cs.water = Texture::STILL_WATER.into();
-        cs.map_background = Texture::CONCRETE.into();
-        cs
-    }
-
-    fn mapbox_light() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        cs.map_background = hex("#F2F3F1").into();
-        cs.unzoomed_highway = Color::WHITE;
-        cs.unzoomed_arterial = Color::WHITE;
-        cs.unzoomed_residential = Color::WHITE;
-        cs.grass = hex("#ECEEED").into();
-        cs.water = hex("#CAD2D3").into();
-        cs.residential_building = hex("#E9E9E7").into();
-        cs.commerical_building = hex("#E9E9E7").into();
-        cs
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    fn mapbox_dark() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        cs.map_background = hex("#343332").into();
-        let road = hex("#454545");
-        cs.unzoomed_highway = road;
-        cs.unzoomed_arterial = road;
-        cs.unzoomed_residential = road;
-        cs.grass = hex("#323432").into();
-        cs.water = hex("#181919").into();
-        cs.residential_building = hex("#2C2C2B").into();
-        cs.commerical_building = hex("#2C2C2B").into();
-
-        // TODO Things like this could be refactored in zoomed_road_surface
-        cs.driving_lane = road;
-        cs.parking_lane = road;
-        cs.bike_lane = road;
-        cs.bus_lane = road;
-        cs.sidewalk = Color::grey(0.3);
-        cs.sidewalk_lines = Some(road);
-        cs.normal_intersection = road;
-        cs.general_road_marking = cs.building_outline;
-        cs.road_center_line = cs.general_road_marking;
-        cs.stop_sign = Color::rgb_f(0.67, 0.55, 0.55);
-
-        cs
-    }
-
-    fn faded_zoom() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        cs.unzoomed_highway = hex("#F89E59");
-        cs.unzoomed_arterial = hex("#F2D163");
-        cs.unzoomed_residential = hex("#FFFFFF");
-        cs.sidewalk_lines = None;
-
-        cs.map_background = hex("#E5E4E1").into();
-        cs.grass = hex("#B6E59E").into();
-        cs.water = hex("#75CFF0").into();
-
-        cs.residential_building = hex("#DCD9D6");
-        cs.commerical_building = cs.residential_building;
-
-        cs
-    }
-
-    fn negative_space() -> ColorScheme {
-        let mut cs = ColorScheme::standard();
-        let nonempty_space = Color::BLACK;
-        cs.map_background = Color::WHITE.into();
-        cs.residential_building = nonempty_space;
-        cs.commerical_building = nonempty_space;
-        cs.building_outline = nonempty_space;
-        cs.normal_intersection = nonempty_space;
-        cs.general_road_marking = nonempty_space;
-        cs.road_center_line = nonempty_space;
-        cs.stop_sign = nonempty_space;
-        cs.stop_sign_pole = nonempty_space;
-        cs.sidewalk_lines = Some(nonempty_space);
-        cs.parking_lot = nonempty_space;
-        cs.grass = nonempty_space.into();
-        cs.water = nonempty_space.into();
-        // TODO Why is this showing up?!
-        cs.light_rail_track = Color::INVISIBLE;
-        cs
+    #[test]
+    fn themes_load() {
+        for name in abstutil::list_all_objects(abstutil::path("system/themes")) {
+            if let Err(err) = ColorScheme::load_theme(&name) {
+                panic!("Theme {} is broken: {}", name, err);
+            }
+        }
     }
 }
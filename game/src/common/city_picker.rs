@@ -1,9 +1,8 @@
-use abstutil::prettyprint_usize;
 use geom::{Distance, Percent, Polygon, Pt2D};
 use map_model::City;
 use widgetry::{
-    Autocomplete, Btn, Color, DrawBaselayer, EventCtx, GeomBatch, GfxCtx, Line, Outcome, Panel,
-    ScreenPt, State, Text, TextExt, Widget,
+    tr_n, Autocomplete, Btn, Color, DrawBaselayer, EventCtx, GeomBatch, GfxCtx, Line, Outcome,
+    Panel, ScreenPt, State, Text, TextExt, Widget,
 };
 
 use crate::app::App;
@@ -85,7 +84,7 @@ impl CityPicker {
         }
         if more_cities > 0 {
             other_cities.push(
-                Btn::text_bg2(format!("{} more cities", prettyprint_usize(more_cities))).build(
+                Btn::text_bg2(tr_n("{} more city", "{} more cities", more_cities)).build(
                     ctx,
                     "more cities",
                     None,
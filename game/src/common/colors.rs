@@ -6,14 +6,16 @@ use map_model::{BuildingID, BusStopID, IntersectionID, LaneID, Map, ParkingLotID
 use widgetry::{Color, Drawable, EventCtx, Fill, GeomBatch, Line, LinearGradient, Text, Widget};
 
 use crate::app::App;
+use crate::common::{ExportData, ExportObject};
 
 pub struct ColorDiscrete<'a> {
     map: &'a Map,
-    unzoomed: GeomBatch,
+    pub unzoomed: GeomBatch,
     zoomed: GeomBatch,
     // Store both, so we can build the legend in the original order later
     categories: Vec<(&'static str, Color)>,
     colors: HashMap<&'static str, Color>,
+    export: ExportData,
 }
 
 impl<'a> ColorDiscrete<'a> {
@@ -29,11 +31,14 @@ impl<'a> ColorDiscrete<'a> {
             zoomed: GeomBatch::new(),
             colors: categories.iter().cloned().collect(),
             categories,
+            export: ExportData::new(vec!["category"]),
         }
     }
 
     pub fn add_l(&mut self, l: LaneID, category: &'static str) {
         let color = self.colors[category];
+        self.export
+            .add(ExportObject::Lane(l), vec![category.to_string()]);
         self.unzoomed
             .push(color, self.map.get_parent(l).get_thick_polygon(self.map));
         let lane = self.map.get_l(l);
@@ -45,6 +50,8 @@ impl<'a> ColorDiscrete<'a> {
 
     pub fn add_r(&mut self, r: RoadID, category: &'static str) {
         let color = self.colors[category];
+        self.export
+            .add(ExportObject::Road(r), vec![category.to_string()]);
         self.unzoomed
             .push(color, self.map.get_r(r).get_thick_polygon(self.map));
         self.zoomed.push(
@@ -55,6 +62,8 @@ impl<'a> ColorDiscrete<'a> {
 
     pub fn add_i(&mut self, i: IntersectionID, category: &'static str) {
         let color = self.colors[category];
+        self.export
+            .add(ExportObject::Intersection(i), vec![category.to_string()]);
         self.unzoomed.push(color, self.map.get_i(i).polygon.clone());
         self.zoomed
             .push(color.alpha(0.4), self.map.get_i(i).polygon.clone());
@@ -62,6 +71,8 @@ impl<'a> ColorDiscrete<'a> {
 
     pub fn add_b(&mut self, b: BuildingID, category: &'static str) {
         let color = self.colors[category];
+        self.export
+            .add(ExportObject::Building(b), vec![category.to_string()]);
         self.unzoomed.push(color, self.map.get_b(b).polygon.clone());
         self.zoomed
             .push(color.alpha(0.4), self.map.get_b(b).polygon.clone());
@@ -69,6 +80,8 @@ impl<'a> ColorDiscrete<'a> {
 
     pub fn add_bs(&mut self, bs: BusStopID, category: &'static str) {
         let color = self.colors[category];
+        self.export
+            .add(ExportObject::BusStop(bs), vec![category.to_string()]);
         let pt = self.map.get_bs(bs).sidewalk_pos.pt(self.map);
         self.zoomed.push(
             color.alpha(0.4),
@@ -78,6 +91,11 @@ impl<'a> ColorDiscrete<'a> {
             .push(color, Circle::new(pt, Distance::meters(15.0)).to_polygon());
     }
 
+    /// Everything added so far and its category. Call before `build`.
+    pub fn take_export(&mut self) -> ExportData {
+        std::mem::replace(&mut self.export, ExportData::new(vec!["category"]))
+    }
+
     pub fn build(self, ctx: &mut EventCtx) -> (Drawable, Drawable, Widget) {
         let legend = self
             .categories
@@ -0,0 +1,240 @@
+//! The values behind layers and dashboards, exported to CSV and GeoJSON for reports and other
+//! tools. Geometry is converted to longitude/latitude using the map's `GPSBounds`.
+
+use geojson::{Feature, FeatureCollection, GeoJson, Geometry, Value};
+use serde_json::Map as JsonMap;
+
+use abstutil::{csv_escape, Counter};
+use geom::{GPSBounds, Polygon, Pt2D};
+use map_model::{
+    BuildingID, BusRouteID, BusStopID, IntersectionID, LaneID, Map, ParkingLotID, RoadID,
+};
+use sim::{TripEndpoint, TripID};
+
+use crate::app::App;
+
+/// What a row describes. This determines the geometry written for it.
+pub enum ExportObject {
+    Road(RoadID),
+    Lane(LaneID),
+    Intersection(IntersectionID),
+    Building(BuildingID),
+    ParkingLot(ParkingLotID),
+    BusStop(BusStopID),
+    /// A line connecting each stop
+    BusRoute(BusRouteID),
+    /// A straight line from the trip's start to its end
+    Trip(TripID),
+    /// Something without an ID, like a cluster of intersections
+    Area(Polygon),
+    Point(Pt2D),
+}
+
+/// A table of values, one row per map object or trip.
+pub struct ExportData {
+    columns: Vec<String>,
+    rows: Vec<(ExportObject, Vec<String>)>,
+}
+
+impl ExportData {
+    pub fn new(columns: Vec<&str>) -> ExportData {
+        ExportData {
+            columns: columns.into_iter().map(|x| x.to_string()).collect(),
+            rows: Vec::new(),
+        }
+    }
+
+    /// A single "count" column for roads and intersections, like most layers show.
+    pub fn counts(roads: &Counter<RoadID>, intersections: &Counter<IntersectionID>) -> ExportData {
+        let mut data = ExportData::new(vec!["count"]);
+        for (r, cnt) in roads.borrow() {
+            data.add(ExportObject::Road(*r), vec![cnt.to_string()]);
+        }
+        for (i, cnt) in intersections.borrow() {
+            data.add(ExportObject::Intersection(*i), vec![cnt.to_string()]);
+        }
+        data
+    }
+
+    pub fn add(&mut self, obj: ExportObject, values: Vec<String>) {
+        assert_eq!(values.len(), self.columns.len());
+        self.rows.push((obj, values));
+    }
+
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// Each row has the object's type, ID, and the longitude/latitude of its center, then the
+    /// values.
+    pub fn to_csv(&self, app: &App) -> String {
+        let gps_bounds = app.primary.map.get_gps_bounds();
+        let mut header = vec![
+            "type".to_string(),
+            "id".to_string(),
+            "longitude".to_string(),
+            "latitude".to_string(),
+        ];
+        header.extend(self.columns.iter().map(|x| csv_escape(x)));
+        let mut out = header.join(",");
+        out.push('\n');
+        for (obj, values) in &self.rows {
+            let (kind, id) = describe(obj);
+            let gps = center(obj, app).to_gps(gps_bounds);
+            let mut row = vec![
+                kind.to_string(),
+                csv_escape(&id),
+                gps.x().to_string(),
+                gps.y().to_string(),
+            ];
+            row.extend(values.iter().map(|x| csv_escape(x)));
+            out.push_str(&row.join(","));
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn to_geojson(&self, app: &App) -> String {
+        let gps_bounds = app.primary.map.get_gps_bounds();
+        let mut features = Vec::new();
+        for (obj, values) in &self.rows {
+            let (kind, id) = describe(obj);
+            let mut properties = JsonMap::new();
+            properties.insert("type".to_string(), kind.into());
+            properties.insert("id".to_string(), id.into());
+            for (col, value) in self.columns.iter().zip(values) {
+                // Keep numbers as numbers, so other tools can style by them
+                let value = match value.parse::<f64>() {
+                    Ok(x) if x.is_finite() => x.into(),
+                    _ => value.clone().into(),
+                };
+                properties.insert(col.clone(), value);
+            }
+            features.push(Feature {
+                bbox: None,
+                geometry: Some(Geometry::new(geometry(obj, app, gps_bounds))),
+                id: None,
+                properties: Some(properties),
+                foreign_members: None,
+            });
+        }
+        GeoJson::from(FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members: None,
+        })
+        .to_string()
+    }
+
+    /// Writes `{name}_{map}_{time}.csv` and `.geojson` to the current directory, returning both
+    /// paths.
+    pub fn save(&self, app: &App, name: &str) -> Result<Vec<String>, String> {
+        let base = format!(
+            "{}_{}_{}",
+            name.replace(' ', "_"),
+            app.primary.map.get_name(),
+            app.primary.sim.time().as_filename()
+        );
+        let csv_path = format!("{}.csv", base);
+        let geojson_path = format!("{}.geojson", base);
+        std::fs::write(&csv_path, self.to_csv(app))
+            .map_err(|err| format!("Couldn't write {}: {}", csv_path, err))?;
+        std::fs::write(&geojson_path, self.to_geojson(app))
+            .map_err(|err| format!("Couldn't write {}: {}", geojson_path, err))?;
+        Ok(vec![csv_path, geojson_path])
+    }
+}
+
+fn describe(obj: &ExportObject) -> (&'static str, String) {
+    match obj {
+        ExportObject::Road(r) => ("road", r.0.to_string()),
+        ExportObject::Lane(l) => ("lane", l.0.to_string()),
+        ExportObject::Intersection(i) => ("intersection", i.0.to_string()),
+        ExportObject::Building(b) => ("building", b.0.to_string()),
+        ExportObject::ParkingLot(pl) => ("parking lot", pl.0.to_string()),
+        ExportObject::BusStop(bs) => ("bus stop", bs.to_string()),
+        ExportObject::BusRoute(br) => ("bus route", br.0.to_string()),
+        ExportObject::Trip(t) => ("trip", t.0.to_string()),
+        ExportObject::Area(_) => ("area", String::new()),
+        ExportObject::Point(_) => ("point", String::new()),
+    }
+}
+
+fn endpoint(map: &Map, endpt: TripEndpoint) -> Pt2D {
+    match endpt {
+        TripEndpoint::Bldg(b) => map.get_b(b).label_center,
+        TripEndpoint::Border(i, _) => map.get_i(i).polygon.center(),
+    }
+}
+
+fn route_pts(map: &Map, br: BusRouteID) -> Vec<Pt2D> {
+    map.get_br(br)
+        .stops
+        .iter()
+        .map(|bs| map.get_bs(*bs).sidewalk_pos.pt(map))
+        .collect()
+}
+
+fn center(obj: &ExportObject, app: &App) -> Pt2D {
+    let map = &app.primary.map;
+    match obj {
+        ExportObject::Road(r) => map.get_r(*r).center_pts.middle(),
+        ExportObject::Lane(l) => map.get_l(*l).lane_center_pts.middle(),
+        ExportObject::Intersection(i) => map.get_i(*i).polygon.center(),
+        ExportObject::Building(b) => map.get_b(*b).label_center,
+        ExportObject::ParkingLot(pl) => map.get_pl(*pl).polygon.center(),
+        ExportObject::BusStop(bs) => map.get_bs(*bs).sidewalk_pos.pt(map),
+        ExportObject::BusRoute(br) => Pt2D::center(&route_pts(map, *br)),
+        ExportObject::Trip(t) => {
+            let trip = app.primary.sim.trip_info(*t);
+            Pt2D::center(&vec![endpoint(map, trip.start), endpoint(map, trip.end)])
+        }
+        ExportObject::Area(poly) => poly.center(),
+        ExportObject::Point(pt) => *pt,
+    }
+}
+
+fn geometry(obj: &ExportObject, app: &App, gps_bounds: &GPSBounds) -> Value {
+    let map = &app.primary.map;
+    let line = |pts: &Vec<Pt2D>| -> Value {
+        Value::LineString(pts.iter().map(|pt| lon_lat(*pt, gps_bounds)).collect())
+    };
+    match obj {
+        ExportObject::Road(r) => line(map.get_r(*r).center_pts.points()),
+        ExportObject::Lane(l) => line(map.get_l(*l).lane_center_pts.points()),
+        ExportObject::Intersection(i) => polygon(&map.get_i(*i).polygon, gps_bounds),
+        ExportObject::Building(b) => polygon(&map.get_b(*b).polygon, gps_bounds),
+        ExportObject::ParkingLot(pl) => polygon(&map.get_pl(*pl).polygon, gps_bounds),
+        ExportObject::Area(poly) => polygon(poly, gps_bounds),
+        ExportObject::BusRoute(br) => line(&route_pts(map, *br)),
+        ExportObject::Trip(t) => {
+            let trip = app.primary.sim.trip_info(*t);
+            line(&vec![endpoint(map, trip.start), endpoint(map, trip.end)])
+        }
+        ExportObject::BusStop(_) | ExportObject::Point(_) => {
+            Value::Point(lon_lat(center(obj, app), gps_bounds))
+        }
+    }
+}
+
+fn lon_lat(pt: Pt2D, gps_bounds: &GPSBounds) -> Vec<f64> {
+    let gps = pt.to_gps(gps_bounds);
+    vec![gps.x(), gps.y()]
+}
+
+// Just the outer ring
+fn polygon(poly: &Polygon, gps_bounds: &GPSBounds) -> Value {
+    let mut ring: Vec<Vec<f64>> = poly
+        .points()
+        .iter()
+        .map(|pt| lon_lat(*pt, gps_bounds))
+        .collect();
+    if ring.first() != ring.last() {
+        ring.push(ring[0].clone());
+    }
+    Value::Polygon(vec![ring])
+}
@@ -1,6 +1,7 @@
 use geom::{Bounds, Histogram, Polygon, Pt2D, Statistic};
 use widgetry::{Checkbox, Choice, Color, EventCtx, GeomBatch, Panel, Spinner, TextExt, Widget};
 
+use crate::app::App;
 use crate::common::{ColorLegend, ColorScale};
 
 const NEIGHBORS: [[isize; 2]; 9] = [
@@ -25,14 +26,34 @@ pub struct HeatmapOptions {
     color_scheme: String,
 }
 
+const GRADIENTS: [&str; 8] = [
+    "Turbo", "Inferno", "Warm", "Cool", "Oranges", "Spectral", "Viridis", "Cividis",
+];
+
+/// Looks up one of the gradients offered for heatmaps by name.
+pub fn heatmap_gradient(name: &str) -> Option<colorous::Gradient> {
+    match name {
+        "Turbo" => Some(colorous::TURBO),
+        "Inferno" => Some(colorous::INFERNO),
+        "Warm" => Some(colorous::WARM),
+        "Cool" => Some(colorous::COOL),
+        "Oranges" => Some(colorous::ORANGES),
+        "Spectral" => Some(colorous::SPECTRAL),
+        "Viridis" => Some(colorous::VIRIDIS),
+        "Cividis" => Some(colorous::CIVIDIS),
+        _ => None,
+    }
+}
+
 impl HeatmapOptions {
-    pub fn new() -> HeatmapOptions {
+    /// Starts with the heatmap gradient from the current color scheme.
+    pub fn new(app: &App) -> HeatmapOptions {
         HeatmapOptions {
             resolution: 10,
             radius: 3,
             smoothing: true,
             contours: true,
-            color_scheme: "Turbo".to_string(),
+            color_scheme: app.cs.heatmap.clone(),
         }
     }
 
@@ -61,17 +82,14 @@ impl HeatmapOptions {
                     ctx,
                     "Color scheme",
                     self.color_scheme.clone(),
-                    vec!["Turbo", "Inferno", "Warm", "Cool", "Oranges", "Spectral"]
-                        .into_iter()
-                        .map(|x| Choice::string(x))
-                        .collect(),
+                    GRADIENTS.iter().map(|x| Choice::string(x)).collect(),
                 ),
             ]),
             legend,
         ]
     }
 
-    pub fn from_controls(c: &Panel) -> HeatmapOptions {
+    pub fn from_controls(c: &Panel, app: &App) -> HeatmapOptions {
         // Did we just change?
         if c.has_widget("resolution") {
             HeatmapOptions {
@@ -82,7 +100,7 @@ impl HeatmapOptions {
                 color_scheme: c.dropdown_value("Color scheme"),
             }
         } else {
-            HeatmapOptions::new()
+            HeatmapOptions::new(app)
         }
     }
 }
@@ -97,15 +115,7 @@ pub fn make_heatmap(
 ) -> Widget {
     // 7 colors, 8 labels
     let num_colors = 7;
-    let gradient = match opts.color_scheme.as_ref() {
-        "Turbo" => colorous::TURBO,
-        "Inferno" => colorous::INFERNO,
-        "Warm" => colorous::WARM,
-        "Cool" => colorous::COOL,
-        "Oranges" => colorous::ORANGES,
-        "Spectral" => colorous::SPECTRAL,
-        _ => unreachable!(),
-    };
+    let gradient = heatmap_gradient(&opts.color_scheme).unwrap();
     let colors: Vec<Color> = (0..num_colors)
         .map(|i| {
             let c = gradient.eval_rational(i, num_colors);
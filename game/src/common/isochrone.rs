@@ -1,40 +1,121 @@
-use geom::{Distance, Polygon};
-use map_model::{connectivity, BuildingID};
+//! Shows how far somebody can get from one or more buildings within some amount of time, by
+//! walking, biking, driving, or walking and riding transit.
+
+use std::collections::HashMap;
+
+use geom::{Duration, Polygon};
+use map_model::{connectivity, BuildingID, PathConstraints};
+use sim::TripMode;
 use widgetry::{
-    Btn, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Line, Outcome, Panel,
-    State, VerticalAlignment, Widget,
+    tr_n, Btn, Checkbox, Choice, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment,
+    Line, Outcome, Panel, State, Text, TextExt, VerticalAlignment, Widget,
 };
 
 use crate::app::App;
 use crate::common::heatmap::Grid;
+use crate::common::{ColorLegend, ColorScale};
 use crate::game::Transition;
+use crate::helpers::ID;
 
-// TODO Move cursor live
 pub struct IsochroneViewer {
     panel: Panel,
     draw: Drawable,
+    starts: Vec<BuildingID>,
+    opts: Options,
+    /// Where buildings connect to the network for the current mode. Building this is expensive,
+    /// so keep it while the start points move around.
+    targets: Option<(PathConstraints, connectivity::BuildingTargets)>,
+}
+
+#[derive(Clone)]
+struct Options {
+    mode: TripMode,
+    step_minutes: usize,
+    max_minutes: usize,
+    follow_cursor: bool,
 }
 
 impl IsochroneViewer {
     pub fn new(ctx: &mut EventCtx, app: &App, start: BuildingID) -> Box<dyn State<App>> {
-        let draw = make_isochrone(ctx, app, start);
-        Box::new(IsochroneViewer {
-            panel: Panel::new(Widget::col(vec![
-                Widget::row(vec![
-                    Line("Isochrone").small_heading().draw(ctx),
-                    Btn::close(ctx),
-                ]),
-                // TODO legend, mode picker
-            ]))
-            .aligned(HorizontalAlignment::Center, VerticalAlignment::Top)
-            .build(ctx),
-            draw,
-        })
+        let mut viewer = IsochroneViewer {
+            panel: Panel::empty(ctx),
+            draw: ctx.upload(GeomBatch::new()),
+            starts: vec![start],
+            opts: Options {
+                mode: TripMode::Walk,
+                step_minutes: 5,
+                max_minutes: 15,
+                follow_cursor: false,
+            },
+            targets: None,
+        };
+        viewer.recalculate(ctx, app);
+        Box::new(viewer)
+    }
+
+    fn recalculate(&mut self, ctx: &mut EventCtx, app: &App) {
+        self.redraw_isochrone(ctx, app);
+        let thresholds = self.opts.thresholds();
+        let colors = colors(thresholds.len());
+        self.panel = make_panel(
+            ctx,
+            app,
+            &self.opts,
+            self.starts.len(),
+            &thresholds,
+            &colors,
+        );
+    }
+
+    /// Only the isochrone depends on the start points; the panel doesn't need to change.
+    fn redraw_isochrone(&mut self, ctx: &mut EventCtx, app: &App) {
+        let thresholds = self.opts.thresholds();
+        let colors = colors(thresholds.len());
+        let time_limit = *thresholds.last().unwrap();
+        let costs = match constraints(self.opts.mode) {
+            Some(constraints) => {
+                if self.targets.as_ref().map(|(c, _)| *c) != Some(constraints) {
+                    self.targets = Some((
+                        constraints,
+                        connectivity::BuildingTargets::new(&app.primary.map, constraints),
+                    ));
+                }
+                let targets = &self.targets.as_ref().unwrap().1;
+                connectivity::nearest_n_from(
+                    &app.primary.map,
+                    targets,
+                    self.starts.clone(),
+                    1,
+                    time_limit,
+                )
+                .into_iter()
+                .map(|(b, list)| (b, list[0].1))
+                .collect()
+            }
+            None => connectivity::all_costs_by_transit(
+                &app.primary.map,
+                self.starts.clone(),
+                app.primary.sim.time(),
+                time_limit,
+            ),
+        };
+        self.draw = make_isochrone(ctx, app, &self.starts, costs, &thresholds, &colors);
+    }
+}
+
+impl Options {
+    /// Each isochrone is everything reachable within one of these times. The last is always the
+    /// maximum, even if the step doesn't evenly divide it.
+    fn thresholds(&self) -> Vec<Duration> {
+        thresholds(self.step_minutes, self.max_minutes)
+            .into_iter()
+            .map(Duration::minutes)
+            .collect()
     }
 }
 
 impl State<App> for IsochroneViewer {
-    fn event(&mut self, ctx: &mut EventCtx, _: &mut App) -> Transition {
+    fn event(&mut self, ctx: &mut EventCtx, app: &mut App) -> Transition {
         ctx.canvas_movement();
 
         match self.panel.event(ctx) {
@@ -42,11 +123,47 @@ impl State<App> for IsochroneViewer {
                 "close" => {
                     return Transition::Pop;
                 }
+                "reset start points" => {
+                    self.starts.truncate(1);
+                    self.recalculate(ctx, app);
+                }
                 _ => unreachable!(),
             },
+            Outcome::Changed => {
+                self.opts = Options {
+                    mode: self.panel.dropdown_value("mode"),
+                    step_minutes: self.panel.dropdown_value("step"),
+                    max_minutes: self.panel.dropdown_value("max"),
+                    follow_cursor: self.panel.is_checked("move the start with the cursor"),
+                };
+                self.recalculate(ctx, app);
+            }
             _ => {}
         }
 
+        if ctx.redo_mouseover() {
+            app.primary.current_selection = match app.mouseover_unzoomed_everything(ctx) {
+                Some(ID::Building(b)) => Some(ID::Building(b)),
+                _ => None,
+            };
+            if self.opts.follow_cursor {
+                if let Some(ID::Building(b)) = app.primary.current_selection {
+                    if self.starts.last() != Some(&b) {
+                        *self.starts.last_mut().unwrap() = b;
+                        self.redraw_isochrone(ctx, app);
+                    }
+                }
+            }
+        }
+        if !self.opts.follow_cursor {
+            if let Some(ID::Building(b)) = app.primary.current_selection {
+                if !self.starts.contains(&b) && app.per_obj.left_click(ctx, "add a start point") {
+                    self.starts.push(b);
+                    self.recalculate(ctx, app);
+                }
+            }
+        }
+
         Transition::Keep
     }
 
@@ -54,57 +171,209 @@ impl State<App> for IsochroneViewer {
         g.redraw(&self.draw);
         self.panel.draw(g);
     }
+
+    fn on_destroy(&mut self, _: &mut EventCtx, app: &mut App) {
+        app.primary.current_selection = None;
+    }
+}
+
+/// Steps of `step` minutes, ending with `max`
+fn thresholds(step: usize, max: usize) -> Vec<usize> {
+    let mut list: Vec<usize> = (1..).map(|i| i * step).take_while(|m| *m < max).collect();
+    list.push(max);
+    list
 }
 
-fn make_isochrone(ctx: &mut EventCtx, app: &App, start: BuildingID) -> Drawable {
+/// How to flood the network for one mode. Transit isn't a single mode, so it's handled separately.
+fn constraints(mode: TripMode) -> Option<PathConstraints> {
+    match mode {
+        TripMode::Walk => Some(PathConstraints::Pedestrian),
+        TripMode::Bike => Some(PathConstraints::Bike),
+        TripMode::Drive => Some(PathConstraints::Car),
+        TripMode::Transit => None,
+    }
+}
+
+/// `costs` is the time to reach each building from the closest start.
+fn make_isochrone(
+    ctx: &mut EventCtx,
+    app: &App,
+    starts: &[BuildingID],
+    costs: HashMap<BuildingID, Duration>,
+    thresholds: &[Duration],
+    colors: &[Color],
+) -> Drawable {
     let bounds = app.primary.map.get_bounds();
     let resolution_m = 100.0;
-    // Distance in meters
+    let time_limit = *thresholds.last().unwrap();
+    // How much sooner than the time limit each cell can be reached, in seconds. 0 means it can't be
+    // reached at all.
     let mut grid: Grid<f64> = Grid::new(
         (bounds.width() / resolution_m).ceil() as usize,
         (bounds.height() / resolution_m).ceil() as usize,
         0.0,
     );
 
-    for (b, cost) in connectivity::all_costs_from(&app.primary.map, start) {
+    for (b, cost) in costs {
         let pt = app.primary.map.get_b(b).polygon.center();
         let idx = grid.idx(
             ((pt.x() - bounds.min_x) / resolution_m) as usize,
             ((pt.y() - bounds.min_y) / resolution_m) as usize,
         );
-        // Don't add! If two buildings map to the same cell, should pick a finer resolution.
-        grid.data[idx] = cost.inner_meters();
+        // If two buildings map to the same cell, keep the closer one. A finer resolution would be
+        // better.
+        let value = (time_limit - cost).inner_seconds() + 1.0;
+        grid.data[idx] = grid.data[idx].max(value);
     }
 
-    let thresholds = vec![
-        0.1,
-        Distance::miles(0.5).inner_meters(),
-        Distance::miles(3.0).inner_meters(),
-        Distance::miles(6.0).inner_meters(),
-    ];
-    let colors = vec![
-        Color::BLACK.alpha(0.5),
-        Color::GREEN.alpha(0.5),
-        Color::BLUE.alpha(0.5),
-        Color::RED.alpha(0.5),
-    ];
+    // Draw the farthest isochrone first, so closer ones are on top
+    let contour_thresholds: Vec<f64> = thresholds
+        .iter()
+        .map(|t| (time_limit - *t).inner_seconds() + 1.0)
+        .collect();
     let c = contour::ContourBuilder::new(grid.width as u32, grid.height as u32, false);
     let mut batch = GeomBatch::new();
     for (feature, color) in c
-        .contours(&grid.data, &thresholds)
+        .contours(&grid.data, &contour_thresholds)
         .unwrap()
         .into_iter()
-        .zip(colors)
+        .zip(colors.iter())
+        .rev()
     {
         match feature.geometry.unwrap().value {
             geojson::Value::MultiPolygon(polygons) => {
                 for p in polygons {
-                    batch.push(color, Polygon::from_geojson(&p).scale(resolution_m));
+                    batch.push(
+                        color.alpha(0.5),
+                        Polygon::from_geojson(&p).scale(resolution_m),
+                    );
                 }
             }
             _ => unreachable!(),
         }
     }
+    for b in starts {
+        batch.push(Color::BLACK, app.primary.map.get_b(*b).polygon.clone());
+    }
 
     batch.upload(ctx)
 }
+
+/// Closer isochrones are green, farther ones red
+fn colors(n: usize) -> Vec<Color> {
+    let scale = ColorScale(vec![Color::GREEN, Color::YELLOW, Color::RED]);
+    (0..n)
+        .map(|i| {
+            if n == 1 {
+                scale.eval(0.0)
+            } else {
+                scale.eval((i as f64) / ((n - 1) as f64))
+            }
+        })
+        .collect()
+}
+
+fn make_panel(
+    ctx: &mut EventCtx,
+    app: &App,
+    opts: &Options,
+    num_starts: usize,
+    thresholds: &[Duration],
+    colors: &[Color],
+) -> Panel {
+    let mut col = vec![
+        Widget::row(vec![
+            Line("Isochrone").small_heading().draw(ctx),
+            Btn::close(ctx),
+        ]),
+        Widget::row(vec![
+            "By".draw_text(ctx),
+            Widget::dropdown(
+                ctx,
+                "mode",
+                opts.mode,
+                TripMode::all()
+                    .into_iter()
+                    .map(|m| Choice::new(m.ongoing_verb(), m))
+                    .collect(),
+            ),
+        ]),
+        Widget::row(vec![
+            "Every".draw_text(ctx),
+            Widget::dropdown(
+                ctx,
+                "step",
+                opts.step_minutes,
+                vec![5, 10, 15]
+                    .into_iter()
+                    .map(|m| Choice::new(format!("{} minutes", m), m))
+                    .collect(),
+            ),
+            "up to".draw_text(ctx),
+            Widget::dropdown(
+                ctx,
+                "max",
+                opts.max_minutes,
+                vec![15, 30, 45, 60]
+                    .into_iter()
+                    .map(|m| Choice::new(format!("{} minutes", m), m))
+                    .collect(),
+            ),
+        ]),
+    ];
+    if opts.mode == TripMode::Transit {
+        col.push(
+            Text::from(
+                Line(format!(
+                    "Leaving at {}, waiting for the scheduled bus or train",
+                    app.primary.sim.time().ampm_tostring()
+                ))
+                .secondary(),
+            )
+            .wrap_to_pct(ctx, 30)
+            .draw(ctx),
+        );
+    }
+    for (t, color) in thresholds.iter().cloned().zip(colors.iter()) {
+        col.push(ColorLegend::row(
+            ctx,
+            *color,
+            format!("within {}", t.to_string(&app.opts.units)),
+        ));
+    }
+    col.push(Checkbox::switch(
+        ctx,
+        "move the start with the cursor",
+        None,
+        opts.follow_cursor,
+    ));
+    if num_starts > 1 {
+        col.push(Widget::row(vec![
+            tr_n("{} start point", "{} start points", num_starts).draw_text(ctx),
+            Btn::text_bg2("reset start points").build_def(ctx, None),
+        ]));
+    } else if !opts.follow_cursor {
+        col.push(
+            Line("Click a building to add another start point")
+                .secondary()
+                .draw(ctx),
+        );
+    }
+
+    Panel::new(Widget::col(col))
+        .aligned(HorizontalAlignment::Center, VerticalAlignment::Top)
+        .build(ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds_end_at_max() {
+        assert_eq!(thresholds(5, 15), vec![5, 10, 15]);
+        assert_eq!(thresholds(10, 15), vec![10, 15]);
+        assert_eq!(thresholds(15, 15), vec![15]);
+        assert_eq!(thresholds(10, 45), vec![10, 20, 30, 40, 45]);
+    }
+}
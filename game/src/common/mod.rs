@@ -8,10 +8,13 @@ use widgetry::{
 
 pub use self::city_picker::CityPicker;
 pub use self::colors::{ColorDiscrete, ColorLegend, ColorNetwork, ColorScale, DivergingScale};
-pub use self::heatmap::{make_heatmap, HeatmapOptions};
+pub use self::export::{ExportData, ExportObject};
+pub use self::heatmap::{heatmap_gradient, make_heatmap, HeatmapOptions};
 pub use self::isochrone::IsochroneViewer;
+pub use self::lasso::Lasso;
 pub use self::minimap::Minimap;
 pub use self::navigate::Navigator;
+pub use self::route_planner::RoutePlanner;
 pub use self::warp::Warping;
 use crate::app::App;
 use crate::game::Transition;
@@ -21,10 +24,13 @@ pub use crate::info::{ContextualActions, Tab};
 
 mod city_picker;
 mod colors;
+mod export;
 mod heatmap;
 mod isochrone;
+mod lasso;
 mod minimap;
 mod navigate;
+mod route_planner;
 mod warp;
 
 // TODO This is now just used in two modes...
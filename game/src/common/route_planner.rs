@@ -0,0 +1,435 @@
+//! Pick any two places and compare walking, biking, transit, and driving between them. If the map
+//! has been edited, each route is also compared to the unedited map.
+
+use geom::{Distance, Duration, PolyLine, Polygon, Speed, Time};
+use map_model::{
+    BikeRoutingProfile, Map, Path, PathConstraints, PathRequest, PathStep, Position,
+    NORMAL_LANE_THICKNESS,
+};
+use sim::{Scenario, TripEndpoint, TripMode, VehicleType};
+use widgetry::{
+    Btn, Choice, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Key, Line,
+    Outcome, Panel, State, Text, TextExt, VerticalAlignment, Widget,
+};
+
+use crate::app::App;
+use crate::game::Transition;
+use crate::helpers::{cmp_duration_shorter, color_for_mode, ID};
+
+pub struct RoutePlanner {
+    panel: Panel,
+    from: TripEndpoint,
+    to: Option<TripEndpoint>,
+    // The next click changes the start, instead of the destination
+    picking_from: bool,
+    bike_profile: BikeRoutingProfile,
+    draw_routes: Drawable,
+}
+
+/// A rough guess about a trip, using only the path and speed limits. Congestion, delay at
+/// intersections, and waiting for a bus are all ignored.
+pub struct RouteEstimate {
+    /// Transit trips have a walking leg before and after the ride.
+    pub legs: Vec<PolyLine>,
+    pub time: Duration,
+    pub distance: Distance,
+    pub elevation_gain: Distance,
+    /// Extra details, like which bus to take
+    pub notes: Vec<String>,
+}
+
+impl RoutePlanner {
+    pub fn new(ctx: &mut EventCtx, app: &App, from: TripEndpoint) -> Box<dyn State<App>> {
+        let mut planner = RoutePlanner {
+            panel: Panel::empty(ctx),
+            from,
+            to: None,
+            picking_from: false,
+            bike_profile: BikeRoutingProfile::Fastest,
+            draw_routes: ctx.upload(GeomBatch::new()),
+        };
+        planner.recalculate(ctx, app);
+        Box::new(planner)
+    }
+
+    fn recalculate(&mut self, ctx: &mut EventCtx, app: &App) {
+        let mut col = vec![
+            Widget::row(vec![
+                Line("Route planner").small_heading().draw(ctx),
+                Btn::close(ctx),
+            ]),
+            if self.picking_from {
+                Btn::text_bg2("keep the current start").build(ctx, "change start", Key::S)
+            } else {
+                Btn::text_bg2("change start").build_def(ctx, Key::S)
+            },
+            Widget::row(vec![
+                "Cyclists prefer:".draw_text(ctx).centered_vert(),
+                Widget::dropdown(
+                    ctx,
+                    "bike profile",
+                    self.bike_profile,
+                    vec![
+                        Choice::new("the fastest route", BikeRoutingProfile::Fastest),
+                        Choice::new("a balanced route", BikeRoutingProfile::Balanced),
+                        Choice::new("low-stress streets", BikeRoutingProfile::LowStress),
+                    ],
+                ),
+            ]),
+        ];
+        let mut batch = GeomBatch::new();
+        batch.push(Color::BLUE.alpha(0.8), endpoint_polygon(app, &self.from));
+
+        if let Some(ref to) = self.to {
+            batch.push(Color::GREEN.alpha(0.8), endpoint_polygon(app, to));
+
+            let map = &app.primary.map;
+            let edited = !map.get_edits().commands.is_empty();
+            if edited {
+                app.primary.calculate_unedited_map();
+            }
+            let unedited_map = app.primary.unedited_map.borrow();
+            let now = app.primary.sim.time();
+
+            for mode in TripMode::all() {
+                let color = color_for_mode(app, mode);
+                let mut txt = Text::from(Line(mode.ongoing_verb()).fg(color));
+                match estimate_route(
+                    map,
+                    self.from.clone(),
+                    to.clone(),
+                    mode,
+                    self.bike_profile,
+                    now,
+                ) {
+                    Some(route) => {
+                        txt.add(Line(format!(
+                            "{}, {}, {} climbing",
+                            route.time.to_string(&app.opts.units),
+                            route.distance.to_string(&app.opts.units),
+                            route.elevation_gain.to_string(&app.opts.units)
+                        )));
+                        if edited {
+                            match estimate_route(
+                                unedited_map.as_ref().unwrap(),
+                                self.from.clone(),
+                                to.clone(),
+                                mode,
+                                self.bike_profile,
+                                now,
+                            ) {
+                                Some(before) => {
+                                    let mut line = vec![Line("Compared to before your edits: ")];
+                                    line.extend(cmp_duration_shorter(app, route.time, before.time));
+                                    txt.add_appended(line);
+                                }
+                                None => {
+                                    txt.add(Line("Not possible before your edits").secondary());
+                                }
+                            }
+                        }
+                        for note in &route.notes {
+                            txt.add(Line(note).secondary());
+                        }
+                        for leg in route.legs {
+                            batch.push(color.alpha(0.8), leg.make_polygons(NORMAL_LANE_THICKNESS));
+                        }
+                    }
+                    None => {
+                        txt.add(Line("Not possible").secondary());
+                    }
+                }
+                col.push(txt.draw(ctx));
+            }
+            col.push(
+                Text::from_multiline(vec![
+                    Line(format!(
+                        "Routes avoid anything closed at {}.",
+                        now.ampm_tostring()
+                    ))
+                    .secondary(),
+                    Line(
+                        "Times ignore traffic, waiting at intersections, and waiting for transit.",
+                    )
+                    .secondary(),
+                ])
+                .draw(ctx),
+            );
+        }
+        if self.picking_from {
+            col.push("Click a building or border to start the route there".draw_text(ctx));
+        } else if self.to.is_none() {
+            col.push("Click a building or border to plan a route there".draw_text(ctx));
+        }
+
+        self.panel = Panel::new(Widget::col(col))
+            .aligned(HorizontalAlignment::Right, VerticalAlignment::Top)
+            .build(ctx);
+        self.draw_routes = ctx.upload(batch);
+    }
+}
+
+impl State<App> for RoutePlanner {
+    fn event(&mut self, ctx: &mut EventCtx, app: &mut App) -> Transition {
+        ctx.canvas_movement();
+
+        match self.panel.event(ctx) {
+            Outcome::Clicked(x) => match x.as_ref() {
+                "close" => {
+                    return Transition::Pop;
+                }
+                "change start" => {
+                    self.picking_from = !self.picking_from;
+                    self.recalculate(ctx, app);
+                }
+                _ => unreachable!(),
+            },
+            Outcome::Changed => {
+                self.bike_profile = self.panel.dropdown_value("bike profile");
+                self.recalculate(ctx, app);
+            }
+            _ => {}
+        }
+
+        if ctx.redo_mouseover() {
+            app.primary.current_selection = app.mouseover_unzoomed_everything(ctx);
+            match app.primary.current_selection {
+                Some(ID::Building(_)) => {}
+                Some(ID::Intersection(i)) if app.primary.map.get_i(i).is_border() => {}
+                _ => {
+                    app.primary.current_selection = None;
+                }
+            }
+        }
+        let hovering = match app.primary.current_selection {
+            Some(ID::Building(b)) => Some(TripEndpoint::Bldg(b)),
+            Some(ID::Intersection(i)) => Some(TripEndpoint::Border(i, None)),
+            _ => None,
+        };
+        if let Some(endpt) = hovering {
+            if self.picking_from {
+                if endpt != self.from
+                    && self.to.as_ref() != Some(&endpt)
+                    && app.per_obj.left_click(ctx, "start the route here")
+                {
+                    self.from = endpt;
+                    self.picking_from = false;
+                    self.recalculate(ctx, app);
+                }
+            } else if endpt != self.from
+                && self.to.as_ref() != Some(&endpt)
+                && app.per_obj.left_click(ctx, "plan a route here")
+            {
+                self.to = Some(endpt);
+                self.recalculate(ctx, app);
+            }
+        }
+
+        Transition::Keep
+    }
+
+    fn draw(&self, g: &mut GfxCtx, _: &App) {
+        g.redraw(&self.draw_routes);
+        self.panel.draw(g);
+    }
+
+    fn on_destroy(&mut self, _: &mut EventCtx, app: &mut App) {
+        app.primary.current_selection = None;
+    }
+}
+
+/// Estimates a trip using one mode, or returns None if it's not possible. Like the simulation,
+/// cyclists follow a routing profile, and vehicles go around lanes that time-of-day rules or truck
+/// bans close at `now`. Transit trips use the same bus or train the simulation would pick; if no
+/// route helps, there's no transit estimate.
+pub fn estimate_route(
+    map: &Map,
+    from: TripEndpoint,
+    to: TripEndpoint,
+    mode: TripMode,
+    bike_profile: BikeRoutingProfile,
+    now: Time,
+) -> Option<RouteEstimate> {
+    if mode != TripMode::Transit {
+        let req = TripEndpoint::path_req(from, to, mode, map)?;
+        let path = match mode {
+            TripMode::Walk => map.pathfind(req.clone())?,
+            TripMode::Bike => sim::avoid_closed_lanes(
+                &req,
+                map.pathfind_bike(req.clone(), bike_profile)?,
+                now,
+                VehicleType::Bike,
+                map,
+            ),
+            TripMode::Drive => sim::avoid_closed_lanes(
+                &req,
+                map.pathfind(req.clone())?,
+                now,
+                VehicleType::Car,
+                map,
+            ),
+            TripMode::Transit => unreachable!(),
+        };
+        let leg = estimate_leg(map, &req, path)?;
+        return Some(RouteEstimate {
+            legs: vec![leg.pl],
+            time: leg.time,
+            distance: leg.distance,
+            elevation_gain: leg.elevation_gain,
+            notes: Vec::new(),
+        });
+    }
+
+    let walk = TripEndpoint::path_req(from, to, TripMode::Walk, map)?;
+    let (stop1, maybe_stop2, route) = map.should_use_transit(walk.start, walk.end)?;
+    let route = map.get_br(route);
+    let board = map.get_bs(stop1);
+    let mut legs = vec![pathfind_leg(
+        map,
+        PathRequest {
+            start: walk.start,
+            end: board.sidewalk_pos,
+            constraints: PathConstraints::Pedestrian,
+        },
+    )?];
+    let mut notes = Vec::new();
+    if let Some(stop2) = maybe_stop2 {
+        let alight = map.get_bs(stop2);
+        legs.push(pathfind_leg(
+            map,
+            PathRequest {
+                start: board.driving_pos,
+                end: alight.driving_pos,
+                constraints: route.route_type,
+            },
+        )?);
+        legs.push(pathfind_leg(
+            map,
+            PathRequest {
+                start: alight.sidewalk_pos,
+                end: walk.end,
+                constraints: PathConstraints::Pedestrian,
+            },
+        )?);
+        notes.push(format!(
+            "Ride {} from {} to {}",
+            route.short_name, board.name, alight.name
+        ));
+    } else {
+        // The rest of the trip happens off the map
+        legs.push(pathfind_leg(
+            map,
+            PathRequest {
+                start: board.driving_pos,
+                end: Position::end(route.end_border?, map),
+                constraints: route.route_type,
+            },
+        )?);
+        notes.push(format!(
+            "Ride {} from {} off the map",
+            route.short_name, board.name
+        ));
+    }
+
+    Some(RouteEstimate {
+        time: legs.iter().map(|l| l.time).sum(),
+        distance: legs.iter().map(|l| l.distance).sum(),
+        elevation_gain: legs.iter().map(|l| l.elevation_gain).sum(),
+        legs: legs.into_iter().map(|l| l.pl).collect(),
+        notes,
+    })
+}
+
+struct Leg {
+    pl: PolyLine,
+    time: Duration,
+    distance: Distance,
+    elevation_gain: Distance,
+}
+
+fn pathfind_leg(map: &Map, req: PathRequest) -> Option<Leg> {
+    let path = map.pathfind(req.clone())?;
+    estimate_leg(map, &req, path)
+}
+
+fn estimate_leg(map: &Map, req: &PathRequest, path: Path) -> Option<Leg> {
+    let pl = path.trace(map, req.start.dist_along(), None)?;
+
+    let mut time = Duration::ZERO;
+    let mut full_length = Distance::ZERO;
+    let mut elevation_gain = Distance::ZERO;
+    let mut last_elevation = None;
+    for step in path.get_steps() {
+        let t = step.as_traversable();
+        time += t.length(map) / leg_speed(req.constraints, t.speed_limit(map));
+        full_length += t.length(map);
+
+        if let PathStep::Turn(t) = step {
+            let elevation = map.get_i(t.parent).elevation;
+            if let Some(last) = last_elevation {
+                if elevation > last {
+                    elevation_gain += elevation - last;
+                }
+            }
+            last_elevation = Some(elevation);
+        }
+    }
+    // The first and last steps are only partly used
+    let distance = path.total_length();
+    if full_length > Distance::ZERO {
+        time = time * (distance / full_length);
+    }
+
+    Some(Leg {
+        pl,
+        time,
+        distance,
+        elevation_gain,
+    })
+}
+
+/// How fast someone moves along a road with some speed limit, ignoring traffic
+fn leg_speed(constraints: PathConstraints, speed_limit: Speed) -> Speed {
+    match constraints {
+        PathConstraints::Pedestrian => Scenario::max_ped_speed(),
+        PathConstraints::Bike => speed_limit.min(Scenario::max_bike_speed()),
+        PathConstraints::Car | PathConstraints::Bus | PathConstraints::Train => speed_limit,
+    }
+}
+
+fn endpoint_polygon(app: &App, endpt: &TripEndpoint) -> Polygon {
+    match endpt {
+        TripEndpoint::Bldg(b) => app.primary.map.get_b(*b).polygon.clone(),
+        TripEndpoint::Border(i, _) => app.primary.map.get_i(*i).polygon.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leg_speeds() {
+        let slow_street = Speed::miles_per_hour(5.0);
+        let highway = Speed::miles_per_hour(60.0);
+
+        // Pedestrians ignore the speed limit
+        assert_eq!(
+            leg_speed(PathConstraints::Pedestrian, slow_street),
+            Scenario::max_ped_speed()
+        );
+        assert_eq!(
+            leg_speed(PathConstraints::Pedestrian, highway),
+            Scenario::max_ped_speed()
+        );
+        // Cyclists can't go faster than the speed limit or their own top speed
+        assert_eq!(leg_speed(PathConstraints::Bike, slow_street), slow_street);
+        assert_eq!(
+            leg_speed(PathConstraints::Bike, highway),
+            Scenario::max_bike_speed()
+        );
+        // Vehicles go exactly the speed limit
+        assert_eq!(leg_speed(PathConstraints::Car, highway), highway);
+        assert_eq!(leg_speed(PathConstraints::Bus, slow_street), slow_street);
+    }
+}
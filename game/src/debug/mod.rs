@@ -14,9 +14,10 @@ use crate::app::{App, ShowLayers, ShowObject};
 use crate::common::{tool_panel, CommonState, ContextualActions};
 use crate::game::{ChooseSomething, PopupMsg, PromptInput, Transition};
 use crate::helpers::ID;
+use crate::layer::{all_layer_names, layer_by_name, Layer};
 use crate::load::MapLoader;
 use crate::options::OptionsPanel;
-use crate::render::{calculate_corners, DrawOptions};
+use crate::render::{calculate_corners, DrawMap, DrawOptions};
 use crate::sandbox::GameplayMode;
 
 mod floodfill;
@@ -58,6 +59,9 @@ impl DebugMode {
                     Btn::text_fg("unhide everything").build_def(ctx, lctrl(Key::H)),
                     Btn::text_fg("screenshot everything").build_def(ctx, None),
                     Btn::text_fg("screenshot all of the everything").build_def(ctx, None),
+                    Btn::text_fg("render map to file").build_def(ctx, None),
+                    Btn::text_fg("render every layer to file").build_def(ctx, None),
+                    Btn::text_fg("export accessibility tree").build_def(ctx, None),
                     Btn::text_fg("search OSM metadata").build_def(ctx, Key::Slash),
                     Btn::text_fg("clear OSM search results").build_def(ctx, lctrl(Key::Slash)),
                     Btn::text_fg("save sim state").build_def(ctx, Key::O),
@@ -205,6 +209,16 @@ impl State<App> for DebugMode {
                     app.primary.current_selection = app.mouseover_debug_mode(ctx, self);
                     self.reset_info(ctx);
                 }
+                "export accessibility tree" => {
+                    // What assistive tools would see for this panel
+                    let path = "accessibility_tree.json".to_string();
+                    abstutil::write_json(path.clone(), &self.panel.accessibility_tree());
+                    return Transition::Push(PopupMsg::new(
+                        ctx,
+                        "Accessibility tree",
+                        vec![format!("Wrote {}", path)],
+                    ));
+                }
                 "search OSM metadata" => {
                     return Transition::Push(PromptInput::new(
                         ctx,
@@ -220,6 +234,36 @@ impl State<App> for DebugMode {
                     screenshot_everything(ctx, app);
                     return Transition::Keep;
                 }
+                "render map to file" => {
+                    let name = app.primary.map.get_name().to_string();
+                    let results: Vec<String> = vec!["png", "svg"]
+                        .into_iter()
+                        .map(|ext| {
+                            let path = format!("{}.{}", name, ext);
+                            match render_map_to_file(app, app.primary.layer.as_deref(), &path) {
+                                Ok(()) => format!("Wrote {}", path),
+                                Err(err) => err,
+                            }
+                        })
+                        .collect();
+                    return Transition::Push(PopupMsg::new(ctx, "Render map", results));
+                }
+                "render every layer to file" => {
+                    let name = app.primary.map.get_name().to_string();
+                    let mut results = Vec::new();
+                    for layer_name in all_layer_names() {
+                        let layer = match layer_by_name(ctx, app, layer_name) {
+                            Some(l) => l,
+                            None => continue,
+                        };
+                        let path = format!("{}_{}.png", name, layer_name.replace(" ", "_"));
+                        results.push(match render_map_to_file(app, Some(layer.as_ref()), &path) {
+                            Ok(()) => format!("Wrote {}", path),
+                            Err(err) => err,
+                        });
+                    }
+                    return Transition::Push(PopupMsg::new(ctx, "Render layers", results));
+                }
                 "screenshot all of the everything" => {
                     return Transition::Push(ScreenshotTest::new(
                         ctx,
@@ -773,6 +817,17 @@ impl State<App> for ScreenshotTest {
     fn draw(&self, _: &mut GfxCtx, _: &App) {}
 }
 
+/// Renders the unzoomed map and a layer on the CPU, without relying on the screen.
+fn render_map_to_file(app: &App, layer: Option<&dyn Layer>, path: &str) -> Result<(), String> {
+    let mut batch = DrawMap::unzoomed_batch(&app.primary.map, &app.cs);
+    if let Some(layer) = layer {
+        if let Some(layer_batch) = layer.export_batch() {
+            batch.append(layer_batch.clone());
+        }
+    }
+    batch.render_to_file(&app.primary.map.get_bounds(), path)
+}
+
 fn screenshot_everything(ctx: &mut EventCtx, app: &App) {
     let bounds = app.primary.map.get_bounds();
     assert!(bounds.min_x == 0.0 && bounds.min_y == 0.0);
@@ -130,7 +130,7 @@ impl State<App> for PopularDestinations {
                     app,
                     self.per_bldg.clone(),
                     if self.panel.is_checked("Show heatmap") {
-                        Some(HeatmapOptions::from_controls(&self.panel))
+                        Some(HeatmapOptions::from_controls(&self.panel, app))
                     } else {
                         None
                     },
@@ -3,7 +3,7 @@ use maplit::btreeset;
 use geom::Speed;
 use map_model::{LaneType, RoadID};
 use widgetry::{
-    hotkeys, Btn, Choice, Color, Drawable, EventCtx, GfxCtx, HorizontalAlignment, Key, Line,
+    hotkeys, tr_n, Btn, Choice, Color, Drawable, EventCtx, GfxCtx, HorizontalAlignment, Key, Line,
     Outcome, Panel, State, Text, TextExt, VerticalAlignment, Widget,
 };
 
@@ -33,7 +33,7 @@ fn make_select_panel(ctx: &mut EventCtx, selector: &RoadSelector) -> Panel {
             if selector.roads.is_empty() {
                 Btn::text_fg("Edit 0 roads").inactive(ctx)
             } else {
-                Btn::text_fg(format!("Edit {} roads", selector.roads.len())).build(
+                Btn::text_fg(tr_n("Edit {} road", "Edit {} roads", selector.roads.len())).build(
                     ctx,
                     "edit roads",
                     hotkeys(vec![Key::E, Key::Enter]),
@@ -131,7 +131,7 @@ impl BulkEdit {
     ) -> Box<dyn State<App>> {
         Box::new(BulkEdit {
             panel: Panel::new(Widget::col(vec![
-                Line(format!("Editing {} roads", roads.len()))
+                Line(tr_n("Editing {} road", "Editing {} roads", roads.len()))
                     .small_heading()
                     .draw(ctx),
                 "Lane types".draw_text(ctx),
@@ -243,6 +243,7 @@ fn make_lt_switcher(
                     Choice::new("---", None),
                     Choice::new("driving", Some(LaneType::Driving)),
                     Choice::new("parking", Some(LaneType::Parking)),
+                    Choice::new("loading zone", Some(LaneType::LoadingZone)),
                     Choice::new("bike", Some(LaneType::Biking)),
                     Choice::new("bus", Some(LaneType::Bus)),
                     Choice::new("construction", Some(LaneType::Construction)),
@@ -257,6 +258,7 @@ fn make_lt_switcher(
                     Choice::new("---", None),
                     Choice::new("driving", Some(LaneType::Driving)),
                     Choice::new("parking", Some(LaneType::Parking)),
+                    Choice::new("loading zone", Some(LaneType::LoadingZone)),
                     Choice::new("bike", Some(LaneType::Biking)),
                     Choice::new("bus", Some(LaneType::Bus)),
                     Choice::new("construction", Some(LaneType::Construction)),
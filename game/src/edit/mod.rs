@@ -4,7 +4,9 @@ use maplit::btreeset;
 
 use abstutil::{prettyprint_usize, Timer};
 use geom::Speed;
-use map_model::{EditCmd, IntersectionID, LaneID, LaneType, MapEdits};
+use map_model::{
+    ControlStopSign, EditCmd, EditIntersection, IntersectionID, LaneID, LaneType, MapEdits,
+};
 use widgetry::{
     lctrl, Btn, Choice, Color, Drawable, EventCtx, GfxCtx, HorizontalAlignment, Key, Line, Menu,
     Outcome, Panel, State, Text, TextExt, VerticalAlignment, Widget,
@@ -162,9 +164,8 @@ impl State<App> for EditMode {
                     app.primary.current_selection = None;
                 }
             } else if let Some(ID::Intersection(i)) = app.primary.current_selection {
-                if app.primary.map.maybe_get_stop_sign(i).is_some()
-                    && !self.mode.can_edit_stop_signs()
-                {
+                let i = app.primary.map.get_i(i);
+                if (i.is_stop_sign() || i.is_roundabout()) && !self.mode.can_edit_stop_signs() {
                     app.primary.current_selection = None;
                 }
             } else if let Some(ID::Road(_)) = app.primary.current_selection {
@@ -742,6 +743,22 @@ pub fn maybe_edit_intersection(
         ));
     }
 
+    if app.primary.map.get_i(id).is_roundabout()
+        && mode.can_edit_stop_signs()
+        && app
+            .per_obj
+            .left_click(ctx, "convert roundabout to stop signs")
+    {
+        let mut edits = app.primary.map.get_edits().clone();
+        edits.commands.push(EditCmd::ChangeIntersection {
+            i: id,
+            old: app.primary.map.get_i_edit(id),
+            new: EditIntersection::StopSign(ControlStopSign::new(&app.primary.map, id)),
+        });
+        apply_map_edits(ctx, app, edits);
+        return Some(StopSignEditor::new(ctx, app, id, mode.clone()));
+    }
+
     if app.primary.map.get_i(id).is_closed()
         && app.per_obj.left_click(ctx, "re-open closed intersection")
     {
@@ -820,6 +837,7 @@ fn cmd_to_id(cmd: &EditCmd) -> Option<ID> {
         EditCmd::ChangeRoad { r, .. } => Some(ID::Road(*r)),
         EditCmd::ChangeIntersection { i, .. } => Some(ID::Intersection(*i)),
         EditCmd::ChangeRouteSchedule { .. } => None,
+        EditCmd::ChangeModalFilter { i, .. } => Some(ID::Intersection(*i)),
     }
 }
 
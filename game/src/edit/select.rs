@@ -4,7 +4,7 @@ use map_model::{IntersectionID, RoadID};
 use widgetry::{Btn, Color, Drawable, EventCtx, GeomBatch, GfxCtx, Key, RewriteColor, Widget};
 
 use crate::app::App;
-use crate::common::CommonState;
+use crate::common::{CommonState, Lasso};
 use crate::helpers::{intersections_from_roads, ID};
 
 pub struct RoadSelector {
@@ -22,6 +22,8 @@ pub enum Mode {
     },
     Paint,
     Erase,
+    /// Select every road inside a freehand shape, like a neighborhood boundary
+    Boundary(Option<Lasso>),
 }
 
 impl RoadSelector {
@@ -70,6 +72,15 @@ impl RoadSelector {
                     Key::R,
                 )
             },
+            if let Mode::Boundary(_) = self.mode {
+                Widget::draw_svg_transform(
+                    ctx,
+                    "system/assets/tools/select.svg",
+                    RewriteColor::ChangeAll(Color::hex("#4CA7E9")),
+                )
+            } else {
+                Btn::svg_def("system/assets/tools/select.svg").build(ctx, "draw boundary", Key::B)
+            },
             if let Mode::Pan = self.mode {
                 Widget::draw_svg_transform(
                     ctx,
@@ -108,7 +119,7 @@ impl RoadSelector {
         if ctx.redo_mouseover() {
             app.primary.current_selection = app.mouseover_unzoomed_roads_and_intersections(ctx);
             match self.mode {
-                Mode::Pan => {
+                Mode::Pan | Mode::Boundary(_) => {
                     app.primary.current_selection = None;
                 }
                 Mode::Route { .. } => {
@@ -138,6 +149,26 @@ impl RoadSelector {
             Mode::Pan | Mode::Route { .. } => {
                 ctx.canvas_movement();
             }
+            Mode::Boundary(None) => {
+                if let Some(pt) = ctx.canvas.get_cursor_in_map_space() {
+                    if ctx.input.left_mouse_button_pressed() {
+                        self.mode = Mode::Boundary(Some(Lasso::new(pt)));
+                    }
+                }
+            }
+            Mode::Boundary(Some(ref mut lasso)) => {
+                if let Some(ring) = lasso.event(ctx) {
+                    let map = &app.primary.map;
+                    for r in map.all_roads() {
+                        if !r.is_light_rail() && ring.contains_pt(r.center_pts.middle()) {
+                            self.roads.insert(r.id);
+                        }
+                    }
+                    self.mode = Mode::Pan;
+                    self.roads_changed(ctx, app);
+                    return true;
+                }
+            }
             Mode::Paint | Mode::Erase => {
                 if self.dragging && ctx.input.left_mouse_button_released() {
                     self.dragging = false;
@@ -166,7 +197,7 @@ impl RoadSelector {
                             false
                         }
                     }
-                    Mode::Route { .. } | Mode::Pan => unreachable!(),
+                    Mode::Route { .. } | Mode::Pan | Mode::Boundary(_) => unreachable!(),
                 };
                 if change {
                     self.roads_changed(ctx, app);
@@ -193,6 +224,12 @@ impl RoadSelector {
                     self.mode = Mode::Pan;
                     return true;
                 }
+                "draw boundary" => {
+                    app.primary.current_selection = None;
+                    self.dragging = false;
+                    self.mode = Mode::Boundary(None);
+                    return true;
+                }
                 "select along route" => {
                     app.primary.current_selection = None;
                     self.dragging = false;
@@ -283,6 +320,7 @@ impl RoadSelector {
                 Mode::Paint => Some("system/assets/tools/pencil.svg"),
                 Mode::Erase => Some("system/assets/tools/eraser.svg"),
                 Mode::Route { .. } => Some("system/assets/timeline/start_pos.svg"),
+                Mode::Boundary(_) => Some("system/assets/tools/select.svg"),
             } {
                 let mut batch = GeomBatch::new();
                 batch.append(
@@ -296,6 +334,10 @@ impl RoadSelector {
             }
         }
 
+        if let Mode::Boundary(Some(ref lasso)) = self.mode {
+            lasso.draw(g);
+        }
+
         if let Mode::Route {
             ref i1,
             ref preview_path,
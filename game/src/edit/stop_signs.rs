@@ -62,6 +62,18 @@ impl StopSignEditor {
             },
             Btn::text_fg("close intersection for construction").build_def(ctx, Key::C),
             Btn::text_fg("convert to traffic signal").build_def(ctx, None),
+            if app
+                .primary
+                .map
+                .get_i(id)
+                .roads
+                .iter()
+                .any(|r| app.primary.map.get_r(*r).is_roundabout())
+            {
+                Btn::text_fg("convert to roundabout").build_def(ctx, None)
+            } else {
+                Widget::nothing()
+            },
             Btn::text_fg("Finish").build_def(ctx, Key::Escape),
         ]))
         .aligned(HorizontalAlignment::Center, VerticalAlignment::Top)
@@ -183,6 +195,16 @@ impl State<App> for StopSignEditor {
                         self.mode.clone(),
                     ));
                 }
+                "convert to roundabout" => {
+                    let mut edits = app.primary.map.get_edits().clone();
+                    edits.commands.push(EditCmd::ChangeIntersection {
+                        i: self.id,
+                        old: app.primary.map.get_i_edit(self.id),
+                        new: EditIntersection::Roundabout,
+                    });
+                    apply_map_edits(ctx, app, edits);
+                    return Transition::Pop;
+                }
                 _ => unreachable!(),
             },
             _ => {}
@@ -7,9 +7,9 @@ use map_model::{
     TurnPriority,
 };
 use widgetry::{
-    lctrl, Btn, Color, DrawBaselayer, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment,
-    Key, Line, MultiButton, Outcome, Panel, RewriteColor, State, Text, TextExt, VerticalAlignment,
-    Widget,
+    lctrl, tr_n, Btn, Color, DrawBaselayer, Drawable, EventCtx, GeomBatch, GfxCtx,
+    HorizontalAlignment, Key, Line, MultiButton, Outcome, Panel, RewriteColor, State, Text,
+    TextExt, VerticalAlignment, Widget,
 };
 
 use crate::app::{App, ShowEverything};
@@ -585,7 +585,9 @@ fn make_side_panel(
             txt.add(Line(format!("  {}", r)).secondary());
         }
     } else {
-        txt.add(Line(format!("{} intersections", members.len())).big_heading_plain());
+        txt.add(
+            Line(tr_n("{} intersection", "{} intersections", members.len())).big_heading_plain(),
+        );
         txt.add(
             Line(
                 members
@@ -6,7 +6,7 @@ use geom::{Distance, Duration};
 use map_model::IntersectionID;
 use sim::Scenario;
 use widgetry::{
-    Btn, Color, Drawable, EventCtx, GfxCtx, HorizontalAlignment, Key, Line, Outcome, Panel,
+    tr_n, Btn, Color, Drawable, EventCtx, GfxCtx, HorizontalAlignment, Key, Line, Outcome, Panel,
     RewriteColor, Spinner, State, Text, TextExt, VerticalAlignment, Widget,
 };
 
@@ -49,9 +49,13 @@ impl ShowAbsolute {
         Box::new(ShowAbsolute {
             panel: Panel::new(Widget::col(vec![
                 Widget::row(vec![
-                    Line(format!("Tuning offset for {} signals", members.len()))
-                        .small_heading()
-                        .draw(ctx),
+                    Line(tr_n(
+                        "Tuning offset for {} signal",
+                        "Tuning offset for {} signals",
+                        members.len(),
+                    ))
+                    .small_heading()
+                    .draw(ctx),
                     Btn::close(ctx),
                 ]),
                 "Select an intersection as the base".draw_text(ctx),
@@ -148,9 +152,13 @@ impl ShowRelative {
         Box::new(ShowRelative {
             panel: Panel::new(Widget::col(vec![
                 Widget::row(vec![
-                    Line(format!("Tuning offset for {} signals", members.len()))
-                        .small_heading()
-                        .draw(ctx),
+                    Line(tr_n(
+                        "Tuning offset for {} signal",
+                        "Tuning offset for {} signals",
+                        members.len(),
+                    ))
+                    .small_heading()
+                    .draw(ctx),
                     Btn::close(ctx),
                 ]),
                 "Select a second intersection to tune offset between the two".draw_text(ctx),
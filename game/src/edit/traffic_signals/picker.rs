@@ -2,8 +2,8 @@ use std::collections::BTreeSet;
 
 use map_model::IntersectionID;
 use widgetry::{
-    hotkeys, Btn, Color, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Key, Line, Outcome,
-    Panel, State, VerticalAlignment, Widget,
+    hotkeys, tr_n, Btn, Color, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Key, Line,
+    Outcome, Panel, State, VerticalAlignment, Widget,
 };
 
 use crate::app::App;
@@ -113,10 +113,9 @@ fn make_btn(ctx: &mut EventCtx, num: usize) -> Widget {
         return Btn::text_bg2("Edit 0 signals").inactive(ctx).named("edit");
     }
 
-    let title = if num == 1 {
-        "Edit 1 signal".to_string()
-    } else {
-        format!("Edit {} signals", num)
-    };
-    Btn::text_bg2(title).build(ctx, "edit", hotkeys(vec![Key::Enter, Key::E]))
+    Btn::text_bg2(tr_n("Edit {} signal", "Edit {} signals", num)).build(
+        ctx,
+        "edit",
+        hotkeys(vec![Key::Enter, Key::E]),
+    )
 }
@@ -2,7 +2,7 @@ use std::collections::BTreeSet;
 
 use abstutil::Timer;
 use map_model::{connectivity, EditCmd, LaneID, LaneType, Map, PathConstraints};
-use widgetry::{Color, EventCtx, State};
+use widgetry::{tr_n, Color, EventCtx, State};
 
 use crate::app::App;
 use crate::common::ColorDiscrete;
@@ -113,7 +113,11 @@ pub fn check_blackholes(
     Some(PopupMsg::also_draw(
         ctx,
         "Error",
-        vec![format!("{} lanes have been disconnected", num)],
+        vec![tr_n(
+            "{} lane has been disconnected",
+            "{} lanes have been disconnected",
+            num,
+        )],
         unzoomed,
         zoomed,
     ))
@@ -149,6 +153,11 @@ pub fn try_change_lt(
             "A parking lane needs a driving lane somewhere on the same road"
         ));
     }
+    if all_types.contains(&LaneType::LoadingZone) && !all_types.contains(&LaneType::Driving) {
+        errors.push(format!(
+            "A loading zone needs a driving lane somewhere on the same road"
+        ));
+    }
 
     // Don't let players orphan a bus stop.
     // TODO This allows a bus stop switching sides of the road. Really need to re-do bus matching
@@ -1,13 +1,14 @@
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use enumset::EnumSet;
 use maplit::btreeset;
 
-use map_model::{AccessRestrictions, PathConstraints, RoadID};
+use geom::{Circle, Distance};
+use map_model::{AccessRestrictions, IntersectionID, PathConstraints, RoadID};
 use sim::TripMode;
 use widgetry::{
-    Btn, Color, Drawable, EventCtx, GfxCtx, HorizontalAlignment, Key, Line, Outcome, Panel,
-    Spinner, State, Text, TextExt, VerticalAlignment, Widget,
+    Btn, Checkbox, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Key, Line,
+    Outcome, Panel, Spinner, State, Text, TextExt, VerticalAlignment, Widget,
 };
 
 use crate::app::App;
@@ -16,7 +17,20 @@ use crate::common::CommonState;
 use crate::edit::apply_map_edits;
 use crate::edit::select::RoadSelector;
 use crate::game::Transition;
-use crate::helpers::{checkbox_per_mode, intersections_from_roads};
+use crate::helpers::{checkbox_per_mode, intersections_from_roads, ID};
+
+/// Modes that can be banned from individual roads. Pedestrians and buses are left out, since
+/// buildings and bus stops would be orphaned.
+const BANNABLE: [(PathConstraints, &str); 2] = [
+    (PathConstraints::Car, "ban cars entirely"),
+    (PathConstraints::Bike, "ban bikes entirely"),
+];
+const BAN_TRUCKS: &str = "ban trucks entirely";
+
+/// The modes a newly placed modal filter blocks
+fn filter_blocks() -> EnumSet<PathConstraints> {
+    PathConstraints::Car | PathConstraints::Bus
+}
 
 pub struct ZoneEditor {
     panel: Panel,
@@ -24,6 +38,10 @@ pub struct ZoneEditor {
     allow_through_traffic: BTreeSet<TripMode>,
     unzoomed: Drawable,
     zoomed: Drawable,
+    /// Modal filters placed or removed, but not applied yet
+    filters: BTreeMap<IntersectionID, EnumSet<PathConstraints>>,
+    draw_filters: Drawable,
+    placing_filters: bool,
 
     orig_members: BTreeSet<RoadID>,
 }
@@ -44,6 +62,8 @@ impl ZoneEditor {
             .map(|c| TripMode::from_constraints(c))
             .collect();
         let cap_vehicles_per_hour = start.access_restrictions.cap_vehicles_per_hour;
+        let banned = start.access_restrictions.banned;
+        let ban_trucks = start.access_restrictions.ban_trucks;
 
         let (unzoomed, zoomed, legend) = draw_zone(ctx, app, &members);
         let orig_members = members.clone();
@@ -54,6 +74,8 @@ impl ZoneEditor {
                 Line("Editing restricted access zone")
                     .small_heading()
                     .draw(ctx),
+                "Paint roads, or draw the boundary of a neighborhood to select every road inside"
+                    .draw_text(ctx),
                 selector.make_controls(ctx).named("selector"),
                 legend,
                 make_instructions(ctx, &allow_through_traffic),
@@ -64,6 +86,19 @@ impl ZoneEditor {
                     Spinner::new(ctx, (0, 1000), cap_vehicles_per_hour.unwrap_or(0) as isize)
                         .named("cap_vehicles"),
                 ]),
+                Widget::custom_row(
+                    BANNABLE
+                        .iter()
+                        .map(|(c, label)| {
+                            Checkbox::switch(ctx, *label, None, banned.contains(*c))
+                                .margin_right(24)
+                        })
+                        .chain(std::iter::once(Checkbox::switch(
+                            ctx, BAN_TRUCKS, None, ban_trucks,
+                        )))
+                        .collect(),
+                ),
+                Checkbox::switch(ctx, "place modal filters at intersections", Key::F, false),
                 Widget::custom_row(vec![
                     Btn::text_fg("Apply").build_def(ctx, Key::Enter),
                     Btn::text_fg("Cancel").build_def(ctx, Key::Escape),
@@ -77,13 +112,53 @@ impl ZoneEditor {
             allow_through_traffic,
             unzoomed,
             zoomed,
+            filters: BTreeMap::new(),
+            draw_filters: draw_filters(ctx, app, &BTreeMap::new()),
+            placing_filters: false,
         })
     }
 }
 
+impl ZoneEditor {
+    fn current_filter(&self, app: &App, i: IntersectionID) -> EnumSet<PathConstraints> {
+        self.filters
+            .get(&i)
+            .cloned()
+            .unwrap_or_else(|| app.primary.map.get_i(i).modal_filter)
+    }
+
+    fn place_filters(&mut self, ctx: &mut EventCtx, app: &mut App) {
+        ctx.canvas_movement();
+        if ctx.redo_mouseover() {
+            app.primary.current_selection =
+                match app.mouseover_unzoomed_roads_and_intersections(ctx) {
+                    Some(ID::Intersection(i)) if !app.primary.map.get_i(i).is_border() => {
+                        Some(ID::Intersection(i))
+                    }
+                    _ => None,
+                };
+        }
+        if let Some(ID::Intersection(i)) = app.primary.current_selection {
+            if self.current_filter(app, i).is_empty() {
+                if app.per_obj.left_click(ctx, "add modal filter") {
+                    self.filters.insert(i, filter_blocks());
+                    self.draw_filters = draw_filters(ctx, app, &self.filters);
+                }
+            } else if app.per_obj.left_click(ctx, "remove modal filter") {
+                self.filters.insert(i, EnumSet::new());
+                self.draw_filters = draw_filters(ctx, app, &self.filters);
+            }
+        }
+    }
+}
+
 // TODO Handle splitting/merging zones.
 impl State<App> for ZoneEditor {
     fn event(&mut self, ctx: &mut EventCtx, app: &mut App) -> Transition {
+        if self.placing_filters {
+            self.place_filters(ctx, app);
+        }
+
         match self.panel.event(ctx) {
             Outcome::Clicked(x) => match x.as_ref() {
                 "Apply" => {
@@ -106,6 +181,12 @@ impl State<App> for ZoneEditor {
                     // The original allow_through_traffic always includes this, and there's no way
                     // to exclude it, so stay consistent.
                     allow_through_traffic.insert(PathConstraints::Train);
+                    let mut banned = EnumSet::new();
+                    for (c, label) in BANNABLE.iter() {
+                        if self.panel.is_checked(label) {
+                            banned.insert(*c);
+                        }
+                    }
                     let new_access_restrictions = AccessRestrictions {
                         allow_through_traffic,
                         cap_vehicles_per_hour: {
@@ -116,6 +197,8 @@ impl State<App> for ZoneEditor {
                                 Some(n)
                             }
                         },
+                        banned,
+                        ban_trucks: self.panel.is_checked(BAN_TRUCKS),
                     };
                     for r in &self.selector.roads {
                         let old_access_restrictions =
@@ -129,6 +212,14 @@ impl State<App> for ZoneEditor {
                         }
                     }
 
+                    for (i, filter) in &self.filters {
+                        if app.primary.map.get_i(*i).modal_filter != *filter {
+                            edits
+                                .commands
+                                .push(app.primary.map.modal_filter_cmd(*i, *filter));
+                        }
+                    }
+
                     apply_map_edits(ctx, app, edits);
                     return Transition::Pop;
                 }
@@ -146,6 +237,14 @@ impl State<App> for ZoneEditor {
                 }
             },
             Outcome::Changed => {
+                let placing_filters = self
+                    .panel
+                    .is_checked("place modal filters at intersections");
+                if placing_filters != self.placing_filters {
+                    self.placing_filters = placing_filters;
+                    app.primary.current_selection = None;
+                }
+
                 let mut new_allow_through_traffic = BTreeSet::new();
                 for m in TripMode::all() {
                     if self.panel.is_checked(m.ongoing_verb()) {
@@ -157,7 +256,7 @@ impl State<App> for ZoneEditor {
                 self.allow_through_traffic = new_allow_through_traffic;
             }
             _ => {
-                if self.selector.event(ctx, app, None) {
+                if !self.placing_filters && self.selector.event(ctx, app, None) {
                     let new_controls = self.selector.make_controls(ctx).named("selector");
                     self.panel.replace(ctx, "selector", new_controls);
                     let (unzoomed, zoomed, _) = draw_zone(ctx, app, &self.selector.roads);
@@ -177,9 +276,13 @@ impl State<App> for ZoneEditor {
         } else {
             g.redraw(&self.zoomed);
         }
+        g.redraw(&self.draw_filters);
         self.panel.draw(g);
-        self.selector.draw(g, app, false);
-        CommonState::draw_osd(g, app);
+        if self.placing_filters {
+            CommonState::draw_osd(g, app);
+        } else {
+            self.selector.draw(g, app, false);
+        }
     }
 }
 
@@ -211,6 +314,25 @@ fn draw_zone(
     colorer.build(ctx)
 }
 
+/// Marks every intersection with a modal filter, including ones placed but not applied yet
+fn draw_filters(
+    ctx: &mut EventCtx,
+    app: &App,
+    filters: &BTreeMap<IntersectionID, EnumSet<PathConstraints>>,
+) -> Drawable {
+    let mut batch = GeomBatch::new();
+    for i in app.primary.map.all_intersections() {
+        let filter = filters.get(&i.id).cloned().unwrap_or(i.modal_filter);
+        if !filter.is_empty() {
+            batch.push(
+                Color::RED,
+                Circle::new(i.polygon.center(), Distance::meters(3.0)).to_polygon(),
+            );
+        }
+    }
+    ctx.upload(batch)
+}
+
 fn make_instructions(ctx: &mut EventCtx, allow_through_traffic: &BTreeSet<TripMode>) -> Widget {
     if allow_through_traffic == &TripMode::all().into_iter().collect() {
         Text::from(Line(
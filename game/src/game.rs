@@ -28,6 +28,7 @@ impl<T: 'static> ChooseSomething<T> {
                 Widget::row(vec![Line(query).small_heading().draw(ctx), Btn::close(ctx)]),
                 Menu::new(ctx, choices).named("menu"),
             ]))
+            .keyboard_focus()
             .build(ctx),
             cb,
         })
@@ -101,6 +102,7 @@ impl PromptInput {
                 Widget::text_entry(ctx, String::new(), true).named("input"),
                 Btn::text_fg("confirm").build_def(ctx, Key::Enter),
             ]))
+            .keyboard_focus()
             .build(ctx),
             cb,
         })
@@ -138,7 +138,7 @@ pub fn color_for_agent_type(app: &App, a: AgentType) -> Color {
         AgentType::Bike => app.cs.unzoomed_bike,
         AgentType::Bus | AgentType::Train => app.cs.unzoomed_bus,
         AgentType::TransitRider => app.cs.bus_trip,
-        AgentType::Car => app.cs.unzoomed_car,
+        AgentType::Car | AgentType::Truck => app.cs.unzoomed_car,
     }
 }
 
@@ -3,7 +3,7 @@ use std::collections::BTreeMap;
 use geom::{Angle, Circle, Distance, Speed, Time};
 use map_model::{BuildingID, LaneID, OffstreetParking, Traversable, SIDEWALK_THICKNESS};
 use sim::{DrawPedestrianInput, PedestrianID, PersonID, TripMode, TripResult};
-use widgetry::{Btn, Color, EventCtx, Line, Text, TextExt, Widget};
+use widgetry::{tr_n, Btn, Color, EventCtx, Line, Text, TextExt, Widget};
 
 use crate::app::App;
 use crate::info::{header_btns, make_table, make_tabs, Details, Tab};
@@ -55,11 +55,11 @@ pub fn info(ctx: &mut EventCtx, app: &App, details: &mut Details, id: BuildingID
 
     if !b.amenities.is_empty() {
         txt.add(Line(""));
-        if b.amenities.len() == 1 {
-            txt.add(Line("1 amenity:"));
-        } else {
-            txt.add(Line(format!("{} amenities:", b.amenities.len())));
-        }
+        txt.add(Line(tr_n(
+            "{} amenity:",
+            "{} amenities:",
+            b.amenities.len(),
+        )));
         for a in &b.amenities {
             txt.add(Line(format!(
                 "  {} ({})",
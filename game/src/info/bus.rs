@@ -2,7 +2,7 @@ use abstutil::{prettyprint_usize, Counter};
 use geom::{Circle, Distance, Time};
 use map_model::{BusRoute, BusRouteID, BusStopID, PathStep};
 use sim::{AgentID, CarID};
-use widgetry::{Btn, Color, EventCtx, Key, Line, RewriteColor, Text, TextExt, Widget};
+use widgetry::{tr_n, Btn, Color, EventCtx, Key, Line, RewriteColor, Text, TextExt, Widget};
 
 use crate::app::App;
 use crate::common::ColorNetwork;
@@ -235,7 +235,7 @@ pub fn route(ctx: &mut EventCtx, app: &App, details: &mut Details, id: BusRouteI
         .draw(ctx),
     );
 
-    rows.push(format!("{} stops", route.stops.len()).draw_text(ctx));
+    rows.push(tr_n("{} stop", "{} stops", route.stops.len()).draw_text(ctx));
     {
         let i = map.get_i(map.get_l(route.start).src_i);
         let name = format!("Starts at {}", i.name(app.opts.language.as_ref(), map));
@@ -5,7 +5,7 @@ use geom::{ArrowCap, Distance, Duration, PolyLine, Polygon, Time};
 use map_model::{IntersectionID, IntersectionType, PhaseType};
 use sim::AgentType;
 use widgetry::{
-    Btn, Checkbox, Color, DrawWithTooltips, EventCtx, FanChart, GeomBatch, Line, PlotOptions,
+    tr_n, Btn, Checkbox, Color, DrawWithTooltips, EventCtx, FanChart, GeomBatch, Line, PlotOptions,
     ScatterPlot, Series, Text, Widget,
 };
 
@@ -32,6 +32,20 @@ pub fn info(ctx: &EventCtx, app: &App, details: &mut Details, id: IntersectionID
     for r in road_names {
         txt.add(Line(format!("  {}", r)));
     }
+    if i.has_modal_filter() {
+        let blocked: Vec<String> = i
+            .modal_filter
+            .iter()
+            .map(|c| format!("{:?}", c).to_ascii_lowercase())
+            .collect();
+        txt.add(Line(format!("Modal filter blocks {}", blocked.join(", "))));
+    }
+    if let Some(crossing) = i.crossing {
+        txt.add(Line(format!(
+            "Mid-block crossing ({})",
+            format!("{:?}", crossing).to_ascii_lowercase()
+        )));
+    }
     rows.push(txt.draw(ctx));
 
     if app.opts.dev {
@@ -266,7 +280,7 @@ pub fn traffic_signal(
     let signal = app.primary.map.get_traffic_signal(id);
     {
         let mut txt = Text::new();
-        txt.add(Line(format!("{} stages", signal.stages.len())).small_heading());
+        txt.add(Line(tr_n("{} stage", "{} stages", signal.stages.len())).small_heading());
         txt.add(Line(format!("Signal offset: {}", signal.offset)));
         {
             let mut total = Duration::ZERO;
@@ -388,6 +402,7 @@ fn header(
         IntersectionType::TrafficSignal => format!("{} (Traffic signals)", id),
         IntersectionType::Border => format!("Border #{}", id.0),
         IntersectionType::Construction => format!("{} (under construction)", id),
+        IntersectionType::Roundabout => format!("{} (Roundabout)", id),
     };
     rows.push(Widget::row(vec![
         Line(label).small_heading().draw(ctx),
@@ -18,6 +18,23 @@ pub fn info(ctx: &EventCtx, app: &App, details: &mut Details, id: LaneID) -> Vec
     if !l.is_walkable() {
         kv.push(("Type", l.lane_type.describe().to_string()));
     }
+    let idx = r.offset(l.id);
+    for (i, lt, windows) in &r.time_rules.lane_types {
+        if *i == idx {
+            let windows: Vec<String> = windows.iter().map(|w| w.to_string()).collect();
+            kv.push((
+                "Part-time",
+                format!("{} during {}", lt.describe(), windows.join(", ")),
+            ));
+        }
+    }
+    for (_, windows) in &r.time_rules.access {
+        let windows: Vec<String> = windows.iter().map(|w| w.to_string()).collect();
+        kv.push(("Closed to through-traffic", windows.join(", ")));
+    }
+    if r.access_restrictions.ban_trucks {
+        kv.push(("Trucks", "banned".to_string()));
+    }
     if r.is_private() {
         let mut ban = Vec::new();
         for p in PathConstraints::all() {
@@ -580,7 +580,9 @@ fn header(
                         ("walking", Some("system/assets/meters/pedestrian.svg"))
                     }
                     AgentID::Car(c) => match c.1 {
-                        VehicleType::Car => ("driving", Some("system/assets/meters/car.svg")),
+                        VehicleType::Car | VehicleType::Truck => {
+                            ("driving", Some("system/assets/meters/car.svg"))
+                        }
                         VehicleType::Bike => ("biking", Some("system/assets/meters/bike.svg")),
                         VehicleType::Bus | VehicleType::Train => unreachable!(),
                     },
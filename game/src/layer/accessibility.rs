@@ -0,0 +1,280 @@
+use abstutil::{prettyprint_usize, Timer};
+use geom::Duration;
+use map_model::accessibility::{self, Opportunity};
+use map_model::{BuildingID, Map, PathConstraints};
+use widgetry::{
+    Btn, Checkbox, Choice, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Line,
+    Outcome, Panel, Text, TextExt, VerticalAlignment, Widget,
+};
+
+use crate::app::App;
+use crate::common::{ColorLegend, ColorNetwork, DivergingScale, ExportData, ExportObject};
+use crate::layer::{Layer, LayerOutcome};
+
+const TIME_LIMITS: [usize; 3] = [15, 30, 45];
+
+/// How many jobs, grocery stores, schools, or clinics each residence can reach within some time.
+pub struct Accessibility {
+    opts: Options,
+    after: accessibility::Accessibility,
+    // Only calculated once the user asks to compare
+    before: Option<accessibility::Accessibility>,
+    unzoomed: Drawable,
+    zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
+    panel: Panel,
+    export: ExportData,
+}
+
+#[derive(Clone, PartialEq)]
+pub struct Options {
+    pub constraints: PathConstraints,
+    pub opportunity: Opportunity,
+    pub minutes: usize,
+    pub compare: bool,
+}
+
+impl Layer for Accessibility {
+    fn name(&self) -> Option<&'static str> {
+        Some("accessibility")
+    }
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        app: &mut App,
+        minimap: &Panel,
+    ) -> Option<LayerOutcome> {
+        self.panel.align_above(ctx, minimap);
+        match self.panel.event(ctx) {
+            Outcome::Clicked(x) => match x.as_ref() {
+                "close" => {
+                    return Some(LayerOutcome::Close);
+                }
+                _ => unreachable!(),
+            },
+            Outcome::Changed => {
+                let new_opts = self.options();
+                if new_opts.constraints != self.opts.constraints {
+                    return Some(LayerOutcome::Replace(Box::new(Accessibility::new(
+                        ctx, app, new_opts,
+                    ))));
+                }
+                if new_opts.compare && self.before.is_none() {
+                    self.before = calculate_before(ctx, app, new_opts.constraints);
+                }
+                self.opts = new_opts;
+                self.recolor(ctx, app);
+                self.panel.align_above(ctx, minimap);
+            }
+            _ => {}
+        }
+        None
+    }
+    fn draw(&self, g: &mut GfxCtx, app: &App) {
+        self.panel.draw(g);
+        if g.canvas.cam_zoom < app.opts.min_zoom_for_detail {
+            g.redraw(&self.unzoomed);
+        } else {
+            g.redraw(&self.zoomed);
+        }
+    }
+    fn draw_minimap(&self, g: &mut GfxCtx) {
+        g.redraw(&self.unzoomed);
+    }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
+}
+
+impl Accessibility {
+    pub fn new(ctx: &mut EventCtx, app: &App, opts: Options) -> Accessibility {
+        let constraints = opts.constraints;
+        let after = ctx.loading_screen("calculate accessibility", |_, timer| {
+            calculate(&app.primary.map, constraints, timer)
+        });
+        let before = if opts.compare {
+            calculate_before(ctx, app, constraints)
+        } else {
+            None
+        };
+        let mut layer = Accessibility {
+            opts,
+            after,
+            before,
+            unzoomed: ctx.upload(GeomBatch::new()),
+            zoomed: ctx.upload(GeomBatch::new()),
+            unzoomed_batch: GeomBatch::new(),
+            panel: Panel::empty(ctx),
+            export: ExportData::new(Vec::new()),
+        };
+        layer.recolor(ctx, app);
+        layer
+    }
+
+    fn options(&self) -> Options {
+        Options {
+            constraints: self.panel.dropdown_value("mode"),
+            opportunity: self.panel.dropdown_value("opportunity"),
+            minutes: self.panel.dropdown_value("minutes"),
+            compare: self.panel.maybe_is_checked("Compare before edits") == Some(true),
+        }
+    }
+
+    fn recolor(&mut self, ctx: &mut EventCtx, app: &App) {
+        let limit = Duration::minutes(self.opts.minutes);
+        let o = self.opts.opportunity;
+        let mut colorer = ColorNetwork::new(app);
+
+        let legend = if let (true, Some(before)) = (self.opts.compare, self.before.as_ref()) {
+            let scale =
+                DivergingScale::new(Color::hex("#A32015"), Color::WHITE, Color::hex("#5D9630"))
+                    .range(0.0, 2.0)
+                    .ignore(0.9, 1.1);
+            let mut export = ExportData::new(vec!["before", "after"]);
+            for b in self.after.residences() {
+                let after = self.after.get(b, limit, o).unwrap();
+                let before = before.get(b, limit, o).unwrap_or(0);
+                export.add(
+                    ExportObject::Building(b),
+                    vec![before.to_string(), after.to_string()],
+                );
+                if before == 0 && after == 0 {
+                    continue;
+                }
+                let ratio = if before == 0 {
+                    2.0
+                } else {
+                    (after as f64) / (before as f64)
+                };
+                if let Some(c) = scale.eval(ratio) {
+                    colorer.add_b(b, c);
+                }
+            }
+            self.export = export;
+            scale.make_legend(ctx, vec!["less", "same", "more"])
+        } else {
+            let mut export = ExportData::new(vec![o.describe()]);
+            let scores: Vec<(BuildingID, usize)> = self
+                .after
+                .residences()
+                .into_iter()
+                .map(|b| (b, self.after.get(b, limit, o).unwrap()))
+                .collect();
+            let max = scores.iter().map(|(_, cnt)| *cnt).max().unwrap_or(0);
+            for (b, cnt) in scores {
+                export.add(ExportObject::Building(b), vec![cnt.to_string()]);
+                if cnt > 0 {
+                    colorer.add_b(
+                        b,
+                        app.cs.good_to_bad_green.eval((cnt as f64) / (max as f64)),
+                    );
+                }
+            }
+            self.export = export;
+            ColorLegend::gradient(
+                ctx,
+                &app.cs.good_to_bad_green,
+                vec!["1".to_string(), prettyprint_usize(max)],
+            )
+        };
+
+        self.unzoomed_batch = colorer.unzoomed.clone();
+        let (unzoomed, zoomed) = colorer.build(ctx);
+        self.unzoomed = unzoomed;
+        self.zoomed = zoomed;
+        self.panel = make_panel(ctx, app, &self.opts, legend);
+    }
+}
+
+fn calculate(
+    map: &Map,
+    constraints: PathConstraints,
+    timer: &mut Timer,
+) -> accessibility::Accessibility {
+    accessibility::Accessibility::calculate(
+        map,
+        constraints,
+        TIME_LIMITS.iter().map(|m| Duration::minutes(*m)).collect(),
+        timer,
+    )
+}
+
+fn calculate_before(
+    ctx: &mut EventCtx,
+    app: &App,
+    constraints: PathConstraints,
+) -> Option<accessibility::Accessibility> {
+    if app.primary.map.get_edits().commands.is_empty() {
+        return None;
+    }
+    app.primary.calculate_unedited_map();
+    let unedited_map = app.primary.unedited_map.borrow();
+    Some(
+        ctx.loading_screen("calculate accessibility before edits", |_, timer| {
+            calculate(unedited_map.as_ref().unwrap(), constraints, timer)
+        }),
+    )
+}
+
+fn make_panel(ctx: &mut EventCtx, app: &App, opts: &Options, legend: Widget) -> Panel {
+    Panel::new(Widget::col(vec![
+        Widget::row(vec![
+            Widget::draw_svg(ctx, "system/assets/tools/layers.svg"),
+            "Accessibility".draw_text(ctx),
+            Btn::close(ctx),
+        ]),
+        Text::from(
+            Line("How many opportunities each residence can reach, ignoring traffic").secondary(),
+        )
+        .wrap_to_pct(ctx, 15)
+        .draw(ctx),
+        Widget::row(vec![
+            "Count".draw_text(ctx),
+            Widget::dropdown(
+                ctx,
+                "opportunity",
+                opts.opportunity,
+                Opportunity::all()
+                    .into_iter()
+                    .map(|o| Choice::new(o.describe(), o))
+                    .collect(),
+            ),
+        ]),
+        Widget::row(vec![
+            "within".draw_text(ctx),
+            Widget::dropdown(
+                ctx,
+                "minutes",
+                opts.minutes,
+                TIME_LIMITS
+                    .iter()
+                    .map(|m| Choice::new(format!("{} minutes", m), *m))
+                    .collect(),
+            ),
+        ]),
+        Widget::row(vec![
+            "by".draw_text(ctx),
+            Widget::dropdown(
+                ctx,
+                "mode",
+                opts.constraints,
+                vec![
+                    Choice::new("walking", PathConstraints::Pedestrian),
+                    Choice::new("biking", PathConstraints::Bike),
+                    Choice::new("driving", PathConstraints::Car),
+                ],
+            ),
+        ]),
+        if app.primary.map.get_edits().commands.is_empty() {
+            Widget::nothing()
+        } else {
+            Checkbox::switch(ctx, "Compare before edits", None, opts.compare)
+        },
+        legend,
+    ]))
+    .aligned(HorizontalAlignment::Right, VerticalAlignment::Center)
+    .build(ctx)
+}
@@ -1,17 +1,23 @@
 use geom::{ArrowCap, Distance, PolyLine};
 use widgetry::{
-    Btn, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Line, Panel, Text,
-    TextExt, VerticalAlignment, Widget,
+    Btn, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Line, Panel, TextExt,
+    VerticalAlignment, Widget,
 };
 
 use crate::app::App;
-use crate::common::{ColorLegend, ColorNetwork};
+use crate::common::{ColorLegend, ColorNetwork, ExportData, ExportObject};
 use crate::layer::{Layer, LayerOutcome};
 
+/// Roads this steep or steeper are the reddest. Few streets top 15%, and that's already a hard
+/// climb on a bike.
+const STEEPEST_GRADE: f64 = 0.15;
+
 pub struct Elevation {
     unzoomed: Drawable,
     zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
     panel: Panel,
+    export: ExportData,
 }
 
 impl Layer for Elevation {
@@ -32,21 +38,33 @@ impl Layer for Elevation {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl Elevation {
     pub fn new(ctx: &mut EventCtx, app: &App) -> Elevation {
         let mut colorer = ColorNetwork::new(app);
 
+        let mut export = ExportData::new(vec!["percent grade"]);
         let mut max = 0.0_f64;
         for r in app.primary.map.all_roads() {
-            let pct = r.percent_grade(&app.primary.map).abs();
+            let grade = r.percent_grade(&app.primary.map);
+            export.add(
+                ExportObject::Road(r.id),
+                vec![format!("{:.1}", grade * 100.0)],
+            );
+            let pct = grade.abs();
             max = max.max(pct);
 
-            let color = app.cs.good_to_bad_red.eval(
-                // TODO Rescale based on a reasonable steepest grade, once the data doesn't suck
-                pct.max(0.0).min(1.0),
-            );
+            let color = app
+                .cs
+                .good_to_bad_red
+                .eval((pct / STEEPEST_GRADE).max(0.0).min(1.0));
             colorer.add_r(r.id, color);
         }
 
@@ -93,20 +111,25 @@ impl Elevation {
                 "Elevation change".draw_text(ctx),
                 Btn::close(ctx),
             ]),
-            Text::from_multiline(vec![
-                Line(format!("Steepest road: {:.0}% grade", max * 100.0)),
-                Line("Note: elevation data is currently wrong!").secondary(),
-            ])
-            .draw(ctx),
-            ColorLegend::gradient(ctx, &app.cs.good_to_bad_red, vec!["flat", "steep"]),
+            Line(format!("Steepest road: {:.0}% grade", max * 100.0)).draw(ctx),
+            ColorLegend::gradient(
+                ctx,
+                &app.cs.good_to_bad_red,
+                vec![
+                    "flat".to_string(),
+                    format!("{:.0}%+", STEEPEST_GRADE * 100.0),
+                ],
+            ),
         ]))
         .aligned(HorizontalAlignment::Right, VerticalAlignment::Center)
         .build(ctx);
 
         Elevation {
-            unzoomed: ctx.upload(colorer.unzoomed),
+            unzoomed: ctx.upload(colorer.unzoomed.clone()),
             zoomed: ctx.upload(colorer.zoomed),
+            unzoomed_batch: colorer.unzoomed,
             panel,
+            export,
         }
     }
 }
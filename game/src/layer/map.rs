@@ -1,14 +1,14 @@
 use abstutil::{prettyprint_usize, Counter};
 use geom::{Distance, Time};
-use map_model::{LaneType, PathConstraints};
+use map_model::{connectivity, LaneType, LevelOfTrafficStress, PathConstraints};
 use sim::AgentType;
 use widgetry::{
-    Btn, Color, Drawable, EventCtx, GfxCtx, HorizontalAlignment, Line, Panel, Text, TextExt,
-    VerticalAlignment, Widget,
+    tr_n, Btn, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Line, Panel,
+    Text, TextExt, VerticalAlignment, Widget,
 };
 
 use crate::app::App;
-use crate::common::{ColorDiscrete, ColorLegend, ColorNetwork};
+use crate::common::{ColorDiscrete, ColorLegend, ColorNetwork, ExportData, ExportObject};
 use crate::helpers::{amenity_type, ID};
 use crate::layer::{Layer, LayerOutcome};
 
@@ -17,6 +17,8 @@ pub struct BikeNetwork {
     time: Time,
     unzoomed: Drawable,
     zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
+    export: ExportData,
 }
 
 impl Layer for BikeNetwork {
@@ -46,6 +48,12 @@ impl Layer for BikeNetwork {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl BikeNetwork {
@@ -112,7 +120,7 @@ impl BikeNetwork {
                 Btn::close(ctx),
             ]),
             Text::from_multiline(vec![
-                Line(format!("{} lanes", num_lanes)),
+                Line(tr_n("{} lane", "{} lanes", num_lanes)),
                 Line(format!(
                     "total distance of {}",
                     total_dist.to_string(&app.opts.units)
@@ -135,11 +143,30 @@ impl BikeNetwork {
         .aligned(HorizontalAlignment::Right, VerticalAlignment::Center)
         .build(ctx);
 
+        let mut export = ExportData::new(vec!["count", "on bike lanes"]);
+        for (counter, on) in vec![(&on_bike_lanes, true), (&off_bike_lanes, false)] {
+            for (r, cnt) in counter.borrow() {
+                export.add(
+                    ExportObject::Road(*r),
+                    vec![cnt.to_string(), on.to_string()],
+                );
+            }
+        }
+        for (counter, on) in vec![(&intersections_on, true), (&intersections_off, false)] {
+            for (i, cnt) in counter.borrow() {
+                export.add(
+                    ExportObject::Intersection(*i),
+                    vec![cnt.to_string(), on.to_string()],
+                );
+            }
+        }
+
         let mut colorer = ColorNetwork::new(app);
         colorer.ranked_roads(on_bike_lanes, &app.cs.good_to_bad_green);
         colorer.ranked_roads(off_bike_lanes, &app.cs.good_to_bad_red);
         colorer.ranked_intersections(intersections_on, &app.cs.good_to_bad_green);
         colorer.ranked_intersections(intersections_off, &app.cs.good_to_bad_red);
+        let unzoomed_batch = colorer.unzoomed.clone();
         let (unzoomed, zoomed) = colorer.build(ctx);
 
         BikeNetwork {
@@ -147,6 +174,8 @@ impl BikeNetwork {
             time: app.primary.sim.time(),
             unzoomed,
             zoomed,
+            unzoomed_batch,
+            export,
         }
     }
 }
@@ -155,7 +184,9 @@ pub struct Static {
     panel: Panel,
     pub unzoomed: Drawable,
     pub zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
     name: &'static str,
+    export: ExportData,
 }
 
 impl Layer for Static {
@@ -176,16 +207,24 @@ impl Layer for Static {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl Static {
     fn new(
         ctx: &mut EventCtx,
-        colorer: ColorDiscrete,
+        mut colorer: ColorDiscrete,
         name: &'static str,
         title: String,
         extra: Widget,
     ) -> Static {
+        let unzoomed_batch = colorer.unzoomed.clone();
+        let export = colorer.take_export();
         let (unzoomed, zoomed, legend) = colorer.build(ctx);
         let panel = Panel::new(Widget::col(vec![
             Widget::row(vec![
@@ -203,7 +242,9 @@ impl Static {
             panel,
             unzoomed,
             zoomed,
+            unzoomed_batch,
             name,
+            export,
         }
     }
 
@@ -231,7 +272,11 @@ impl Static {
             "map edits",
             format!("Map edits ({})", edits.edits_name),
             Text::from_multiline(vec![
-                Line(format!("{} roads changed", edits.changed_roads.len())),
+                Line(tr_n(
+                    "{} road changed",
+                    "{} roads changed",
+                    edits.changed_roads.len(),
+                )),
                 Line(format!(
                     "{} intersections changed",
                     edits.original_intersections.len()
@@ -283,6 +328,90 @@ impl Static {
         )
     }
 
+    pub fn bike_stress(ctx: &mut EventCtx, app: &App) -> Static {
+        let mut colorer = ColorDiscrete::new(
+            app,
+            vec![
+                ("LTS 1", Color::hex("#2C7BB6")),
+                ("LTS 2", Color::hex("#ABD9E9")),
+                ("LTS 3", Color::hex("#FDAE61")),
+                ("LTS 4", Color::hex("#D7191C")),
+            ],
+        );
+        let mut length_per_level = Counter::new();
+        for r in app.primary.map.all_roads() {
+            if !r
+                .all_lanes()
+                .into_iter()
+                .any(|l| PathConstraints::Bike.can_use(app.primary.map.get_l(l), &app.primary.map))
+            {
+                continue;
+            }
+            let lts = r.bike_lts();
+            let category = match lts {
+                LevelOfTrafficStress::LTS1 => "LTS 1",
+                LevelOfTrafficStress::LTS2 => "LTS 2",
+                LevelOfTrafficStress::LTS3 => "LTS 3",
+                LevelOfTrafficStress::LTS4 => "LTS 4",
+            };
+            colorer.add_r(r.id, category);
+            length_per_level.add(lts, r.center_pts.length().inner_meters() as usize);
+        }
+
+        let total = length_per_level.sum().max(1);
+        let mut txt = Text::from(Line("Share of bikeable road length").secondary());
+        for lts in LevelOfTrafficStress::all() {
+            txt.add(Line(format!(
+                "{}: {}%",
+                lts,
+                100 * length_per_level.get(lts) / total
+            )));
+        }
+        Static::new(
+            ctx,
+            colorer,
+            "bike stress",
+            "Bike level of traffic stress".to_string(),
+            txt.draw(ctx),
+        )
+    }
+
+    pub fn low_stress_islands(ctx: &mut EventCtx, app: &App) -> Static {
+        let mut colorer = ColorDiscrete::new(
+            app,
+            vec![
+                ("largest island", Color::hex("#2C7BB6")),
+                ("second largest island", Color::hex("#1A9641")),
+                ("other islands", Color::hex("#FDAE61")),
+            ],
+        );
+        let islands =
+            connectivity::low_stress_islands(&app.primary.map, LevelOfTrafficStress::LTS2);
+        for (idx, island) in islands.iter().enumerate() {
+            let category = match idx {
+                0 => "largest island",
+                1 => "second largest island",
+                _ => "other islands",
+            };
+            for r in island {
+                colorer.add_r(*r, category);
+            }
+        }
+
+        Static::new(
+            ctx,
+            colorer,
+            "low-stress islands",
+            "Low-stress bike network".to_string(),
+            Text::from_multiline(vec![
+                Line("Roads at LTS 1 or 2, grouped into connected islands").secondary(),
+                Line(tr_n("{} island", "{} islands", islands.len())),
+            ])
+            .wrap_to_pct(ctx, 15)
+            .draw(ctx),
+        )
+    }
+
     pub fn no_sidewalks(ctx: &mut EventCtx, app: &App) -> Static {
         let mut colorer = ColorDiscrete::new(app, vec![("no sidewalks", Color::RED)]);
         for l in app.primary.map.all_lanes() {
@@ -332,7 +461,9 @@ pub struct CongestionCaps {
     time: Time,
     unzoomed: Drawable,
     zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
     tooltip: Option<Text>,
+    export: ExportData,
 }
 
 impl Layer for CongestionCaps {
@@ -393,6 +524,12 @@ impl Layer for CongestionCaps {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl CongestionCaps {
@@ -400,6 +537,7 @@ impl CongestionCaps {
         let mut colorer = ColorNetwork::new(app);
         let map = &app.primary.map;
 
+        let mut export = ExportData::new(vec!["cap per hour", "current"]);
         let mut num_roads = 0;
         for r in map.all_roads() {
             if let Some(cap) = r.access_restrictions.cap_vehicles_per_hour {
@@ -410,6 +548,10 @@ impl CongestionCaps {
                     .find(|l| PathConstraints::Car.can_use(map.get_l(*l), map))
                 {
                     let current = app.primary.sim.get_cap_counter(l);
+                    export.add(
+                        ExportObject::Road(r.id),
+                        vec![cap.to_string(), current.to_string()],
+                    );
                     let pct = ((current as f64) / (cap as f64)).min(1.0);
                     colorer.add_r(r.id, app.cs.good_to_bad_red.eval(pct));
                 }
@@ -422,12 +564,13 @@ impl CongestionCaps {
                 "Congestion caps".draw_text(ctx),
                 Btn::close(ctx),
             ]),
-            format!("{} roads have caps", prettyprint_usize(num_roads)).draw_text(ctx),
+            tr_n("{} road has a cap", "{} roads have caps", num_roads).draw_text(ctx),
             ColorLegend::gradient(ctx, &app.cs.good_to_bad_red, vec!["available", "full"]),
         ]))
         .aligned(HorizontalAlignment::Right, VerticalAlignment::Center)
         .build(ctx);
 
+        let unzoomed_batch = colorer.unzoomed.clone();
         let (unzoomed, zoomed) = colorer.build(ctx);
 
         CongestionCaps {
@@ -435,7 +578,9 @@ impl CongestionCaps {
             time: app.primary.sim.time(),
             unzoomed,
             zoomed,
+            unzoomed_batch,
             tooltip: None,
+            export,
         }
     }
 }
@@ -1,13 +1,17 @@
+use map_model::accessibility::Opportunity;
+use map_model::PathConstraints;
 use widgetry::{
-    Btn, DrawBaselayer, EventCtx, GfxCtx, Key, Line, Outcome, Panel, State, TextExt, Widget,
+    tr_n, Btn, DrawBaselayer, EventCtx, GeomBatch, GfxCtx, Key, Line, Outcome, Panel, State,
+    TextExt, Widget,
 };
 
 use crate::app::App;
-use crate::common::HeatmapOptions;
-use crate::game::Transition;
+use crate::common::{ExportData, HeatmapOptions};
+use crate::game::{PopupMsg, Transition};
 use crate::helpers::{grey_out_map, hotkey_btn};
 use crate::sandbox::dashboards;
 
+mod accessibility;
 mod elevation;
 pub mod map;
 mod pandemic;
@@ -27,6 +31,14 @@ pub trait Layer {
     fn draw(&self, g: &mut GfxCtx, app: &App);
     // Just draw contents and do it always
     fn draw_minimap(&self, g: &mut GfxCtx);
+    /// The unzoomed contents, kept on the CPU so they can be rendered to a file.
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        None
+    }
+    /// The values behind the layer, for exporting to CSV and GeoJSON.
+    fn export_data(&self) -> Option<&ExportData> {
+        None
+    }
 }
 
 impl dyn Layer {
@@ -101,51 +113,42 @@ impl PickLayer {
 
         col.push(btn("None", Key::N));
 
-        col.push(
-            Widget::custom_row(vec![
-                Widget::col(vec![
-                    "Traffic".draw_text(ctx),
-                    btn("delay", Key::D),
-                    btn("throughput", Key::T),
-                    btn("traffic jams", Key::J),
-                ]),
-                Widget::col(vec![
-                    "Map".draw_text(ctx),
-                    btn("map edits", Key::E),
-                    btn("parking occupancy", Key::P),
-                    btn("bike network", Key::B),
-                    btn("transit network", Key::U),
-                    btn("population map", Key::X),
-                    btn("no sidewalks", Key::S),
-                ]),
-            ])
-            .evenly_spaced(),
-        );
+        for pair in LAYERS.chunks(2) {
+            col.push(
+                Widget::custom_row(
+                    pair.iter()
+                        .map(|(category, layers)| {
+                            let mut column = vec![category.draw_text(ctx)];
+                            // The data dashboards aren't layers, but they live alongside them
+                            if *category == "Data" {
+                                column.push(btn("traffic signal demand", Key::M));
+                                column.push(btn("commuter patterns", Key::R));
+                            }
+                            for (name, key) in layers.iter() {
+                                if *name == "pandemic model"
+                                    && app.primary.sim.get_pandemic_model().is_none()
+                                {
+                                    continue;
+                                }
+                                column.push(btn(name, *key));
+                            }
+                            Widget::col(column)
+                        })
+                        .collect(),
+                )
+                .evenly_spaced(),
+            );
+        }
 
-        col.push(
-            Widget::custom_row(vec![
-                Widget::col(vec![
-                    "Experimental".draw_text(ctx),
-                    btn("amenities", Key::A),
-                    btn("backpressure", Key::Z),
-                    btn("elevation", Key::V),
-                    btn("parking efficiency", Key::O),
-                    btn("blackholes", Key::L),
-                    btn("congestion caps", Key::C),
-                    if app.primary.sim.get_pandemic_model().is_some() {
-                        btn("pandemic model", Key::Y)
-                    } else {
-                        Widget::nothing()
-                    },
-                ]),
-                Widget::col(vec![
-                    "Data".draw_text(ctx),
-                    btn("traffic signal demand", Key::M),
-                    btn("commuter patterns", Key::R),
-                ]),
-            ])
-            .evenly_spaced(),
-        );
+        if app
+            .primary
+            .layer
+            .as_ref()
+            .and_then(|l| l.export_data())
+            .is_some()
+        {
+            col.push(Btn::text_bg2("export current layer data").build_def(ctx, None));
+        }
 
         Box::new(PickLayer {
             panel: Panel::new(Widget::col(col))
@@ -163,78 +166,35 @@ impl State<App> for PickLayer {
                 "None" => {
                     app.primary.layer = None;
                 }
-                "amenities" => {
-                    app.primary.layer = Some(Box::new(map::Static::amenities(ctx, app)));
-                }
-                "backpressure" => {
-                    app.primary.layer = Some(Box::new(traffic::Backpressure::new(ctx, app)));
-                }
-                "bike network" => {
-                    app.primary.layer = Some(Box::new(map::BikeNetwork::new(ctx, app)));
-                }
-                "delay" => {
-                    app.primary.layer = Some(Box::new(traffic::Delay::new(ctx, app)));
-                }
-                "elevation" => {
-                    app.primary.layer = Some(Box::new(elevation::Elevation::new(ctx, app)));
-                }
-                "map edits" => {
-                    app.primary.layer = Some(Box::new(map::Static::edits(ctx, app)));
-                }
-                "no sidewalks" => {
-                    app.primary.layer = Some(Box::new(map::Static::no_sidewalks(ctx, app)));
-                }
-                "pandemic model" => {
-                    app.primary.layer = Some(Box::new(pandemic::Pandemic::new(
-                        ctx,
-                        app,
-                        pandemic::Options {
-                            heatmap: Some(HeatmapOptions::new()),
-                            state: pandemic::SEIR::Infected,
-                        },
-                    )));
-                }
-                "blackholes" => {
-                    app.primary.layer = Some(Box::new(map::Static::blackholes(ctx, app)));
-                }
-                "congestion caps" => {
-                    app.primary.layer = Some(Box::new(map::CongestionCaps::new(ctx, app)));
-                }
-                "parking occupancy" => {
-                    app.primary.layer = Some(Box::new(parking::Occupancy::new(
-                        ctx, app, true, true, true, false, true,
-                    )));
-                }
-                "parking efficiency" => {
-                    app.primary.layer = Some(Box::new(parking::Efficiency::new(ctx, app)));
-                }
-                "population map" => {
-                    app.primary.layer = Some(Box::new(population::PopulationMap::new(
-                        ctx,
-                        app,
-                        population::Options {
-                            heatmap: Some(HeatmapOptions::new()),
-                        },
-                    )));
-                }
-                "throughput" => {
-                    app.primary.layer = Some(Box::new(traffic::Throughput::new(ctx, app)));
-                }
-                "traffic jams" => {
-                    app.primary.layer = Some(Box::new(traffic::TrafficJams::new(ctx, app)));
-                }
-                "transit network" => {
-                    app.primary.layer = Some(Box::new(transit::TransitNetwork::new(
-                        ctx, app, false, true, true,
-                    )));
-                }
                 "traffic signal demand" => {
                     return Transition::Replace(dashboards::TrafficSignalDemand::new(ctx, app));
                 }
                 "commuter patterns" => {
                     return Transition::Replace(dashboards::CommuterPatterns::new(ctx, app));
                 }
-                _ => unreachable!(),
+                "export current layer data" => {
+                    let layer = app.primary.layer.as_ref().unwrap();
+                    let name = layer.name().unwrap_or("layer");
+                    let data = layer.export_data().unwrap();
+                    return Transition::Replace(match data.save(app, name) {
+                        Ok(paths) => PopupMsg::new(
+                            ctx,
+                            "Data exported",
+                            vec![tr_n(
+                                "{} row written to:",
+                                "{} rows written to:",
+                                data.len(),
+                            )]
+                            .into_iter()
+                            .chain(paths)
+                            .collect(),
+                        ),
+                        Err(err) => PopupMsg::new(ctx, "Export failed", vec![err]),
+                    });
+                }
+                name => {
+                    app.primary.layer = Some(layer_by_name(ctx, app, name).unwrap());
+                }
             },
             _ => {
                 if self.panel.clicked_outside(ctx) {
@@ -255,3 +215,106 @@ impl State<App> for PickLayer {
         self.panel.draw(g);
     }
 }
+
+/// Every layer offered by `PickLayer`, grouped into the columns shown there. The names match
+/// `layer_by_name`.
+const LAYERS: [(&str, &[(&str, Key)]); 4] = [
+    (
+        "Traffic",
+        &[
+            ("delay", Key::D),
+            ("throughput", Key::T),
+            ("traffic jams", Key::J),
+        ],
+    ),
+    (
+        "Map",
+        &[
+            ("map edits", Key::E),
+            ("parking occupancy", Key::P),
+            ("bike network", Key::B),
+            ("bike stress", Key::K),
+            ("low-stress islands", Key::I),
+            ("transit network", Key::U),
+            ("population map", Key::X),
+            ("no sidewalks", Key::S),
+        ],
+    ),
+    (
+        "Experimental",
+        &[
+            ("amenities", Key::A),
+            ("backpressure", Key::Z),
+            ("elevation", Key::V),
+            ("parking efficiency", Key::O),
+            ("blackholes", Key::L),
+            ("congestion caps", Key::C),
+            ("pandemic model", Key::Y),
+        ],
+    ),
+    ("Data", &[("accessibility", Key::G)]),
+];
+
+/// The names of every layer `layer_by_name` can build.
+pub fn all_layer_names() -> impl Iterator<Item = &'static str> {
+    LAYERS
+        .iter()
+        .flat_map(|(_, layers)| layers.iter().map(|(name, _)| *name))
+}
+
+/// Builds one of the layers offered by `PickLayer`, using the same name as its button and the
+/// default options. Returns `None` for unknown names and for layers that don't apply to the
+/// current simulation.
+pub fn layer_by_name(ctx: &mut EventCtx, app: &App, name: &str) -> Option<Box<dyn Layer>> {
+    Some(match name {
+        "accessibility" => Box::new(accessibility::Accessibility::new(
+            ctx,
+            app,
+            accessibility::Options {
+                constraints: PathConstraints::Pedestrian,
+                opportunity: Opportunity::Groceries,
+                minutes: 15,
+                compare: false,
+            },
+        )),
+        "amenities" => Box::new(map::Static::amenities(ctx, app)),
+        "backpressure" => Box::new(traffic::Backpressure::new(ctx, app)),
+        "bike network" => Box::new(map::BikeNetwork::new(ctx, app)),
+        "delay" => Box::new(traffic::Delay::new(ctx, app)),
+        "elevation" => Box::new(elevation::Elevation::new(ctx, app)),
+        "map edits" => Box::new(map::Static::edits(ctx, app)),
+        "no sidewalks" => Box::new(map::Static::no_sidewalks(ctx, app)),
+        "pandemic model" => {
+            app.primary.sim.get_pandemic_model()?;
+            Box::new(pandemic::Pandemic::new(
+                ctx,
+                app,
+                pandemic::Options {
+                    heatmap: Some(HeatmapOptions::new(app)),
+                    state: pandemic::SEIR::Infected,
+                },
+            ))
+        }
+        "bike stress" => Box::new(map::Static::bike_stress(ctx, app)),
+        "low-stress islands" => Box::new(map::Static::low_stress_islands(ctx, app)),
+        "blackholes" => Box::new(map::Static::blackholes(ctx, app)),
+        "congestion caps" => Box::new(map::CongestionCaps::new(ctx, app)),
+        "parking occupancy" => Box::new(parking::Occupancy::new(
+            ctx, app, true, true, true, false, true,
+        )),
+        "parking efficiency" => Box::new(parking::Efficiency::new(ctx, app)),
+        "population map" => Box::new(population::PopulationMap::new(
+            ctx,
+            app,
+            population::Options {
+                heatmap: Some(HeatmapOptions::new(app)),
+            },
+        )),
+        "throughput" => Box::new(traffic::Throughput::new(ctx, app)),
+        "traffic jams" => Box::new(traffic::TrafficJams::new(ctx, app)),
+        "transit network" => Box::new(transit::TransitNetwork::new(ctx, app, false, true, true)),
+        _ => {
+            return None;
+        }
+    })
+}
@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use abstutil::prettyprint_usize;
+use abstutil::{prettyprint_usize, Counter};
 use geom::{Circle, Distance, Pt2D, Time};
 use sim::PersonState;
 use widgetry::{
@@ -9,7 +9,7 @@ use widgetry::{
 };
 
 use crate::app::App;
-use crate::common::{make_heatmap, HeatmapOptions};
+use crate::common::{make_heatmap, ExportData, ExportObject, HeatmapOptions};
 use crate::layer::{Layer, LayerOutcome};
 
 // TODO Disable drawing unzoomed agents... or alternatively, implement this by asking Sim to
@@ -18,7 +18,9 @@ pub struct Pandemic {
     time: Time,
     opts: Options,
     draw: Drawable,
+    batch: GeomBatch,
     panel: Panel,
+    export: ExportData,
 }
 
 impl Layer for Pandemic {
@@ -46,7 +48,7 @@ impl Layer for Pandemic {
                 _ => unreachable!(),
             },
             _ => {
-                let new_opts = self.options();
+                let new_opts = self.options(app);
                 if self.opts != new_opts {
                     *self = Pandemic::new(ctx, app, new_opts);
                     self.panel.align_above(ctx, minimap);
@@ -64,6 +66,12 @@ impl Layer for Pandemic {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.draw);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl Pandemic {
@@ -93,6 +101,7 @@ impl Pandemic {
         // drawing repeat circles.
         let mut seen_bldgs = HashSet::new();
         let mut repeat_pts = Vec::new();
+        let mut people_per_bldg = Counter::new();
         for person in app.primary.sim.get_all_people() {
             match person.state {
                 // Already covered above
@@ -102,6 +111,7 @@ impl Pandemic {
                         continue;
                     }
 
+                    people_per_bldg.inc(b);
                     let pt = app.primary.map.get_b(b).polygon.center();
                     if seen_bldgs.contains(&b) {
                         repeat_pts.push(pt);
@@ -114,6 +124,15 @@ impl Pandemic {
             }
         }
 
+        // Everyone on a trip is a separate point, then buildings are counted
+        let mut export = ExportData::new(vec!["people"]);
+        for pt in pts.iter().take(pts.len() - seen_bldgs.len()) {
+            export.add(ExportObject::Point(*pt), vec!["1".to_string()]);
+        }
+        for (b, cnt) in people_per_bldg.consume() {
+            export.add(ExportObject::Building(b), vec![cnt.to_string()]);
+        }
+
         let mut batch = GeomBatch::new();
         let legend = if let Some(ref o) = opts.heatmap {
             pts.extend(repeat_pts);
@@ -136,14 +155,16 @@ impl Pandemic {
         Pandemic {
             time: app.primary.sim.time(),
             opts,
-            draw: ctx.upload(batch),
+            draw: ctx.upload(batch.clone()),
+            batch,
             panel: controls,
+            export,
         }
     }
 
-    fn options(&self) -> Options {
+    fn options(&self, app: &App) -> Options {
         let heatmap = if self.panel.is_checked("Show heatmap") {
-            Some(HeatmapOptions::from_controls(&self.panel))
+            Some(HeatmapOptions::from_controls(&self.panel, app))
         } else {
             None
         };
@@ -12,7 +12,7 @@ use widgetry::{
 };
 
 use crate::app::App;
-use crate::common::{ColorLegend, ColorNetwork};
+use crate::common::{ColorLegend, ColorNetwork, ExportData, ExportObject};
 use crate::layer::{Layer, LayerOutcome};
 use crate::render::unzoomed_agent_radius;
 
@@ -25,7 +25,9 @@ pub struct Occupancy {
     looking_for_parking: bool,
     unzoomed: Drawable,
     zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
     panel: Panel,
+    export: ExportData,
 }
 
 impl Layer for Occupancy {
@@ -85,6 +87,12 @@ impl Layer for Occupancy {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl Occupancy {
@@ -142,7 +150,9 @@ impl Occupancy {
                 looking_for_parking: false,
                 unzoomed: ctx.upload(GeomBatch::new()),
                 zoomed: ctx.upload(GeomBatch::new()),
+                unzoomed_batch: GeomBatch::new(),
                 panel,
+                export: ExportData::new(vec!["filled", "available", "percent filled"]),
             };
         }
 
@@ -257,10 +267,23 @@ impl Occupancy {
         .build(ctx);
 
         let mut colorer = ColorNetwork::new(app);
+        let mut export = ExportData::new(vec!["filled", "available", "percent filled"]);
         for loc in keys {
             let open = avail_spots.get(loc);
             let closed = filled_spots.get(loc);
             let percent = (closed as f64) / ((open + closed) as f64);
+            export.add(
+                match loc {
+                    Loc::Road(r) => ExportObject::Road(r),
+                    Loc::Bldg(b) => ExportObject::Building(b),
+                    Loc::Lot(pl) => ExportObject::ParkingLot(pl),
+                },
+                vec![
+                    closed.to_string(),
+                    open.to_string(),
+                    format!("{:.1}", 100.0 * percent),
+                ],
+            );
             let color = app.cs.good_to_bad_red.eval(percent);
             match loc {
                 Loc::Road(r) => colorer.add_r(r, color),
@@ -286,6 +309,7 @@ impl Occupancy {
             }
         }
 
+        let unzoomed_batch = colorer.unzoomed.clone();
         let (unzoomed, zoomed) = colorer.build(ctx);
 
         Occupancy {
@@ -297,7 +321,9 @@ impl Occupancy {
             looking_for_parking,
             unzoomed,
             zoomed,
+            unzoomed_batch,
             panel,
+            export,
         }
     }
 }
@@ -323,7 +349,9 @@ pub struct Efficiency {
     time: Time,
     unzoomed: Drawable,
     zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
     panel: Panel,
+    export: ExportData,
 }
 
 impl Layer for Efficiency {
@@ -363,6 +391,12 @@ impl Layer for Efficiency {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl Efficiency {
@@ -389,60 +423,68 @@ impl Efficiency {
 
         let map = &app.primary.map;
         // TODO This is going to spam constantly while the sim is running! Probably cache per car.
-        let (unzoomed, zoomed) = ctx.loading_screen("measure parking efficiency", |ctx, timer| {
-            let mut unzoomed = GeomBatch::new();
-            let mut zoomed = GeomBatch::new();
+        let (unzoomed_batch, zoomed, export) =
+            ctx.loading_screen("measure parking efficiency", |ctx, timer| {
+                let mut unzoomed = GeomBatch::new();
+                let mut zoomed = GeomBatch::new();
+                let mut export = ExportData::new(vec!["walk to car (minutes)"]);
 
-            timer.start("gather requests");
-            let requests: Vec<PathRequest> = app
-                .primary
-                .sim
-                .all_parked_car_positions(map)
-                .into_iter()
-                .map(|(start, end)| PathRequest {
-                    start,
-                    end,
-                    constraints: PathConstraints::Pedestrian,
-                })
-                .collect();
-            timer.stop("gather requests");
-            for (car_pt, dist) in timer
-                .parallelize("calculate paths", Parallelism::Fastest, requests, |req| {
-                    let car_pt = req.start.pt(map);
-                    // TODO Walking paths should really return some indication of "zero length
-                    // path" for this
-                    if req.start == req.end {
-                        Some((car_pt, Distance::ZERO))
-                    } else {
-                        map.pathfind(req).map(|path| (car_pt, path.total_length()))
-                    }
-                })
-                .into_iter()
-                .flatten()
-            {
-                // TODO Actual car shapes? At least cache the circle?
-                let time = dist / Scenario::max_ped_speed();
-                let color = app
-                    .cs
-                    .good_to_bad_red
-                    .eval((time / Duration::minutes(10)).min(1.0));
-                unzoomed.push(
-                    color,
-                    Circle::new(car_pt, Distance::meters(5.0)).to_polygon(),
-                );
-                zoomed.push(
-                    color.alpha(0.5),
-                    Circle::new(car_pt, Distance::meters(2.0)).to_polygon(),
-                );
-            }
-            (ctx.upload(unzoomed), ctx.upload(zoomed))
-        });
+                timer.start("gather requests");
+                let requests: Vec<PathRequest> = app
+                    .primary
+                    .sim
+                    .all_parked_car_positions(map)
+                    .into_iter()
+                    .map(|(start, end)| PathRequest {
+                        start,
+                        end,
+                        constraints: PathConstraints::Pedestrian,
+                    })
+                    .collect();
+                timer.stop("gather requests");
+                for (car_pt, dist) in timer
+                    .parallelize("calculate paths", Parallelism::Fastest, requests, |req| {
+                        let car_pt = req.start.pt(map);
+                        // TODO Walking paths should really return some indication of "zero length
+                        // path" for this
+                        if req.start == req.end {
+                            Some((car_pt, Distance::ZERO))
+                        } else {
+                            map.pathfind(req).map(|path| (car_pt, path.total_length()))
+                        }
+                    })
+                    .into_iter()
+                    .flatten()
+                {
+                    // TODO Actual car shapes? At least cache the circle?
+                    let time = dist / Scenario::max_ped_speed();
+                    export.add(
+                        ExportObject::Point(car_pt),
+                        vec![format!("{:.1}", time.inner_seconds() / 60.0)],
+                    );
+                    let color = app
+                        .cs
+                        .good_to_bad_red
+                        .eval((time / Duration::minutes(10)).min(1.0));
+                    unzoomed.push(
+                        color,
+                        Circle::new(car_pt, Distance::meters(5.0)).to_polygon(),
+                    );
+                    zoomed.push(
+                        color.alpha(0.5),
+                        Circle::new(car_pt, Distance::meters(2.0)).to_polygon(),
+                    );
+                }
+                (unzoomed, ctx.upload(zoomed), export)
+            });
 
         Efficiency {
             time: app.primary.sim.time(),
-            unzoomed,
+            unzoomed: ctx.upload(unzoomed_batch.clone()),
             zoomed,
+            unzoomed_batch,
             panel,
+            export,
         }
     }
 }
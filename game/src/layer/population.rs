@@ -1,6 +1,6 @@
 use std::collections::HashSet;
 
-use abstutil::prettyprint_usize;
+use abstutil::{prettyprint_usize, Counter};
 use geom::{Circle, Distance, Pt2D, Time};
 use sim::PersonState;
 use widgetry::{
@@ -9,7 +9,7 @@ use widgetry::{
 };
 
 use crate::app::App;
-use crate::common::{make_heatmap, HeatmapOptions};
+use crate::common::{make_heatmap, ExportData, ExportObject, HeatmapOptions};
 use crate::layer::{Layer, LayerOutcome};
 
 // TODO Disable drawing unzoomed agents... or alternatively, implement this by asking Sim to
@@ -18,7 +18,9 @@ pub struct PopulationMap {
     time: Time,
     opts: Options,
     draw: Drawable,
+    batch: GeomBatch,
     panel: Panel,
+    export: ExportData,
 }
 
 impl Layer for PopulationMap {
@@ -47,7 +49,7 @@ impl Layer for PopulationMap {
                 _ => unreachable!(),
             },
             _ => {
-                let new_opts = self.options();
+                let new_opts = self.options(app);
                 if self.opts != new_opts {
                     *self = PopulationMap::new(ctx, app, new_opts);
                     self.panel.align_above(ctx, minimap);
@@ -65,6 +67,12 @@ impl Layer for PopulationMap {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.draw);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl PopulationMap {
@@ -82,11 +90,13 @@ impl PopulationMap {
         // drawing repeat circles.
         let mut seen_bldgs = HashSet::new();
         let mut repeat_pts = Vec::new();
+        let mut people_per_bldg = Counter::new();
         for person in app.primary.sim.get_all_people() {
             match person.state {
                 // Already covered above
                 PersonState::Trip(_) => {}
                 PersonState::Inside(b) => {
+                    people_per_bldg.inc(b);
                     let pt = app.primary.map.get_b(b).polygon.center();
                     if seen_bldgs.contains(&b) {
                         repeat_pts.push(pt);
@@ -99,6 +109,15 @@ impl PopulationMap {
             }
         }
 
+        // Everyone on a trip is a separate point, then buildings are counted
+        let mut export = ExportData::new(vec!["people"]);
+        for pt in pts.iter().take(pts.len() - seen_bldgs.len()) {
+            export.add(ExportObject::Point(*pt), vec!["1".to_string()]);
+        }
+        for (b, cnt) in people_per_bldg.consume() {
+            export.add(ExportObject::Building(b), vec![cnt.to_string()]);
+        }
+
         let mut batch = GeomBatch::new();
         let legend = if let Some(ref o) = opts.heatmap {
             pts.extend(repeat_pts);
@@ -121,14 +140,16 @@ impl PopulationMap {
         PopulationMap {
             time: app.primary.sim.time(),
             opts,
-            draw: ctx.upload(batch),
+            draw: ctx.upload(batch.clone()),
+            batch,
             panel: controls,
+            export,
         }
     }
 
-    fn options(&self) -> Options {
+    fn options(&self, app: &App) -> Options {
         let heatmap = if self.panel.is_checked("Show heatmap") {
-            Some(HeatmapOptions::from_controls(&self.panel))
+            Some(HeatmapOptions::from_controls(&self.panel, app))
         } else {
             None
         };
@@ -7,12 +7,12 @@ use geom::{Circle, Distance, Duration, Polygon, Pt2D, Time};
 use map_model::{IntersectionID, Map, Traversable};
 use sim::VehicleType;
 use widgetry::{
-    Btn, Checkbox, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Line,
+    tr_n, Btn, Checkbox, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Line,
     Outcome, Panel, Text, TextExt, VerticalAlignment, Widget,
 };
 
 use crate::app::App;
-use crate::common::{ColorLegend, ColorNetwork, DivergingScale};
+use crate::common::{ColorLegend, ColorNetwork, DivergingScale, ExportData, ExportObject};
 use crate::helpers::ID;
 use crate::layer::{Layer, LayerOutcome};
 use crate::render::unzoomed_agent_radius;
@@ -21,7 +21,9 @@ pub struct Backpressure {
     time: Time,
     unzoomed: Drawable,
     zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
     panel: Panel,
+    export: ExportData,
 }
 
 impl Layer for Backpressure {
@@ -51,6 +53,12 @@ impl Layer for Backpressure {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl Backpressure {
@@ -91,16 +99,20 @@ impl Backpressure {
         .aligned(HorizontalAlignment::Right, VerticalAlignment::Center)
         .build(ctx);
 
+        let export = ExportData::counts(&cnt_per_r, &cnt_per_i);
         let mut colorer = ColorNetwork::new(app);
         colorer.pct_roads(cnt_per_r, &app.cs.good_to_bad_red);
         colorer.pct_intersections(cnt_per_i, &app.cs.good_to_bad_red);
+        let unzoomed_batch = colorer.unzoomed.clone();
         let (unzoomed, zoomed) = colorer.build(ctx);
 
         Backpressure {
             time: app.primary.sim.time(),
             unzoomed,
             zoomed,
+            unzoomed_batch,
             panel,
+            export,
         }
     }
 }
@@ -111,7 +123,9 @@ pub struct Throughput {
     tooltip: Option<Text>,
     unzoomed: Drawable,
     zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
     panel: Panel,
+    export: ExportData,
 }
 
 impl Layer for Throughput {
@@ -190,6 +204,12 @@ impl Layer for Throughput {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl Throughput {
@@ -216,9 +236,11 @@ impl Throughput {
         .aligned(HorizontalAlignment::Right, VerticalAlignment::Center)
         .build(ctx);
 
+        let export = ExportData::counts(&road_counter, &intersection_counter);
         let mut colorer = ColorNetwork::new(app);
         colorer.ranked_roads(road_counter, &app.cs.good_to_bad_red);
         colorer.ranked_intersections(intersection_counter, &app.cs.good_to_bad_red);
+        let unzoomed_batch = colorer.unzoomed.clone();
         let (unzoomed, zoomed) = colorer.build(ctx);
 
         Throughput {
@@ -226,7 +248,9 @@ impl Throughput {
             tooltip: None,
             unzoomed,
             zoomed,
+            unzoomed_batch,
             panel,
+            export,
         }
     }
 }
@@ -235,7 +259,9 @@ pub struct CompareThroughput {
     time: Time,
     unzoomed: Drawable,
     zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
     panel: Panel,
+    export: ExportData,
 }
 
 impl Layer for CompareThroughput {
@@ -278,6 +304,12 @@ impl Layer for CompareThroughput {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl CompareThroughput {
@@ -319,12 +351,21 @@ impl CompareThroughput {
             .range(0.0, 2.0)
             .ignore(0.7, 1.3);
 
+        let mut export = ExportData::new(vec!["before", "after"]);
         for (r, before, after) in before_road.compare(after_road) {
+            export.add(
+                ExportObject::Road(r),
+                vec![before.to_string(), after.to_string()],
+            );
             if let Some(c) = scale.eval((after as f64) / (before as f64)) {
                 colorer.add_r(r, c);
             }
         }
         for (i, before, after) in before_intersection.compare(after_intersection) {
+            export.add(
+                ExportObject::Intersection(i),
+                vec![before.to_string(), after.to_string()],
+            );
             if let Some(c) = scale.eval((after as f64) / (before as f64)) {
                 colorer.add_i(i, c);
             }
@@ -341,13 +382,16 @@ impl CompareThroughput {
         ]))
         .aligned(HorizontalAlignment::Right, VerticalAlignment::Center)
         .build(ctx);
+        let unzoomed_batch = colorer.unzoomed.clone();
         let (unzoomed, zoomed) = colorer.build(ctx);
 
         CompareThroughput {
             time: app.primary.sim.time(),
             unzoomed,
             zoomed,
+            unzoomed_batch,
             panel,
+            export,
         }
     }
 }
@@ -356,7 +400,9 @@ pub struct TrafficJams {
     time: Time,
     unzoomed: Drawable,
     zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
     panel: Panel,
+    export: ExportData,
 }
 
 impl Layer for TrafficJams {
@@ -386,6 +432,12 @@ impl Layer for TrafficJams {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl TrafficJams {
@@ -397,12 +449,15 @@ impl TrafficJams {
             app.primary.map.get_boundary_polygon().clone(),
         );
         let mut zoomed = GeomBatch::new();
-        let mut cnt = 0;
-        for (epicenter, boundary) in cluster_jams(
+        let mut export = ExportData::new(vec!["epicenter", "intersections"]);
+        for (epicenter_id, num_members, epicenter, boundary) in cluster_jams(
             &app.primary.map,
             app.primary.sim.delayed_intersections(Duration::minutes(5)),
         ) {
-            cnt += 1;
+            export.add(
+                ExportObject::Area(boundary.clone()),
+                vec![epicenter_id.0.to_string(), num_members.to_string()],
+            );
             unzoomed.push(
                 Color::RED,
                 boundary.to_outline(Distance::meters(5.0)).unwrap(),
@@ -429,16 +484,18 @@ impl TrafficJams {
             )
             .wrap_to_pct(ctx, 15)
             .draw(ctx),
-            format!("{} jams detected", cnt).draw_text(ctx),
+            tr_n("{} jam detected", "{} jams detected", export.len()).draw_text(ctx),
         ]))
         .aligned(HorizontalAlignment::Right, VerticalAlignment::Center)
         .build(ctx);
 
         TrafficJams {
             time: app.primary.sim.time(),
-            unzoomed: ctx.upload(unzoomed),
+            unzoomed: ctx.upload(unzoomed.clone()),
             zoomed: ctx.upload(zoomed),
+            unzoomed_batch: unzoomed,
             panel,
+            export,
         }
     }
 }
@@ -448,8 +505,11 @@ struct Jam {
     members: BTreeSet<IntersectionID>,
 }
 
-// (Epicenter, entire shape)
-fn cluster_jams(map: &Map, problems: Vec<(IntersectionID, Time)>) -> Vec<(Polygon, Polygon)> {
+// (Epicenter, number of intersections, epicenter shape, entire shape)
+fn cluster_jams(
+    map: &Map,
+    problems: Vec<(IntersectionID, Time)>,
+) -> Vec<(IntersectionID, usize, Polygon, Polygon)> {
     let mut jams: Vec<Jam> = Vec::new();
     // The delay itself doesn't matter, as long as they're sorted.
     for (i, _) in problems {
@@ -467,6 +527,8 @@ fn cluster_jams(map: &Map, problems: Vec<(IntersectionID, Time)>) -> Vec<(Polygo
     jams.into_iter()
         .map(|jam| {
             (
+                jam.epicenter,
+                jam.members.len(),
                 map.get_i(jam.epicenter).polygon.clone(),
                 Polygon::convex_hull(jam.all_polygons(map)),
             )
@@ -498,7 +560,9 @@ impl Jam {
 pub struct Delay {
     time: Time,
     unzoomed: Drawable,
+    unzoomed_batch: GeomBatch,
     panel: Panel,
+    export: ExportData,
 }
 
 impl Layer for Delay {
@@ -536,6 +600,12 @@ impl Layer for Delay {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl Delay {
@@ -553,8 +623,20 @@ impl Delay {
         )
         .to_polygon();
         let ped_circle = Circle::new(Pt2D::new(0.0, 0.0), unzoomed_agent_radius(None)).to_polygon();
+        let mut export = ExportData::new(vec!["person", "delay (minutes)"]);
         for agent in app.primary.sim.get_unzoomed_agents(&app.primary.map) {
-            if let Some(delay) = agent.person.and_then(|p| delays.remove(&p)) {
+            let person = match agent.person {
+                Some(p) => p,
+                None => continue,
+            };
+            if let Some(delay) = delays.remove(&person) {
+                export.add(
+                    ExportObject::Point(agent.pos),
+                    vec![
+                        person.0.to_string(),
+                        format!("{:.1}", delay.inner_seconds() / 60.0),
+                    ],
+                );
                 let color = app
                     .cs
                     .good_to_bad_red
@@ -569,7 +651,8 @@ impl Delay {
 
         Delay {
             time: app.primary.sim.time(),
-            unzoomed: ctx.upload(unzoomed),
+            unzoomed: ctx.upload(unzoomed.clone()),
+            unzoomed_batch: unzoomed,
             panel: Panel::new(Widget::col(vec![
                 Widget::row(vec![
                     Widget::draw_svg(ctx, "system/assets/tools/layers.svg"),
@@ -580,6 +663,7 @@ impl Delay {
             ]))
             .aligned(HorizontalAlignment::Right, VerticalAlignment::Center)
             .build(ctx),
+            export,
         }
     }
 }
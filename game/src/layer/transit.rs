@@ -1,17 +1,19 @@
 use map_model::{PathConstraints, PathStep};
 use widgetry::{
-    Btn, Checkbox, Drawable, EventCtx, GfxCtx, HorizontalAlignment, Outcome, Panel, TextExt,
-    VerticalAlignment, Widget,
+    Btn, Checkbox, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Outcome, Panel,
+    TextExt, VerticalAlignment, Widget,
 };
 
 use crate::app::App;
-use crate::common::ColorDiscrete;
+use crate::common::{ColorDiscrete, ExportData};
 use crate::layer::{Layer, LayerOutcome};
 
 pub struct TransitNetwork {
     panel: Panel,
     unzoomed: Drawable,
     zoomed: Drawable,
+    unzoomed_batch: GeomBatch,
+    export: ExportData,
 }
 
 impl Layer for TransitNetwork {
@@ -57,6 +59,12 @@ impl Layer for TransitNetwork {
     fn draw_minimap(&self, g: &mut GfxCtx) {
         g.redraw(&self.unzoomed);
     }
+    fn export_batch(&self) -> Option<&GeomBatch> {
+        Some(&self.unzoomed_batch)
+    }
+    fn export_data(&self) -> Option<&ExportData> {
+        Some(&self.export)
+    }
 }
 
 impl TransitNetwork {
@@ -112,6 +120,8 @@ impl TransitNetwork {
                 }
             }
         }
+        let export = colorer.take_export();
+        let unzoomed_batch = colorer.unzoomed.clone();
         let (unzoomed, zoomed, legend) = colorer.build(ctx);
 
         let panel = Panel::new(Widget::col(vec![
@@ -132,6 +142,8 @@ impl TransitNetwork {
             panel,
             unzoomed,
             zoomed,
+            unzoomed_batch,
+            export,
         }
     }
 }
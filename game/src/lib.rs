@@ -73,6 +73,16 @@ pub fn main(mut args: CmdArgs) {
             );
         }
     }
+    if let Some(lang) = args.optional("--ui_language") {
+        match options::set_ui_language(Some(lang.clone())) {
+            Ok(()) => {
+                opts.ui_language = Some(lang);
+            }
+            Err(err) => {
+                warn!("Invalid --ui_language={}, using English: {}", lang, err);
+            }
+        }
+    }
     let mut settings = widgetry::Settings::new("A/B Street");
     settings.window_icon(abstutil::path("system/assets/pregame/icon.png"));
     if args.enabled("--dump_raw_events") {
@@ -124,9 +134,15 @@ pub fn main(mut args: CmdArgs) {
     }
     let start_with_edits = args.optional("--edits");
     let osm_viewer = args.enabled("--osm");
+    let render_map_to = args.optional("--render_map_to");
 
     args.done();
 
+    if let Some(path) = render_map_to {
+        render_map_to_file(&flags, &opts, &path);
+        return;
+    }
+
     widgetry::run(settings, |ctx| {
         setup_app(ctx, flags, opts, start_with_edits, mode, osm_viewer)
     });
@@ -197,6 +213,20 @@ fn setup_app(
     (app, states)
 }
 
+/// Renders the unzoomed map to a PNG or SVG without opening a window.
+fn render_map_to_file(flags: &Flags, opts: &Options, path: &str) {
+    let mut timer = Timer::new(format!("render map to {}", path));
+    let (map, _, _) = flags.sim_flags.load(&mut timer);
+    let cs = colors::ColorScheme::new(opts.color_scheme.clone());
+    let batch = render::DrawMap::unzoomed_batch(&map, &cs);
+    timer.start("rasterize");
+    if let Err(err) = batch.render_to_file(&map.get_bounds(), path) {
+        panic!("{}", err);
+    }
+    timer.stop("rasterize");
+    println!("Rendered {} to {}", map.get_name(), path);
+}
+
 fn smoke_test() {
     let mut timer = Timer::new("run a smoke-test for all maps");
     for name in abstutil::list_all_objects(abstutil::path_all_maps()) {
@@ -263,8 +293,15 @@ fn check_proposals() {
         ) {
             Ok(perma) => {
                 let map = map_model::Map::new(abstutil::path_map(&perma.map_name), &mut timer);
-                if let Err(err) = map_model::PermanentMapEdits::from_permanent(perma, &map) {
-                    timer.error(format!("{} is out-of-date: {}", name, err));
+                match map_model::PermanentMapEdits::from_permanent(perma, &map) {
+                    Ok((_, relocations)) => {
+                        for relocation in relocations {
+                            timer.warn(format!("{}: {}", name, relocation));
+                        }
+                    }
+                    Err(err) => {
+                        timer.error(format!("{} is out-of-date: {}", name, err));
+                    }
                 }
             }
             Err(err) => {
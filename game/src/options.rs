@@ -1,4 +1,5 @@
 use geom::{Duration, UnitFmt};
+use widgetry::i18n::{self, Catalog};
 use widgetry::{
     Btn, Checkbox, Choice, EventCtx, GeomBatch, GfxCtx, Key, Line, Outcome, Panel, Spinner, State,
     TextExt, Widget,
@@ -6,7 +7,7 @@ use widgetry::{
 
 use crate::app::App;
 use crate::colors::{ColorScheme, ColorSchemeChoice};
-use crate::game::Transition;
+use crate::game::{PopupMsg, Transition};
 use crate::helpers::grey_out_map;
 use crate::render::{DrawBuilding, DrawMap};
 
@@ -41,6 +42,8 @@ pub struct Options {
     /// Display roads and buildings in an alternate language, if possible. None means to use the
     /// OSM native name.
     pub language: Option<String>,
+    /// Translate the UI, using a catalog in data/system/translations. None means English.
+    pub ui_language: Option<String>,
     /// How to render geometric units
     pub units: UnitFmt,
 }
@@ -61,6 +64,7 @@ impl Options {
             jump_to_delay: Duration::minutes(5),
 
             language: None,
+            ui_language: None,
             units: UnitFmt {
                 round_durations: true,
                 // TODO Should default be based on the map?
@@ -174,7 +178,7 @@ impl OptionsPanel {
                         Widget::dropdown(
                             ctx,
                             "Color scheme",
-                            app.opts.color_scheme,
+                            app.opts.color_scheme.clone(),
                             ColorSchemeChoice::choices(),
                         ),
                     ]),
@@ -205,6 +209,19 @@ impl OptionsPanel {
                             choices
                         }),
                     ]),
+                    Widget::row(vec![
+                        "UI language".draw_text(ctx),
+                        Widget::dropdown(ctx, "UI language", app.opts.ui_language.clone(), {
+                            let mut choices = Vec::new();
+                            choices.push(Choice::new("English", None));
+                            for lang in
+                                abstutil::list_all_objects(abstutil::path("system/translations"))
+                            {
+                                choices.push(Choice::new(lang.clone(), Some(lang)));
+                            }
+                            choices
+                        }),
+                    ]),
                     Checkbox::toggle(
                         ctx,
                         "metric / imperial units",
@@ -232,11 +249,26 @@ impl OptionsPanel {
                     .build_def(ctx, Key::Enter)
                     .centered_horiz(),
             ]))
+            .keyboard_focus()
             .build(ctx),
         })
     }
 }
 
+/// Loads the translation catalog for a language, or switches back to English. Panels built
+/// afterwards will use the new language.
+pub fn set_ui_language(language: Option<String>) -> Result<(), String> {
+    let catalog = match language {
+        Some(lang) => Some(Catalog::load(abstutil::path(format!(
+            "system/translations/{}.json",
+            lang
+        )))?),
+        None => None,
+    };
+    i18n::set_catalog(catalog);
+    Ok(())
+}
+
 impl State<App> for OptionsPanel {
     fn event(&mut self, ctx: &mut EventCtx, app: &mut App) -> Transition {
         match self.panel.event(ctx) {
@@ -308,7 +340,7 @@ impl State<App> for OptionsPanel {
                     let scheme = self.panel.dropdown_value("Color scheme");
                     if app.opts.color_scheme != scheme {
                         app.opts.color_scheme = scheme;
-                        app.cs = ColorScheme::new(app.opts.color_scheme);
+                        app.cs = ColorScheme::new(app.opts.color_scheme.clone());
                         ctx.set_style(app.cs.gui_style.clone());
 
                         ctx.loading_screen("rerendering map colors", |ctx, timer| {
@@ -330,6 +362,18 @@ impl State<App> for OptionsPanel {
                         }
                     }
 
+                    let ui_language = self.panel.dropdown_value("UI language");
+                    if ui_language != app.opts.ui_language {
+                        match set_ui_language(ui_language.clone()) {
+                            Ok(()) => {
+                                app.opts.ui_language = ui_language;
+                            }
+                            Err(err) => {
+                                return Transition::Replace(PopupMsg::new(ctx, "Error", vec![err]));
+                            }
+                        }
+                    }
+
                     return Transition::Pop;
                 }
                 _ => unreachable!(),
@@ -422,7 +422,10 @@ impl State<App> for Proposals {
                             // Apply edits before setting up the sandbox, for simplicity
                             let maybe_err = ctx.loading_screen("apply edits", |ctx, mut timer| {
                                 match PermanentMapEdits::from_permanent(edits, &app.primary.map) {
-                                    Ok(edits) => {
+                                    Ok((edits, relocations)) => {
+                                        for relocation in relocations {
+                                            timer.warn(relocation);
+                                        }
                                         apply_map_edits(ctx, app, edits);
                                         app.primary
                                             .map
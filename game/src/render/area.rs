@@ -1,5 +1,6 @@
 use geom::Polygon;
-use map_model::{Area, AreaID, AreaType, Map};
+use map_model::osm::RoadRank;
+use map_model::{Area, AreaID, AreaType, LaneType, Map};
 use widgetry::{Color, EventCtx, Fill, GeomBatch, GfxCtx, Line, Text};
 
 use crate::app::App;
@@ -43,6 +44,9 @@ impl DrawArea {
             AreaType::Water => cs.water.clone(),
             AreaType::PedestrianIsland => Color::grey(0.3).into(),
             AreaType::Island => cs.map_background.clone(),
+            AreaType::PedestrianPlaza => cs
+                .zoomed_road_surface(LaneType::Sidewalk, RoadRank::Local)
+                .into(),
         }
     }
 }
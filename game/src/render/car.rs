@@ -238,6 +238,8 @@ fn zoomed_color_car(input: &DrawCarInput, cs: &ColorScheme) -> Color {
         cs.bus_body
     } else if input.id.1 == VehicleType::Train {
         cs.train_body
+    } else if input.id.1 == VehicleType::Truck {
+        cs.truck_body
     } else {
         match input.status {
             CarStatus::Moving => cs.rotating_color_agents(input.id.0),
@@ -93,7 +93,7 @@ impl DrawIntersection {
                         .centered_on(i.polygon.center()),
                 );
             }
-            IntersectionType::TrafficSignal => {}
+            IntersectionType::TrafficSignal | IntersectionType::Roundabout => {}
         }
 
         let zorder = i.get_zorder(map);
@@ -69,7 +69,7 @@ impl DrawLane {
                 }
             }
             LaneType::Shoulder => {}
-            LaneType::Parking => {
+            LaneType::Parking | LaneType::LoadingZone => {
                 draw.extend(general_road_marking, calculate_parking_lines(lane, map));
             }
             LaneType::Driving | LaneType::Bus => {
@@ -214,6 +214,13 @@ impl DrawMap {
         timer: &mut Timer,
     ) -> Drawable {
         timer.start("generate unzoomed roads and intersections");
+        let draw_all_unzoomed_roads_and_intersections =
+            DrawMap::unzoomed_roads_and_intersections(map, cs).upload(ctx);
+        timer.stop("generate unzoomed roads and intersections");
+        draw_all_unzoomed_roads_and_intersections
+    }
+
+    fn unzoomed_roads_and_intersections(map: &Map, cs: &ColorScheme) -> GeomBatch {
         let mut unzoomed_pieces: Vec<(isize, Polygon, Color)> = Vec::new();
         for r in map.all_roads() {
             unzoomed_pieces.push((
@@ -250,9 +257,35 @@ impl DrawMap {
         for (_, poly, color) in unzoomed_pieces {
             unzoomed_batch.push(color, poly);
         }
-        let draw_all_unzoomed_roads_and_intersections = unzoomed_batch.upload(ctx);
-        timer.stop("generate unzoomed roads and intersections");
-        draw_all_unzoomed_roads_and_intersections
+        unzoomed_batch
+    }
+
+    /// Everything in the unzoomed view of the map, built without touching the GPU. Use with
+    /// `GeomBatch::render_to_file` to make figures from batch tools.
+    pub fn unzoomed_batch(map: &Map, cs: &ColorScheme) -> GeomBatch {
+        let mut batch = GeomBatch::new();
+        batch.push(
+            cs.map_background.clone(),
+            map.get_boundary_polygon().clone(),
+        );
+        for a in map.all_areas() {
+            batch.push(DrawArea::fill(a.area_type, cs), a.polygon.clone());
+        }
+        for pl in map.all_parking_lots() {
+            batch.push(cs.parking_lot, pl.polygon.clone());
+        }
+        batch.append(DrawMap::unzoomed_roads_and_intersections(map, cs));
+        for b in map.all_buildings() {
+            batch.push(
+                if b.amenities.is_empty() {
+                    cs.residential_building
+                } else {
+                    cs.commerical_building
+                },
+                b.polygon.clone(),
+            );
+        }
+        batch
     }
 
     // The alt to these is implementing std::ops::Index, but that's way more verbose!
@@ -506,7 +539,7 @@ impl UnzoomedAgents {
 
     pub fn color(&self, agent: &UnzoomedAgent) -> Option<Color> {
         match agent.id.to_vehicle_type() {
-            Some(VehicleType::Car) => {
+            Some(VehicleType::Car) | Some(VehicleType::Truck) => {
                 if self.cars {
                     Some(self.car_color)
                 } else {
@@ -536,4 +569,27 @@ impl UnzoomedAgents {
             }
         }
     }
+
+    /// Draws agents from somewhere besides the primary simulation, like a recording or a second
+    /// simulation.
+    pub fn batch(&self, agents: &[UnzoomedAgent]) -> GeomBatch {
+        let mut batch = GeomBatch::new();
+        let car_circle = Circle::new(
+            Pt2D::new(0.0, 0.0),
+            unzoomed_agent_radius(Some(VehicleType::Car)),
+        )
+        .to_polygon();
+        let ped_circle = Circle::new(Pt2D::new(0.0, 0.0), unzoomed_agent_radius(None)).to_polygon();
+        for agent in agents {
+            if let Some(color) = self.color(agent) {
+                let circle = if agent.id.to_vehicle_type().is_some() {
+                    &car_circle
+                } else {
+                    &ped_circle
+                };
+                batch.push(color, circle.translate(agent.pos.x(), agent.pos.y()));
+            }
+        }
+        batch
+    }
 }
@@ -0,0 +1,381 @@
+//! Run the baseline and the current proposal in lockstep, showing both maps side-by-side with the
+//! same camera. The baseline is the unedited map, with the same scenario and RNG seed.
+
+use std::collections::BTreeMap;
+
+use abstutil::Timer;
+use geom::{Duration, Polygon, Time};
+use map_model::{IntersectionID, Map};
+use sim::{Analytics, Scenario, Sim};
+use widgetry::{
+    Btn, Choice, Color, DrawBaselayer, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment,
+    Line, Outcome, Panel, ScreenPt, ScreenRectangle, State, Text, TextExt, UpdateType,
+    VerticalAlignment, Widget,
+};
+
+use crate::app::{App, PerMap};
+use crate::common::{ColorNetwork, DivergingScale};
+use crate::game::{PopupMsg, Transition};
+use crate::sandbox::gameplay::LoadScenario;
+use crate::sandbox::GameplayMode;
+
+pub struct CompareSims {
+    panel: Panel,
+    paused: bool,
+    time: Time,
+    baseline_agents: Drawable,
+    proposal_agents: Drawable,
+    // Unzoomed and zoomed
+    diff: Option<(Drawable, Drawable)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Metric {
+    Throughput,
+    Delay,
+}
+
+impl CompareSims {
+    pub fn new(ctx: &mut EventCtx, app: &mut App, mode: &GameplayMode) -> Box<dyn State<App>> {
+        if app.primary.map.get_edits().commands.is_empty() {
+            return PopupMsg::new(
+                ctx,
+                "Nothing to compare",
+                vec!["Edit the map first, then compare your proposal to the baseline."],
+            );
+        }
+        let baseline = match ctx.loading_screen("load baseline simulation", |ctx, mut timer| {
+            load_baseline(ctx, app, mode, &mut timer)
+        }) {
+            Ok(baseline) => baseline,
+            Err(err) => {
+                return PopupMsg::new(ctx, "Can't load the baseline", vec![err]);
+            }
+        };
+        app.secondary = Some(baseline);
+
+        let mut state = CompareSims {
+            panel: make_panel(ctx, app, true, 1.0, Some(Metric::Throughput)),
+            paused: true,
+            time: app.primary.sim.time(),
+            baseline_agents: ctx.upload(GeomBatch::new()),
+            proposal_agents: ctx.upload(GeomBatch::new()),
+            diff: None,
+        };
+        state.recalculate(ctx, app);
+        Box::new(state)
+    }
+
+    fn metric(&self) -> Option<Metric> {
+        self.panel.dropdown_value("metric")
+    }
+
+    fn speed(&self) -> f64 {
+        self.panel.dropdown_value("speed")
+    }
+
+    fn recalculate(&mut self, ctx: &mut EventCtx, app: &App) {
+        let baseline = app.secondary.as_ref().unwrap();
+        self.time = app.primary.sim.time();
+        self.baseline_agents = draw_agents(ctx, app, baseline);
+        self.proposal_agents = draw_agents(ctx, app, &app.primary);
+        self.diff = self.metric().map(|metric| {
+            let mut colorer = ColorNetwork::new(app);
+            let scale = scale();
+            let after = app.primary.sim.get_analytics();
+            let before = baseline.sim.get_analytics();
+            match metric {
+                Metric::Throughput => {
+                    let roads = before
+                        .road_thruput
+                        .all_total_counts()
+                        .compare(after.road_thruput.all_total_counts());
+                    for (r, before, after) in roads {
+                        if let Some(c) = scale.eval((after as f64) / (before as f64)) {
+                            colorer.add_r(r, c);
+                        }
+                    }
+                    let intersections = before
+                        .intersection_thruput
+                        .all_total_counts()
+                        .compare(after.intersection_thruput.all_total_counts());
+                    for (i, before, after) in intersections {
+                        if let Some(c) = scale.eval((after as f64) / (before as f64)) {
+                            colorer.add_i(i, c);
+                        }
+                    }
+                }
+                Metric::Delay => {
+                    let before = average_delays(before);
+                    for (i, after) in average_delays(after) {
+                        if let Some(before) = before.get(&i) {
+                            if let Some(c) = scale.eval(after / *before) {
+                                colorer.add_i(i, c);
+                            }
+                        }
+                    }
+                }
+            }
+            colorer.build(ctx)
+        });
+
+        let time = Text::from(Line(self.time.ampm_tostring()))
+            .draw(ctx)
+            .named("time");
+        self.panel.replace(ctx, "time", time);
+    }
+}
+
+impl State<App> for CompareSims {
+    fn event(&mut self, ctx: &mut EventCtx, app: &mut App) -> Transition {
+        ctx.canvas_movement();
+
+        match self.panel.event(ctx) {
+            Outcome::Clicked(x) => match x.as_ref() {
+                "close" => {
+                    return Transition::Pop;
+                }
+                "play" | "pause" => {
+                    self.paused = x == "pause";
+                    let panel = make_panel(ctx, app, self.paused, self.speed(), self.metric());
+                    self.panel = panel;
+                    self.recalculate(ctx, app);
+                }
+                _ => unreachable!(),
+            },
+            Outcome::Changed => {
+                let panel = make_panel(ctx, app, self.paused, self.speed(), self.metric());
+                self.panel = panel;
+                self.recalculate(ctx, app);
+            }
+            _ => {}
+        }
+
+        if !self.paused {
+            if let Some(real_dt) = ctx.input.nonblocking_is_update_event() {
+                ctx.input.use_update_event();
+                // The proposal might not keep up with real time, and neither might the baseline.
+                // Only move the proposal forward once the baseline has caught up, so neither one
+                // blocks the UI for long.
+                if app.secondary.as_ref().unwrap().sim.time() == app.primary.sim.time() {
+                    let dt = self.speed() * real_dt;
+                    app.primary.sim.time_limited_step(
+                        &app.primary.map,
+                        dt,
+                        Duration::seconds(0.033),
+                        &mut app.primary.sim_cb,
+                    );
+                }
+                let baseline = app.secondary.as_mut().unwrap();
+                let dt = app.primary.sim.time() - baseline.sim.time();
+                baseline.sim.time_limited_step(
+                    &baseline.map,
+                    dt,
+                    Duration::seconds(0.033),
+                    &mut baseline.sim_cb,
+                );
+            }
+            ctx.request_update(UpdateType::Game);
+        }
+
+        // Only compare the two once they're at the same time
+        if self.time != app.primary.sim.time()
+            && app.secondary.as_ref().unwrap().sim.time() == app.primary.sim.time()
+        {
+            self.recalculate(ctx, app);
+        }
+
+        Transition::Keep
+    }
+
+    fn draw_baselayer(&self) -> DrawBaselayer {
+        DrawBaselayer::Custom
+    }
+
+    fn draw(&self, g: &mut GfxCtx, app: &App) {
+        g.clear(app.cs.void_background);
+
+        // Both halves show the area that'd be centered in a window half as wide.
+        let half_width = g.canvas.window_width / 2.0;
+        let top_left = g.canvas.screen_to_map(ScreenPt::new(half_width / 2.0, 0.0));
+        let zoom = g.canvas.cam_zoom;
+        let unzoomed = zoom < app.opts.min_zoom_for_detail;
+        for (per_map, agents, x1) in vec![
+            (app.secondary.as_ref().unwrap(), &self.baseline_agents, 0.0),
+            (&app.primary, &self.proposal_agents, half_width),
+        ] {
+            g.fork(top_left, ScreenPt::new(x1, 0.0), zoom, None);
+            g.enable_clipping(ScreenRectangle {
+                x1,
+                y1: 0.0,
+                x2: x1 + half_width,
+                y2: g.canvas.window_height,
+            });
+
+            // TODO The detailed renderables all assume they're drawing the primary map, so just
+            // use the simpler unzoomed view everywhere.
+            let draw_map = &per_map.draw_map;
+            g.redraw(&draw_map.boundary_polygon);
+            g.redraw(&draw_map.draw_all_areas);
+            g.redraw(&draw_map.draw_all_unzoomed_parking_lots);
+            g.redraw(&draw_map.draw_all_unzoomed_roads_and_intersections);
+            g.redraw(&draw_map.draw_all_buildings);
+            if let Some((ref unzoomed_diff, ref zoomed_diff)) = self.diff {
+                g.redraw(if unzoomed { unzoomed_diff } else { zoomed_diff });
+            }
+            g.redraw(agents);
+
+            g.disable_clipping();
+            g.unfork();
+        }
+
+        g.fork_screenspace();
+        g.draw_polygon(
+            Color::BLACK,
+            Polygon::rectangle(4.0, g.canvas.window_height).translate(half_width - 2.0, 0.0),
+        );
+        g.unfork();
+
+        self.panel.draw(g);
+    }
+
+    fn on_destroy(&mut self, _: &mut EventCtx, app: &mut App) {
+        app.secondary = None;
+    }
+}
+
+fn load_baseline(
+    ctx: &mut EventCtx,
+    app: &App,
+    mode: &GameplayMode,
+    timer: &mut Timer,
+) -> Result<PerMap, String> {
+    let flags = &app.primary.current_flags;
+    let map = Map::new(abstutil::path_map(app.primary.map.get_name()), timer);
+    let mut scenario: Scenario =
+        match mode.scenario(&map, flags.num_agents, flags.sim_flags.make_rng(), timer) {
+            LoadScenario::Nothing => {
+                return Err("This mode doesn't have a scenario to compare".to_string());
+            }
+            LoadScenario::Scenario(scenario) => scenario,
+            LoadScenario::Path(path) => abstutil::maybe_read_binary(path.clone(), timer)
+                .map_err(|err| format!("Couldn't load {}: {}", path, err))?,
+        };
+    if let GameplayMode::PlayScenario(_, _, ref modifiers) = mode {
+        for m in modifiers {
+            scenario = m.apply(&map, scenario);
+        }
+    }
+
+    // Set things up exactly the same way as the sandbox did for the proposal
+    let mut sim = Sim::new(&map, flags.sim_flags.opts.clone(), timer);
+    scenario.instantiate(&mut sim, &map, &mut flags.sim_flags.make_rng(), timer);
+    sim.tiny_step(&map, &mut None);
+    // Catch up to the proposal in chunks, so the loading screen shows progress
+    let chunk = Duration::minutes(10);
+    let end_time = app.primary.sim.time();
+    timer.start_iter(
+        "catch up to the proposal",
+        ((end_time - sim.time()) / chunk).ceil() as usize,
+    );
+    while sim.time() < end_time {
+        timer.next();
+        let dt = (end_time - sim.time()).min(chunk);
+        sim.timed_step(&map, dt, &mut None, &mut Timer::throwaway());
+    }
+
+    Ok(PerMap::new(
+        map,
+        sim,
+        flags.clone(),
+        &app.opts,
+        &app.cs,
+        ctx,
+        timer,
+    ))
+}
+
+fn make_panel(
+    ctx: &mut EventCtx,
+    app: &App,
+    paused: bool,
+    speed: f64,
+    metric: Option<Metric>,
+) -> Panel {
+    Panel::new(Widget::col(vec![
+        Widget::row(vec![
+            Line("Baseline vs. proposal").small_heading().draw(ctx),
+            Btn::close(ctx),
+        ]),
+        Text::from(Line("Left: the map without edits. Right: your proposal.").secondary())
+            .draw(ctx),
+        Widget::row(vec![
+            app.primary
+                .sim
+                .time()
+                .ampm_tostring()
+                .draw_text(ctx)
+                .named("time"),
+            if paused {
+                Btn::text_bg2("play").build_def(ctx, None)
+            } else {
+                Btn::text_bg2("pause").build_def(ctx, None)
+            },
+            Widget::dropdown(
+                ctx,
+                "speed",
+                speed,
+                vec![
+                    Choice::new("real-time", 1.0),
+                    Choice::new("5x", 5.0),
+                    Choice::new("30x", 30.0),
+                    Choice::new("3600x", 3600.0),
+                ],
+            ),
+        ]),
+        Widget::row(vec![
+            "Color by change in:".draw_text(ctx),
+            Widget::dropdown(
+                ctx,
+                "metric",
+                metric,
+                vec![
+                    Choice::new("throughput", Some(Metric::Throughput)),
+                    Choice::new("intersection delay", Some(Metric::Delay)),
+                    Choice::new("nothing", None),
+                ],
+            ),
+        ]),
+        if metric.is_some() {
+            scale().make_legend(ctx, vec!["less", "same", "more"])
+        } else {
+            Widget::nothing()
+        },
+    ]))
+    .aligned(HorizontalAlignment::Center, VerticalAlignment::Top)
+    .build(ctx)
+}
+
+fn scale() -> DivergingScale {
+    DivergingScale::new(Color::hex("#5D9630"), Color::WHITE, Color::hex("#A32015"))
+        .range(0.0, 2.0)
+        .ignore(0.7, 1.3)
+}
+
+fn draw_agents(ctx: &mut EventCtx, app: &App, per_map: &PerMap) -> Drawable {
+    let agents = per_map.sim.get_unzoomed_agents(&per_map.map);
+    ctx.upload(app.unzoomed_agents.batch(&agents))
+}
+
+/// Average delay per intersection so far, in seconds.
+fn average_delays(analytics: &Analytics) -> BTreeMap<IntersectionID, f64> {
+    analytics
+        .intersection_delays
+        .iter()
+        .filter(|(_, list)| !list.is_empty())
+        .map(|(i, list)| {
+            let total: f64 = list.iter().map(|(_, _, dt, _)| dt.inner_seconds()).sum();
+            (*i, total / (list.len() as f64))
+        })
+        .collect()
+}
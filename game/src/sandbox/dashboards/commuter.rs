@@ -2,18 +2,18 @@ use std::collections::{BTreeSet, HashMap, HashSet};
 
 use maplit::hashset;
 
-use abstutil::{prettyprint_usize, Counter, MultiMap};
+use abstutil::{Counter, MultiMap};
 use geom::{Distance, PolyLine, Polygon, Time};
 use map_model::{osm, BuildingID, BuildingType, IntersectionID, LaneID, Map, RoadID, TurnType};
-use sim::{TripEndpoint, TripInfo, TripMode};
+use sim::{TripEndpoint, TripInfo, TripMode, TripPurpose};
 use widgetry::{
-    Btn, Checkbox, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Key, Line,
-    Outcome, Panel, RewriteColor, Slider, State, Text, TextExt, VerticalAlignment, Widget,
+    tr_n, Btn, Checkbox, Color, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Key,
+    Line, Outcome, Panel, RewriteColor, Slider, State, Text, TextExt, VerticalAlignment, Widget,
 };
 
 use crate::app::App;
-use crate::common::{ColorLegend, CommonState};
-use crate::game::Transition;
+use crate::common::{ColorLegend, CommonState, ExportData, ExportObject};
+use crate::game::{PopupMsg, Transition};
 use crate::helpers::checkbox_per_mode;
 
 pub struct CommuterPatterns {
@@ -82,6 +82,10 @@ impl CommuterPatterns {
             .collect();
         let mut trips_to_block: Vec<Vec<TripInfo>> = trips_from_block.clone();
         for (_, trip) in app.primary.sim.all_trip_info() {
+            // Delivery trucks aren't commuting
+            if let TripPurpose::Delivery = trip.purpose {
+                continue;
+            }
             let block1 = match trip.start {
                 TripEndpoint::Bldg(b) => bldg_to_block[&b],
                 TripEndpoint::Border(i, _) => border_to_block[&i],
@@ -173,6 +177,33 @@ impl CommuterPatterns {
             .collect()
     }
 
+    /// The trips between the selected block and every other block, using the current filters.
+    fn export_data(&self) -> Option<ExportData> {
+        let base = match self.current_block.0 {
+            BlockSelection::NothingSelected => {
+                return None;
+            }
+            BlockSelection::Unlocked(id) | BlockSelection::Locked { base: id, .. } => {
+                &self.blocks[id]
+            }
+        };
+        let mut data = ExportData::new(vec![
+            "block",
+            if self.filter.from_block {
+                "trips from selected block"
+            } else {
+                "trips to selected block"
+            },
+        ]);
+        for (block, cnt) in self.count_per_block(base) {
+            data.add(
+                ExportObject::Area(block.shape.clone()),
+                vec![block.id.to_string(), cnt.to_string()],
+            );
+        }
+        Some(data)
+    }
+
     fn build_block_drawable<'a>(
         &self,
         block_selection: BlockSelection,
@@ -306,9 +337,10 @@ impl CommuterPatterns {
     fn redraw_panel(&mut self, state: Option<&PanelState>, ctx: &mut EventCtx, app: &App) {
         if let Some(state) = state {
             let mut txt = Text::new();
-            txt.add(Line(format!(
+            txt.add(Line(tr_n(
+                "Total: {} trip",
                 "Total: {} trips",
-                abstutil::prettyprint_usize(state.total_trips)
+                state.total_trips,
             )));
 
             for (name, cnt) in &state.building_counts {
@@ -325,7 +357,7 @@ impl CommuterPatterns {
                 &app.cs.good_to_bad_red,
                 vec![
                     "0".to_string(),
-                    format!("{} trips", prettyprint_usize(state.max_count)),
+                    tr_n("{} trip", "{} trips", state.max_count),
                 ],
             )
             .named("scale");
@@ -350,6 +382,20 @@ impl State<App> for CommuterPatterns {
                     app.primary.sim = app.primary.suspended_sim.take().unwrap();
                     return Transition::Pop;
                 }
+                "Export to CSV" => {
+                    let result = match self.export_data() {
+                        Some(data) => data.save(app, "commuter_patterns"),
+                        None => Err("Select a block first".to_string()),
+                    };
+                    return Transition::Push(match result {
+                        Ok(paths) => PopupMsg::new(
+                            ctx,
+                            "Data exported",
+                            vec![format!("Data exported to {}", paths.join(" and "))],
+                        ),
+                        Err(err) => PopupMsg::new(ctx, "Export failed", vec![err]),
+                    });
+                }
                 _ => unreachable!(),
             },
             _ => {}
@@ -702,6 +748,7 @@ fn make_panel(ctx: &mut EventCtx, app: &App) -> Panel {
         checkbox_per_mode(ctx, app, &TripMode::all().into_iter().collect()),
         ColorLegend::gradient(ctx, &app.cs.good_to_bad_red, vec!["0", "0"]).named("scale"),
         "None selected".draw_text(ctx).named("current"),
+        Btn::text_bg2("Export to CSV").build_def(ctx, None),
     ]))
     .aligned(HorizontalAlignment::Right, VerticalAlignment::Top)
     .build(ctx)
@@ -2,34 +2,37 @@ use geom::{Distance, Pt2D};
 use sim::{TripEndpoint, TripID};
 use widgetry::{
     Color, DrawBaselayer, EventCtx, GeomBatch, GfxCtx, Outcome, Panel, RewriteColor, ScreenPt,
-    State,
+    State, Table,
 };
 
 use crate::app::App;
-use crate::game::Transition;
+use crate::common::{ExportData, ExportObject};
+use crate::game::{PopupMsg, Transition};
 use crate::helpers::color_for_trip_phase;
 use crate::info::{OpenTrip, Tab};
-use crate::sandbox::dashboards::table::Table;
 use crate::sandbox::dashboards::trip_table;
 use crate::sandbox::dashboards::DashTab;
 use crate::sandbox::SandboxMode;
 
-pub struct GenericTripTable<T, F, P: 'static + Fn(&mut EventCtx, &App, &Table<T, F>) -> Panel> {
-    table: Table<T, F>,
+pub struct GenericTripTable<T, F, P: 'static + Fn(&mut EventCtx, &App, &Table<App, T, F>) -> Panel>
+{
+    table: Table<App, T, F>,
     panel: Panel,
     make_panel: P,
     tab: DashTab,
+    trip: fn(&T) -> TripID,
 }
 
-impl<T: 'static, F: 'static, P: 'static + Fn(&mut EventCtx, &App, &Table<T, F>) -> Panel>
+impl<T: 'static, F: 'static, P: 'static + Fn(&mut EventCtx, &App, &Table<App, T, F>) -> Panel>
     GenericTripTable<T, F, P>
 {
     pub fn new(
         ctx: &mut EventCtx,
         app: &App,
         tab: DashTab,
-        table: Table<T, F>,
+        table: Table<App, T, F>,
         make_panel: P,
+        trip: fn(&T) -> TripID,
     ) -> Box<dyn State<App>> {
         let panel = (make_panel)(ctx, app, &table);
         Box::new(GenericTripTable {
@@ -37,6 +40,7 @@ impl<T: 'static, F: 'static, P: 'static + Fn(&mut EventCtx, &App, &Table<T, F>)
             panel,
             make_panel,
             tab,
+            trip,
         })
     }
 
@@ -45,10 +49,20 @@ impl<T: 'static, F: 'static, P: 'static + Fn(&mut EventCtx, &App, &Table<T, F>)
         new.restore(ctx, &self.panel);
         self.panel = new;
     }
+
+    /// The filtered rows, with a line from each trip's start to its end.
+    fn export_data(&self, app: &App) -> ExportData {
+        let (columns, rows) = self.table.export_rows(app);
+        let mut data = ExportData::new(columns.iter().map(|x| x.as_str()).collect());
+        for (row, values) in rows {
+            data.add(ExportObject::Trip((self.trip)(row)), values);
+        }
+        data
+    }
 }
 
-impl<T: 'static, F: 'static, P: 'static + Fn(&mut EventCtx, &App, &Table<T, F>) -> Panel> State<App>
-    for GenericTripTable<T, F, P>
+impl<T: 'static, F: 'static, P: 'static + Fn(&mut EventCtx, &App, &Table<App, T, F>) -> Panel>
+    State<App> for GenericTripTable<T, F, P>
 {
     fn event(&mut self, ctx: &mut EventCtx, app: &mut App) -> Transition {
         match self.panel.event(ctx) {
@@ -71,6 +85,16 @@ impl<T: 'static, F: 'static, P: 'static + Fn(&mut EventCtx, &App, &Table<T, F>)
                             );
                         })),
                     ]);
+                } else if x == "Export to CSV" {
+                    let name = format!("{:?}", self.tab);
+                    return Transition::Push(match self.export_data(app).save(app, &name) {
+                        Ok(paths) => PopupMsg::new(
+                            ctx,
+                            "Data exported",
+                            vec![format!("Data exported to {}", paths.join(" and "))],
+                        ),
+                        Err(err) => PopupMsg::new(ctx, "Export failed", vec![err]),
+                    });
                 } else if x == "close" {
                     return Transition::Pop;
                 } else if x == "finished trips" {
@@ -7,8 +7,8 @@ use widgetry::{
 };
 
 use crate::app::App;
-use crate::common::Tab;
-use crate::game::Transition;
+use crate::common::{ExportData, ExportObject, Tab};
+use crate::game::{PopupMsg, Transition};
 use crate::sandbox::dashboards::DashTab;
 use crate::sandbox::SandboxMode;
 
@@ -107,6 +107,7 @@ fn downsample(raw: Vec<(Time, usize)>) -> Vec<(Time, usize)> {
 
 pub struct TransitRoutes {
     panel: Panel,
+    export: ExportData,
 }
 
 impl TransitRoutes {
@@ -150,6 +151,19 @@ impl TransitRoutes {
         }
         routes.sort();
 
+        let mut export = ExportData::new(vec!["name", "boardings", "alightings", "waiting"]);
+        for (boardings, alightings, waiting, name, id) in &routes {
+            export.add(
+                ExportObject::BusRoute(*id),
+                vec![
+                    name.clone(),
+                    (-boardings).to_string(),
+                    (-alightings).to_string(),
+                    (-waiting).to_string(),
+                ],
+            );
+        }
+
         let col = vec![
             DashTab::TransitRoutes.picker(ctx, app),
             Line(format!("{} Transit routes", routes.len()))
@@ -167,6 +181,7 @@ impl TransitRoutes {
                 .named("search"),
             ])
             .padding(8),
+            Btn::text_bg2("Export to CSV").build_def(ctx, None),
             // TODO Maybe a table instead
             Widget::col(
                 routes
@@ -191,6 +206,7 @@ impl TransitRoutes {
             panel: Panel::new(Widget::col(col))
                 .exact_size_percent(90, 90)
                 .build(ctx),
+            export,
         })
     }
 }
@@ -203,6 +219,15 @@ impl State<App> for TransitRoutes {
                     BusRouteID(x.parse::<usize>().unwrap())
                 } else if x == "close" {
                     return Transition::Pop;
+                } else if x == "Export to CSV" {
+                    return Transition::Push(match self.export.save(app, "transit_routes") {
+                        Ok(paths) => PopupMsg::new(
+                            ctx,
+                            "Data exported",
+                            vec![format!("Data exported to {}", paths.join(" and "))],
+                        ),
+                        Err(err) => PopupMsg::new(ctx, "Export failed", vec![err]),
+                    });
                 } else {
                     unreachable!()
                 }
@@ -12,7 +12,6 @@ mod generic_trip_table;
 mod misc;
 mod parking_overhead;
 mod summaries;
-mod table;
 mod traffic_signals;
 mod trip_table;
 
@@ -1,10 +1,9 @@
 use geom::Duration;
 use sim::{TripEndpoint, TripID, TripPhaseType};
-use widgetry::{Checkbox, EventCtx, Filler, Line, Panel, State, Text, Widget};
+use widgetry::{Checkbox, Col, EventCtx, Filler, Filter, Line, Panel, State, Table, Text, Widget};
 
 use crate::app::App;
 use crate::sandbox::dashboards::generic_trip_table::GenericTripTable;
-use crate::sandbox::dashboards::table::{Col, Filter, Table};
 use crate::sandbox::dashboards::DashTab;
 
 // TODO Compare all of these things before/after
@@ -14,7 +13,9 @@ pub struct ParkingOverhead;
 impl ParkingOverhead {
     pub fn new(ctx: &mut EventCtx, app: &App) -> Box<dyn State<App>> {
         let table = make_table(app);
-        GenericTripTable::new(ctx, app, DashTab::ParkingOverhead, table, make_panel)
+        GenericTripTable::new(ctx, app, DashTab::ParkingOverhead, table, make_panel, |x| {
+            x.trip
+        })
     }
 }
 
@@ -92,8 +93,8 @@ fn produce_raw_data(app: &App) -> Vec<Entry> {
     data
 }
 
-fn make_table(app: &App) -> Table<Entry, Filters> {
-    let filter: Filter<Entry, Filters> = Filter {
+fn make_table(app: &App) -> Table<App, Entry, Filters> {
+    let filter: Filter<App, Entry, Filters> = Filter {
         state: Filters {
             starts_off_map: true,
             ends_off_map: true,
@@ -126,44 +127,36 @@ fn make_table(app: &App) -> Table<Entry, Filters> {
         filter,
     );
     table.static_col("Trip ID", Box::new(|x| x.trip.0.to_string()));
-    table.column(
+    table.text_col(
         "Total duration",
-        Box::new(|ctx, app, x| {
-            Text::from(Line(x.total_duration.to_string(&app.opts.units))).render(ctx)
-        }),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.total_duration))),
+        Box::new(|app, x| x.total_duration.to_string(&app.opts.units)),
+        Col::sortable_by_key(|x| x.total_duration),
     );
-    table.column(
+    table.text_col(
         "Driving duration",
-        Box::new(|ctx, app, x| {
-            Text::from(Line(x.driving_duration.to_string(&app.opts.units))).render(ctx)
-        }),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.driving_duration))),
+        Box::new(|app, x| x.driving_duration.to_string(&app.opts.units)),
+        Col::sortable_by_key(|x| x.driving_duration),
     );
-    table.column(
+    table.text_col(
         "Parking duration",
-        Box::new(|ctx, app, x| {
-            Text::from(Line(x.parking_duration.to_string(&app.opts.units))).render(ctx)
-        }),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.parking_duration))),
+        Box::new(|app, x| x.parking_duration.to_string(&app.opts.units)),
+        Col::sortable_by_key(|x| x.parking_duration),
     );
-    table.column(
+    table.text_col(
         "Walking duration",
-        Box::new(|ctx, app, x| {
-            Text::from(Line(x.walking_duration.to_string(&app.opts.units))).render(ctx)
-        }),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.walking_duration))),
+        Box::new(|app, x| x.walking_duration.to_string(&app.opts.units)),
+        Col::sortable_by_key(|x| x.walking_duration),
     );
-    table.column(
+    table.text_col(
         "Percent overhead",
-        Box::new(|ctx, _, x| Text::from(Line(format!("{}%", x.percent_overhead))).render(ctx)),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.percent_overhead))),
+        Box::new(|_, x| format!("{}%", x.percent_overhead)),
+        Col::sortable_by_key(|x| x.percent_overhead),
     );
 
     table
 }
 
-fn make_panel(ctx: &mut EventCtx, app: &App, table: &Table<Entry, Filters>) -> Panel {
+fn make_panel(ctx: &mut EventCtx, app: &App, table: &Table<App, Entry, Filters>) -> Panel {
     let mut col = vec![DashTab::ParkingOverhead.picker(ctx, app)];
     col.push(
         Widget::row(vec![
@@ -5,18 +5,20 @@ use geom::{ArrowCap, Distance, Duration, Polygon, Time};
 use map_model::{ControlTrafficSignal, IntersectionID, MovementID, PathStep, TurnType};
 use sim::TripEndpoint;
 use widgetry::{
-    Btn, Color, DrawBaselayer, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Key,
-    Line, Outcome, Panel, Spinner, State, Text, TextExt, VerticalAlignment, Widget,
+    Btn, Col, Color, DrawBaselayer, Drawable, EventCtx, Filter, GeomBatch, GfxCtx,
+    HorizontalAlignment, Key, Line, Outcome, Panel, Spinner, State, Table, Text, TextExt,
+    VerticalAlignment, Widget,
 };
 
 use crate::app::{App, ShowEverything};
-use crate::common::CommonState;
-use crate::game::Transition;
+use crate::common::{CommonState, ExportData, ExportObject};
+use crate::game::{PopupMsg, Transition};
 use crate::helpers::ID;
 use crate::render::DrawOptions;
 
 pub struct TrafficSignalDemand {
     panel: Panel,
+    table: Table<App, SignalDemand, ()>,
     all_demand: HashMap<IntersectionID, Demand>,
     hour: Time,
     draw_all: Drawable,
@@ -35,35 +37,23 @@ impl TrafficSignalDemand {
 
         let hour = Time::START_OF_DAY;
         let draw_all = Demand::draw_demand(ctx, app, &all_demand, hour);
+        let table = make_table(SignalDemand::all(&all_demand, hour));
+        let panel = make_panel(ctx, app, &table, hour);
         Box::new(TrafficSignalDemand {
             all_demand,
             hour,
             draw_all,
             selected: None,
-            panel: Panel::new(Widget::col(vec![
-                Widget::row(vec![
-                    Line("Traffic signal demand over time")
-                        .small_heading()
-                        .draw(ctx),
-                    Btn::close(ctx),
-                ]),
-                Text::from_all(vec![
-                    Line("Press "),
-                    Key::LeftArrow.txt(ctx),
-                    Line(" and "),
-                    Key::RightArrow.txt(ctx),
-                    Line(" to adjust the hour"),
-                ])
-                .draw(ctx),
-                Widget::row(vec![
-                    "Hour:".draw_text(ctx),
-                    Spinner::new(ctx, (0, 24), 7).named("hour"),
-                ]),
-            ]))
-            .aligned(HorizontalAlignment::Center, VerticalAlignment::Top)
-            .build(ctx),
+            table,
+            panel,
         })
     }
+
+    fn recalc(&mut self, ctx: &mut EventCtx, app: &App) {
+        let mut new = make_panel(ctx, app, &self.table, self.hour);
+        new.restore(ctx, &self.panel);
+        self.panel = new;
+    }
 }
 
 impl State<App> for TrafficSignalDemand {
@@ -104,24 +94,50 @@ impl State<App> for TrafficSignalDemand {
                     app.primary.sim = app.primary.suspended_sim.take().unwrap();
                     return Transition::Pop;
                 }
-                _ => unreachable!(),
+                "Export to CSV" => {
+                    let data = Demand::export(&self.all_demand, self.hour);
+                    let name = format!("signal_demand_{}", self.hour.get_hours());
+                    return Transition::Push(match data.save(app, &name) {
+                        Ok(paths) => PopupMsg::new(
+                            ctx,
+                            "Data exported",
+                            vec![format!("Data exported to {}", paths.join(" and "))],
+                        ),
+                        Err(err) => PopupMsg::new(ctx, "Export failed", vec![err]),
+                    });
+                }
+                x => {
+                    if self.table.clicked(x) {
+                        self.recalc(ctx, app);
+                    } else if !self.table.select_row(ctx, app, x) {
+                        unreachable!()
+                    }
+                }
             },
             Outcome::Changed => {
+                self.table.panel_changed(&self.panel);
                 changed = true;
             }
             _ => {}
         }
-        if ctx.input.pressed(Key::LeftArrow) {
+        // The arrow keys page through the table
+        if ctx.input.pressed(Key::DownArrow) {
             self.panel.modify_spinner("hour", -1);
             changed = true;
         }
-        if ctx.input.pressed(Key::RightArrow) {
+        if ctx.input.pressed(Key::UpArrow) {
             self.panel.modify_spinner("hour", 1);
             changed = true;
         }
         if changed {
-            self.hour = Time::START_OF_DAY + Duration::hours(self.panel.spinner("hour") as usize);
-            self.draw_all = Demand::draw_demand(ctx, app, &self.all_demand, self.hour);
+            let hour = Time::START_OF_DAY + Duration::hours(self.panel.spinner("hour") as usize);
+            if hour != self.hour {
+                self.hour = hour;
+                self.draw_all = Demand::draw_demand(ctx, app, &self.all_demand, self.hour);
+                self.table
+                    .replace_data(SignalDemand::all(&self.all_demand, self.hour));
+            }
+            self.recalc(ctx, app);
         }
 
         Transition::Keep
@@ -148,6 +164,94 @@ impl State<App> for TrafficSignalDemand {
     }
 }
 
+fn make_panel(
+    ctx: &mut EventCtx,
+    app: &App,
+    table: &Table<App, SignalDemand, ()>,
+    hour: Time,
+) -> Panel {
+    Panel::new(Widget::col(vec![
+        Widget::row(vec![
+            Line("Traffic signal demand over time")
+                .small_heading()
+                .draw(ctx),
+            Btn::close(ctx),
+        ]),
+        Text::from_all(vec![
+            Line("Press "),
+            Key::DownArrow.txt(ctx),
+            Line(" and "),
+            Key::UpArrow.txt(ctx),
+            Line(" to adjust the hour"),
+        ])
+        .draw(ctx),
+        Widget::row(vec![
+            "Hour:".draw_text(ctx),
+            Spinner::new(ctx, (0, 24), hour.get_hours() as isize).named("hour"),
+        ]),
+        table.render(ctx, app),
+    ]))
+    .aligned(HorizontalAlignment::Center, VerticalAlignment::Top)
+    .build(ctx)
+}
+
+/// One row of the table, summarizing a signal for the current hour
+struct SignalDemand {
+    id: IntersectionID,
+    total: usize,
+    busiest_movement: usize,
+    movements: usize,
+}
+
+impl SignalDemand {
+    fn all(all_demand: &HashMap<IntersectionID, Demand>, hour: Time) -> Vec<SignalDemand> {
+        all_demand
+            .iter()
+            .map(|(i, demand)| {
+                let cnt = demand.count(hour);
+                SignalDemand {
+                    id: *i,
+                    total: cnt.sum(),
+                    busiest_movement: cnt.max(),
+                    movements: cnt.borrow().len(),
+                }
+            })
+            .collect()
+    }
+}
+
+fn make_table(data: Vec<SignalDemand>) -> Table<App, SignalDemand, ()> {
+    let mut table = Table::new(
+        data,
+        Box::new(|x| x.id.to_string()),
+        "Total demand",
+        Filter::empty(),
+    );
+    table.rows_per_page(5);
+    table.static_col("Intersection", Box::new(|x| x.id.0.to_string()));
+    table.text_col(
+        "Total demand",
+        Box::new(|_, x| prettyprint_usize(x.total)),
+        Col::sortable_by_key(|x| x.total),
+    );
+    table.text_col(
+        "Busiest movement",
+        Box::new(|_, x| prettyprint_usize(x.busiest_movement)),
+        Col::sortable_by_key(|x| x.busiest_movement),
+    );
+    table.text_col(
+        "Movements used",
+        Box::new(|_, x| x.movements.to_string()),
+        Col::sortable_by_key(|x| x.movements),
+    );
+    // Jump to the signal, so the arrows can be inspected
+    table.on_select(Box::new(|ctx, app, x| {
+        ctx.canvas
+            .center_on_map_pt(app.primary.map.get_i(x.id).polygon.center());
+    }));
+    table
+}
+
 struct Demand {
     // Unsorted
     raw: Vec<(Time, MovementID)>,
@@ -214,6 +318,26 @@ impl Demand {
         cnt
     }
 
+    /// One row per movement with any demand during the hour.
+    fn export(all_demand: &HashMap<IntersectionID, Demand>, hour: Time) -> ExportData {
+        let mut data = ExportData::new(vec!["from road", "to road", "count"]);
+        let mut intersections: Vec<&IntersectionID> = all_demand.keys().collect();
+        intersections.sort();
+        for i in intersections {
+            for (m, cnt) in all_demand[i].count(hour).consume() {
+                data.add(
+                    ExportObject::Intersection(*i),
+                    vec![
+                        m.from.id.0.to_string(),
+                        m.to.id.0.to_string(),
+                        cnt.to_string(),
+                    ],
+                );
+            }
+        }
+        data
+    }
+
     fn make_arrows(&self, ts: &ControlTrafficSignal, hour: Time) -> Vec<(Polygon, usize)> {
         let cnt = self.count(hour);
         let total_demand = cnt.sum() as f64;
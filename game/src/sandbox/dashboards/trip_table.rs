@@ -3,12 +3,13 @@ use std::collections::{BTreeSet, HashMap};
 use abstutil::prettyprint_usize;
 use geom::{Duration, Time};
 use sim::{TripEndpoint, TripID, TripMode};
-use widgetry::{Btn, Checkbox, EventCtx, Filler, Line, Panel, State, Text, Widget};
+use widgetry::{
+    Btn, Checkbox, Col, EventCtx, Filler, Filter, Line, Panel, State, Table, Text, Widget,
+};
 
 use crate::app::App;
 use crate::helpers::{checkbox_per_mode, cmp_duration_shorter, color_for_mode};
 use crate::sandbox::dashboards::generic_trip_table::GenericTripTable;
-use crate::sandbox::dashboards::table::{Col, Filter, Table};
 use crate::sandbox::dashboards::DashTab;
 
 pub struct FinishedTripTable;
@@ -21,6 +22,7 @@ impl FinishedTripTable {
             DashTab::FinishedTripTable,
             make_table_finished_trips(app),
             make_panel_finished_trips,
+            |x| x.id,
         )
     }
 }
@@ -35,6 +37,7 @@ impl CancelledTripTable {
             DashTab::CancelledTripTable,
             make_table_cancelled_trips(app),
             make_panel_cancelled_trips,
+            |x| x.id,
         )
     }
 }
@@ -49,6 +52,7 @@ impl UnfinishedTripTable {
             DashTab::UnfinishedTripTable,
             make_table_unfinished_trips(app),
             make_panel_unfinished_trips,
+            |x| x.id,
         )
     }
 }
@@ -165,7 +169,7 @@ fn produce_raw_data(app: &App) -> (Vec<FinishedTrip>, Vec<CancelledTrip>) {
     (finished, cancelled)
 }
 
-fn make_table_finished_trips(app: &App) -> Table<FinishedTrip, Filters> {
+fn make_table_finished_trips(app: &App) -> Table<App, FinishedTrip, Filters> {
     let (finished, _) = produce_raw_data(app);
     let any_congestion_caps = app
         .primary
@@ -173,7 +177,7 @@ fn make_table_finished_trips(app: &App) -> Table<FinishedTrip, Filters> {
         .all_zones()
         .iter()
         .any(|z| z.restrictions.cap_vehicles_per_hour.is_some());
-    let filter: Filter<FinishedTrip, Filters> = Filter {
+    let filter: Filter<App, FinishedTrip, Filters> = Filter {
         state: Filters {
             modes: TripMode::all().into_iter().collect(),
             off_map_starts: true,
@@ -321,17 +325,19 @@ fn make_table_finished_trips(app: &App) -> Table<FinishedTrip, Filters> {
         }),
         Col::Static,
     );
-    table.column(
+    table.text_col(
         "Departure",
-        Box::new(|ctx, _, x| Text::from(Line(x.departure.ampm_tostring())).render(ctx)),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.departure))),
+        Box::new(|_, x| x.departure.ampm_tostring()),
+        Col::sortable_by_key(|x| x.departure),
     );
-    table.column(
+    table.text_col(
         "Duration",
-        Box::new(|ctx, app, x| {
-            Text::from(Line(x.duration_after.to_string(&app.opts.units))).render(ctx)
-        }),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.duration_after))),
+        Box::new(|app, x| x.duration_after.to_string(&app.opts.units)),
+        Col::sortable_by_key(|x| x.duration_after),
+    );
+    table.range_filter(
+        "Duration (minutes)",
+        Box::new(|x| x.duration_after.inner_seconds() / 60.0),
     );
 
     if app.has_prebaked().is_some() {
@@ -345,14 +351,12 @@ fn make_table_finished_trips(app: &App) -> Table<FinishedTrip, Filters> {
                 ))
                 .render(ctx)
             }),
-            Col::Sortable(Box::new(|rows| {
-                rows.sort_by_key(|x| x.duration_after - x.duration_before)
-            })),
+            Col::sortable_by_key(|x| x.duration_after - x.duration_before),
         );
-        table.column(
+        table.text_col(
             "Normalized",
-            Box::new(|ctx, _, x| {
-                Text::from(Line(if x.duration_after == x.duration_before {
+            Box::new(|_, x| {
+                if x.duration_after == x.duration_before {
                     format!("same")
                 } else if x.duration_after < x.duration_before {
                     format!(
@@ -364,33 +368,30 @@ fn make_table_finished_trips(app: &App) -> Table<FinishedTrip, Filters> {
                         "{}% slower ",
                         (100.0 * ((x.duration_after / x.duration_before) - 1.0)) as usize
                     )
-                }))
-                .render(ctx)
+                }
             }),
-            Col::Sortable(Box::new(|rows| {
-                rows.sort_by_key(|x| (100.0 * (x.duration_after / x.duration_before)) as isize)
-            })),
+            Col::sortable_by_key(|x| (100.0 * (x.duration_after / x.duration_before)) as isize),
         );
     }
 
-    table.column(
+    table.text_col(
         "Time spent waiting",
-        Box::new(|ctx, app, x| Text::from(Line(x.waiting.to_string(&app.opts.units))).render(ctx)),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.waiting))),
+        Box::new(|app, x| x.waiting.to_string(&app.opts.units)),
+        Col::sortable_by_key(|x| x.waiting),
     );
-    table.column(
+    table.text_col(
         "Percent waiting",
-        Box::new(|ctx, _, x| Text::from(Line(x.percent_waiting.to_string())).render(ctx)),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.percent_waiting))),
+        Box::new(|_, x| x.percent_waiting.to_string()),
+        Col::sortable_by_key(|x| x.percent_waiting),
     );
 
     table
 }
 
-fn make_table_cancelled_trips(app: &App) -> Table<CancelledTrip, Filters> {
+fn make_table_cancelled_trips(app: &App) -> Table<App, CancelledTrip, Filters> {
     let (_, cancelled) = produce_raw_data(app);
     // Reuse the same filters, but ignore modified and capped trips
-    let filter: Filter<CancelledTrip, Filters> = Filter {
+    let filter: Filter<App, CancelledTrip, Filters> = Filter {
         state: Filters {
             modes: TripMode::all().into_iter().collect(),
             off_map_starts: true,
@@ -454,18 +455,16 @@ fn make_table_cancelled_trips(app: &App) -> Table<CancelledTrip, Filters> {
         }),
         Col::Static,
     );
-    table.column(
+    table.text_col(
         "Departure",
-        Box::new(|ctx, _, x| Text::from(Line(x.departure.ampm_tostring())).render(ctx)),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.departure))),
+        Box::new(|_, x| x.departure.ampm_tostring()),
+        Col::sortable_by_key(|x| x.departure),
     );
     if app.has_prebaked().is_some() {
-        table.column(
+        table.text_col(
             "Estimated duration",
-            Box::new(|ctx, app, x| {
-                Text::from(Line(x.duration_before.to_string(&app.opts.units))).render(ctx)
-            }),
-            Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.duration_before))),
+            Box::new(|app, x| x.duration_before.to_string(&app.opts.units)),
+            Col::sortable_by_key(|x| x.duration_before),
         );
     }
     table.static_col("Reason", Box::new(|x| x.reason.clone()));
@@ -473,7 +472,7 @@ fn make_table_cancelled_trips(app: &App) -> Table<CancelledTrip, Filters> {
     table
 }
 
-fn make_table_unfinished_trips(app: &App) -> Table<UnfinishedTrip, Filters> {
+fn make_table_unfinished_trips(app: &App) -> Table<App, UnfinishedTrip, Filters> {
     // Only make one pass through prebaked data
     let trip_times_before = if app.has_prebaked().is_some() {
         let mut times = HashMap::new();
@@ -504,7 +503,7 @@ fn make_table_unfinished_trips(app: &App) -> Table<UnfinishedTrip, Filters> {
     }
 
     // Reuse the same filters, but ignore modified and capped trips
-    let filter: Filter<UnfinishedTrip, Filters> = Filter {
+    let filter: Filter<App, UnfinishedTrip, Filters> = Filter {
         state: Filters {
             modes: TripMode::all().into_iter().collect(),
             off_map_starts: true,
@@ -554,18 +553,16 @@ fn make_table_unfinished_trips(app: &App) -> Table<UnfinishedTrip, Filters> {
         }),
         Col::Static,
     );
-    table.column(
+    table.text_col(
         "Departure",
-        Box::new(|ctx, _, x| Text::from(Line(x.departure.ampm_tostring())).render(ctx)),
-        Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.departure))),
+        Box::new(|_, x| x.departure.ampm_tostring()),
+        Col::sortable_by_key(|x| x.departure),
     );
     if app.has_prebaked().is_some() {
-        table.column(
+        table.text_col(
             "Estimated duration",
-            Box::new(|ctx, app, x| {
-                Text::from(Line(x.duration_before.to_string(&app.opts.units))).render(ctx)
-            }),
-            Col::Sortable(Box::new(|rows| rows.sort_by_key(|x| x.duration_before))),
+            Box::new(|app, x| x.duration_before.to_string(&app.opts.units)),
+            Col::sortable_by_key(|x| x.duration_before),
         );
     }
 
@@ -633,7 +630,7 @@ fn trip_category_selector(ctx: &mut EventCtx, app: &App, tab: DashTab) -> Widget
 fn make_panel_finished_trips(
     ctx: &mut EventCtx,
     app: &App,
-    table: &Table<FinishedTrip, Filters>,
+    table: &Table<App, FinishedTrip, Filters>,
 ) -> Panel {
     Panel::new(Widget::col(vec![
         DashTab::FinishedTripTable.picker(ctx, app),
@@ -651,7 +648,7 @@ fn make_panel_finished_trips(
 fn make_panel_cancelled_trips(
     ctx: &mut EventCtx,
     app: &App,
-    table: &Table<CancelledTrip, Filters>,
+    table: &Table<App, CancelledTrip, Filters>,
 ) -> Panel {
     Panel::new(Widget::col(vec![
         DashTab::FinishedTripTable.picker(ctx, app),
@@ -668,7 +665,7 @@ fn make_panel_cancelled_trips(
 fn make_panel_unfinished_trips(
     ctx: &mut EventCtx,
     app: &App,
-    table: &Table<UnfinishedTrip, Filters>,
+    table: &Table<App, UnfinishedTrip, Filters>,
 ) -> Panel {
     Panel::new(Widget::col(vec![
         DashTab::FinishedTripTable.picker(ctx, app),
@@ -3,8 +3,8 @@ use std::collections::BTreeMap;
 use geom::{Duration, Time};
 use sim::{OrigPersonID, PersonID, TripID};
 use widgetry::{
-    Btn, Color, EventCtx, GfxCtx, HorizontalAlignment, Line, Outcome, Panel, RewriteColor, State,
-    Text, TextExt, VerticalAlignment, Widget,
+    tr_n, Btn, Color, EventCtx, GfxCtx, HorizontalAlignment, Line, Outcome, Panel, RewriteColor,
+    State, Text, TextExt, VerticalAlignment, Widget,
 };
 
 use crate::app::App;
@@ -245,7 +245,7 @@ fn make_meter(
         Widget::horiz_separator(ctx, 0.2),
         Widget::row(vec![
             Btn::svg_def("system/assets/tools/location.svg").build(ctx, "locate VIP", None),
-            format!("{}/{} trips done", done, trips).draw_text(ctx),
+            format!("{}/{}", done, tr_n("{} trip done", "{} trips done", trips)).draw_text(ctx),
             txt.draw(ctx),
         ]),
     ]))
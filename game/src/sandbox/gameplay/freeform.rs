@@ -3,7 +3,7 @@ use rand::Rng;
 
 use abstutil::Timer;
 use geom::{Distance, Polygon};
-use map_model::{BuildingID, IntersectionID, Position, NORMAL_LANE_THICKNESS};
+use map_model::{BikeRoutingProfile, BuildingID, IntersectionID, Position, NORMAL_LANE_THICKNESS};
 use sim::{
     DrivingGoal, IndividTrip, PersonID, PersonSpec, Scenario, SidewalkSpot, SpawnTrip,
     TripEndpoint, TripMode, TripPurpose, TripSpec,
@@ -289,6 +289,7 @@ impl State<App> for AgentSpawner {
                             scenario.people.push(PersonSpec {
                                 id: PersonID(app.primary.sim.get_all_people().len() + i),
                                 orig_id: None,
+                                bike_profile: BikeRoutingProfile::default(),
                                 trips: vec![IndividTrip::new(
                                     app.primary.sim.time(),
                                     TripPurpose::Shopping,
@@ -161,7 +161,9 @@ impl GameplayMode {
                 }
                 EditCmd::ChangeIntersection { ref new, .. } => match new {
                     // TODO Conflating construction
-                    EditIntersection::StopSign(_) | EditIntersection::Closed => {
+                    EditIntersection::StopSign(_)
+                    | EditIntersection::Closed
+                    | EditIntersection::Roundabout => {
                         if !self.can_edit_stop_signs() {
                             return false;
                         }
@@ -169,6 +171,11 @@ impl GameplayMode {
                     _ => {}
                 },
                 EditCmd::ChangeRouteSchedule { .. } => {}
+                EditCmd::ChangeModalFilter { .. } => {
+                    if !self.can_edit_lanes() {
+                        return false;
+                    }
+                }
             }
         }
         true
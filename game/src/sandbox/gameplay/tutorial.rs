@@ -3,7 +3,7 @@ use std::collections::BTreeSet;
 use abstutil::Timer;
 use geom::{ArrowCap, Distance, Duration, PolyLine, Pt2D, Time};
 use map_model::raw::OriginalRoad;
-use map_model::{osm, BuildingID, DirectedRoadID, Direction, Map, Position};
+use map_model::{osm, BikeRoutingProfile, BuildingID, DirectedRoadID, Direction, Map, Position};
 use sim::{
     AgentID, Analytics, BorderSpawnOverTime, CarID, DrivingGoal, IndividTrip, OriginDestination,
     PersonID, PersonSpec, Scenario, ScenarioGenerator, SpawnOverTime, SpawnTrip, TripPurpose,
@@ -1062,6 +1062,7 @@ impl TutorialState {
                     scenario.people.push(PersonSpec {
                         id: PersonID(0),
                         orig_id: None,
+                        bike_profile: BikeRoutingProfile::default(),
                         trips: vec![IndividTrip::new(
                             Time::START_OF_DAY,
                             TripPurpose::Shopping,
@@ -1080,6 +1081,7 @@ impl TutorialState {
                         scenario.people.push(PersonSpec {
                             id: PersonID(i + 1),
                             orig_id: None,
+                            bike_profile: BikeRoutingProfile::default(),
                             trips: vec![IndividTrip::new(
                                 Time::START_OF_DAY,
                                 TripPurpose::Shopping,
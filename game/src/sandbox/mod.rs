@@ -5,7 +5,7 @@ pub use time_warp::TimeWarpScreen;
 
 use abstutil::prettyprint_usize;
 use geom::{Circle, Distance, Time};
-use sim::{Analytics, Scenario};
+use sim::{Analytics, Scenario, TripEndpoint};
 use widgetry::{
     lctrl, Btn, Choice, EventCtx, GfxCtx, HorizontalAlignment, Key, Line, Outcome, Panel, State,
     Text, TextExt, UpdateType, VerticalAlignment, Widget,
@@ -14,7 +14,9 @@ use widgetry::{
 use self::misc_tools::RoutePreview;
 pub use self::misc_tools::TurnExplorer;
 use crate::app::App;
-use crate::common::{tool_panel, CommonState, ContextualActions, IsochroneViewer, Minimap};
+use crate::common::{
+    tool_panel, CommonState, ContextualActions, IsochroneViewer, Minimap, RoutePlanner,
+};
 use crate::debug::DebugMode;
 use crate::edit::{
     apply_map_edits, can_edit_lane, EditMode, LaneEditor, SaveEdits, StopSignEditor,
@@ -28,9 +30,11 @@ use crate::options::OptionsPanel;
 use crate::pregame::MainMenu;
 use crate::render::{unzoomed_agent_radius, UnzoomedAgents};
 
+mod compare;
 pub mod dashboards;
 pub mod gameplay;
 mod misc_tools;
+mod playback;
 mod speed;
 mod time_warp;
 mod uber_turns;
@@ -186,7 +190,7 @@ impl State<App> for SandboxMode {
             }
         }
         if let Some(ref mut am) = self.controls.agent_meter {
-            if let Some(t) = am.event(ctx, app) {
+            if let Some(t) = am.event(ctx, app, &self.gameplay_mode) {
                 return t;
             }
         }
@@ -369,10 +373,15 @@ impl AgentMeter {
                         prettyprint_usize(counts.sov_drivers)
                     ))
                     .secondary(),
+                    Line(format!(
+                        "Delivery trucks: {}",
+                        prettyprint_usize(counts.trucks)
+                    ))
+                    .secondary(),
                 ]),
             )
             .margin_right(5),
-            prettyprint_usize(counts.sov_drivers).draw_text(ctx),
+            prettyprint_usize(counts.sov_drivers + counts.trucks).draw_text(ctx),
         ]));
 
         row.push(Widget::custom_row(vec![
@@ -430,6 +439,11 @@ impl AgentMeter {
                     .build(ctx, "more data", Key::Q)
                     .align_right(),
             ]),
+            if app.primary.map.get_edits().commands.is_empty() {
+                Widget::nothing()
+            } else {
+                Btn::text_bg2("compare with baseline").build_def(ctx, None)
+            },
         ];
 
         let panel = Panel::new(Widget::col(rows))
@@ -442,10 +456,15 @@ impl AgentMeter {
         }
     }
 
-    pub fn event(&mut self, ctx: &mut EventCtx, app: &mut App) -> Option<Transition> {
+    pub fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        app: &mut App,
+        gameplay: &GameplayMode,
+    ) -> Option<Transition> {
         if self.time != app.primary.sim.time() {
             *self = AgentMeter::new(ctx, app);
-            return self.event(ctx, app);
+            return self.event(ctx, app, gameplay);
         }
         match self.panel.event(ctx) {
             Outcome::Clicked(x) => match x.as_ref() {
@@ -454,6 +473,11 @@ impl AgentMeter {
                         ctx, app,
                     )));
                 }
+                "compare with baseline" => {
+                    return Some(Transition::Push(compare::CompareSims::new(
+                        ctx, app, gameplay,
+                    )));
+                }
                 "see why results are tentative" => {
                     return Some(Transition::Push(PopupMsg::new(
                         ctx,
@@ -513,6 +537,7 @@ impl ContextualActions for Actions {
                     }
                 }
                 ID::Building(_) => {
+                    actions.push((Key::P, "plan a route from here".to_string()));
                     if app.opts.dev {
                         actions.push((Key::I, "explore isochrone from here".to_string()));
                     }
@@ -559,6 +584,9 @@ impl ContextualActions for Actions {
                 Transition::Push(EditMode::new(ctx, app, self.gameplay.clone())),
                 Transition::Push(LaneEditor::new(ctx, app, l, self.gameplay.clone())),
             ]),
+            (ID::Building(b), "plan a route from here") => {
+                Transition::Push(RoutePlanner::new(ctx, app, TripEndpoint::Bldg(b)))
+            }
             (ID::Building(b), "explore isochrone from here") => {
                 Transition::Push(IsochroneViewer::new(ctx, app, b))
             }
@@ -0,0 +1,340 @@
+//! Play back a recording of agent positions. Unlike the live simulation, this can jump backwards
+//! instantly, and clips can be exported as an animated GIF or a sequence of PNGs.
+
+use instant::Instant;
+
+use abstutil::Timer;
+use geom::{Duration, Time};
+use sim::Recording;
+use widgetry::{
+    Btn, Choice, DrawBaselayer, Drawable, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Key,
+    Line, Outcome, Panel, Slider, State, Text, TextExt, UpdateType, VerticalAlignment, Widget,
+};
+
+use crate::app::App;
+use crate::game::{PopupMsg, Transition};
+use crate::render::DrawMap;
+
+/// How often agent positions are captured while recording. Smaller intervals make smoother
+/// playback, but use more memory.
+pub const RECORDING_INTERVAL: Duration = Duration::const_seconds(10.0);
+
+/// The longer side of exported images, in pixels
+const EXPORT_SIZE_PX: u32 = 800;
+
+pub struct Playback {
+    panel: Panel,
+    recording: Recording,
+    time: Time,
+    paused: bool,
+    // The part of the recording to export
+    clip: (Time, Time),
+    agents: Drawable,
+}
+
+impl Playback {
+    /// Plays back `app.primary.recording`, handing it back when done.
+    pub fn new(ctx: &mut EventCtx, app: &mut App) -> Box<dyn State<App>> {
+        let recording = app.primary.recording.take().unwrap();
+        let (start, end) = match recording.time_range() {
+            Some(pair) => pair,
+            None => {
+                app.primary.recording = Some(recording);
+                return PopupMsg::new(
+                    ctx,
+                    "Nothing recorded",
+                    vec!["Run the simulation while recording first."],
+                );
+            }
+        };
+        let mut playback = Playback {
+            panel: make_panel(ctx, true, 5.0, start),
+            recording,
+            time: start,
+            paused: true,
+            clip: (start, end),
+            agents: ctx.upload(GeomBatch::new()),
+        };
+        playback.jump_to(ctx, app, start);
+        Box::new(playback)
+    }
+
+    fn speed(&self) -> f64 {
+        self.panel.dropdown_value("speed")
+    }
+
+    fn percent(&self, time: Time) -> f64 {
+        let (start, end) = self.recording.time_range().unwrap();
+        if start == end {
+            return 0.0;
+        }
+        ((time - start) / (end - start)).min(1.0).max(0.0)
+    }
+
+    fn jump_to(&mut self, ctx: &mut EventCtx, app: &App, time: Time) {
+        self.time = time;
+        let agents = self.recording.agents_at(time).unwrap();
+        self.agents = ctx.upload(app.unzoomed_agents.batch(&agents));
+
+        let pct = self.percent(time);
+        self.panel.slider_mut("timeline").set_percent(ctx, pct);
+        self.panel.replace(
+            ctx,
+            "time",
+            Text::from(Line(time.ampm_tostring()))
+                .draw(ctx)
+                .named("time"),
+        );
+        self.panel.replace(
+            ctx,
+            "clip",
+            Text::from(
+                Line(format!(
+                    "Clip: {} to {}",
+                    self.clip.0.ampm_tostring(),
+                    self.clip.1.ampm_tostring()
+                ))
+                .secondary(),
+            )
+            .draw(ctx)
+            .named("clip"),
+        );
+    }
+
+    /// One batch of agents per recorded frame in the clip
+    fn clip_frames(&self, app: &App) -> Vec<GeomBatch> {
+        self.recording
+            .agents_between(self.clip.0, self.clip.1)
+            .iter()
+            .map(|agents| app.unzoomed_agents.batch(agents))
+            .collect()
+    }
+
+    fn export(&self, ctx: &mut EventCtx, app: &App, gif: bool) -> Result<Vec<String>, String> {
+        let frames = self.clip_frames(app);
+        if frames.is_empty() {
+            return Err("The clip doesn't have any frames".to_string());
+        }
+        // Export exactly what's on the screen
+        let bounds = ctx.canvas.get_screen_bounds();
+        let base = format!(
+            "recording_{}_{}",
+            app.primary.map.get_name(),
+            self.clip.0.as_filename()
+        );
+        let timer_name = format!("render {} frames", frames.len());
+        ctx.loading_screen("export recording", |_, timer: &mut Timer| {
+            timer.start(&timer_name);
+            let background = DrawMap::unzoomed_batch(&app.primary.map, &app.cs);
+            let result = if gif {
+                // Play back as fast as the current speed
+                let delay = self.recording.interval / self.speed();
+                let delay_ms = (delay.inner_seconds() * 1000.0).max(20.0) as u32;
+                let path = format!("{}.gif", base);
+                GeomBatch::render_to_gif(
+                    &background,
+                    frames,
+                    &bounds,
+                    EXPORT_SIZE_PX,
+                    delay_ms,
+                    &path,
+                )
+                .map(|_| vec![path])
+            } else {
+                GeomBatch::render_to_png_sequence(
+                    &background,
+                    frames,
+                    &bounds,
+                    EXPORT_SIZE_PX,
+                    &base,
+                )
+            };
+            timer.stop(&timer_name);
+            result
+        })
+    }
+}
+
+impl State<App> for Playback {
+    fn event(&mut self, ctx: &mut EventCtx, app: &mut App) -> Transition {
+        ctx.canvas_movement();
+
+        match self.panel.event(ctx) {
+            Outcome::Clicked(x) => match x.as_ref() {
+                "close" => {
+                    return Transition::Pop;
+                }
+                "play" | "pause" => {
+                    self.paused = x == "pause";
+                    // Playing from the very end starts over
+                    let (start, end) = self.recording.time_range().unwrap();
+                    if !self.paused && self.time == end {
+                        self.time = start;
+                    }
+                    self.panel = make_panel(ctx, self.paused, self.speed(), self.time);
+                    self.jump_to(ctx, app, self.time);
+                }
+                "start clip here" => {
+                    self.clip.0 = self.time;
+                    if self.clip.1 < self.time {
+                        self.clip.1 = self.recording.time_range().unwrap().1;
+                    }
+                    self.jump_to(ctx, app, self.time);
+                }
+                "end clip here" => {
+                    self.clip.1 = self.time;
+                    if self.clip.0 > self.time {
+                        self.clip.0 = self.recording.time_range().unwrap().0;
+                    }
+                    self.jump_to(ctx, app, self.time);
+                }
+                "export GIF" | "export frames" => {
+                    let title = if x == "export GIF" {
+                        "Exported an animation"
+                    } else {
+                        "Exported frames"
+                    };
+                    return Transition::Push(match self.export(ctx, app, x == "export GIF") {
+                        Ok(paths) => {
+                            let msg = if paths.len() == 1 {
+                                format!("Wrote {}", paths[0])
+                            } else {
+                                format!("Wrote {} to {}", paths[0], paths.last().unwrap())
+                            };
+                            PopupMsg::new(ctx, title, vec![msg])
+                        }
+                        Err(err) => PopupMsg::new(ctx, "Export failed", vec![err]),
+                    });
+                }
+                _ => unreachable!(),
+            },
+            _ => {}
+        }
+
+        // Did the user drag the timeline?
+        let pct = self.panel.slider("timeline").get_percent();
+        if (pct - self.percent(self.time)).abs() > 0.001 {
+            let (start, end) = self.recording.time_range().unwrap();
+            self.jump_to(ctx, app, start + pct * (end - start));
+        }
+
+        if !self.paused {
+            if let Some(real_dt) = ctx.input.nonblocking_is_update_event() {
+                ctx.input.use_update_event();
+                let end = self.recording.time_range().unwrap().1;
+                let time = end.min(self.time + self.speed() * real_dt);
+                if time == end {
+                    self.paused = true;
+                    self.panel = make_panel(ctx, self.paused, self.speed(), time);
+                }
+                self.jump_to(ctx, app, time);
+            }
+            if !self.paused {
+                ctx.request_update(UpdateType::Game);
+            }
+        }
+
+        Transition::Keep
+    }
+
+    fn draw_baselayer(&self) -> DrawBaselayer {
+        DrawBaselayer::Custom
+    }
+
+    fn draw(&self, g: &mut GfxCtx, app: &App) {
+        g.clear(app.cs.void_background);
+        // Agents are only recorded as dots, so always show the unzoomed view.
+        let draw_map = &app.primary.draw_map;
+        g.redraw(&draw_map.boundary_polygon);
+        g.redraw(&draw_map.draw_all_areas);
+        g.redraw(&draw_map.draw_all_unzoomed_parking_lots);
+        g.redraw(&draw_map.draw_all_unzoomed_roads_and_intersections);
+        g.redraw(&draw_map.draw_all_buildings);
+        g.redraw(&self.agents);
+
+        self.panel.draw(g);
+    }
+
+    fn on_destroy(&mut self, _: &mut EventCtx, app: &mut App) {
+        // Keep the recording around until the player discards it
+        let interval = self.recording.interval;
+        app.primary.recording = Some(std::mem::replace(
+            &mut self.recording,
+            Recording::new(interval),
+        ));
+    }
+}
+
+/// Like `Sim::time_limited_step`, but while recording, capture frames along the way. Otherwise
+/// jumping far ahead in one step would leave a gap in the recording.
+pub fn time_limited_step(app: &mut App, dt: Duration, real_time_limit: Duration) {
+    let recording = match app.primary.recording {
+        Some(ref mut recording) if app.primary.is_recording => recording,
+        _ => {
+            app.primary.sim.time_limited_step(
+                &app.primary.map,
+                dt,
+                real_time_limit,
+                &mut app.primary.sim_cb,
+            );
+            return;
+        }
+    };
+
+    let started_at = Instant::now();
+    let end_time = app.primary.sim.time() + dt;
+    loop {
+        let real_time_left = real_time_limit - Duration::realtime_elapsed(started_at);
+        if app.primary.sim.time() >= end_time || real_time_left <= Duration::ZERO {
+            break;
+        }
+        app.primary.sim.time_limited_step(
+            &app.primary.map,
+            (end_time - app.primary.sim.time()).min(recording.interval),
+            real_time_left,
+            &mut app.primary.sim_cb,
+        );
+        recording.observe(&app.primary.sim, &app.primary.map);
+    }
+}
+
+fn make_panel(ctx: &mut EventCtx, paused: bool, speed: f64, time: Time) -> Panel {
+    Panel::new(Widget::col(vec![
+        Widget::row(vec![
+            Line("Recorded playback").small_heading().draw(ctx),
+            Btn::close(ctx),
+        ]),
+        Widget::row(vec![
+            if paused {
+                Btn::text_bg2("play").build_def(ctx, Key::Space)
+            } else {
+                Btn::text_bg2("pause").build_def(ctx, Key::Space)
+            },
+            Widget::dropdown(
+                ctx,
+                "speed",
+                speed,
+                vec![
+                    Choice::new("real-time", 1.0),
+                    Choice::new("5x", 5.0),
+                    Choice::new("30x", 30.0),
+                    Choice::new("300x", 300.0),
+                    Choice::new("3600x", 3600.0),
+                ],
+            ),
+            time.ampm_tostring().draw_text(ctx).named("time"),
+        ]),
+        Slider::horizontal(ctx, 0.5 * ctx.canvas.window_width, 25.0, 0.0).named("timeline"),
+        Widget::row(vec![
+            Btn::text_bg2("start clip here").build_def(ctx, None),
+            Btn::text_bg2("end clip here").build_def(ctx, None),
+        ]),
+        "Clip".draw_text(ctx).named("clip"),
+        Widget::row(vec![
+            Btn::text_bg2("export GIF").build_def(ctx, None),
+            Btn::text_bg2("export frames").build_def(ctx, None),
+        ]),
+    ]))
+    .aligned(HorizontalAlignment::Center, VerticalAlignment::Bottom)
+    .build(ctx)
+}
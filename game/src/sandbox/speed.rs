@@ -1,5 +1,5 @@
 use geom::{Duration, Polygon, Time};
-use sim::AlertLocation;
+use sim::{AlertLocation, Recording};
 use widgetry::{
     Btn, Choice, Color, EventCtx, GeomBatch, GfxCtx, HorizontalAlignment, Key, Line, Outcome,
     Panel, PersistentSplit, RewriteColor, Text, VerticalAlignment, Widget,
@@ -9,6 +9,7 @@ use crate::app::App;
 use crate::common::Warping;
 use crate::game::{PopupMsg, Transition};
 use crate::helpers::ID;
+use crate::sandbox::playback::{self, Playback, RECORDING_INTERVAL};
 use crate::sandbox::time_warp::JumpToTime;
 use crate::sandbox::{GameplayMode, SandboxMode, TimeWarpScreen};
 
@@ -107,6 +108,24 @@ impl SpeedControls {
                     .container()
                     .padding(9),
             ])
+            .bg(app.cs.section_bg)
+            .margin_right(16),
+        );
+
+        row.push(
+            if app.primary.is_recording {
+                Btn::text_fg("stop recording").build_def(ctx, None)
+            } else if app.primary.recording.is_some() {
+                Widget::custom_row(vec![
+                    Btn::text_fg("record").build_def(ctx, None),
+                    Btn::text_fg("play back recording").build_def(ctx, None),
+                    Btn::text_fg("discard recording").build_def(ctx, None),
+                ])
+            } else {
+                Btn::text_fg("record").build_def(ctx, None)
+            }
+            .container()
+            .padding(9)
             .bg(app.cs.section_bg),
         );
 
@@ -178,6 +197,33 @@ impl SpeedControls {
                         )));
                     }
                 }
+                "record" => {
+                    // Continue an existing recording
+                    let recording = app
+                        .primary
+                        .recording
+                        .get_or_insert_with(|| Recording::new(RECORDING_INTERVAL));
+                    recording.observe(&app.primary.sim, &app.primary.map);
+                    app.primary.is_recording = true;
+                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                    return None;
+                }
+                "stop recording" => {
+                    app.primary.is_recording = false;
+                    self.paused = true;
+                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                    return Some(Transition::Push(Playback::new(ctx, app)));
+                }
+                "play back recording" => {
+                    self.paused = true;
+                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                    return Some(Transition::Push(Playback::new(ctx, app)));
+                }
+                "discard recording" => {
+                    app.primary.recording = None;
+                    self.panel = SpeedControls::make_panel(ctx, app, self.paused, self.setting);
+                    return None;
+                }
                 "jump to specific time" => {
                     return Some(Transition::Push(JumpToTime::new(
                         ctx,
@@ -260,12 +306,7 @@ impl SpeedControls {
                 let dt = multiplier * real_dt;
                 // TODO This should match the update frequency in widgetry. Plumb along the deadline
                 // or frequency to here.
-                app.primary.sim.time_limited_step(
-                    &app.primary.map,
-                    dt,
-                    Duration::seconds(0.033),
-                    &mut app.primary.sim_cb,
-                );
+                playback::time_limited_step(app, dt, Duration::seconds(0.033));
                 app.recalculate_current_selection(ctx);
             }
         }
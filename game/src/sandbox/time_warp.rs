@@ -12,7 +12,7 @@ use crate::common::Warping;
 use crate::game::{PopupMsg, Transition};
 use crate::helpers::{grey_out_map, ID};
 use crate::render::DrawOptions;
-use crate::sandbox::{GameplayMode, SandboxMode};
+use crate::sandbox::{playback, GameplayMode, SandboxMode};
 
 // TODO Text entry would be great
 pub struct JumpToTime {
@@ -299,12 +299,8 @@ impl State<App> for TimeWarpScreen {
     fn event(&mut self, ctx: &mut EventCtx, app: &mut App) -> Transition {
         if ctx.input.nonblocking_is_update_event().is_some() {
             ctx.input.use_update_event();
-            app.primary.sim.time_limited_step(
-                &app.primary.map,
-                self.target - app.primary.sim.time(),
-                Duration::seconds(0.033),
-                &mut app.primary.sim_cb,
-            );
+            let dt = self.target - app.primary.sim.time();
+            playback::time_limited_step(app, dt, Duration::seconds(0.033));
             for (t, maybe_i, alert) in app.primary.sim.clear_alerts() {
                 // TODO Just the first :(
                 return Transition::Replace(PopupMsg::new(
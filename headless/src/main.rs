@@ -17,13 +17,14 @@ use std::convert::TryFrom;
 use std::error::Error;
 use std::sync::RwLock;
 
+use geojson::{Feature, FeatureCollection, GeoJson, Geometry, Value};
 use hyper::{Body, Request, Response, Server, StatusCode};
 use rand::SeedableRng;
 use rand_xorshift::XorShiftRng;
 use serde::{Deserialize, Serialize};
 
 use abstutil::{serialize_btreemap, CmdArgs, Timer};
-use geom::{Duration, LonLat, Time};
+use geom::{Duration, GPSBounds, LonLat, Polygon, Pt2D, Time};
 use map_model::{
     CompressedMovementID, ControlTrafficSignal, EditCmd, EditIntersection, IntersectionID, Map,
     MovementID, PermanentMapEdits, RoadID, TurnID,
@@ -310,6 +311,7 @@ fn handle_command(
                 .map(|((r, a, hr), cnt)| (*r, *a, *hr, *cnt))
                 .collect(),
         })),
+        "/data/export-layer" => export_layer(&params["name"], sim, map),
         // Controlling the map
         "/map/get-edits" => {
             let mut edits = map.get_edits().clone();
@@ -399,7 +401,10 @@ impl LoadSim {
 
         let mut map = Map::new(abstutil::path_map(&scenario.map_name), timer);
         if let Some(perma) = self.edits.clone() {
-            let edits = PermanentMapEdits::from_permanent(perma, &map).unwrap();
+            let (edits, relocations) = PermanentMapEdits::from_permanent(perma, &map).unwrap();
+            for relocation in relocations {
+                timer.warn(relocation);
+            }
             map.must_apply_edits(edits, timer);
             map.recalculate_pathfinding_after_edits(timer);
         }
@@ -415,3 +420,92 @@ impl LoadSim {
         (map, sim)
     }
 }
+
+/// The values behind some of the game's layers, as GeoJSON in the same format the game's "export
+/// current layer data" writes.
+fn export_layer(name: &str, sim: &Sim, map: &Map) -> Result<String, Box<dyn Error>> {
+    let gps_bounds = map.get_gps_bounds();
+    let mut features = Vec::new();
+    match name {
+        "throughput" => {
+            let stats = sim.get_analytics();
+            for (r, cnt) in stats.road_thruput.all_total_counts().consume() {
+                features.push(layer_feature(
+                    "road",
+                    r.0,
+                    line_string(map.get_r(r).center_pts.points(), gps_bounds),
+                    "count",
+                    cnt as f64,
+                ));
+            }
+            for (i, cnt) in stats.intersection_thruput.all_total_counts().consume() {
+                features.push(layer_feature(
+                    "intersection",
+                    i.0,
+                    polygon(&map.get_i(i).polygon, gps_bounds),
+                    "count",
+                    cnt as f64,
+                ));
+            }
+        }
+        "delay" => {
+            let mut delays = sim.all_waiting_people();
+            for agent in sim.get_unzoomed_agents(map) {
+                if let Some(delay) = agent.person.and_then(|p| delays.remove(&p)) {
+                    features.push(layer_feature(
+                        "person",
+                        agent.person.unwrap().0,
+                        Value::Point(lon_lat(agent.pos, gps_bounds)),
+                        "delay (minutes)",
+                        delay.inner_seconds() / 60.0,
+                    ));
+                }
+            }
+        }
+        _ => {
+            return Err(format!("Unknown layer {}; try throughput or delay", name).into());
+        }
+    }
+    Ok(GeoJson::from(FeatureCollection {
+        bbox: None,
+        features,
+        foreign_members: None,
+    })
+    .to_string())
+}
+
+fn layer_feature(kind: &str, id: usize, geometry: Value, column: &str, value: f64) -> Feature {
+    let mut properties = serde_json::Map::new();
+    properties.insert("type".to_string(), kind.into());
+    properties.insert("id".to_string(), id.to_string().into());
+    properties.insert(column.to_string(), value.into());
+    Feature {
+        bbox: None,
+        geometry: Some(Geometry::new(geometry)),
+        id: None,
+        properties: Some(properties),
+        foreign_members: None,
+    }
+}
+
+fn lon_lat(pt: Pt2D, gps_bounds: &GPSBounds) -> Vec<f64> {
+    let gps = pt.to_gps(gps_bounds);
+    vec![gps.x(), gps.y()]
+}
+
+fn line_string(pts: &[Pt2D], gps_bounds: &GPSBounds) -> Value {
+    Value::LineString(pts.iter().map(|pt| lon_lat(*pt, gps_bounds)).collect())
+}
+
+// Just the outer ring
+fn polygon(poly: &Polygon, gps_bounds: &GPSBounds) -> Value {
+    let mut ring: Vec<Vec<f64>> = poly
+        .points()
+        .iter()
+        .map(|pt| lon_lat(*pt, gps_bounds))
+        .collect();
+    if ring.first() != ring.last() {
+        ring.push(ring[0].clone());
+    }
+    Value::Polygon(vec![ring])
+}
@@ -9,7 +9,7 @@ use geom::{Polygon, Ring};
 use kml::ExtraShapes;
 
 use crate::configuration::ImporterConfiguration;
-use crate::utils::{download, download_kml, osmconvert};
+use crate::utils::{clip_osm, download, download_kml};
 
 fn input(config: &ImporterConfiguration, timer: &mut Timer) {
     download(
@@ -52,12 +52,7 @@ fn input(config: &ImporterConfiguration, timer: &mut Timer) {
 
 pub fn osm_to_raw(name: &str, timer: &mut Timer, config: &ImporterConfiguration) {
     input(config, timer);
-    osmconvert(
-        "input/berlin/osm/berlin-latest.osm.pbf",
-        format!("input/berlin/polygons/{}.poly", name),
-        format!("input/berlin/osm/{}.osm", name),
-        config,
-    );
+    clip_osm("berlin", "input/berlin/osm/berlin-latest.osm.pbf", timer);
 
     let map = convert_osm::convert(
         convert_osm::Options {
@@ -79,6 +74,8 @@ pub fn osm_to_raw(name: &str, timer: &mut Timer, config: &ImporterConfiguration)
             private_offstreet_parking: convert_osm::PrivateOffstreetParking::FixedPerBldg(3),
             elevation: None,
             include_railroads: true,
+            include_footways: false,
+            split_midblock_crossings: false,
         },
         timer,
     );
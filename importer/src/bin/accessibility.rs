@@ -0,0 +1,97 @@
+//! Counts how many jobs, grocery stores, schools, and clinics every residence can reach within 15,
+//! 30, and 45 minutes by walking, biking, and driving, and writes one CSV row per residence. If
+//! edits are passed in, each score also has a column for the map before the edits.
+//!
+//! Usage: accessibility --map=data/system/maps/montlake.bin [--edits=proposal.json]
+//! [--output=accessibility.csv]
+
+use abstutil::{CmdArgs, Timer};
+use geom::Duration;
+use map_model::accessibility::{Accessibility, Opportunity};
+use map_model::{Map, MapEdits, PathConstraints};
+
+const MINUTES: [usize; 3] = [15, 30, 45];
+
+fn main() {
+    let mut args = CmdArgs::new();
+    let map_path = args.required("--map");
+    let edits_path = args.optional("--edits");
+    let output = args
+        .optional("--output")
+        .unwrap_or_else(|| "accessibility.csv".to_string());
+    args.done();
+
+    let mut timer = Timer::new("calculate accessibility");
+    let mut map = Map::new(map_path, &mut timer);
+    let before = if let Some(path) = edits_path {
+        let before = calculate_all(&map, &mut timer);
+        let edits = MapEdits::load(&map, path.clone(), &mut timer)
+            .unwrap_or_else(|err| panic!("Couldn't load {}: {}", path, err));
+        map.must_apply_edits(edits, &mut timer);
+        Some(before)
+    } else {
+        None
+    };
+    let after = calculate_all(&map, &mut timer);
+
+    let mut header = vec![
+        "building".to_string(),
+        "longitude".to_string(),
+        "latitude".to_string(),
+    ];
+    for (name, _) in &after {
+        for o in Opportunity::all() {
+            for m in &MINUTES {
+                let column = format!("{}_{}_{}min", name, o.describe().replace(' ', "_"), m);
+                if before.is_some() {
+                    header.push(format!("{}_before", column));
+                }
+                header.push(column);
+            }
+        }
+    }
+    let mut out = header.join(",");
+    out.push('\n');
+
+    for b in after[0].1.residences() {
+        let gps = map.get_b(b).label_center.to_gps(map.get_gps_bounds());
+        let mut row = vec![b.0.to_string(), gps.x().to_string(), gps.y().to_string()];
+        for (idx, (_, scores)) in after.iter().enumerate() {
+            for o in Opportunity::all() {
+                for m in &MINUTES {
+                    let limit = Duration::minutes(*m);
+                    if let Some(ref before) = before {
+                        row.push(before[idx].1.get(b, limit, o).unwrap_or(0).to_string());
+                    }
+                    row.push(scores.get(b, limit, o).unwrap().to_string());
+                }
+            }
+        }
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+
+    std::fs::write(&output, out).unwrap();
+    println!("Wrote {}", output);
+}
+
+fn calculate_all(map: &Map, timer: &mut Timer) -> Vec<(&'static str, Accessibility)> {
+    vec![
+        ("walk", PathConstraints::Pedestrian),
+        ("bike", PathConstraints::Bike),
+        ("drive", PathConstraints::Car),
+    ]
+    .into_iter()
+    .map(|(name, constraints)| {
+        timer.start(format!("calculate for {}", name));
+        let scores = Accessibility::calculate(
+            map,
+            constraints,
+            MINUTES.iter().map(|m| Duration::minutes(*m)).collect(),
+            timer,
+        );
+        timer.stop(format!("calculate for {}", name));
+        (name, scores)
+    })
+    .collect()
+}
@@ -0,0 +1,103 @@
+//! Combine proposals worked on separately. Usage:
+//!
+//! - `edits_tool diff a.json b.json`
+//! - `edits_tool merge a.json b.json --out=merged.json [--prefer_second] [--force]`
+//! - `edits_tool rebase --map=data/system/maps/montlake.bin a.json --out=rebased.json`
+
+use abstutil::{CmdArgs, Timer};
+use map_model::{Map, PermanentMapEdits};
+
+fn main() {
+    let mut args = CmdArgs::new();
+    let mut timer = Timer::throwaway();
+    match args.required_free().as_ref() {
+        "diff" => {
+            let first = load(args.required_free(), None, &mut timer);
+            let second = load(args.required_free(), None, &mut timer);
+            args.done();
+
+            let diff = first.diff(&second);
+            for target in diff.only_first {
+                println!("- {} only changed in {}", target, first.edits_name);
+            }
+            for target in diff.only_second {
+                println!("- {} only changed in {}", target, second.edits_name);
+            }
+            for target in diff.same {
+                println!("- {} changed the same way in both", target);
+            }
+            for (target, details) in diff.different {
+                println!("- {} changed differently: {}", target, details.join(", "));
+            }
+        }
+        "merge" => {
+            let first = load(args.required_free(), None, &mut timer);
+            let second = load(args.required_free(), None, &mut timer);
+            let out = args.required("--out");
+            let prefer_second = args.enabled("--prefer_second");
+            let force = args.enabled("--force");
+            args.done();
+
+            if first.map_name != second.map_name {
+                println!(
+                    "{} is for {}, but {} is for {}",
+                    first.edits_name, first.map_name, second.edits_name, second.map_name
+                );
+                std::process::exit(1);
+            }
+
+            let (merged, conflicts) = first.merge(&second, prefer_second);
+            for conflict in &conflicts {
+                println!("Conflict: both change {}", conflict.target);
+            }
+            if !conflicts.is_empty() && !force {
+                println!(
+                    "{} conflicts. Pass --force to keep the version from {}",
+                    conflicts.len(),
+                    if prefer_second {
+                        &second.edits_name
+                    } else {
+                        &first.edits_name
+                    }
+                );
+                std::process::exit(1);
+            }
+            abstutil::write_json(out, &merged);
+        }
+        "rebase" => {
+            let map = Map::new(args.required("--map"), &mut timer);
+            let path = args.required_free();
+            let out = args.required("--out");
+            args.done();
+            let edits = load(path, Some(&map), &mut timer);
+
+            let (rebased, problems) = edits.rebase(&map);
+            for problem in &problems {
+                println!("Couldn't rebase: {}", problem);
+            }
+            abstutil::write_json(out, &rebased);
+        }
+        x => {
+            println!("Unknown command {}; try diff, merge, or rebase", x);
+            std::process::exit(1);
+        }
+    }
+}
+
+// Bad input shouldn't panic; just explain the problem and quit.
+fn load(path: String, map: Option<&Map>, timer: &mut Timer) -> PermanentMapEdits {
+    let result = if !path.ends_with(".json") {
+        Err("edits must be a .json file".to_string())
+    } else if let Some(map) = map {
+        PermanentMapEdits::load_from_file(map, path.clone(), timer)
+    } else {
+        abstutil::maybe_read_json(path.clone(), timer).map_err(|err| err.to_string())
+    };
+    match result {
+        Ok(edits) => edits,
+        Err(err) => {
+            println!("Couldn't load {}: {}", path, err);
+            std::process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Finds UI strings in the source code that a translation catalog doesn't cover yet, and writes
+//! them out in the catalog format for translators to fill in.
+//!
+//! Only string literals passed directly to `Line`, `tr`, `tr_n`, the text button builders, or
+//! `draw_text` / `batch_text` are found. Anything built with `format!` needs to use `tr_n` or be
+//! split up first.
+//!
+//! Usage: extract_ui_strings --src=game/src,widgetry/src --catalog=data/system/translations/es.json
+//! --output=missing.json
+
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value};
+
+use abstutil::CmdArgs;
+
+/// A literal directly after one of these gets translated by `Line`.
+const PREFIXES: [&str; 6] = [
+    "Line(",
+    "tr(",
+    "Btn::text_fg(",
+    "Btn::text_bg1(",
+    "Btn::text_bg2(",
+    "Btn::plaintext(",
+];
+/// And a literal directly before one of these.
+const SUFFIXES: [&str; 2] = [".draw_text(", ".batch_text("];
+
+fn main() {
+    let mut args = CmdArgs::new();
+    let src = args
+        .optional("--src")
+        .unwrap_or_else(|| "game/src,widgetry/src".to_string());
+    let catalog_path = args.optional("--catalog");
+    let output = args.optional("--output");
+    args.done();
+
+    // Key to the (one, other) forms, for plurals
+    let mut found: BTreeMap<String, Option<(String, String)>> = BTreeMap::new();
+    let mut num_files = 0;
+    for dir in src.split(',') {
+        for path in rust_files(dir) {
+            num_files += 1;
+            let code = std::fs::read_to_string(&path).unwrap();
+            for (key, plural) in extract(&code) {
+                found.insert(key, plural);
+            }
+        }
+    }
+
+    let (language, existing) = if let Some(ref path) = catalog_path {
+        let catalog: Value = serde_json::from_str(&std::fs::read_to_string(path).unwrap())
+            .unwrap_or_else(|err| panic!("Couldn't parse {}: {}", path, err));
+        (
+            catalog["language"].as_str().unwrap().to_string(),
+            catalog["messages"].as_object().unwrap().clone(),
+        )
+    } else {
+        ("TODO".to_string(), Map::new())
+    };
+
+    let mut missing = Map::new();
+    for (key, plural) in &found {
+        if existing.contains_key(key) {
+            continue;
+        }
+        let msg = match plural {
+            Some((one, other)) => json!({ "one": one, "other": other }),
+            None => Value::String(key.clone()),
+        };
+        missing.insert(key.clone(), msg);
+    }
+    println!(
+        "Found {} UI strings in {} files; {} aren't translated",
+        found.len(),
+        num_files,
+        missing.len()
+    );
+
+    let result = serde_json::to_string_pretty(&json!({
+        "language": language,
+        "messages": missing,
+    }))
+    .unwrap();
+    if let Some(path) = output {
+        std::fs::write(&path, result).unwrap();
+        println!("Wrote {}", path);
+    } else {
+        println!("{}", result);
+    }
+}
+
+fn rust_files(dir: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut queue = vec![dir.to_string()];
+    while let Some(dir) = queue.pop() {
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                queue.push(path.display().to_string());
+            } else if path.extension().map(|x| x == "rs").unwrap_or(false) {
+                files.push(path.display().to_string());
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Returns every translatable literal. Plurals from `tr_n` are keyed by the plural form.
+fn extract(code: &str) -> Vec<(String, Option<(String, String)>)> {
+    let literals = string_literals(code);
+    let mut results = Vec::new();
+    for (idx, (value, start, end)) in literals.iter().enumerate() {
+        let before = code[..*start].trim_end();
+        let after = &code[*end..];
+        if before.ends_with("tr_n(") {
+            if let Some((other, other_start, _)) = literals.get(idx + 1) {
+                if code[*end..*other_start].trim() == "," {
+                    results.push((other.clone(), Some((value.clone(), other.clone()))));
+                }
+            }
+        } else if PREFIXES.iter().any(|p| ends_with_call(before, p))
+            || SUFFIXES.iter().any(|s| after.starts_with(s))
+        {
+            results.push((value.clone(), None));
+        }
+    }
+    results
+}
+
+// Make sure "tr(" doesn't match "str(" or "attr(".
+fn ends_with_call(before: &str, prefix: &str) -> bool {
+    if !before.ends_with(prefix) {
+        return false;
+    }
+    match before[..before.len() - prefix.len()].chars().last() {
+        Some(c) => !(c.is_alphanumeric() || c == '_'),
+        None => true,
+    }
+}
+
+/// Finds all normal string literals, skipping comments, char literals, and raw strings. Returns
+/// the unescaped value, and the byte range of the literal including quotes.
+fn string_literals(code: &str) -> Vec<(String, usize, usize)> {
+    let bytes = code.as_bytes();
+    let mut results = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                    i += 1;
+                }
+                i += 2;
+            }
+            b'r' if bytes.get(i + 1) == Some(&b'"') || bytes.get(i + 1) == Some(&b'#') => {
+                // Raw strings aren't used for UI text; just skip them
+                let mut hashes = 0;
+                i += 1;
+                while bytes.get(i) == Some(&b'#') {
+                    hashes += 1;
+                    i += 1;
+                }
+                if bytes.get(i) != Some(&b'"') {
+                    continue;
+                }
+                let closing = format!("\"{}", "#".repeat(hashes));
+                i += 1;
+                match code[i..].find(&closing) {
+                    Some(offset) => {
+                        i += offset + closing.len();
+                    }
+                    None => {
+                        i = bytes.len();
+                    }
+                }
+            }
+            b'\'' => {
+                // A char literal like '"' or '\'', or a lifetime
+                if bytes.get(i + 1) == Some(&b'\\') {
+                    i += 2;
+                    while i < bytes.len() && bytes[i] != b'\'' {
+                        i += 1;
+                    }
+                    i += 1;
+                } else if bytes.get(i + 2) == Some(&b'\'') {
+                    i += 3;
+                } else {
+                    i += 1;
+                }
+            }
+            b'"' => {
+                let start = i;
+                let mut value = String::new();
+                i += 1;
+                while i < bytes.len() && bytes[i] != b'"' {
+                    if bytes[i] == b'\\' {
+                        match bytes.get(i + 1) {
+                            Some(b'n') => value.push('\n'),
+                            Some(b't') => value.push('\t'),
+                            Some(b'\n') => {
+                                // A line continuation also skips leading whitespace
+                                i += 2;
+                                while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+                                    i += 1;
+                                }
+                                continue;
+                            }
+                            Some(c) => value.push(*c as char),
+                            None => {}
+                        }
+                        i += 2;
+                    } else {
+                        let c = code[i..].chars().next().unwrap();
+                        value.push(c);
+                        i += c.len_utf8();
+                    }
+                }
+                i += 1;
+                results.push((value, start, i));
+            }
+            _ => {
+                i += 1;
+            }
+        }
+    }
+    results
+}
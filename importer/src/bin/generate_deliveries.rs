@@ -0,0 +1,21 @@
+//! Generates a "deliveries" scenario for a map and saves it. Combine it with another scenario by
+//! using the AddExtraTrips modifier.
+
+use rand::SeedableRng;
+use rand_xorshift::XorShiftRng;
+
+use abstutil::{CmdArgs, Timer};
+use map_model::Map;
+use sim::ScenarioGenerator;
+
+fn main() {
+    let mut args = CmdArgs::new();
+    let seed: u64 = args.required("--rng").parse().unwrap();
+    let mut rng = XorShiftRng::seed_from_u64(seed);
+    let mut timer = Timer::new("generate deliveries");
+    let map = Map::new(args.required("--map"), &mut timer);
+    args.done();
+
+    let scenario = ScenarioGenerator::deliveries(&map, &mut rng, &mut timer);
+    scenario.save();
+}
@@ -0,0 +1,94 @@
+//! After re-importing maps, check that every community proposal in data/system/proposals still
+//! applies. Edits to roads that moved are relocated; pass --rewrite to save the migrated proposals.
+
+use std::collections::HashMap;
+
+use abstutil::{CmdArgs, Timer};
+use map_model::{Map, PermanentMapEdits};
+
+fn main() {
+    let mut args = CmdArgs::new();
+    let rewrite = args.enabled("--rewrite");
+    args.done();
+
+    let mut timer = Timer::new("validate proposals");
+    let mut maps: HashMap<String, Map> = HashMap::new();
+    let mut num_broken = 0;
+    for path in abstutil::list_dir(abstutil::path("system/proposals")) {
+        if !path.ends_with(".json") {
+            continue;
+        }
+        // A malformed proposal shouldn't stop the rest from being checked
+        let value: serde_json::Value = match abstutil::maybe_read_json(path.clone(), &mut timer) {
+            Ok(value) => value,
+            Err(err) => {
+                println!("{}: can't parse: {}", path, err);
+                num_broken += 1;
+                continue;
+            }
+        };
+        let map_name = match value["map_name"].as_str() {
+            Some(name) => name.to_string(),
+            None => {
+                println!("{}: no map_name", path);
+                num_broken += 1;
+                continue;
+            }
+        };
+        if !maps.contains_key(&map_name) {
+            if !abstutil::file_exists(abstutil::path_map(&map_name)) {
+                println!("{}: the map {} doesn't exist", path, map_name);
+                num_broken += 1;
+                continue;
+            }
+            let map = Map::new(abstutil::path_map(&map_name), &mut timer);
+            maps.insert(map_name.clone(), map);
+        }
+        let map = &maps[&map_name];
+
+        let perma = match PermanentMapEdits::load_from_file(map, path.clone(), &mut timer) {
+            Ok(perma) => perma,
+            Err(err) => {
+                println!("{}: can't upgrade: {}", path, err);
+                num_broken += 1;
+                continue;
+            }
+        };
+        let (rebased, problems) = perma.rebase(map);
+        let orig_changes = perma.net_changes();
+        let relocated = rebased
+            .net_changes()
+            .keys()
+            .filter(|target| !orig_changes.contains_key(target))
+            .count();
+
+        let mut broken = !problems.is_empty();
+        if problems.is_empty() {
+            println!("{}: OK, {} edits relocated", path, relocated);
+        } else {
+            println!(
+                "{}: {} edits relocated, but {} couldn't be migrated:",
+                path,
+                relocated,
+                problems.len()
+            );
+            for problem in problems {
+                println!("  - {}", problem);
+            }
+        }
+        if let Err(err) = PermanentMapEdits::from_permanent(rebased.clone(), map) {
+            println!("  - the migrated edits still don't apply: {}", err);
+            broken = true;
+        }
+        if broken {
+            num_broken += 1;
+        } else if rewrite && relocated > 0 {
+            abstutil::write_json(path, &rebased);
+        }
+    }
+
+    println!("{} proposals need attention", num_broken);
+    if num_broken > 0 {
+        std::process::exit(1);
+    }
+}
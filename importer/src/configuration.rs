@@ -5,7 +5,6 @@ use serde_json;
 #[serde(default)]
 pub struct ImporterConfiguration {
     pub curl: String,
-    pub osmconvert: String,
     pub unzip: String,
     pub gunzip: String,
     pub gunzip_args: String,
@@ -15,7 +14,6 @@ impl Default for ImporterConfiguration {
     fn default() -> ImporterConfiguration {
         ImporterConfiguration {
             curl: String::from("curl"),
-            osmconvert: String::from("osmconvert"),
             unzip: String::from("unzip"),
             gunzip: String::from("gunzip"),
             gunzip_args: String::from(""),
@@ -1,5 +1,5 @@
 use crate::configuration::ImporterConfiguration;
-use crate::utils::{download, osmconvert};
+use crate::utils::{clip_osm, download};
 
 fn input(config: &ImporterConfiguration) {
     download(
@@ -11,11 +11,10 @@ fn input(config: &ImporterConfiguration) {
 
 pub fn osm_to_raw(name: &str, timer: &mut abstutil::Timer, config: &ImporterConfiguration) {
     input(config);
-    osmconvert(
+    clip_osm(
+        "krakow",
         "input/krakow/osm/malopolskie-latest.osm.pbf",
-        format!("input/krakow/polygons/{}.poly", name),
-        format!("input/krakow/osm/{}.osm", name),
-        config,
+        timer,
     );
 
     let map = convert_osm::convert(
@@ -39,6 +38,8 @@ pub fn osm_to_raw(name: &str, timer: &mut abstutil::Timer, config: &ImporterConf
             // TODO: investigate why some many buildings drop their private parkings
             elevation: None,
             include_railroads: true,
+            include_footways: false,
+            split_midblock_crossings: false,
         },
         timer,
     );
@@ -30,6 +30,8 @@ struct Job {
     oneshot: Option<String>,
     oneshot_clip: Option<String>,
     oneshot_drive_on_left: bool,
+    oneshot_footways: bool,
+    oneshot_midblock_crossings: bool,
 }
 
 fn main() {
@@ -58,6 +60,10 @@ fn main() {
         oneshot: args.optional("--oneshot"),
         oneshot_clip: args.optional("--oneshot_clip"),
         oneshot_drive_on_left: args.enabled("--oneshot_drive_on_left"),
+        // Import separately mapped sidewalks, crossings, and trails in the --oneshot map.
+        oneshot_footways: args.enabled("--oneshot_footways"),
+        // Split roads at crossings in the middle of a block in the --oneshot map.
+        oneshot_midblock_crossings: args.enabled("--oneshot_midblock_crossings"),
     };
     args.done();
     if !job.osm_to_raw
@@ -89,6 +95,8 @@ fn main() {
             path,
             job.oneshot_clip,
             !job.oneshot_drive_on_left,
+            job.oneshot_footways,
+            job.oneshot_midblock_crossings,
             !job.skip_ch,
             job.keep_bldg_tags,
         );
@@ -205,6 +213,8 @@ fn oneshot(
     osm_path: String,
     clip: Option<String>,
     drive_on_right: bool,
+    include_footways: bool,
+    split_midblock_crossings: bool,
     build_ch: bool,
     keep_bldg_tags: bool,
 ) {
@@ -232,6 +242,8 @@ fn oneshot(
             private_offstreet_parking: convert_osm::PrivateOffstreetParking::FixedPerBldg(1),
             elevation: None,
             include_railroads: true,
+            include_footways,
+            split_midblock_crossings,
         },
         &mut timer,
     );
@@ -9,13 +9,13 @@ use map_model::{BusRouteID, Map};
 use sim::Scenario;
 
 use crate::configuration::ImporterConfiguration;
-use crate::utils::{download, download_kml, osmconvert};
+use crate::utils::{clip_osm, download, download_kml};
 
 fn input(config: &ImporterConfiguration, timer: &mut abstutil::Timer) {
     download(
         config,
-        "input/seattle/N47W122.hgt",
-        "https://dds.cr.usgs.gov/srtm/version2_1/SRTM1/Region_01/N47W122.hgt.zip",
+        "input/seattle/N47W123.hgt",
+        "https://dds.cr.usgs.gov/srtm/version2_1/SRTM1/Region_01/N47W123.hgt.zip",
     );
     download(
         config,
@@ -61,11 +61,10 @@ fn input(config: &ImporterConfiguration, timer: &mut abstutil::Timer) {
 
 pub fn osm_to_raw(name: &str, timer: &mut abstutil::Timer, config: &ImporterConfiguration) {
     input(config, timer);
-    osmconvert(
+    clip_osm(
+        "seattle",
         "input/seattle/osm/washington-latest.osm.pbf",
-        format!("input/seattle/polygons/{}.poly", name),
-        format!("input/seattle/osm/{}.osm", name),
-        config,
+        timer,
     );
 
     let map = convert_osm::convert(
@@ -99,9 +98,11 @@ pub fn osm_to_raw(name: &str, timer: &mut abstutil::Timer, config: &ImporterConf
                     _ => 1,
                 },
             ),
-            elevation: Some(abstutil::path("input/seattle/N47W122.hgt")),
+            elevation: Some(abstutil::path("input/seattle/N47W123.hgt")),
             // They mess up 16th and E Marginal badly enough to cause gridlock.
             include_railroads: false,
+            include_footways: false,
+            split_midblock_crossings: false,
         },
         timer,
     );
@@ -2,7 +2,10 @@ use std::collections::HashMap;
 
 use abstutil::{prettyprint_usize, MultiMap, Parallelism, Timer};
 use geom::LonLat;
-use map_model::{osm, BuildingID, IntersectionID, Map, PathConstraints, PathRequest, PathStep};
+use map_model::{
+    osm, BikeRoutingProfile, BuildingID, IntersectionID, Map, PathConstraints, PathRequest,
+    PathStep,
+};
 use sim::{
     IndividTrip, OffMapLocation, OrigPersonID, PersonID, PersonSpec, Scenario, SpawnTrip,
     TripEndpoint, TripMode,
@@ -299,6 +302,7 @@ pub fn make_weekday_scenario(
         people.push(PersonSpec {
             id,
             orig_id: Some(orig_id),
+            bike_profile: BikeRoutingProfile::default(),
             trips,
         });
     }
@@ -370,6 +374,7 @@ pub fn make_weekday_scenario_with_everyone(
         people.push(PersonSpec {
             id,
             orig_id: Some(orig_id),
+            bike_profile: BikeRoutingProfile::default(),
             trips,
         });
     }
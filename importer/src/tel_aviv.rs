@@ -1,5 +1,5 @@
 use crate::configuration::ImporterConfiguration;
-use crate::utils::{download, osmconvert};
+use crate::utils::{clip_osm, download};
 
 fn input(config: &ImporterConfiguration) {
     download(
@@ -11,11 +11,10 @@ fn input(config: &ImporterConfiguration) {
 
 pub fn osm_to_raw(name: &str, timer: &mut abstutil::Timer, config: &ImporterConfiguration) {
     input(config);
-    osmconvert(
+    clip_osm(
+        "tel_aviv",
         "input/tel_aviv/osm/israel-and-palestine-latest.osm.pbf",
-        format!("input/tel_aviv/polygons/{}.poly", name),
-        format!("input/tel_aviv/osm/{}.osm", name),
-        config,
+        timer,
     );
 
     let map = convert_osm::convert(
@@ -38,6 +37,8 @@ pub fn osm_to_raw(name: &str, timer: &mut abstutil::Timer, config: &ImporterConf
             private_offstreet_parking: convert_osm::PrivateOffstreetParking::FixedPerBldg(10),
             elevation: None,
             include_railroads: true,
+            include_footways: false,
+            split_midblock_crossings: false,
         },
         timer,
     );
@@ -95,31 +95,29 @@ pub fn download_kml(
     std::fs::rename(tmp, output.replace(".bin", ".kml")).unwrap();
 }
 
-// Uses osmconvert to clip the input .osm (or .pbf) against a polygon and produce some output.
-// Skips if the output exists.
-pub fn osmconvert(
-    input: &str,
-    clipping_polygon: String,
-    output: String,
-    config: &ImporterConfiguration,
-) {
+// Clips a large .osm.pbf extract covering a city to each of the city's clipping polygons,
+// producing a .osm per map. The extract is only read through once for all of the polygons. Skips
+// polygons whose output already exists.
+pub fn clip_osm(city: &str, input: &str, timer: &mut Timer) {
     let input = abstutil::path(input);
-    let clipping_polygon = abstutil::path(clipping_polygon);
-    let output = abstutil::path(output);
-
-    if Path::new(&output).exists() {
-        println!("- {} already exists", output);
+    let mut clips = Vec::new();
+    for name in abstutil::list_all_objects(abstutil::path(format!("input/{}/polygons", city))) {
+        let output = abstutil::path(format!("input/{}/osm/{}.osm", city, name));
+        if Path::new(&output).exists() {
+            println!("- {} already exists", output);
+            continue;
+        }
+        let clipping_polygon = abstutil::path(format!("input/{}/polygons/{}.poly", city, name));
+        clips.push((
+            geom::LonLat::read_osmosis_polygon(clipping_polygon).unwrap(),
+            output,
+        ));
+    }
+    if clips.is_empty() {
         return;
     }
-    println!("- Clipping {} to {}", input, clipping_polygon);
-
-    must_run_cmd(
-        Command::new(&config.osmconvert)
-            .arg(input)
-            .arg(format!("-B={}", clipping_polygon))
-            .arg("--complete-ways")
-            .arg(format!("-o={}", output)),
-    );
+    println!("- Clipping {} to {} polygons", input, clips.len());
+    convert_osm::reader::clip_to_osm(&input, clips, timer).unwrap();
 }
 
 // Converts a RawMap to a Map.
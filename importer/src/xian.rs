@@ -1,5 +1,5 @@
 use crate::configuration::ImporterConfiguration;
-use crate::utils::{download, osmconvert};
+use crate::utils::{clip_osm, download};
 
 fn input(config: &ImporterConfiguration) {
     download(
@@ -11,12 +11,7 @@ fn input(config: &ImporterConfiguration) {
 
 pub fn osm_to_raw(name: &str, timer: &mut abstutil::Timer, config: &ImporterConfiguration) {
     input(config);
-    osmconvert(
-        "input/xian/osm/china-latest.osm.pbf",
-        format!("input/xian/polygons/{}.poly", name),
-        format!("input/xian/osm/{}.osm", name),
-        config,
-    );
+    clip_osm("xian", "input/xian/osm/china-latest.osm.pbf", timer);
 
     let map = convert_osm::convert(
         convert_osm::Options {
@@ -35,6 +30,8 @@ pub fn osm_to_raw(name: &str, timer: &mut abstutil::Timer, config: &ImporterConf
             private_offstreet_parking: convert_osm::PrivateOffstreetParking::FixedPerBldg(3),
             elevation: None,
             include_railroads: true,
+            include_footways: false,
+            split_midblock_crossings: false,
         },
         timer,
     );
@@ -148,6 +148,8 @@ impl widgetry::State<App> for MainState {
                         } else if !app.model.intersection_geom && ctx.input.pressed(Key::P) {
                             let draw = preview_intersection(i, &app.model, ctx);
                             self.state = State::PreviewIntersection(draw, false);
+                        } else if ctx.input.pressed(Key::C) {
+                            app.model.change_crossing_type(i, ctx);
                         }
                     }
                     Some(ID::Building(b)) => {
@@ -176,6 +178,9 @@ impl widgetry::State<App> for MainState {
                         } else if ctx.input.pressed(Key::Backspace) {
                             app.model.delete_r_pt(r, idx, ctx);
                             app.model.world.handle_mouseover(ctx);
+                        } else if ctx.input.pressed(Key::C) {
+                            app.model.split_r_at_crossing(r, idx, ctx);
+                            app.model.world.handle_mouseover(ctx);
                         }
                     }
                     None => {
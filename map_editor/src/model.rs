@@ -3,7 +3,7 @@ use std::collections::BTreeMap;
 use abstutil::{Tags, Timer};
 use geom::{Bounds, Circle, Distance, FindClosest, GPSBounds, LonLat, PolyLine, Polygon, Pt2D};
 use map_model::raw::{OriginalRoad, RawBuilding, RawIntersection, RawMap, RawRoad};
-use map_model::{osm, IntersectionType};
+use map_model::{osm, CrossingType, IntersectionType};
 use widgetry::{Color, EventCtx, Line, Text};
 
 use crate::world::{Object, ObjectID, World};
@@ -145,6 +145,9 @@ impl Model {
             }
             ID::Intersection(i) => {
                 txt.add_highlighted(Line(i.to_string()), Color::BLUE);
+                if let Some(crossing) = self.map.intersections[&i].crossing {
+                    txt.add(Line(format!("Mid-block crossing ({:?})", crossing)));
+                }
                 for r in self.map.roads_per_intersection(i) {
                     txt.add(Line(format!("- {}", r)));
                 }
@@ -195,6 +198,7 @@ impl Model {
             IntersectionType::StopSign => Color::RED,
             IntersectionType::Border => Color::BLUE,
             IntersectionType::Construction => Color::ORANGE,
+            IntersectionType::Roundabout => Color::PURPLE,
         };
 
         let poly = if self.intersection_geom && !self.map.roads_per_intersection(id).is_empty() {
@@ -215,6 +219,7 @@ impl Model {
             RawIntersection {
                 point,
                 intersection_type: IntersectionType::StopSign,
+                crossing: None,
                 // TODO If this isn't a synthetic map, load the elevation data and grab a real
                 // value.
                 elevation: Distance::ZERO,
@@ -240,6 +245,29 @@ impl Model {
         self.map.delete_intersection(id);
         self.world.delete(ID::Intersection(id));
     }
+
+    /// Cycles through the ways a mid-block crossing can be marked
+    pub fn change_crossing_type(&mut self, id: osm::NodeID, ctx: &EventCtx) {
+        let i = self.map.intersections.get_mut(&id).unwrap();
+        let crossing = match i.crossing {
+            Some(CrossingType::Marked) => CrossingType::Unmarked,
+            Some(CrossingType::Unmarked) => CrossingType::Signalized,
+            Some(CrossingType::Signalized) => CrossingType::Marked,
+            None => {
+                println!("{} isn't a mid-block crossing", id);
+                return;
+            }
+        };
+        i.crossing = Some(crossing);
+        i.intersection_type = if crossing == CrossingType::Signalized {
+            IntersectionType::TrafficSignal
+        } else {
+            IntersectionType::StopSign
+        };
+
+        self.world.delete(ID::Intersection(id));
+        self.intersection_added(id, ctx);
+    }
 }
 
 // Roads
@@ -411,6 +439,33 @@ impl Model {
         new_id
     }
 
+    pub fn split_r_at_crossing(&mut self, id: OriginalRoad, idx: usize, ctx: &EventCtx) {
+        assert_eq!(self.showing_pts, Some(id));
+
+        self.stop_showing_pts(id);
+        let new_i = self.map.new_osm_node_id(time_to_id());
+        match self
+            .map
+            .split_road_at_crossing(id, idx, new_i, CrossingType::Marked)
+        {
+            Ok((r1, r2)) => {
+                self.road_deleted(id);
+                self.world.delete(ID::Intersection(id.i1));
+                self.world.delete(ID::Intersection(id.i2));
+
+                self.road_added(r1, ctx);
+                self.road_added(r2, ctx);
+                self.intersection_added(id.i1, ctx);
+                self.intersection_added(new_i, ctx);
+                self.intersection_added(id.i2, ctx);
+            }
+            Err(err) => {
+                println!("{}", err);
+                self.show_r_points(id, ctx);
+            }
+        }
+    }
+
     pub fn clear_r_pts(&mut self, id: OriginalRoad, ctx: &EventCtx) {
         assert_eq!(self.showing_pts, Some(id));
 
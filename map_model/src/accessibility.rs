@@ -0,0 +1,173 @@
+//! Cumulative opportunity accessibility: how many jobs, grocery stores, schools, and clinics each
+//! residence can reach within some amount of time, using one mode. This is the usual
+//! "15-minute city" metric.
+
+use std::collections::BTreeMap;
+
+use abstutil::Timer;
+use geom::Duration;
+
+use crate::connectivity::{all_costs_to, BuildingTargets};
+use crate::{Building, BuildingID, BuildingType, Map, PathConstraints};
+
+/// Something worth reaching from home
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Opportunity {
+    Jobs,
+    Groceries,
+    Schools,
+    Medical,
+}
+
+impl Opportunity {
+    pub fn all() -> Vec<Opportunity> {
+        vec![
+            Opportunity::Jobs,
+            Opportunity::Groceries,
+            Opportunity::Schools,
+            Opportunity::Medical,
+        ]
+    }
+
+    pub fn describe(self) -> &'static str {
+        match self {
+            Opportunity::Jobs => "jobs",
+            Opportunity::Groceries => "grocery stores",
+            Opportunity::Schools => "schools",
+            Opportunity::Medical => "medical",
+        }
+    }
+
+    /// How many of this opportunity a building has. Jobs are the estimated number of workers;
+    /// everything else counts matching amenities.
+    pub fn count(self, b: &Building) -> usize {
+        match self {
+            Opportunity::Jobs => match b.bldg_type {
+                BuildingType::Commercial(workers)
+                | BuildingType::ResidentialCommercial(_, workers) => workers,
+                BuildingType::Residential(_) | BuildingType::Empty => 0,
+            },
+            _ => b
+                .amenities
+                .iter()
+                .filter(|a| Opportunity::from_amenity(&a.amenity_type) == Some(self))
+                .count(),
+        }
+    }
+
+    fn from_amenity(amenity_type: &str) -> Option<Opportunity> {
+        match amenity_type {
+            "supermarket" | "convenience" | "greengrocer" => Some(Opportunity::Groceries),
+            "kindergarten" | "school" | "college" | "university" => Some(Opportunity::Schools),
+            "doctors" | "clinic" | "hospital" | "pharmacy" => Some(Opportunity::Medical),
+            _ => None,
+        }
+    }
+}
+
+/// For every residence, the number of each opportunity reachable within each time limit.
+pub struct Accessibility {
+    pub constraints: PathConstraints,
+    /// Sorted, shortest first
+    pub time_limits: Vec<Duration>,
+    scores: BTreeMap<BuildingID, Vec<BTreeMap<Opportunity, usize>>>,
+}
+
+impl Accessibility {
+    /// Floods the network backwards once from every building with some opportunity, so the times
+    /// are from each residence to the opportunity, even along one-way streets.
+    pub fn calculate(
+        map: &Map,
+        constraints: PathConstraints,
+        mut time_limits: Vec<Duration>,
+        timer: &mut Timer,
+    ) -> Accessibility {
+        time_limits.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let max_limit = *time_limits.last().unwrap();
+
+        let mut scores: BTreeMap<BuildingID, Vec<BTreeMap<Opportunity, usize>>> = map
+            .all_buildings()
+            .iter()
+            .filter(|b| b.bldg_type.has_residents())
+            .map(|b| {
+                let empty: BTreeMap<Opportunity, usize> =
+                    Opportunity::all().into_iter().map(|o| (o, 0)).collect();
+                (b.id, vec![empty; time_limits.len()])
+            })
+            .collect();
+
+        let sources: Vec<(BuildingID, Vec<(Opportunity, usize)>)> = map
+            .all_buildings()
+            .iter()
+            .filter_map(|b| {
+                let counts: Vec<(Opportunity, usize)> = Opportunity::all()
+                    .into_iter()
+                    .map(|o| (o, o.count(b)))
+                    .filter(|(_, cnt)| *cnt > 0)
+                    .collect();
+                if counts.is_empty() {
+                    None
+                } else {
+                    Some((b.id, counts))
+                }
+            })
+            .collect();
+
+        let targets = BuildingTargets::new(map, constraints);
+        timer.start_iter("flood backwards from opportunities", sources.len());
+        for (src, counts) in sources {
+            timer.next();
+            for (b, cost) in all_costs_to(map, &targets, src, max_limit) {
+                if let Some(per_limit) = scores.get_mut(&b) {
+                    for (idx, limit) in time_limits.iter().enumerate() {
+                        if cost <= *limit {
+                            for (o, cnt) in &counts {
+                                *per_limit[idx].get_mut(o).unwrap() += cnt;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Accessibility {
+            constraints,
+            time_limits,
+            scores,
+        }
+    }
+
+    /// How many of the opportunity a residence can reach within the time limit. Returns None if
+    /// the building isn't a residence or the time limit wasn't calculated.
+    pub fn get(&self, b: BuildingID, time_limit: Duration, o: Opportunity) -> Option<usize> {
+        let idx = self.time_limits.iter().position(|l| *l == time_limit)?;
+        Some(self.scores.get(&b)?[idx][&o])
+    }
+
+    /// Every residence considered
+    pub fn residences(&self) -> Vec<BuildingID> {
+        self.scores.keys().cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn amenities() {
+        assert_eq!(
+            Opportunity::from_amenity("supermarket"),
+            Some(Opportunity::Groceries)
+        );
+        assert_eq!(
+            Opportunity::from_amenity("school"),
+            Some(Opportunity::Schools)
+        );
+        assert_eq!(
+            Opportunity::from_amenity("pharmacy"),
+            Some(Opportunity::Medical)
+        );
+        assert_eq!(Opportunity::from_amenity("cafe"), None);
+    }
+}
@@ -1,13 +1,22 @@
 // TODO Possibly these should be methods on Map.
 
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, HashSet};
+use std::hash::Hash;
 
-use petgraph::graphmap::DiGraphMap;
+use petgraph::graphmap::{DiGraphMap, UnGraphMap};
 
-use geom::Distance;
+use geom::{Distance, Duration, Speed, Time};
 
 pub use crate::pathfind::driving_cost;
-use crate::{BuildingID, LaneID, Map, PathConstraints, PathRequest};
+use crate::{
+    bike_speed_on_grade, max_biking_speed, max_walking_speed, walking_speed_on_grade, Building,
+    BuildingID, BusRoute, BusRouteID, BusStopID, IntersectionID, Lane, LaneID,
+    LevelOfTrafficStress, Map, PathConstraints, Position, RoadID, Traversable,
+};
+
+/// How many times somebody riding transit will change vehicles
+pub const MAX_TRANSFERS: usize = 2;
 
 /// Calculate the srongy connected components (SCC) of the part of the map accessible by constraints
 /// (ie, the graph of sidewalks or driving+bike lanes). The largest component is the "main" graph;
@@ -46,24 +55,511 @@ pub fn find_scc(map: &Map, constraints: PathConstraints) -> (HashSet<LaneID>, Ha
     (largest_group, disconnected)
 }
 
-/// Starting from one building, calculate the cost to all others.
-// TODO Also take a PathConstraints and use different cost functions based on that -- maybe just
-// total time?
-pub fn all_costs_from(map: &Map, start: BuildingID) -> HashMap<BuildingID, Distance> {
-    let mut results = HashMap::new();
-    let start = map.get_b(start).sidewalk_pos;
-    // TODO This is SO inefficient. Flood out and mark off buildings as we go. Granularity of lane
-    // makes more sense.
+/// Splits the roads a cyclist can use without exceeding `max_stress` into connected islands,
+/// returning the largest (by length) first. Direction is ignored, since somebody can always walk
+/// their bike a short way.
+pub fn low_stress_islands(map: &Map, max_stress: LevelOfTrafficStress) -> Vec<Vec<RoadID>> {
+    let mut graph: UnGraphMap<IntersectionID, ()> = UnGraphMap::new();
+    let mut roads = Vec::new();
+    for r in map.all_roads() {
+        if r.bike_lts() <= max_stress
+            && r.all_lanes()
+                .into_iter()
+                .any(|l| PathConstraints::Bike.can_use(map.get_l(l), map))
+        {
+            graph.add_edge(r.src_i, r.dst_i, ());
+            roads.push(r);
+        }
+    }
+
+    let mut island_per_intersection: HashMap<IntersectionID, usize> = HashMap::new();
+    let components = petgraph::algo::kosaraju_scc(&graph);
+    for (idx, component) in components.iter().enumerate() {
+        for i in component {
+            island_per_intersection.insert(*i, idx);
+        }
+    }
+    let mut islands: Vec<(Distance, Vec<RoadID>)> =
+        std::iter::repeat_with(|| (Distance::ZERO, Vec::new()))
+            .take(components.len())
+            .collect();
+    for r in roads {
+        let island = &mut islands[island_per_intersection[&r.src_i]];
+        island.0 += r.center_pts.length();
+        island.1.push(r.id);
+    }
+    islands.sort_by_key(|(length, _)| Reverse(*length));
+    islands.into_iter().map(|(_, roads)| roads).collect()
+}
+
+/// Where each building connects to the network used by some mode. Flooding from many different
+/// starts should build this once and reuse it. It's only valid until the map is edited.
+pub struct BuildingTargets {
+    constraints: PathConstraints,
+    per_lane: HashMap<LaneID, Vec<(BuildingID, Distance)>>,
+}
+
+impl BuildingTargets {
+    pub fn new(map: &Map, constraints: PathConstraints) -> BuildingTargets {
+        let mut per_lane: HashMap<LaneID, Vec<(BuildingID, Distance)>> = HashMap::new();
+        for b in map.all_buildings() {
+            if let Some(pos) = building_pos(map, b, constraints) {
+                per_lane
+                    .entry(pos.lane())
+                    .or_insert_with(Vec::new)
+                    .push((b.id, pos.dist_along()));
+            }
+        }
+        BuildingTargets {
+            constraints,
+            per_lane,
+        }
+    }
+}
+
+/// Starting from one building, calculate the time to reach every other building within
+/// `time_limit`, using the mode the targets were built for. This floods the lane and turn graph
+/// once, instead of pathfinding separately to every building.
+pub fn all_costs_from(
+    map: &Map,
+    targets: &BuildingTargets,
+    start: BuildingID,
+    time_limit: Duration,
+) -> HashMap<BuildingID, Duration> {
+    nearest_n_from(map, targets, vec![start], 1, time_limit)
+        .into_iter()
+        .map(|(b, mut list)| (b, list.pop().unwrap().1))
+        .collect()
+}
+
+/// Calculate the time from every other building to reach one building within `time_limit`, using
+/// the mode the targets were built for. This floods the lane and turn graph backwards from `end`,
+/// so one-way streets and turn restrictions are respected in the direction of travel.
+pub fn all_costs_to(
+    map: &Map,
+    targets: &BuildingTargets,
+    end: BuildingID,
+    time_limit: Duration,
+) -> HashMap<BuildingID, Duration> {
+    let seeds = building_pos(map, map.get_b(end), targets.constraints)
+        .map(|pos| vec![(Duration::ZERO, pos, end)])
+        .unwrap_or_else(Vec::new);
+    flood(
+        map,
+        &targets.per_lane,
+        seeds,
+        1,
+        time_limit,
+        targets.constraints,
+        true,
+    )
+    .into_iter()
+    .map(|(b, per_src)| (b, per_src[&end]))
+    .collect()
+}
+
+/// For every building, find the `n` closest `sources` (like all of the grocery stores) within
+/// `time_limit`, using the mode the targets were built for. Returns each building's sources,
+/// sorted by time. Times are from the source to the building; for walking, the direction doesn't
+/// matter.
+pub fn nearest_n_from(
+    map: &Map,
+    targets: &BuildingTargets,
+    sources: Vec<BuildingID>,
+    n: usize,
+    time_limit: Duration,
+) -> HashMap<BuildingID, Vec<(BuildingID, Duration)>> {
+    let constraints = targets.constraints;
+    let seeds = sources
+        .into_iter()
+        .filter_map(|src| {
+            building_pos(map, map.get_b(src), constraints).map(|pos| (Duration::ZERO, pos, src))
+        })
+        .collect();
+    flood(
+        map,
+        &targets.per_lane,
+        seeds,
+        n,
+        time_limit,
+        constraints,
+        false,
+    )
+    .into_iter()
+    .map(|(b, per_src)| {
+        let mut list: Vec<(BuildingID, Duration)> = per_src.into_iter().collect();
+        list.sort_by_key(|(_, t)| *t);
+        list.truncate(n);
+        (b, list)
+    })
+    .collect()
+}
+
+/// Starting from some buildings at `departure`, calculate the time to reach every other building
+/// within `time_limit` by walking and riding buses and trains. Riders wait at a stop for the next
+/// scheduled vehicle, then stay on for any number of stops. Up to `MAX_TRANSFERS` transfers are
+/// considered.
+pub fn all_costs_by_transit(
+    map: &Map,
+    sources: Vec<BuildingID>,
+    departure: Time,
+    time_limit: Duration,
+) -> HashMap<BuildingID, Duration> {
+    let mut targets: HashMap<LaneID, Vec<(TransitTarget, Distance)>> = HashMap::new();
     for b in map.all_buildings() {
-        if let Some(path) = map.pathfind(PathRequest {
-            start,
-            end: b.sidewalk_pos,
-            constraints: PathConstraints::Pedestrian,
-        }) {
-            // TODO Distance isn't an interesting thing to show at all, we want the path cost
-            // (probably in time)
-            results.insert(b.id, path.total_length());
+        targets
+            .entry(b.sidewalk_pos.lane())
+            .or_insert_with(Vec::new)
+            .push((TransitTarget::Bldg(b.id), b.sidewalk_pos.dist_along()));
+    }
+    for bs in map.all_bus_stops().values() {
+        targets
+            .entry(bs.sidewalk_pos.lane())
+            .or_insert_with(Vec::new)
+            .push((TransitTarget::Stop(bs.id), bs.sidewalk_pos.dist_along()));
+    }
+
+    let mut best: HashMap<TransitTarget, Duration> = HashMap::new();
+    let mut schedules: HashMap<BusRouteID, Vec<Duration>> = HashMap::new();
+    let mut seeds: Vec<(Duration, Position, ())> = sources
+        .into_iter()
+        .map(|b| (Duration::ZERO, map.get_b(b).sidewalk_pos, ()))
+        .collect();
+    // Each round walks from wherever the last round's riders got off
+    for _ in 0..=MAX_TRANSFERS {
+        if seeds.is_empty() {
+            break;
+        }
+        let mut boarding: Vec<(BusStopID, Duration)> = Vec::new();
+        for (target, per_src) in flood(
+            map,
+            &targets,
+            seeds,
+            1,
+            time_limit,
+            PathConstraints::Pedestrian,
+            false,
+        ) {
+            let t = per_src[&()];
+            if best.get(&target).map(|x| t < *x).unwrap_or(true) {
+                best.insert(target, t);
+                if let TransitTarget::Stop(bs) = target {
+                    boarding.push((bs, t));
+                }
+            }
+        }
+
+        // Where can riders get off, and when?
+        let mut alighting: HashMap<BusStopID, Duration> = HashMap::new();
+        for (bs, t) in boarding {
+            for route in map.get_routes_serving_stop(bs) {
+                let offsets = schedules
+                    .entry(route.id)
+                    .or_insert_with(|| stop_offsets(map, route));
+                let idx = route.stops.iter().position(|x| *x == bs).unwrap();
+                // When does the next vehicle pass this stop?
+                let arrive = departure + t;
+                let next = match route
+                    .spawn_times
+                    .iter()
+                    .map(|spawn| *spawn + offsets[idx])
+                    .find(|pass| *pass >= arrive)
+                {
+                    Some(pass) => pass,
+                    None => continue,
+                };
+                for (later, offset) in route.stops.iter().zip(offsets.iter()).skip(idx + 1) {
+                    let t = (next - departure) + (*offset - offsets[idx]);
+                    if t > time_limit {
+                        break;
+                    }
+                    if alighting.get(later).map(|x| t < *x).unwrap_or(true) {
+                        alighting.insert(*later, t);
+                    }
+                }
+            }
+        }
+
+        seeds = alighting
+            .into_iter()
+            .filter(|(bs, t)| {
+                best.get(&TransitTarget::Stop(*bs))
+                    .map(|x| t < x)
+                    .unwrap_or(true)
+            })
+            .map(|(bs, t)| (t, map.get_bs(bs).sidewalk_pos, ()))
+            .collect();
+    }
+
+    best.into_iter()
+        .filter_map(|(target, t)| match target {
+            TransitTarget::Bldg(b) => Some((b, t)),
+            TransitTarget::Stop(_) => None,
+        })
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum TransitTarget {
+    Bldg(BuildingID),
+    Stop(BusStopID),
+}
+
+/// How long after leaving the start of the route a vehicle reaches each stop, ignoring traffic and
+/// time spent at stops
+fn stop_offsets(map: &Map, route: &BusRoute) -> Vec<Duration> {
+    let mut offsets = Vec::new();
+    let mut total = Duration::ZERO;
+    for req in route.all_steps(map).into_iter().take(route.stops.len()) {
+        let constraints = req.constraints;
+        if let Some(path) = map.pathfind(req) {
+            let mut full_length = Distance::ZERO;
+            let mut time = Duration::ZERO;
+            for step in path.get_steps() {
+                let t = step.as_traversable();
+                full_length += t.length(map);
+                time += t.length(map) / travel_speed(t, false, constraints, map);
+            }
+            // The first and last steps are only partly used
+            if full_length > Distance::ZERO {
+                time = time * (path.total_length() / full_length);
+            }
+            total += time;
+        }
+        offsets.push(total);
+    }
+    offsets
+}
+
+/// Floods the lane and turn graph from every seed, which starts at some time. Returns the best
+/// time to reach each target from each of the closest `n` seed labels. If `reverse` is true, the
+/// graph is flooded backwards, so the times are from each target to the seeds instead.
+fn flood<S: Copy + Ord, K: Copy + Eq + Hash>(
+    map: &Map,
+    targets: &HashMap<LaneID, Vec<(K, Distance)>>,
+    seeds: Vec<(Duration, Position, S)>,
+    n: usize,
+    time_limit: Duration,
+    constraints: PathConstraints,
+    reverse: bool,
+) -> HashMap<K, BTreeMap<S, Duration>> {
+    // (time so far, the lane, where we entered it, the source)
+    let mut queue: BinaryHeap<Reverse<(Duration, LaneID, Distance, S)>> = BinaryHeap::new();
+    for (time, pos, src) in seeds {
+        queue.push(Reverse((time, pos.lane(), pos.dist_along(), src)));
+    }
+
+    let walking = constraints == PathConstraints::Pedestrian;
+    // Each lane entry is expanded at most n times, once for each of the closest sources
+    let mut visited: BTreeMap<(LaneID, Distance), Vec<S>> = BTreeMap::new();
+    // A target might be reached from either end of its lane, so keep the best time per source
+    let mut results: HashMap<K, BTreeMap<S, Duration>> = HashMap::new();
+    while let Some(Reverse((time, l, entry, src))) = queue.pop() {
+        let sources_here = visited.entry((l, entry)).or_insert_with(Vec::new);
+        if sources_here.len() == n || sources_here.contains(&src) {
+            continue;
+        }
+        sources_here.push(src);
+
+        let lane = map.get_l(l);
+        let fwd_speed = travel_speed(Traversable::Lane(l), false, constraints, map);
+        let back_speed = travel_speed(Traversable::Lane(l), true, constraints, map);
+        let time_to = |dist: Distance| {
+            time_along_lane(walking, reverse, entry, dist, fwd_speed, back_speed)
+                .map(|dt| time + dt)
+        };
+
+        if let Some(list) = targets.get(&l) {
+            for (target, dist) in list {
+                let t = match time_to(*dist) {
+                    Some(t) if t <= time_limit => t,
+                    _ => continue,
+                };
+                let per_src = results.entry(*target).or_insert_with(BTreeMap::new);
+                if per_src.get(&src).map(|x| t < *x).unwrap_or(true) {
+                    per_src.insert(src, t);
+                }
+            }
+        }
+
+        let turns = if reverse {
+            map.get_turns_to_lane(l)
+        } else {
+            map.get_turns_from_lane(l)
+        };
+        for turn in turns {
+            // When flooding backwards, the next lane is the one the turn comes from
+            let next = map.get_l(if reverse { turn.id.src } else { turn.id.dst });
+            if !constraints.can_use(next, map) {
+                continue;
+            }
+            // Where the turn touches a lane. If a lane loops back to the same intersection,
+            // assume the turn leaves from its end and leads to its start.
+            let end_at = |l: &Lane, leaving: bool| {
+                let at_end = if l.src_i == l.dst_i {
+                    leaving
+                } else {
+                    turn.id.parent == l.dst_i
+                };
+                if at_end {
+                    l.length()
+                } else {
+                    Distance::ZERO
+                }
+            };
+            let exit = end_at(lane, !reverse);
+            let next_entry = end_at(next, reverse);
+            let t = match time_to(exit) {
+                Some(t) => {
+                    t + turn.geom.length()
+                        / travel_speed(Traversable::Turn(turn.id), false, constraints, map)
+                }
+                None => continue,
+            };
+            if t <= time_limit {
+                queue.push(Reverse((t, next.id, next_entry, src)));
+            }
         }
     }
     results
 }
+
+/// The time to move between the point where a lane was entered and some other point along it, or
+/// None if that's impossible. Vehicles can only move forwards along a lane; pedestrians can go
+/// either way, at different speeds if the lane is sloped. When flooding backwards, the movement
+/// is from `dist` to `entry`.
+fn time_along_lane(
+    walking: bool,
+    reverse: bool,
+    entry: Distance,
+    dist: Distance,
+    fwd_speed: Speed,
+    back_speed: Speed,
+) -> Option<Duration> {
+    let (from, to) = if reverse {
+        (dist, entry)
+    } else {
+        (entry, dist)
+    };
+    if to >= from {
+        Some((to - from) / fwd_speed)
+    } else if walking {
+        Some((from - to) / back_speed)
+    } else {
+        None
+    }
+}
+
+/// How fast somebody can move along a lane or turn, ignoring any delays. These match the
+/// simulation's fastest pedestrians and cyclists, who slow down uphill and speed up downhill.
+/// Pedestrians walking against the direction of a sidewalk face the opposite grade.
+pub fn travel_speed(
+    t: Traversable,
+    contraflow: bool,
+    constraints: PathConstraints,
+    map: &Map,
+) -> Speed {
+    let grade = if contraflow {
+        -t.percent_grade(map)
+    } else {
+        t.percent_grade(map)
+    };
+    speed_on_grade(constraints, grade, t.speed_limit(map))
+}
+
+fn speed_on_grade(constraints: PathConstraints, grade: f64, speed_limit: Speed) -> Speed {
+    match constraints {
+        PathConstraints::Pedestrian => walking_speed_on_grade(max_walking_speed(), grade),
+        PathConstraints::Bike => bike_speed_on_grade(max_biking_speed(), grade).min(speed_limit),
+        PathConstraints::Car | PathConstraints::Bus | PathConstraints::Train => speed_limit,
+    }
+}
+
+/// Where a building connects to the part of the map used by some mode
+fn building_pos(map: &Map, b: &Building, constraints: PathConstraints) -> Option<Position> {
+    match constraints {
+        PathConstraints::Pedestrian => Some(b.sidewalk_pos),
+        PathConstraints::Bike => Some(b.biking_connection(map)?.0),
+        PathConstraints::Car => Some(b.driving_connection(map)?.0),
+        PathConstraints::Bus | PathConstraints::Train => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn speeds() {
+        let residential = Speed::miles_per_hour(25.0);
+        let alley = Speed::miles_per_hour(5.0);
+
+        // On flat ground, everybody goes as fast as the simulation's fastest agents
+        assert_eq!(
+            speed_on_grade(PathConstraints::Pedestrian, 0.0, residential),
+            max_walking_speed()
+        );
+        assert_eq!(
+            speed_on_grade(PathConstraints::Bike, 0.0, residential),
+            max_biking_speed()
+        );
+        assert_eq!(
+            speed_on_grade(PathConstraints::Car, 0.0, residential),
+            residential
+        );
+
+        // Hills slow down pedestrians and cyclists, but not vehicles
+        assert!(
+            speed_on_grade(PathConstraints::Pedestrian, 0.1, residential) < max_walking_speed()
+        );
+        assert!(speed_on_grade(PathConstraints::Bike, 0.1, residential) < max_biking_speed());
+        assert_eq!(
+            speed_on_grade(PathConstraints::Car, 0.1, residential),
+            residential
+        );
+
+        // Cyclists never break the speed limit, even downhill
+        assert_eq!(speed_on_grade(PathConstraints::Bike, -0.1, alley), alley);
+        assert!(speed_on_grade(PathConstraints::Bike, -0.1, residential) > max_biking_speed());
+    }
+
+    #[test]
+    fn times_along_lanes() {
+        let fwd = Speed::meters_per_second(2.0);
+        let back = Speed::meters_per_second(1.0);
+        let m = Distance::meters;
+        let secs = |s: f64| Some(Duration::seconds(s));
+
+        // Vehicles only move forwards, so flooding backwards only reaches earlier points
+        assert_eq!(
+            time_along_lane(false, false, m(10.0), m(30.0), fwd, back),
+            secs(10.0)
+        );
+        assert_eq!(
+            time_along_lane(false, false, m(30.0), m(10.0), fwd, back),
+            None
+        );
+        assert_eq!(
+            time_along_lane(false, true, m(30.0), m(10.0), fwd, back),
+            secs(10.0)
+        );
+        assert_eq!(
+            time_along_lane(false, true, m(10.0), m(30.0), fwd, back),
+            None
+        );
+
+        // Pedestrians go either way, using the speed for the direction they actually walk
+        assert_eq!(
+            time_along_lane(true, false, m(30.0), m(10.0), fwd, back),
+            secs(20.0)
+        );
+        assert_eq!(
+            time_along_lane(true, true, m(10.0), m(30.0), fwd, back),
+            secs(20.0)
+        );
+        assert_eq!(
+            time_along_lane(true, true, m(30.0), m(10.0), fwd, back),
+            secs(10.0)
+        );
+    }
+}
@@ -0,0 +1,468 @@
+//! Different people often work on separate proposals for the same map, then need to combine them.
+//! These tools operate on `PermanentMapEdits`, since they refer to stable OSM IDs and don't
+//! require loading the map.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use abstutil::retain_btreemap;
+
+use crate::edits::perma::{PermanentEditCmd, PermanentEditIntersection};
+use crate::edits::{migrate, EditRoad};
+use crate::raw::OriginalRoad;
+use crate::{osm, Map, PermanentMapEdits};
+
+/// The one map object that a `PermanentEditCmd` changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum EditTarget {
+    Road(OriginalRoad),
+    Intersection(osm::NodeID),
+    Route(osm::RelationID),
+    ModalFilter(osm::NodeID),
+}
+
+impl fmt::Display for EditTarget {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EditTarget::Road(r) => write!(f, "{}", r),
+            EditTarget::Intersection(i) => write!(f, "intersection {}", i),
+            EditTarget::Route(r) => write!(f, "route {}", r),
+            EditTarget::ModalFilter(i) => write!(f, "modal filter at {}", i),
+        }
+    }
+}
+
+/// Compares the net effect of two sets of edits, per map object.
+pub struct EditsDiff {
+    /// Only changed by the first edits
+    pub only_first: Vec<EditTarget>,
+    /// Only changed by the second edits
+    pub only_second: Vec<EditTarget>,
+    /// Both edits change this object to the same final state
+    pub same: Vec<EditTarget>,
+    /// Both edits change this object, but to a different final state, along with a description
+    /// of the difference
+    pub different: Vec<(EditTarget, Vec<String>)>,
+}
+
+/// Both sets of edits change the same object differently.
+pub struct EditConflict {
+    pub target: EditTarget,
+    pub first: PermanentEditCmd,
+    pub second: PermanentEditCmd,
+}
+
+impl PermanentEditCmd {
+    pub fn target(&self) -> EditTarget {
+        match self {
+            PermanentEditCmd::ChangeRoad { r, .. } => EditTarget::Road(*r),
+            PermanentEditCmd::ChangeIntersection { i, .. } => EditTarget::Intersection(*i),
+            PermanentEditCmd::ChangeRouteSchedule { osm_rel_id, .. } => {
+                EditTarget::Route(*osm_rel_id)
+            }
+            PermanentEditCmd::ChangeModalFilter { i, .. } => EditTarget::ModalFilter(*i),
+        }
+    }
+
+    /// Combines two commands touching the same target, applied in order, into one.
+    fn then(self, next: PermanentEditCmd) -> PermanentEditCmd {
+        match (self, next) {
+            (
+                PermanentEditCmd::ChangeRoad { r, old, center, .. },
+                PermanentEditCmd::ChangeRoad { new, .. },
+            ) => PermanentEditCmd::ChangeRoad {
+                r,
+                old,
+                new,
+                center,
+            },
+            (
+                PermanentEditCmd::ChangeIntersection { i, old, .. },
+                PermanentEditCmd::ChangeIntersection { new, .. },
+            ) => PermanentEditCmd::ChangeIntersection { i, old, new },
+            (
+                PermanentEditCmd::ChangeRouteSchedule {
+                    osm_rel_id, old, ..
+                },
+                PermanentEditCmd::ChangeRouteSchedule { new, .. },
+            ) => PermanentEditCmd::ChangeRouteSchedule {
+                osm_rel_id,
+                old,
+                new,
+            },
+            (
+                PermanentEditCmd::ChangeModalFilter { i, old, .. },
+                PermanentEditCmd::ChangeModalFilter { new, .. },
+            ) => PermanentEditCmd::ChangeModalFilter { i, old, new },
+            _ => unreachable!(),
+        }
+    }
+
+    fn is_noop(&self) -> bool {
+        match self {
+            PermanentEditCmd::ChangeRoad { old, new, .. } => old == new,
+            PermanentEditCmd::ChangeIntersection { old, new, .. } => old == new,
+            PermanentEditCmd::ChangeRouteSchedule { old, new, .. } => old == new,
+            PermanentEditCmd::ChangeModalFilter { old, new, .. } => old == new,
+        }
+    }
+
+    /// Only compares the final state, not what the command started from.
+    fn same_result(&self, other: &PermanentEditCmd) -> bool {
+        match (self, other) {
+            (
+                PermanentEditCmd::ChangeRoad { new: new1, .. },
+                PermanentEditCmd::ChangeRoad { new: new2, .. },
+            ) => new1 == new2,
+            (
+                PermanentEditCmd::ChangeIntersection { new: new1, .. },
+                PermanentEditCmd::ChangeIntersection { new: new2, .. },
+            ) => new1 == new2,
+            (
+                PermanentEditCmd::ChangeRouteSchedule { new: new1, .. },
+                PermanentEditCmd::ChangeRouteSchedule { new: new2, .. },
+            ) => new1 == new2,
+            (
+                PermanentEditCmd::ChangeModalFilter { new: new1, .. },
+                PermanentEditCmd::ChangeModalFilter { new: new2, .. },
+            ) => new1 == new2,
+            _ => false,
+        }
+    }
+
+    /// Describe how the final state of two commands on the same target differ.
+    fn describe_difference(&self, other: &PermanentEditCmd) -> Vec<String> {
+        match (self, other) {
+            (
+                PermanentEditCmd::ChangeRoad { new: new1, .. },
+                PermanentEditCmd::ChangeRoad { new: new2, .. },
+            ) => new1.diff(new2),
+            (
+                PermanentEditCmd::ChangeIntersection { new: new1, .. },
+                PermanentEditCmd::ChangeIntersection { new: new2, .. },
+            ) => vec![format!("{} vs {}", new1.describe(), new2.describe())],
+            (
+                PermanentEditCmd::ChangeRouteSchedule { new: new1, .. },
+                PermanentEditCmd::ChangeRouteSchedule { new: new2, .. },
+            ) => vec![format!("{} vs {} departures", new1.len(), new2.len())],
+            (
+                PermanentEditCmd::ChangeModalFilter { new: new1, .. },
+                PermanentEditCmd::ChangeModalFilter { new: new2, .. },
+            ) => vec![format!("blocks {:?} vs {:?}", new1, new2)],
+            _ => Vec::new(),
+        }
+    }
+}
+
+impl PermanentEditIntersection {
+    fn describe(&self) -> &'static str {
+        match self {
+            PermanentEditIntersection::StopSign { .. } => "stop sign",
+            PermanentEditIntersection::TrafficSignal(_) => "traffic signal",
+            PermanentEditIntersection::Closed => "closed",
+            PermanentEditIntersection::Roundabout => "roundabout",
+        }
+    }
+}
+
+impl PermanentMapEdits {
+    /// Collapses the stack of commands to the net change per object, like `MapEdits::compress`,
+    /// but without needing the map. Commands that wind up changing nothing are dropped.
+    pub fn net_changes(&self) -> BTreeMap<EditTarget, PermanentEditCmd> {
+        let mut changes: BTreeMap<EditTarget, PermanentEditCmd> = BTreeMap::new();
+        for cmd in &self.commands {
+            let target = cmd.target();
+            let combined = match changes.remove(&target) {
+                Some(prev) => prev.then(cmd.clone()),
+                None => cmd.clone(),
+            };
+            changes.insert(target, combined);
+        }
+        retain_btreemap(&mut changes, |_, cmd| !cmd.is_noop());
+        changes
+    }
+
+    /// Compares the net changes of two sets of edits made against the same map.
+    pub fn diff(&self, other: &PermanentMapEdits) -> EditsDiff {
+        let first = self.net_changes();
+        let mut second = other.net_changes();
+
+        let mut diff = EditsDiff {
+            only_first: Vec::new(),
+            only_second: Vec::new(),
+            same: Vec::new(),
+            different: Vec::new(),
+        };
+        for (target, cmd1) in first {
+            if let Some(cmd2) = second.remove(&target) {
+                if cmd1.same_result(&cmd2) {
+                    diff.same.push(target);
+                } else {
+                    diff.different
+                        .push((target, cmd1.describe_difference(&cmd2)));
+                }
+            } else {
+                diff.only_first.push(target);
+            }
+        }
+        diff.only_second.extend(second.keys().cloned());
+        diff
+    }
+
+    /// Combines two sets of edits made against the same map. Changes to different objects are all
+    /// kept. When both sides change the same object differently, a conflict is reported, and the
+    /// result uses the change from `self`, unless `prefer_other` is set. The name and description
+    /// of `self` are kept.
+    pub fn merge(
+        &self,
+        other: &PermanentMapEdits,
+        prefer_other: bool,
+    ) -> (PermanentMapEdits, Vec<EditConflict>) {
+        let mut merged = self.net_changes();
+        let mut conflicts = Vec::new();
+        for (target, cmd) in other.net_changes() {
+            if let Some(existing) = merged.get(&target) {
+                if existing.same_result(&cmd) {
+                    continue;
+                }
+                conflicts.push(EditConflict {
+                    target,
+                    first: existing.clone(),
+                    second: cmd.clone(),
+                });
+                if !prefer_other {
+                    continue;
+                }
+            }
+            merged.insert(target, cmd);
+        }
+
+        let mut result = self.clone();
+        result.commands = merged.into_iter().map(|(_, cmd)| cmd).collect();
+        if self.merge_zones != other.merge_zones {
+            warn!(
+                "{} and {} disagree about merge_zones; keeping {}",
+                self.edits_name, other.edits_name, self.merge_zones
+            );
+        }
+        (result, conflicts)
+    }
+
+    /// Translates edits made against an older import of a map to a newer one. Roads are matched
+    /// by `OriginalRoad`; when a road was split or its endpoints shifted, the nearby pieces of the
+    /// same OSM way are used instead. Returns the rebased edits and a description of every
+    /// command that couldn't be translated.
+    pub fn rebase(&self, map: &Map) -> (PermanentMapEdits, Vec<String>) {
+        let mut result = self.clone();
+        result.map_name = map.get_name().to_string();
+        result.commands.clear();
+        let mut problems = Vec::new();
+
+        for (_, cmd) in self.net_changes() {
+            match cmd {
+                PermanentEditCmd::ChangeRoad { r, new, center, .. } => {
+                    match migrate::match_road(map, r, center, &new) {
+                        Ok((ids, skipped)) => {
+                            problems.extend(skipped);
+                            for id in ids {
+                                let road = map.get_r(id);
+                                result.commands.push(PermanentEditCmd::ChangeRoad {
+                                    r: road.orig_id,
+                                    old: EditRoad::get_orig_from_osm(road, map.config.driving_side),
+                                    new: new.clone(),
+                                    center: Some(migrate::road_center(map, id)),
+                                });
+                            }
+                        }
+                        Err(err) => {
+                            problems.push(err);
+                        }
+                    }
+                }
+                PermanentEditCmd::ChangeIntersection { i, new, old } => {
+                    let id = match map.find_i_by_osm_id(i) {
+                        Ok(id) => id,
+                        Err(err) => {
+                            problems.push(err);
+                            continue;
+                        }
+                    };
+                    let translate = |x: PermanentEditIntersection| match x {
+                        PermanentEditIntersection::StopSign { must_stop } => {
+                            let mut translated = BTreeMap::new();
+                            for (r, stop) in must_stop {
+                                if map.find_r_by_osm_id(r).is_ok() {
+                                    translated.insert(r, stop);
+                                    continue;
+                                }
+                                // The road leading to this intersection may have been split;
+                                // find the piece of the same way still touching it.
+                                match map.get_i(id).roads.iter().find(|other| {
+                                    map.get_r(**other).orig_id.osm_way_id == r.osm_way_id
+                                }) {
+                                    Some(other) => {
+                                        translated.insert(map.get_r(*other).orig_id, stop);
+                                    }
+                                    None => {
+                                        return Err(format!("{} no longer touches {}", r, i));
+                                    }
+                                }
+                            }
+                            Ok(PermanentEditIntersection::StopSign {
+                                must_stop: translated,
+                            })
+                        }
+                        x => Ok(x),
+                    };
+                    match (translate(new), translate(old)) {
+                        (Ok(new), Ok(old)) => {
+                            result.commands.push(PermanentEditCmd::ChangeIntersection {
+                                i,
+                                new,
+                                old,
+                            });
+                        }
+                        (Err(err), _) | (_, Err(err)) => {
+                            problems.push(err);
+                        }
+                    }
+                }
+                PermanentEditCmd::ChangeRouteSchedule { osm_rel_id, .. } => {
+                    if map.find_br(osm_rel_id).is_some() {
+                        result.commands.push(cmd);
+                    } else {
+                        problems.push(format!("can't find route {}", osm_rel_id));
+                    }
+                }
+                PermanentEditCmd::ChangeModalFilter { i, .. } => match map.find_i_by_osm_id(i) {
+                    Ok(_) => {
+                        result.commands.push(cmd);
+                    }
+                    Err(err) => {
+                        problems.push(err);
+                    }
+                },
+            }
+        }
+
+        (result, problems)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use enumset::EnumSet;
+
+    use geom::{Duration, Time};
+
+    use super::*;
+    use crate::PathConstraints;
+
+    fn edits(name: &str, commands: Vec<PermanentEditCmd>) -> PermanentMapEdits {
+        PermanentMapEdits {
+            map_name: "test".to_string(),
+            edits_name: name.to_string(),
+            version: 3,
+            commands,
+            merge_zones: true,
+            proposal_description: Vec::new(),
+            proposal_link: None,
+        }
+    }
+
+    fn filter(
+        i: i64,
+        old: EnumSet<PathConstraints>,
+        new: EnumSet<PathConstraints>,
+    ) -> PermanentEditCmd {
+        PermanentEditCmd::ChangeModalFilter {
+            i: osm::NodeID(i),
+            old,
+            new,
+        }
+    }
+
+    fn schedule(rel: i64, hours: Vec<usize>) -> PermanentEditCmd {
+        PermanentEditCmd::ChangeRouteSchedule {
+            osm_rel_id: osm::RelationID(rel),
+            old: Vec::new(),
+            new: hours
+                .into_iter()
+                .map(|h| Time::START_OF_DAY + Duration::hours(h))
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_net_changes() {
+        let none = EnumSet::new();
+        let cars = EnumSet::only(PathConstraints::Car);
+        // Adding and then removing a filter is a no-op; changing a schedule twice keeps the last
+        let e = edits(
+            "a",
+            vec![
+                filter(1, none, cars),
+                schedule(5, vec![7]),
+                filter(1, cars, none),
+                schedule(5, vec![8, 9]),
+            ],
+        );
+        let changes = e.net_changes();
+        assert_eq!(changes.len(), 1);
+        assert!(changes[&EditTarget::Route(osm::RelationID(5))] == schedule(5, vec![8, 9]));
+    }
+
+    #[test]
+    fn test_diff() {
+        let none = EnumSet::new();
+        let cars = EnumSet::only(PathConstraints::Car);
+        let a = edits(
+            "a",
+            vec![
+                filter(1, none, cars),
+                filter(2, none, cars),
+                schedule(5, vec![7]),
+            ],
+        );
+        let b = edits(
+            "b",
+            vec![
+                filter(2, none, cars),
+                filter(3, none, cars),
+                schedule(5, vec![8]),
+            ],
+        );
+        let diff = a.diff(&b);
+        assert_eq!(
+            diff.only_first,
+            vec![EditTarget::ModalFilter(osm::NodeID(1))]
+        );
+        assert_eq!(
+            diff.only_second,
+            vec![EditTarget::ModalFilter(osm::NodeID(3))]
+        );
+        assert_eq!(diff.same, vec![EditTarget::ModalFilter(osm::NodeID(2))]);
+        assert_eq!(diff.different.len(), 1);
+        assert_eq!(diff.different[0].0, EditTarget::Route(osm::RelationID(5)));
+    }
+
+    #[test]
+    fn test_merge() {
+        let none = EnumSet::new();
+        let cars = EnumSet::only(PathConstraints::Car);
+        let a = edits("a", vec![filter(1, none, cars), schedule(5, vec![7])]);
+        let b = edits("b", vec![filter(2, none, cars), schedule(5, vec![8])]);
+
+        let (merged, conflicts) = a.merge(&b, false);
+        assert_eq!(merged.edits_name, "a");
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].target, EditTarget::Route(osm::RelationID(5)));
+        assert_eq!(merged.commands.len(), 3);
+        assert!(merged.commands.contains(&schedule(5, vec![7])));
+
+        let (merged, _) = a.merge(&b, true);
+        assert!(merged.commands.contains(&schedule(5, vec![8])));
+        assert!(merged.commands.contains(&filter(1, none, cars)));
+        assert!(merged.commands.contains(&filter(2, none, cars)));
+    }
+}
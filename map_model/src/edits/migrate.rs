@@ -0,0 +1,215 @@
+//! When the map is re-imported from newer OSM data or after importer changes, the
+//! `OriginalRoad`s referenced by old edits may no longer exist, because a way was split in a
+//! different place or an intersection disappeared. This finds where those edits belong now.
+
+use geom::{Bounds, Distance, FindClosest, LonLat, PolyLine, Pt2D};
+
+use crate::edits::EditRoad;
+use crate::raw::OriginalRoad;
+use crate::{Map, RoadID};
+
+/// How far the old location of a road can be from its new location
+const MAX_DIST_MOVED: Distance = Distance::const_meters(30.0);
+
+/// Find the roads in the current map that an edit to `orig` should apply to now. `center` is
+/// where the middle of the road was when the edit was made, if known. Roads from the same OSM way
+/// always point the same direction, so the edit never needs to be flipped. If only some of the
+/// matching roads can take the edit, the second result describes why the others were skipped.
+pub(crate) fn match_road(
+    map: &Map,
+    orig: OriginalRoad,
+    center: Option<LonLat>,
+    new: &EditRoad,
+) -> Result<(Vec<RoadID>, Vec<String>), String> {
+    if let Ok(id) = map.find_r_by_osm_id(orig) {
+        return check_lanes(map, orig, vec![id], new);
+    }
+
+    let pieces: Vec<(RoadID, OriginalRoad, &PolyLine)> = map
+        .all_roads()
+        .iter()
+        .filter(|r| r.orig_id.osm_way_id == orig.osm_way_id)
+        .map(|r| (r.id, r.orig_id, &r.center_pts))
+        .collect();
+    let center = center.map(|gps| Pt2D::from_gps(gps, map.get_gps_bounds()));
+    let ids = match_pieces(orig, center, &pieces, map.get_bounds())?;
+    check_lanes(map, orig, ids, new)
+}
+
+/// Given all of the current pieces of the OSM way that `orig` belonged to, figure out which ones
+/// cover `orig` now.
+fn match_pieces(
+    orig: OriginalRoad,
+    center: Option<Pt2D>,
+    pieces: &[(RoadID, OriginalRoad, &PolyLine)],
+    bounds: &Bounds,
+) -> Result<Vec<RoadID>, String> {
+    if pieces.is_empty() {
+        return Err(format!("{} no longer exists", orig.osm_way_id));
+    }
+
+    // The common case is an intersection added in the middle of the old road. Walk along the
+    // pieces of the way from the original start until reaching the original end.
+    let mut chain = Vec::new();
+    let mut at = orig.i1;
+    while let Some((id, piece, _)) = pieces.iter().find(|(_, piece, _)| piece.i1 == at) {
+        chain.push(*id);
+        if piece.i2 == orig.i2 {
+            return Ok(chain);
+        }
+        if chain.len() == pieces.len() {
+            break;
+        }
+        at = piece.i2;
+    }
+
+    // Otherwise, one or both of the endpoints moved. Only the middle of the old road is used to
+    // find it again; the old endpoints also touch the neighboring pieces of the same way.
+    let pt = match center {
+        Some(pt) => pt,
+        None => {
+            if pieces.len() == 1 {
+                return Ok(vec![pieces[0].0]);
+            }
+            return Err(format!(
+                "{} was split into {} roads, and nothing's known about where {} was",
+                orig.osm_way_id,
+                pieces.len(),
+                orig
+            ));
+        }
+    };
+    let mut closest = FindClosest::new(bounds);
+    for (id, _, pl) in pieces {
+        closest.add(*id, pl.points());
+    }
+    match closest.closest_pt(pt, MAX_DIST_MOVED) {
+        Some((id, _)) => Ok(vec![id]),
+        None => Err(format!(
+            "no part of {} is near where {} was",
+            orig.osm_way_id, orig
+        )),
+    }
+}
+
+/// It'd be pretty hard to understand the original intent of the edit if the number of lanes
+/// changed, so only keep roads that still match.
+fn check_lanes(
+    map: &Map,
+    orig: OriginalRoad,
+    roads: Vec<RoadID>,
+    new: &EditRoad,
+) -> Result<(Vec<RoadID>, Vec<String>), String> {
+    let roads = roads
+        .into_iter()
+        .map(|r| (r, map.get_r(r).orig_id, map.get_r(r).lanes_ltr.len()))
+        .collect();
+    split_by_lanes(orig, roads, new.lanes_ltr.len())
+}
+
+/// Separate the roads with `num_lanes` from the rest, describing why the rest can't be used. Only
+/// fails if no road matches.
+fn split_by_lanes(
+    orig: OriginalRoad,
+    roads: Vec<(RoadID, OriginalRoad, usize)>,
+    num_lanes: usize,
+) -> Result<(Vec<RoadID>, Vec<String>), String> {
+    let mut problems = Vec::new();
+    let mut results = Vec::new();
+    for (r, orig_id, num_current) in roads {
+        if num_current == num_lanes {
+            results.push(r);
+        } else {
+            problems.push(format!(
+                "number of lanes in {} is {} now, but {} in the edits",
+                orig_id, num_current, num_lanes
+            ));
+        }
+    }
+    if results.is_empty() {
+        if problems.is_empty() {
+            return Err(format!("no road matches {}", orig));
+        }
+        return Err(problems.join("; "));
+    }
+    Ok((results, problems))
+}
+
+/// Remember where a road is, so it can be found later with `match_road`.
+pub(crate) fn road_center(map: &Map, r: RoadID) -> LonLat {
+    map.get_r(r)
+        .center_pts
+        .middle()
+        .to_gps(map.get_gps_bounds())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pl(pts: Vec<(f64, f64)>) -> PolyLine {
+        PolyLine::must_new(pts.into_iter().map(|(x, y)| Pt2D::new(x, y)).collect())
+    }
+
+    #[test]
+    fn test_split_road() {
+        // Way 100 used to go from node 1 to node 3 in one piece, then a new intersection at node 2
+        // split it
+        let orig = OriginalRoad::new(100, (1, 3));
+        let first = pl(vec![(0.0, 0.0), (50.0, 0.0)]);
+        let second = pl(vec![(50.0, 0.0), (100.0, 0.0)]);
+        let next_block = pl(vec![(100.0, 0.0), (200.0, 0.0)]);
+        let pieces = vec![
+            (RoadID(0), OriginalRoad::new(100, (1, 2)), &first),
+            (RoadID(1), OriginalRoad::new(100, (2, 3)), &second),
+            (RoadID(2), OriginalRoad::new(100, (3, 4)), &next_block),
+        ];
+        let bounds = Bounds::from(&vec![Pt2D::new(0.0, 0.0), Pt2D::new(200.0, 10.0)]);
+        assert_eq!(
+            match_pieces(orig, None, &pieces, &bounds),
+            Ok(vec![RoadID(0), RoadID(1)])
+        );
+    }
+
+    #[test]
+    fn test_endpoint_moved() {
+        // Way 100 used to have a block from node 2 to node 3, but node 3 disappeared and the block
+        // now ends a little farther along, at node 5. The previous block also touches node 2, but
+        // the edit shouldn't spread to it.
+        let orig = OriginalRoad::new(100, (2, 3));
+        let previous_block = pl(vec![(0.0, 0.0), (100.0, 0.0)]);
+        let moved = pl(vec![(100.0, 0.0), (210.0, 0.0)]);
+        let next_block = pl(vec![(210.0, 0.0), (300.0, 0.0)]);
+        let pieces = vec![
+            (RoadID(0), OriginalRoad::new(100, (1, 2)), &previous_block),
+            (RoadID(1), OriginalRoad::new(100, (2, 5)), &moved),
+            (RoadID(2), OriginalRoad::new(100, (5, 6)), &next_block),
+        ];
+        let bounds = Bounds::from(&vec![Pt2D::new(0.0, 0.0), Pt2D::new(300.0, 10.0)]);
+        assert_eq!(
+            match_pieces(orig, Some(Pt2D::new(150.0, 0.0)), &pieces, &bounds),
+            Ok(vec![RoadID(1)])
+        );
+        // Without knowing where the road was, there's no way to pick
+        assert!(match_pieces(orig, None, &pieces, &bounds).is_err());
+        // And if it moved too far, give up
+        assert!(match_pieces(orig, Some(Pt2D::new(150.0, 100.0)), &pieces, &bounds).is_err());
+    }
+
+    #[test]
+    fn test_some_pieces_lost_lanes() {
+        // Way 100 was split in two, and one of the pieces lost a lane since the edit was made
+        let orig = OriginalRoad::new(100, (1, 3));
+        let roads = vec![
+            (RoadID(0), OriginalRoad::new(100, (1, 2)), 4),
+            (RoadID(1), OriginalRoad::new(100, (2, 3)), 3),
+        ];
+        let (matched, problems) = split_by_lanes(orig, roads.clone(), 4).unwrap();
+        assert_eq!(matched, vec![RoadID(0)]);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains(&OriginalRoad::new(100, (2, 3)).to_string()));
+
+        // If no pieces match, nothing is salvageable
+        assert!(split_by_lanes(orig, roads, 2).is_err());
+    }
+}
@@ -4,9 +4,12 @@
 
 use std::collections::{BTreeMap, BTreeSet};
 
-pub use perma::PermanentMapEdits;
+pub use merge::{EditConflict, EditTarget, EditsDiff};
+pub use perma::{PermanentEditCmd, PermanentEditIntersection, PermanentMapEdits};
 use serde::{Deserialize, Serialize};
 
+use enumset::EnumSet;
+
 use abstutil::{retain_btreemap, retain_btreeset, Timer};
 use geom::{Speed, Time};
 
@@ -14,10 +17,12 @@ use crate::make::initial::lane_specs::get_lane_specs_ltr;
 use crate::{
     connectivity, AccessRestrictions, BusRouteID, ControlStopSign, ControlTrafficSignal, Direction,
     DrivingSide, IntersectionID, IntersectionType, LaneID, LaneType, Map, PathConstraints,
-    Pathfinder, Road, RoadID, TurnID, Zone,
+    Pathfinder, Road, RoadID, TimeRules, TurnID, Zone,
 };
 
 mod compat;
+mod merge;
+mod migrate;
 mod perma;
 
 /// Represents changes to a map. Note this isn't serializable -- that's what `PermanentMapEdits`
@@ -37,6 +42,7 @@ pub struct MapEdits {
     pub changed_roads: BTreeSet<RoadID>,
     pub original_intersections: BTreeMap<IntersectionID, EditIntersection>,
     pub changed_routes: BTreeSet<BusRouteID>,
+    pub changed_modal_filters: BTreeSet<IntersectionID>,
 
     /// Some edits are included in the game by default, in data/system/proposals, as "community
     /// proposals." They require a description and may have a link to a write-up.
@@ -51,6 +57,7 @@ pub enum EditIntersection {
     // generated after all lane edits are applied.
     TrafficSignal(seattle_traffic_signals::TrafficSignal),
     Closed,
+    Roundabout,
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -58,17 +65,23 @@ pub struct EditRoad {
     pub lanes_ltr: Vec<(LaneType, Direction)>,
     pub speed_limit: Speed,
     pub access_restrictions: AccessRestrictions,
+    /// Edits made before this existed don't have it
+    #[serde(default)]
+    pub time_rules: TimeRules,
 }
 
 impl EditRoad {
     pub fn get_orig_from_osm(r: &Road, driving_side: DrivingSide) -> EditRoad {
+        let lanes_ltr: Vec<(LaneType, Direction)> = get_lane_specs_ltr(&r.osm_tags, driving_side)
+            .into_iter()
+            .map(|spec| (spec.lt, spec.dir))
+            .collect();
+        let time_rules = TimeRules::from_osm(&r.osm_tags, &lanes_ltr, driving_side);
         EditRoad {
-            lanes_ltr: get_lane_specs_ltr(&r.osm_tags, driving_side)
-                .into_iter()
-                .map(|spec| (spec.lt, spec.dir))
-                .collect(),
+            lanes_ltr,
             speed_limit: r.speed_limit_from_osm(),
             access_restrictions: r.access_restrictions_from_osm(),
+            time_rules,
         }
     }
 
@@ -101,6 +114,9 @@ impl EditRoad {
         if self.access_restrictions != other.access_restrictions {
             changes.push(format!("access restrictions"));
         }
+        if self.time_rules != other.time_rules {
+            changes.push(format!("time of day rules"));
+        }
         changes
     }
 }
@@ -122,6 +138,12 @@ pub enum EditCmd {
         old: Vec<Time>,
         new: Vec<Time>,
     },
+    /// The modes blocked from passing through an intersection
+    ChangeModalFilter {
+        i: IntersectionID,
+        old: EnumSet<PathConstraints>,
+        new: EnumSet<PathConstraints>,
+    },
 }
 
 pub struct EditEffects {
@@ -143,21 +165,17 @@ impl MapEdits {
             changed_roads: BTreeSet::new(),
             original_intersections: BTreeMap::new(),
             changed_routes: BTreeSet::new(),
+            changed_modal_filters: BTreeSet::new(),
         }
     }
 
     pub fn load(map: &Map, path: String, timer: &mut Timer) -> Result<MapEdits, String> {
-        match abstutil::maybe_read_json(path.clone(), timer) {
-            Ok(perma) => PermanentMapEdits::from_permanent(perma, map),
-            Err(_) => {
-                // The JSON format may have changed, so attempt backwards compatibility.
-                let bytes = abstutil::slurp_file(&path).map_err(|err| err.to_string())?;
-                let contents = std::str::from_utf8(&bytes).map_err(|err| err.to_string())?;
-                let value = serde_json::from_str(contents).map_err(|err| err.to_string())?;
-                let perma = compat::upgrade(value, map)?;
-                PermanentMapEdits::from_permanent(perma, map)
-            }
+        let perma = PermanentMapEdits::load_from_file(map, path, timer)?;
+        let (edits, relocations) = PermanentMapEdits::from_permanent(perma, map)?;
+        for relocation in relocations {
+            timer.warn(relocation);
         }
+        Ok(edits)
     }
 
     fn save(&self, map: &Map) {
@@ -176,6 +194,7 @@ impl MapEdits {
         self.changed_roads.clear();
         self.original_intersections.clear();
         self.changed_routes.clear();
+        self.changed_modal_filters.clear();
 
         for cmd in &self.commands {
             match cmd {
@@ -190,6 +209,9 @@ impl MapEdits {
                 EditCmd::ChangeRouteSchedule { id, .. } => {
                     self.changed_routes.insert(*id);
                 }
+                EditCmd::ChangeModalFilter { i, .. } => {
+                    self.changed_modal_filters.insert(*i);
+                }
             }
         }
 
@@ -204,6 +226,10 @@ impl MapEdits {
             let r = map.get_br(*br);
             r.spawn_times != r.orig_spawn_times
         });
+        // No modal filters are imported, so any that exist are edits
+        retain_btreeset(&mut self.changed_modal_filters, |i| {
+            map.get_i(*i).has_modal_filter()
+        });
     }
 
     /// Assumes update_derived has been called.
@@ -230,6 +256,13 @@ impl MapEdits {
                 old: r.orig_spawn_times.clone(),
             });
         }
+        for i in &self.changed_modal_filters {
+            self.commands.push(EditCmd::ChangeModalFilter {
+                i: *i,
+                old: EnumSet::new(),
+                new: map.get_i(*i).modal_filter,
+            });
+        }
     }
 
     /// Pick apart changed_roads and figure out if an entire road was edited, or just a few lanes.
@@ -242,6 +275,7 @@ impl MapEdits {
             // What exactly changed?
             if r.speed_limit != orig.speed_limit
                 || r.access_restrictions != orig.access_restrictions
+                || r.time_rules != orig.time_rules
             {
                 roads.insert(r.id);
             } else {
@@ -288,10 +322,19 @@ impl EditCmd {
                 EditIntersection::StopSign(_) => format!("stop sign #{}", i.0),
                 EditIntersection::TrafficSignal(_) => format!("traffic signal #{}", i.0),
                 EditIntersection::Closed => format!("close {}", i),
+                EditIntersection::Roundabout => format!("roundabout #{}", i.0),
             },
             EditCmd::ChangeRouteSchedule { id, .. } => {
                 format!("reschedule route {}", map.get_br(*id).short_name)
             }
+            EditCmd::ChangeModalFilter { i, new, .. } => {
+                if new.is_empty() {
+                    format!("remove modal filter #{}", i.0)
+                } else {
+                    details = new.iter().map(|c| format!("block {:?}", c)).collect();
+                    format!("modal filter #{}", i.0)
+                }
+            }
         };
         (summary, details)
     }
@@ -307,6 +350,17 @@ impl EditCmd {
                 let road = &mut map.roads[r.0];
                 road.speed_limit = new.speed_limit;
                 road.access_restrictions = new.access_restrictions.clone();
+                road.time_rules = new.time_rules.clone();
+                if road.time_rules.is_empty() {
+                    map.roads_with_time_rules.remove(r);
+                } else {
+                    map.roads_with_time_rules.insert(*r);
+                }
+                if road.access_restrictions.ban_trucks {
+                    map.roads_banning_trucks.insert(*r);
+                } else {
+                    map.roads_banning_trucks.remove(r);
+                }
                 assert_eq!(road.lanes_ltr.len(), new.lanes_ltr.len());
                 for (idx, (lt, dir)) in new.lanes_ltr.clone().into_iter().enumerate() {
                     let lane = &mut map.lanes[(road.lanes_ltr[idx].0).0];
@@ -353,33 +407,38 @@ impl EditCmd {
                 map.stop_signs.remove(i);
                 map.traffic_signals.remove(i);
                 effects.changed_intersections.insert(*i);
+                map.intersections[i.0].intersection_type = match new {
+                    EditIntersection::StopSign(_) => IntersectionType::StopSign,
+                    EditIntersection::TrafficSignal(_) => IntersectionType::TrafficSignal,
+                    EditIntersection::Closed => IntersectionType::Construction,
+                    EditIntersection::Roundabout => IntersectionType::Roundabout,
+                };
+
+                // Recalculating turns installs a default stop sign or signal, so do it before
+                // installing the one from the edit.
+                if changes_turns(old, new) {
+                    recalculate_turns(*i, map, effects, timer);
+                }
                 match new {
                     EditIntersection::StopSign(ref ss) => {
-                        map.intersections[i.0].intersection_type = IntersectionType::StopSign;
                         map.stop_signs.insert(*i, ss.clone());
                     }
                     EditIntersection::TrafficSignal(ref raw_ts) => {
-                        map.intersections[i.0].intersection_type = IntersectionType::TrafficSignal;
-                        if old == &EditIntersection::Closed {
-                            recalculate_turns(*i, map, effects, timer);
-                        }
                         map.traffic_signals.insert(
                             *i,
                             ControlTrafficSignal::import(raw_ts.clone(), *i, map).unwrap(),
                         );
                     }
-                    EditIntersection::Closed => {
-                        map.intersections[i.0].intersection_type = IntersectionType::Construction;
-                    }
-                }
-
-                if old == &EditIntersection::Closed || new == &EditIntersection::Closed {
-                    recalculate_turns(*i, map, effects, timer);
+                    EditIntersection::Closed | EditIntersection::Roundabout => {}
                 }
             }
             EditCmd::ChangeRouteSchedule { id, new, .. } => {
                 map.bus_routes[id.0].spawn_times = new.clone();
             }
+            EditCmd::ChangeModalFilter { i, new, .. } => {
+                map.intersections[i.0].modal_filter = *new;
+                effects.changed_intersections.insert(*i);
+            }
         }
     }
 
@@ -400,10 +459,25 @@ impl EditCmd {
                 old: new,
                 new: old,
             },
+            EditCmd::ChangeModalFilter { i, old, new } => EditCmd::ChangeModalFilter {
+                i,
+                old: new,
+                new: old,
+            },
         }
     }
 }
 
+/// Closing an intersection removes its turns, and vehicles can't cut across the ring of a
+/// roundabout, so changing to or from either of those needs new turns.
+fn changes_turns(old: &EditIntersection, new: &EditIntersection) -> bool {
+    let special = |x: &EditIntersection| match x {
+        EditIntersection::Closed | EditIntersection::Roundabout => true,
+        EditIntersection::StopSign(_) | EditIntersection::TrafficSignal(_) => false,
+    };
+    special(old) || special(new)
+}
+
 // This clobbers previously set traffic signal overrides.
 // TODO Step 1: Detect and warn about that
 // TODO Step 2: Avoid when possible
@@ -454,6 +528,7 @@ fn recalculate_turns(
             map.traffic_signals
                 .insert(id, ControlTrafficSignal::new(map, id, timer));
         }
+        IntersectionType::Roundabout => {}
         IntersectionType::Border | IntersectionType::Construction => unreachable!(),
     }
 }
@@ -492,6 +567,7 @@ impl Map {
                 .collect(),
             speed_limit: r.speed_limit,
             access_restrictions: r.access_restrictions.clone(),
+            time_rules: r.time_rules.clone(),
         }
     }
 
@@ -499,9 +575,23 @@ impl Map {
         let old = self.get_r_edit(r);
         let mut new = old.clone();
         f(&mut new);
+        // If the player changes a lane's type, whatever it used to become at certain times of day
+        // no longer applies.
+        let lanes_ltr = new.lanes_ltr.clone();
+        new.time_rules
+            .lane_types
+            .retain(|(idx, _, _)| lanes_ltr[*idx].0 == old.lanes_ltr[*idx].0);
         EditCmd::ChangeRoad { r, old, new }
     }
 
+    pub fn modal_filter_cmd(&self, i: IntersectionID, new: EnumSet<PathConstraints>) -> EditCmd {
+        EditCmd::ChangeModalFilter {
+            i,
+            old: self.get_i(i).modal_filter,
+            new,
+        }
+    }
+
     /// Panics on borders
     pub fn get_i_edit(&self, i: IntersectionID) -> EditIntersection {
         match self.get_i(i).intersection_type {
@@ -510,6 +600,7 @@ impl Map {
                 EditIntersection::TrafficSignal(self.get_traffic_signal(i).export(self))
             }
             IntersectionType::Construction => EditIntersection::Closed,
+            IntersectionType::Roundabout => EditIntersection::Roundabout,
             IntersectionType::Border => unreachable!(),
         }
     }
@@ -667,3 +758,26 @@ impl Map {
         self.traffic_signals.insert(signal.id, signal);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changes_turns() {
+        let stop_sign = EditIntersection::StopSign(ControlStopSign {
+            id: IntersectionID(0),
+            roads: BTreeMap::new(),
+        });
+        let closed = EditIntersection::Closed;
+        let roundabout = EditIntersection::Roundabout;
+
+        // Editing a stop sign keeps the turns, so nothing clobbers the new stop sign
+        assert!(!changes_turns(&stop_sign, &stop_sign));
+        assert!(changes_turns(&stop_sign, &closed));
+        assert!(changes_turns(&closed, &stop_sign));
+        assert!(changes_turns(&roundabout, &stop_sign));
+        assert!(changes_turns(&stop_sign, &roundabout));
+        assert!(changes_turns(&closed, &roundabout));
+    }
+}
@@ -1,13 +1,14 @@
 use std::collections::{BTreeMap, BTreeSet};
 
+use enumset::EnumSet;
 use serde::{Deserialize, Serialize};
 
-use abstutil::{deserialize_btreemap, serialize_btreemap};
-use geom::Time;
+use abstutil::{deserialize_btreemap, serialize_btreemap, Timer};
+use geom::{LonLat, Time};
 
-use crate::edits::{EditCmd, EditIntersection, EditRoad, MapEdits};
+use crate::edits::{compat, migrate, EditCmd, EditIntersection, EditRoad, MapEdits};
 use crate::raw::OriginalRoad;
-use crate::{osm, ControlStopSign, IntersectionID, Map};
+use crate::{osm, ControlStopSign, IntersectionID, Map, PathConstraints, RoadID};
 
 /// MapEdits are converted to this before serializing. Referencing things like LaneID in a Map won't
 /// work if the basemap is rebuilt from new OSM data, so instead we use stabler OSM IDs that're less
@@ -17,11 +18,11 @@ pub struct PermanentMapEdits {
     pub map_name: String,
     pub edits_name: String,
     pub version: usize,
-    commands: Vec<PermanentEditCmd>,
+    pub(crate) commands: Vec<PermanentEditCmd>,
     /// If false, adjacent roads with the same AccessRestrictions will not be merged into the same
     /// Zone; every Road will be its own Zone. This is used to experiment with a per-road cap. Note
     /// this is a map-wide setting.
-    merge_zones: bool,
+    pub(crate) merge_zones: bool,
 
     /// Edits without these are player generated.
     pub proposal_description: Vec<String>,
@@ -29,7 +30,7 @@ pub struct PermanentMapEdits {
     pub proposal_link: Option<String>,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub enum PermanentEditIntersection {
     StopSign {
         #[serde(
@@ -40,14 +41,20 @@ pub enum PermanentEditIntersection {
     },
     TrafficSignal(seattle_traffic_signals::TrafficSignal),
     Closed,
+    Roundabout,
 }
 
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, PartialEq)]
 pub enum PermanentEditCmd {
     ChangeRoad {
         r: OriginalRoad,
         new: EditRoad,
         old: EditRoad,
+        /// Where the middle of the road was when the edit was made. If the map is later
+        /// re-imported and `r` no longer exists, this helps find the road again. Older edits
+        /// don't have this.
+        #[serde(default)]
+        center: Option<LonLat>,
     },
     ChangeIntersection {
         i: osm::NodeID,
@@ -59,6 +66,11 @@ pub enum PermanentEditCmd {
         old: Vec<Time>,
         new: Vec<Time>,
     },
+    ChangeModalFilter {
+        i: osm::NodeID,
+        old: EnumSet<PathConstraints>,
+        new: EnumSet<PathConstraints>,
+    },
 }
 
 impl EditCmd {
@@ -68,6 +80,7 @@ impl EditCmd {
                 r: map.get_r(*r).orig_id,
                 new: new.clone(),
                 old: old.clone(),
+                center: Some(migrate::road_center(map, *r)),
             },
             EditCmd::ChangeIntersection { i, new, old } => PermanentEditCmd::ChangeIntersection {
                 i: map.get_i(*i).orig_id,
@@ -81,6 +94,11 @@ impl EditCmd {
                     new: new.clone(),
                 }
             }
+            EditCmd::ChangeModalFilter { i, old, new } => PermanentEditCmd::ChangeModalFilter {
+                i: map.get_i(*i).orig_id,
+                old: *old,
+                new: *new,
+            },
         }
     }
 }
@@ -99,66 +117,122 @@ impl PermanentMapEdits {
         }
     }
 
+    /// Read edits from a file, upgrading them to the latest schema if needed. The map is only used
+    /// to upgrade very old edits.
+    pub fn load_from_file(
+        map: &Map,
+        path: String,
+        timer: &mut Timer,
+    ) -> Result<PermanentMapEdits, String> {
+        match abstutil::maybe_read_json(path.clone(), timer) {
+            Ok(perma) => Ok(perma),
+            Err(_) => {
+                // The JSON format may have changed, so attempt backwards compatibility.
+                let bytes = abstutil::slurp_file(&path).map_err(|err| err.to_string())?;
+                let contents = std::str::from_utf8(&bytes).map_err(|err| err.to_string())?;
+                let value = serde_json::from_str(contents).map_err(|err| err.to_string())?;
+                compat::upgrade(value, map)
+            }
+        }
+    }
+
     /// Load edits from the permanent form, looking up the Map IDs by the hopefully stabler OSM IDs.
-    /// Validate that the basemap hasn't changed in important ways.
-    // TODO When a change has happened, try to preserve as much of the original edits as possible,
-    // and warn the player about the rest?
-    pub fn from_permanent(perma: PermanentMapEdits, map: &Map) -> Result<MapEdits, String> {
+    /// If the basemap has been re-imported and a road's OSM IDs changed, the edit is relocated
+    /// to the matching roads, and each relocation (or piece of a road that couldn't take the edit)
+    /// is described in the second result. Other important changes to the basemap are errors.
+    pub fn from_permanent(
+        perma: PermanentMapEdits,
+        map: &Map,
+    ) -> Result<(MapEdits, Vec<String>), String> {
+        let mut commands = Vec::new();
+        let mut relocations = Vec::new();
+        // When several relocated edits stack on the same road, each one starts from where the
+        // previous one left off.
+        let mut latest: BTreeMap<RoadID, EditRoad> = BTreeMap::new();
+        for cmd in perma.commands {
+            match cmd {
+                PermanentEditCmd::ChangeRoad {
+                    r,
+                    new,
+                    old,
+                    center,
+                } => {
+                    let (ids, skipped) = migrate::match_road(map, r, center, &new)?;
+                    // If the road moved, the old state describes a road that doesn't exist
+                    // anymore, so start from the current one.
+                    let relocated = ids.len() != 1 || map.get_r(ids[0]).orig_id != r;
+                    if relocated {
+                        relocations.push(format!(
+                            "edit to {} moved to {}",
+                            r,
+                            ids.iter()
+                                .map(|id| map.get_r(*id).orig_id.to_string())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        ));
+                    }
+                    for problem in skipped {
+                        relocations.push(format!("edit to {} skipped: {}", r, problem));
+                    }
+                    for id in ids {
+                        let old = if relocated {
+                            latest.get(&id).cloned().unwrap_or_else(|| {
+                                EditRoad::get_orig_from_osm(map.get_r(id), map.config.driving_side)
+                            })
+                        } else {
+                            old.clone()
+                        };
+                        latest.insert(id, new.clone());
+                        commands.push(EditCmd::ChangeRoad {
+                            r: id,
+                            new: new.clone(),
+                            old,
+                        });
+                    }
+                }
+                PermanentEditCmd::ChangeIntersection { i, new, old } => {
+                    let id = map.find_i_by_osm_id(i)?;
+                    commands.push(EditCmd::ChangeIntersection {
+                        i: id,
+                        new: new.from_permanent(id, map).map_err(|err| {
+                            format!("new ChangeIntersection of {} invalid: {}", i, err)
+                        })?,
+                        old: old.from_permanent(id, map).map_err(|err| {
+                            format!("old ChangeIntersection of {} invalid: {}", i, err)
+                        })?,
+                    });
+                }
+                PermanentEditCmd::ChangeRouteSchedule {
+                    osm_rel_id,
+                    old,
+                    new,
+                } => {
+                    let id = map
+                        .find_br(osm_rel_id)
+                        .ok_or(format!("can't find {}", osm_rel_id))?;
+                    commands.push(EditCmd::ChangeRouteSchedule { id, old, new });
+                }
+                PermanentEditCmd::ChangeModalFilter { i, old, new } => {
+                    let id = map.find_i_by_osm_id(i)?;
+                    commands.push(EditCmd::ChangeModalFilter { i: id, old, new });
+                }
+            }
+        }
+
         let mut edits = MapEdits {
             edits_name: perma.edits_name,
             proposal_description: perma.proposal_description,
             proposal_link: perma.proposal_link,
-            commands: perma
-                .commands
-                .into_iter()
-                .map(|cmd| match cmd {
-                    PermanentEditCmd::ChangeRoad { r, new, old } => {
-                        let id = map.find_r_by_osm_id(r)?;
-                        let num_current = map.get_r(id).lanes_ltr().len();
-                        // The basemap changed -- it'd be pretty hard to understand the original
-                        // intent of the edit.
-                        if num_current != new.lanes_ltr.len() {
-                            return Err(format!(
-                                "number of lanes in {} is {} now, but {} in the edits",
-                                r,
-                                num_current,
-                                new.lanes_ltr.len()
-                            ));
-                        }
-                        Ok(EditCmd::ChangeRoad { r: id, new, old })
-                    }
-                    PermanentEditCmd::ChangeIntersection { i, new, old } => {
-                        let id = map.find_i_by_osm_id(i)?;
-                        Ok(EditCmd::ChangeIntersection {
-                            i: id,
-                            new: new.from_permanent(id, map).map_err(|err| {
-                                format!("new ChangeIntersection of {} invalid: {}", i, err)
-                            })?,
-                            old: old.from_permanent(id, map).map_err(|err| {
-                                format!("old ChangeIntersection of {} invalid: {}", i, err)
-                            })?,
-                        })
-                    }
-                    PermanentEditCmd::ChangeRouteSchedule {
-                        osm_rel_id,
-                        old,
-                        new,
-                    } => {
-                        let id = map
-                            .find_br(osm_rel_id)
-                            .ok_or(format!("can't find {}", osm_rel_id))?;
-                        Ok(EditCmd::ChangeRouteSchedule { id, old, new })
-                    }
-                })
-                .collect::<Result<Vec<EditCmd>, String>>()?,
+            commands,
             merge_zones: perma.merge_zones,
 
             changed_roads: BTreeSet::new(),
             original_intersections: BTreeMap::new(),
             changed_routes: BTreeSet::new(),
+            changed_modal_filters: BTreeSet::new(),
         };
         edits.update_derived(map);
-        Ok(edits)
+        Ok((edits, relocations))
     }
 }
 
@@ -176,6 +250,7 @@ impl EditIntersection {
                 PermanentEditIntersection::TrafficSignal(raw_ts.clone())
             }
             EditIntersection::Closed => PermanentEditIntersection::Closed,
+            EditIntersection::Roundabout => PermanentEditIntersection::Roundabout,
         }
     }
 }
@@ -210,6 +285,7 @@ impl PermanentEditIntersection {
             }
             PermanentEditIntersection::TrafficSignal(ts) => Ok(EditIntersection::TrafficSignal(ts)),
             PermanentEditIntersection::Closed => Ok(EditIntersection::Closed),
+            PermanentEditIntersection::Roundabout => Ok(EditIntersection::Roundabout),
         }
     }
 }
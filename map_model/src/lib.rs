@@ -24,7 +24,7 @@
 #[macro_use]
 extern crate log;
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use serde::{Deserialize, Serialize};
 
@@ -33,7 +33,8 @@ use geom::{Bounds, Distance, GPSBounds, Polygon};
 
 pub use crate::city::City;
 pub use crate::edits::{
-    EditCmd, EditEffects, EditIntersection, EditRoad, MapEdits, PermanentMapEdits,
+    EditCmd, EditConflict, EditEffects, EditIntersection, EditRoad, EditTarget, EditsDiff,
+    MapEdits, PermanentEditCmd, PermanentEditIntersection, PermanentMapEdits,
 };
 pub use crate::map::{DrivingSide, MapConfig};
 pub use crate::objects::area::{Area, AreaID, AreaType};
@@ -46,18 +47,23 @@ pub use crate::objects::lane::{
     Lane, LaneID, LaneType, PARKING_LOT_SPOT_LENGTH, PARKING_SPOT_LENGTH,
 };
 pub use crate::objects::parking_lot::{ParkingLot, ParkingLotID};
-pub use crate::objects::road::{DirectedRoadID, Direction, Road, RoadID};
+pub use crate::objects::road::{DirectedRoadID, Direction, LevelOfTrafficStress, Road, RoadID};
 pub use crate::objects::stop_signs::{ControlStopSign, RoadWithStopSign};
+pub use crate::objects::time_rules::{TimeRules, TimeWindow};
 pub use crate::objects::traffic_signals::{ControlTrafficSignal, PhaseType, Stage};
 pub use crate::objects::turn::{
-    CompressedMovementID, Movement, MovementID, Turn, TurnID, TurnPriority, TurnType,
+    CompressedMovementID, CrossingType, Movement, MovementID, Turn, TurnID, TurnPriority, TurnType,
 };
 pub use crate::objects::zone::{AccessRestrictions, Zone};
 pub use crate::pathfind::uber_turns::{IntersectionCluster, UberTurn, UberTurnGroup};
 use crate::pathfind::Pathfinder;
-pub use crate::pathfind::{Path, PathConstraints, PathRequest, PathStep};
-pub use crate::traversable::{Position, Traversable};
+pub use crate::pathfind::{BikeRoutingProfile, Path, PathConstraints, PathRequest, PathStep};
+pub use crate::traversable::{
+    bike_speed_on_grade, max_biking_speed, max_walking_speed, walking_speed_on_grade, Position,
+    Traversable,
+};
 
+pub mod accessibility;
 mod city;
 pub mod connectivity;
 mod edits;
@@ -110,6 +116,8 @@ pub struct Map {
     pathfinder_dirty: bool,
     // Not the source of truth, just cached.
     zones: Vec<Zone>,
+    roads_with_time_rules: BTreeSet<RoadID>,
+    roads_banning_trucks: BTreeSet<RoadID>,
 
     city_name: String,
     name: String,
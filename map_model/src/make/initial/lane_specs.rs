@@ -45,7 +45,7 @@ pub fn get_lane_specs_ltr(tags: &Tags, driving_side: DrivingSide) -> Vec<LaneSpe
     if tags.is_any("railway", vec!["light_rail", "rail"]) {
         return vec![fwd(LaneType::LightRail)];
     }
-    if tags.is(osm::HIGHWAY, "footway") {
+    if tags.is_any(osm::HIGHWAY, vec!["footway", "path", "pedestrian", "steps"]) {
         return vec![fwd(LaneType::Sidewalk)];
     }
 
@@ -210,11 +210,25 @@ pub fn get_lane_specs_ltr(tags: &Tags, driving_side: DrivingSide) -> Vec<LaneSpe
             || tags.is_any(osm::PARKING_BOTH, has_parking.clone());
         let parking_lane_back = tags.is_any(osm::PARKING_LEFT, has_parking.clone())
             || tags.is_any(osm::PARKING_BOTH, has_parking);
+        // Curbside space reserved for loading is still tagged as a parking lane, just with a
+        // condition.
+        let loading_fwd = tags.is("parking:condition:right", "loading")
+            || tags.is("parking:condition:both", "loading");
+        let loading_back = tags.is("parking:condition:left", "loading")
+            || tags.is("parking:condition:both", "loading");
         if parking_lane_fwd {
-            fwd_side.push(fwd(LaneType::Parking));
+            fwd_side.push(fwd(if loading_fwd {
+                LaneType::LoadingZone
+            } else {
+                LaneType::Parking
+            }));
         }
         if parking_lane_back {
-            back_side.push(back(LaneType::Parking));
+            back_side.push(back(if loading_back {
+                LaneType::LoadingZone
+            } else {
+                LaneType::Parking
+            }));
         }
     }
 
@@ -243,7 +257,10 @@ pub fn get_lane_specs_ltr(tags: &Tags, driving_side: DrivingSide) -> Vec<LaneSpe
 
     if tags.is(osm::HIGHWAY, "service") || tags.is("narrow", "yes") {
         for spec in fwd_side.iter_mut().chain(back_side.iter_mut()) {
-            if spec.lt == LaneType::Driving || spec.lt == LaneType::Parking {
+            if spec.lt == LaneType::Driving
+                || spec.lt == LaneType::Parking
+                || spec.lt == LaneType::LoadingZone
+            {
                 spec.width = SERVICE_ROAD_LANE_THICKNESS;
             }
         }
@@ -316,6 +333,7 @@ mod tests {
             LaneType::SharedLeftTurn => "C",
             LaneType::Construction => "x",
             LaneType::LightRail => "l",
+            LaneType::LoadingZone => "L",
         }
     }
 
@@ -399,6 +417,18 @@ mod tests {
                 "sbbdps",
                 "vv^^^^",
             ),
+            (
+                "synthetic example of a loading zone",
+                vec![
+                    "lanes=2",
+                    "sidewalk=both",
+                    "parking:lane:both=parallel",
+                    "parking:condition:right=loading",
+                ],
+                DrivingSide::Right,
+                "spddLs",
+                "vvv^^^",
+            ),
             (
                 "https://www.openstreetmap.org/way/534549104",
                 vec![
@@ -10,7 +10,7 @@ use geom::{Bounds, Circle, Distance, PolyLine, Polygon, Pt2D};
 
 pub use self::geometry::intersection_polygon;
 use crate::raw::{OriginalRoad, RawMap, RawRoad};
-use crate::{osm, DrivingSide, IntersectionType};
+use crate::{osm, CrossingType, DrivingSide, IntersectionType};
 
 mod geometry;
 pub mod lane_specs;
@@ -57,6 +57,7 @@ pub struct Intersection {
     pub polygon: Polygon,
     pub roads: BTreeSet<OriginalRoad>,
     pub intersection_type: IntersectionType,
+    pub crossing: Option<CrossingType>,
     pub elevation: Distance,
 }
 
@@ -77,6 +78,7 @@ impl InitialMap {
                     polygon: Circle::new(Pt2D::new(0.0, 0.0), Distance::meters(1.0)).to_polygon(),
                     roads: BTreeSet::new(),
                     intersection_type: i.intersection_type,
+                    crossing: i.crossing,
                     elevation: i.elevation,
                 },
             );
@@ -3,6 +3,8 @@
 
 use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 
+use enumset::EnumSet;
+
 use abstutil::{Parallelism, Timer};
 use geom::{Bounds, Distance, FindClosest, HashablePt2D, Speed, EPSILON_DIST};
 
@@ -11,7 +13,7 @@ use crate::raw::{OriginalRoad, RawMap};
 use crate::{
     connectivity, osm, AccessRestrictions, Area, AreaID, ControlStopSign, ControlTrafficSignal,
     Direction, Intersection, IntersectionID, IntersectionType, Lane, LaneID, Map, MapEdits,
-    Movement, PathConstraints, Position, Road, RoadID, Zone,
+    Movement, PathConstraints, Position, Road, RoadID, TimeRules, Zone,
 };
 
 mod bridges;
@@ -51,6 +53,8 @@ impl Map {
             areas: Vec::new(),
             parking_lots: Vec::new(),
             zones: Vec::new(),
+            roads_with_time_rules: BTreeSet::new(),
+            roads_banning_trucks: BTreeSet::new(),
             boundary_polygon: raw.boundary_polygon.clone(),
             stop_signs: BTreeMap::new(),
             traffic_signals: BTreeMap::new(),
@@ -81,7 +85,9 @@ impl Map {
                 elevation: i.elevation,
                 // Might change later
                 intersection_type: i.intersection_type,
+                crossing: i.crossing,
                 orig_id: i.id,
+                modal_filter: EnumSet::new(),
                 incoming_lanes: Vec::new(),
                 outgoing_lanes: Vec::new(),
                 roads: i.roads.iter().map(|id| road_id_mapping[id]).collect(),
@@ -137,6 +143,7 @@ impl Map {
                     0
                 },
                 access_restrictions: AccessRestrictions::new(),
+                time_rules: TimeRules::new(),
             };
             road.speed_limit = road.speed_limit_from_osm();
             road.access_restrictions = road.access_restrictions_from_osm();
@@ -213,6 +220,7 @@ impl Map {
                     ));
                 }
             }
+            road.time_rules = road.time_rules_from_osm(map.config.driving_side);
             map.roads.push(road);
         }
 
@@ -241,6 +249,11 @@ impl Map {
                     i.intersection_type = IntersectionType::StopSign;
                 }
             }
+            if i.intersection_type == IntersectionType::StopSign
+                && i.roads.iter().any(|r| map.roads[r.0].is_roundabout())
+            {
+                i.intersection_type = IntersectionType::Roundabout;
+            }
         }
 
         let mut all_turns = Vec::new();
@@ -283,6 +296,18 @@ impl Map {
         );
 
         map.zones = Zone::make_all(&map);
+        map.roads_with_time_rules = map
+            .roads
+            .iter()
+            .filter(|r| !r.time_rules.is_empty())
+            .map(|r| r.id)
+            .collect();
+        map.roads_banning_trucks = map
+            .roads
+            .iter()
+            .filter(|r| r.access_restrictions.ban_trucks)
+            .map(|r| r.id)
+            .collect();
 
         for (idx, a) in raw.areas.iter().enumerate() {
             map.areas.push(Area {
@@ -316,7 +341,9 @@ impl Map {
                         stop_signs.insert(i.id, ControlStopSign::new(&map, i.id));
                     }
                 },
-                IntersectionType::Border | IntersectionType::Construction => {}
+                IntersectionType::Border
+                | IntersectionType::Construction
+                | IntersectionType::Roundabout => {}
             };
         }
         map.stop_signs = stop_signs;
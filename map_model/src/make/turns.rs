@@ -151,6 +151,11 @@ fn does_turn_pass_restrictions(turn: &Turn, i: &Intersection, map: &Map) -> bool
     let src = map.get_parent(turn.id.src);
     let dst = map.get_l(turn.id.dst).parent;
 
+    // At roundabouts, vehicles have to go around the ring, not cut straight across it
+    if i.is_roundabout() && !src.is_roundabout() && !map.get_r(dst).is_roundabout() {
+        return false;
+    }
+
     for (restriction, to) in &src.turn_restrictions {
         // The restriction only applies to one direction of the road.
         if !i.roads.contains(to) {
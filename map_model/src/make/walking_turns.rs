@@ -10,6 +10,15 @@ use crate::{
 
 /// Generate Crosswalk and SharedSidewalkCorner (places where two sidewalks directly meet) turns
 pub fn make_walking_turns(map: &Map, i: &Intersection, timer: &mut Timer) -> Vec<Turn> {
+    let mut result = make_sidewalk_turns(map, i, timer);
+    for turn in make_footway_crossings(map, i) {
+        result.retain(|t| t.id != turn.id);
+        result.push(turn);
+    }
+    result
+}
+
+fn make_sidewalk_turns(map: &Map, i: &Intersection, timer: &mut Timer) -> Vec<Turn> {
     let driving_side = map.config.driving_side;
     let all_roads = map.all_roads();
     let lanes = map.all_lanes();
@@ -253,6 +262,22 @@ pub fn _make_walking_turns_v2(map: &Map, i: &Intersection, timer: &mut Timer) ->
     result
 }
 
+/// A footway tagged as a crossing gets split where it meets the road it crosses. Connect the two
+/// halves straight across the road.
+fn make_footway_crossings(map: &Map, i: &Intersection) -> Vec<Turn> {
+    let halves: Vec<&Lane> = i
+        .roads
+        .iter()
+        .map(|r| map.get_r(*r))
+        .filter(|r| r.crossing_type().is_some())
+        .map(|r| map.get_l(r.lanes_ltr()[0].0))
+        .collect();
+    if halves.len() != 2 {
+        return Vec::new();
+    }
+    make_crosswalks(i.id, halves[0], halves[1]).unwrap_or_else(Vec::new)
+}
+
 fn make_crosswalks(i: IntersectionID, l1: &Lane, l2: &Lane) -> Option<Vec<Turn>> {
     let l1_pt = l1.endpoint(i);
     let l2_pt = l2.endpoint(i);
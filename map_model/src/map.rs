@@ -10,10 +10,11 @@ use geom::{Bounds, Distance, GPSBounds, Polygon, Pt2D, Ring, Time};
 
 use crate::raw::{OriginalRoad, RawMap};
 use crate::{
-    osm, Area, AreaID, Building, BuildingID, BuildingType, BusRoute, BusRouteID, BusStop,
-    BusStopID, ControlStopSign, ControlTrafficSignal, Intersection, IntersectionID, Lane, LaneID,
-    LaneType, Map, MapEdits, MovementID, OffstreetParking, ParkingLot, ParkingLotID, Path,
-    PathConstraints, PathRequest, Pathfinder, Position, Road, RoadID, Turn, TurnID, TurnType, Zone,
+    osm, Area, AreaID, BikeRoutingProfile, Building, BuildingID, BuildingType, BusRoute,
+    BusRouteID, BusStop, BusStopID, ControlStopSign, ControlTrafficSignal, Intersection,
+    IntersectionID, Lane, LaneID, LaneType, Map, MapEdits, MovementID, OffstreetParking,
+    ParkingLot, ParkingLotID, Path, PathConstraints, PathRequest, Pathfinder, Position, Road,
+    RoadID, Turn, TurnID, TurnType, Zone,
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -144,6 +145,8 @@ impl Map {
             areas: Vec::new(),
             parking_lots: Vec::new(),
             zones: Vec::new(),
+            roads_with_time_rules: BTreeSet::new(),
+            roads_banning_trucks: BTreeSet::new(),
             boundary_polygon: Ring::must_new(vec![
                 Pt2D::new(0.0, 0.0),
                 Pt2D::new(1.0, 0.0),
@@ -199,6 +202,16 @@ impl Map {
         &self.zones
     }
 
+    /// Roads with any time-of-day rules. Usually empty.
+    pub fn all_roads_with_time_rules(&self) -> &BTreeSet<RoadID> {
+        &self.roads_with_time_rules
+    }
+
+    /// Roads that trucks can't use at all. Usually empty.
+    pub fn all_roads_banning_trucks(&self) -> &BTreeSet<RoadID> {
+        &self.roads_banning_trucks
+    }
+
     pub fn maybe_get_r(&self, id: RoadID) -> Option<&Road> {
         self.roads.get(id.0)
     }
@@ -361,6 +374,7 @@ impl Map {
             .collect()
     }
 
+    /// Modal filters are respected here.
     pub fn get_turns_for(&self, from: LaneID, constraints: PathConstraints) -> Vec<&Turn> {
         let mut turns: Vec<&Turn> = self
             .get_next_turns_and_lanes(from, self.get_l(from).dst_i)
@@ -377,6 +391,7 @@ impl Map {
                     .map(|(t, _)| t),
             );
         }
+        turns.retain(|t| !self.get_i(t.id.parent).modal_filter.contains(constraints));
         turns
     }
 
@@ -545,6 +560,11 @@ impl Map {
         assert!(!self.pathfinder_dirty);
         self.pathfinder.pathfind_avoiding_lanes(req, avoid, self)
     }
+    /// Like `pathfind`, but cyclists may trade some time for less stressful roads.
+    pub fn pathfind_bike(&self, req: PathRequest, profile: BikeRoutingProfile) -> Option<Path> {
+        assert!(!self.pathfinder_dirty);
+        self.pathfinder.pathfind_bike(req, profile, self)
+    }
 
     pub fn should_use_transit(
         &self,
@@ -28,6 +28,8 @@ pub enum AreaType {
     Water,
     PedestrianIsland,
     Island,
+    /// A square or plaza where people can walk anywhere, mapped as an area instead of a line
+    PedestrianPlaza,
 }
 
 /// Areas are just used for drawing.
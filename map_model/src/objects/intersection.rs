@@ -1,12 +1,16 @@
 use std::collections::BTreeSet;
 use std::fmt;
 
+use enumset::EnumSet;
 use serde::{Deserialize, Serialize};
 
 use abstutil::{deserialize_usize, serialize_usize};
 use geom::{Distance, Polygon};
 
-use crate::{osm, DirectedRoadID, LaneID, Map, PathConstraints, Road, RoadID, TurnID};
+use crate::{
+    osm, CrossingType, DirectedRoadID, LaneID, Map, PathConstraints, Road, RoadID, TurnID,
+    TurnPriority,
+};
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct IntersectionID(
@@ -29,6 +33,8 @@ pub enum IntersectionType {
     TrafficSignal,
     Border,
     Construction,
+    /// Traffic entering the ring yields to traffic already circulating
+    Roundabout,
 }
 
 /// An intersection connects roads. Most have >2 roads and are controlled by stop signs or traffic
@@ -43,7 +49,12 @@ pub struct Intersection {
     pub elevation: Distance,
 
     pub intersection_type: IntersectionType,
+    /// Set when this intersection only exists to split a road at a mid-block crossing
+    pub crossing: Option<CrossingType>,
     pub orig_id: osm::NodeID,
+    /// Bollards, planters, or other barriers stop these modes from passing through. Usually
+    /// empty.
+    pub modal_filter: EnumSet<PathConstraints>,
 
     /// Note that a lane may belong to both incoming_lanes and outgoing_lanes.
     // TODO narrow down when and why. is it just sidewalks in weird cases?
@@ -79,10 +90,30 @@ impl Intersection {
         self.intersection_type == IntersectionType::TrafficSignal
     }
 
+    pub fn is_roundabout(&self) -> bool {
+        self.intersection_type == IntersectionType::Roundabout
+    }
+
+    /// Roundabouts don't need any stop signs or signals; traffic already circulating has
+    /// priority, and everybody entering yields. Crosswalks work like they do at stop signs.
+    pub fn roundabout_priority(&self, turn: TurnID, map: &Map) -> TurnPriority {
+        assert!(self.is_roundabout());
+        let t = map.get_t(turn);
+        if t.between_sidewalks() || map.get_parent(turn.src).is_roundabout() {
+            TurnPriority::Protected
+        } else {
+            TurnPriority::Yield
+        }
+    }
+
     pub fn is_light_rail(&self, map: &Map) -> bool {
         self.roads.iter().all(|r| map.get_r(*r).is_light_rail())
     }
 
+    pub fn has_modal_filter(&self) -> bool {
+        !self.modal_filter.is_empty()
+    }
+
     pub fn is_private(&self, map: &Map) -> bool {
         self.roads.iter().all(|r| map.get_r(*r).is_private())
     }
@@ -44,6 +44,8 @@ pub enum LaneType {
     SharedLeftTurn,
     Construction,
     LightRail,
+    /// Curbside space like a parking lane, but reserved for delivery vehicles to briefly stop.
+    LoadingZone,
 }
 
 impl LaneType {
@@ -58,6 +60,7 @@ impl LaneType {
             LaneType::SharedLeftTurn => false,
             LaneType::Construction => false,
             LaneType::LightRail => true,
+            LaneType::LoadingZone => false,
         }
     }
 
@@ -72,6 +75,7 @@ impl LaneType {
             LaneType::SharedLeftTurn => false,
             LaneType::Construction => false,
             LaneType::LightRail => true,
+            LaneType::LoadingZone => false,
         }
     }
 
@@ -86,6 +90,7 @@ impl LaneType {
             LaneType::SharedLeftTurn => "a shared left-turn lane",
             LaneType::Construction => "a lane that's closed for construction",
             LaneType::LightRail => "a light rail track",
+            LaneType::LoadingZone => "a loading zone for deliveries",
         }
     }
 
@@ -100,6 +105,7 @@ impl LaneType {
             LaneType::SharedLeftTurn => "left-turn lane",
             LaneType::Construction => "construction",
             LaneType::LightRail => "light rail track",
+            LaneType::LoadingZone => "loading zone",
         }
     }
 }
@@ -171,6 +177,17 @@ impl Lane {
         self.lane_center_pts.length()
     }
 
+    /// Returns [-1.0, 1.0]. 0 is flat, positive is uphill going from src_i to dst_i, negative is
+    /// downhill.
+    pub fn percent_grade(&self, map: &Map) -> f64 {
+        let grade = map.get_r(self.parent).percent_grade(map);
+        if self.src_i == map.get_r(self.parent).src_i {
+            grade
+        } else {
+            -grade
+        }
+    }
+
     pub fn intersections(&self) -> Vec<IntersectionID> {
         // TODO I think we're assuming there are no loop lanes
         vec![self.src_i, self.dst_i]
@@ -178,8 +195,9 @@ impl Lane {
 
     // TODO different types for each lane type might be reasonable
 
+    /// Loading zones are divided into spots just like parking lanes.
     pub fn number_parking_spots(&self) -> usize {
-        assert_eq!(self.lane_type, LaneType::Parking);
+        assert!(self.lane_type == LaneType::Parking || self.lane_type == LaneType::LoadingZone);
         // No spots next to intersections
         let spots = (self.length() / PARKING_SPOT_LENGTH).floor() - 2.0;
         if spots >= 1.0 {
@@ -217,6 +235,10 @@ impl Lane {
         self.lane_type == LaneType::Parking
     }
 
+    pub fn is_loading_zone(&self) -> bool {
+        self.lane_type == LaneType::LoadingZone
+    }
+
     pub fn is_light_rail(&self) -> bool {
         self.lane_type == LaneType::LightRail
     }
@@ -6,6 +6,7 @@ pub mod lane;
 pub mod parking_lot;
 pub mod road;
 pub mod stop_signs;
+pub mod time_rules;
 pub mod traffic_signals;
 pub mod turn;
 pub mod zone;
@@ -4,12 +4,12 @@ use enumset::EnumSet;
 use serde::{Deserialize, Serialize};
 
 use abstutil::{deserialize_usize, serialize_usize, Tags};
-use geom::{Distance, PolyLine, Polygon, Speed};
+use geom::{Distance, PolyLine, Polygon, Speed, Time};
 
 use crate::raw::{OriginalRoad, RestrictionType};
 use crate::{
-    osm, AccessRestrictions, BusStopID, IntersectionID, Lane, LaneID, LaneType, Map,
-    PathConstraints, Zone,
+    osm, AccessRestrictions, BusStopID, CrossingType, DrivingSide, IntersectionID, Lane, LaneID,
+    LaneType, Map, PathConstraints, TimeRules, Zone,
 };
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -89,6 +89,44 @@ impl DirectedRoadID {
     }
 }
 
+/// How stressful it is to bike along a road, roughly following the Level of Traffic Stress
+/// classification from Mekuria, Furth, and Nixon (2012). LTS 1 is comfortable for children; LTS 4
+/// is only tolerated by the most confident cyclists.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum LevelOfTrafficStress {
+    LTS1,
+    LTS2,
+    LTS3,
+    LTS4,
+}
+
+impl LevelOfTrafficStress {
+    pub fn all() -> Vec<LevelOfTrafficStress> {
+        vec![
+            LevelOfTrafficStress::LTS1,
+            LevelOfTrafficStress::LTS2,
+            LevelOfTrafficStress::LTS3,
+            LevelOfTrafficStress::LTS4,
+        ]
+    }
+
+    /// From 1 to 4
+    pub fn level(self) -> usize {
+        match self {
+            LevelOfTrafficStress::LTS1 => 1,
+            LevelOfTrafficStress::LTS2 => 2,
+            LevelOfTrafficStress::LTS3 => 3,
+            LevelOfTrafficStress::LTS4 => 4,
+        }
+    }
+}
+
+impl fmt::Display for LevelOfTrafficStress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "LTS {}", self.level())
+    }
+}
+
 /// A Road represents a segment between exactly two Intersections. It contains Lanes as children.
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Road {
@@ -101,6 +139,8 @@ pub struct Road {
     pub orig_id: OriginalRoad,
     pub speed_limit: Speed,
     pub access_restrictions: AccessRestrictions,
+    /// Overrides for lane types and access restrictions during parts of the day
+    pub time_rules: TimeRules,
     pub zorder: isize,
 
     /// Invariant: A road must contain at least one child
@@ -324,6 +364,17 @@ impl Road {
         grade
     }
 
+    /// Classifies the stress of biking along this road, using the lanes, speed limit, and OSM
+    /// tags. Roads without any general traffic, like cycleways, are always LTS 1.
+    pub fn bike_lts(&self) -> LevelOfTrafficStress {
+        let lanes: Vec<(Direction, LaneType)> = self
+            .lanes_ltr
+            .iter()
+            .map(|(_, dir, lt)| (*dir, *lt))
+            .collect();
+        bike_lts(&lanes, self.speed_limit, self.get_rank(), &self.osm_tags)
+    }
+
     pub fn is_light_rail(&self) -> bool {
         self.lanes_ltr().len() == 1 && self.lanes_ltr()[0].2 == LaneType::LightRail
     }
@@ -332,10 +383,23 @@ impl Road {
         self.lanes_ltr().len() == 1 && self.lanes_ltr()[0].2 == LaneType::Sidewalk
     }
 
+    /// For footways tagged as crossings, how the crossing is marked
+    pub fn crossing_type(&self) -> Option<CrossingType> {
+        if !self.is_footway() || !self.osm_tags.is("footway", "crossing") {
+            return None;
+        }
+        Some(CrossingType::from_osm_tags(&self.osm_tags))
+    }
+
     pub fn is_service(&self) -> bool {
         self.osm_tags.is(osm::HIGHWAY, "service")
     }
 
+    /// Is this road part of the ring of a roundabout?
+    pub fn is_roundabout(&self) -> bool {
+        self.osm_tags.is("junction", "roundabout")
+    }
+
     pub fn common_endpt(&self, other: &Road) -> IntersectionID {
         if self.src_i == other.src_i || self.src_i == other.dst_i {
             self.src_i
@@ -347,7 +411,7 @@ impl Road {
     }
 
     pub fn is_private(&self) -> bool {
-        self.access_restrictions != AccessRestrictions::new()
+        self.access_restrictions.is_zone()
     }
 
     pub(crate) fn access_restrictions_from_osm(&self) -> AccessRestrictions {
@@ -362,12 +426,43 @@ impl Road {
         } else {
             EnumSet::all()
         };
+        let mut banned = EnumSet::new();
+        if self.osm_tags.is("motor_vehicle", "no") {
+            banned |= PathConstraints::Car;
+            if !self.osm_tags.is("psv", "yes") && !self.osm_tags.is("bus", "yes") {
+                banned |= PathConstraints::Bus;
+            }
+        }
         AccessRestrictions {
             allow_through_traffic,
             cap_vehicles_per_hour: None,
+            banned,
+            ban_trucks: self.osm_tags.is("hgv", "no"),
         }
     }
 
+    pub(crate) fn time_rules_from_osm(&self, driving_side: DrivingSide) -> TimeRules {
+        let lanes_ltr = self
+            .lanes_ltr
+            .iter()
+            .map(|(_, dir, lt)| (*lt, *dir))
+            .collect();
+        TimeRules::from_osm(&self.osm_tags, &lanes_ltr, driving_side)
+    }
+
+    /// What type does a lane act like at some time of day? This may differ from `lane_type`
+    /// because of `time_rules`.
+    pub fn lane_type_at(&self, l: LaneID, time: Time) -> LaneType {
+        let idx = self.offset(l);
+        self.time_rules
+            .lane_type_at(idx, self.lanes_ltr[idx].2, time)
+    }
+
+    /// Which access restrictions apply at some time of day?
+    pub fn access_restrictions_at(&self, time: Time) -> &AccessRestrictions {
+        self.time_rules.access_at(&self.access_restrictions, time)
+    }
+
     pub fn get_zone<'a>(&self, map: &'a Map) -> Option<&'a Zone> {
         if !self.is_private() {
             return None;
@@ -450,3 +545,191 @@ impl Road {
         }
     }
 }
+
+/// The classification behind `Road::bike_lts`. Lanes are listed left-to-right.
+fn bike_lts(
+    lanes: &[(Direction, LaneType)],
+    speed_limit: Speed,
+    rank: osm::RoadRank,
+    tags: &Tags,
+) -> LevelOfTrafficStress {
+    let mph = Speed::miles_per_hour;
+    let traffic_lanes_per_dir = |dir| {
+        lanes
+            .iter()
+            .filter(|(d, lt)| *d == dir && (*lt == LaneType::Driving || *lt == LaneType::Bus))
+            .count()
+    };
+    let lanes_per_dir =
+        traffic_lanes_per_dir(Direction::Fwd).max(traffic_lanes_per_dir(Direction::Back));
+    if lanes_per_dir == 0
+        || tags.is_any(osm::HIGHWAY, vec!["cycleway", "living_street"])
+        || tags.is_any("cycleway", vec!["track", "separate"])
+    {
+        return LevelOfTrafficStress::LTS1;
+    }
+
+    if lanes.iter().any(|(_, lt)| *lt == LaneType::Biking) {
+        // Riding next to parked cars risks getting doored
+        let next_to_parking = lanes.windows(2).any(|pair| {
+            (pair[0].1 == LaneType::Biking && pair[1].1 == LaneType::Parking)
+                || (pair[0].1 == LaneType::Parking && pair[1].1 == LaneType::Biking)
+        });
+        if speed_limit >= mph(45.0) {
+            LevelOfTrafficStress::LTS4
+        } else if lanes_per_dir >= 3 || speed_limit >= mph(40.0) {
+            LevelOfTrafficStress::LTS3
+        } else if lanes_per_dir == 2 || speed_limit > mph(30.0) {
+            if next_to_parking {
+                LevelOfTrafficStress::LTS3
+            } else {
+                LevelOfTrafficStress::LTS2
+            }
+        } else if next_to_parking {
+            LevelOfTrafficStress::LTS2
+        } else {
+            LevelOfTrafficStress::LTS1
+        }
+    } else {
+        // Mixing with traffic
+        let local = rank == osm::RoadRank::Local;
+        if lanes_per_dir >= 2 {
+            if speed_limit <= mph(25.0) {
+                LevelOfTrafficStress::LTS3
+            } else {
+                LevelOfTrafficStress::LTS4
+            }
+        } else if speed_limit <= mph(25.0) {
+            if local {
+                LevelOfTrafficStress::LTS1
+            } else {
+                LevelOfTrafficStress::LTS2
+            }
+        } else if speed_limit <= mph(30.0) {
+            if local {
+                LevelOfTrafficStress::LTS2
+            } else {
+                LevelOfTrafficStress::LTS3
+            }
+        } else if speed_limit <= mph(35.0) {
+            LevelOfTrafficStress::LTS3
+        } else {
+            LevelOfTrafficStress::LTS4
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bike_stress() {
+        use Direction::{Back, Fwd};
+        use LaneType::{Biking, Driving, Parking, Sidewalk};
+        let mph = Speed::miles_per_hour;
+        let residential = Tags::from_pairs(vec![("highway", "residential")]);
+        let arterial = Tags::from_pairs(vec![("highway", "primary")]);
+        let classify = |lanes: Vec<(Direction, LaneType)>, speed: f64, tags: &Tags| {
+            let rank = osm::RoadRank::from_highway(tags.get(osm::HIGHWAY).unwrap());
+            bike_lts(&lanes, mph(speed), rank, tags)
+        };
+
+        // No general traffic at all
+        assert_eq!(
+            classify(
+                vec![(Fwd, Biking), (Back, Biking)],
+                25.0,
+                &Tags::from_pairs(vec![("highway", "cycleway")])
+            ),
+            LevelOfTrafficStress::LTS1
+        );
+        // A quiet residential street
+        assert_eq!(
+            classify(
+                vec![
+                    (Back, Sidewalk),
+                    (Back, Driving),
+                    (Fwd, Driving),
+                    (Fwd, Sidewalk)
+                ],
+                25.0,
+                &residential
+            ),
+            LevelOfTrafficStress::LTS1
+        );
+        // The same street, but a busier kind of road
+        assert_eq!(
+            classify(
+                vec![
+                    (Back, Sidewalk),
+                    (Back, Driving),
+                    (Fwd, Driving),
+                    (Fwd, Sidewalk)
+                ],
+                25.0,
+                &arterial
+            ),
+            LevelOfTrafficStress::LTS2
+        );
+        // Mixing with two lanes of fast traffic
+        assert_eq!(
+            classify(
+                vec![
+                    (Back, Driving),
+                    (Back, Driving),
+                    (Fwd, Driving),
+                    (Fwd, Driving)
+                ],
+                35.0,
+                &arterial
+            ),
+            LevelOfTrafficStress::LTS4
+        );
+        // A bike lane helps, unless it's in the door zone
+        assert_eq!(
+            classify(
+                vec![(Back, Driving), (Fwd, Driving), (Fwd, Biking)],
+                25.0,
+                &arterial
+            ),
+            LevelOfTrafficStress::LTS1
+        );
+        assert_eq!(
+            classify(
+                vec![
+                    (Back, Driving),
+                    (Fwd, Driving),
+                    (Fwd, Biking),
+                    (Fwd, Parking)
+                ],
+                25.0,
+                &arterial
+            ),
+            LevelOfTrafficStress::LTS2
+        );
+        // A painted lane on a very fast road doesn't help much
+        assert_eq!(
+            classify(
+                vec![(Back, Driving), (Fwd, Driving), (Fwd, Biking)],
+                45.0,
+                &arterial
+            ),
+            LevelOfTrafficStress::LTS4
+        );
+        // Separated cycletracks are always calm
+        assert_eq!(
+            classify(
+                vec![
+                    (Back, Driving),
+                    (Back, Driving),
+                    (Fwd, Driving),
+                    (Fwd, Driving)
+                ],
+                45.0,
+                &Tags::from_pairs(vec![("highway", "primary"), ("cycleway", "track")])
+            ),
+            LevelOfTrafficStress::LTS1
+        );
+    }
+}
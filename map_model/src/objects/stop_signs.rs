@@ -5,8 +5,8 @@ use serde::{Deserialize, Serialize};
 use abstutil::{deserialize_btreemap, serialize_btreemap};
 
 use crate::{
-    osm, Direction, DrivingSide, IntersectionID, LaneID, Map, RoadID, TurnID, TurnPriority,
-    TurnType,
+    osm, CrossingType, Direction, DrivingSide, IntersectionID, LaneID, Map, RoadID, TurnID,
+    TurnPriority, TurnType,
 };
 
 // TODO These are old notes, they don't reflect current reality. But some of the ideas here should
@@ -124,7 +124,13 @@ impl ControlStopSign {
         match map.get_t(turn).turn_type {
             TurnType::SharedSidewalkCorner => TurnPriority::Protected,
             // TODO This actually feels like a policy bit that should be flippable.
-            TurnType::Crosswalk => TurnPriority::Protected,
+            TurnType::Crosswalk => {
+                if map.get_t(turn).crossing_type(map) == Some(CrossingType::Unmarked) {
+                    TurnPriority::Yield
+                } else {
+                    TurnPriority::Protected
+                }
+            }
             _ => {
                 if self.roads[&map.get_l(turn.src).parent].must_stop {
                     TurnPriority::Yield
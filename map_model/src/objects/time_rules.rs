@@ -0,0 +1,418 @@
+//! Some lanes and access rules only apply at certain times of day, like peak-hour bus lanes,
+//! parking lanes that become travel lanes at rush hour, or school streets closed to cars during
+//! drop-off. The lane types in `Road::lanes_ltr` describe the road the rest of the time, and are
+//! what turn generation and the contraction hierarchies use. The simulation consults these rules
+//! as time passes, routing vehicles around lanes that are closed to them.
+//!
+//! Since turns only come from `lanes_ltr`, time windows can only take capacity away, never add
+//! it. A parking lane that acts like a driving lane at rush hour, or a "bus 7-9am, parking
+//! otherwise" lane stored as parking, has no turns onto or off of it, so nothing ever travels in
+//! it. The only effect of the window is that nobody can park there.
+
+use std::fmt;
+
+use enumset::EnumSet;
+use serde::{Deserialize, Serialize};
+
+use abstutil::Tags;
+use geom::{Duration, Time};
+
+use crate::{AccessRestrictions, Direction, DrivingSide, LaneType, PathConstraints};
+
+/// A span of time repeating every day. Simulations only model one typical weekday, so OSM
+/// conditions that don't apply Monday through Friday are skipped.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    /// Since midnight
+    pub start: Duration,
+    /// Since midnight. If this is before `start`, the window wraps past midnight.
+    pub end: Duration,
+}
+
+impl TimeWindow {
+    pub fn contains(&self, time: Time) -> bool {
+        let t = time_of_day(time);
+        if self.start <= self.end {
+            self.start <= t && t < self.end
+        } else {
+            t >= self.start || t < self.end
+        }
+    }
+
+    /// Parses something like "07:00-09:30".
+    pub fn parse(x: &str) -> Option<TimeWindow> {
+        let parts: Vec<&str> = x.trim().split('-').collect();
+        if parts.len() != 2 {
+            return None;
+        }
+        Some(TimeWindow {
+            start: parse_hhmm(parts[0])?,
+            end: parse_hhmm(parts[1])?,
+        })
+    }
+
+    /// The first time after `now` that this window starts or ends.
+    fn next_boundary(&self, now: Time) -> Time {
+        let midnight = now - time_of_day(now);
+        let mut next = None;
+        for boundary in vec![self.start, self.end] {
+            let mut t = midnight + boundary;
+            if t <= now {
+                t += Duration::hours(24);
+            }
+            next = Some(next.map(|x: Time| x.min(t)).unwrap_or(t));
+        }
+        next.unwrap()
+    }
+}
+
+impl fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (h1, m1, _, _) = (Time::START_OF_DAY + self.start).get_parts();
+        let (h2, m2, _, _) = (Time::START_OF_DAY + self.end).get_parts();
+        write!(f, "{:02}:{:02}-{:02}:{:02}", h1, m1, h2, m2)
+    }
+}
+
+fn time_of_day(time: Time) -> Duration {
+    Duration::seconds((time - Time::START_OF_DAY).inner_seconds() % (24.0 * 3600.0))
+}
+
+fn parse_hhmm(x: &str) -> Option<Duration> {
+    let parts: Vec<&str> = x.trim().split(':').collect();
+    if parts.len() != 2 {
+        return None;
+    }
+    let hours = parts[0].parse::<usize>().ok()?;
+    let minutes = parts[1].parse::<usize>().ok()?;
+    if hours > 24 || minutes >= 60 || (hours == 24 && minutes > 0) {
+        return None;
+    }
+    Some(Duration::hours(hours) + Duration::minutes(minutes))
+}
+
+/// Rules for one road that override its usual lane types and access restrictions during parts of
+/// the day.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TimeRules {
+    /// During these windows, the lane at this index in `lanes_ltr` acts like a different type.
+    /// This can close a lane to some vehicles, but can't open it to new ones; see the module docs.
+    pub lane_types: Vec<(usize, LaneType, Vec<TimeWindow>)>,
+    /// During these windows, these restrictions replace the road's usual `access_restrictions`.
+    pub access: Vec<(AccessRestrictions, Vec<TimeWindow>)>,
+}
+
+impl TimeRules {
+    pub fn new() -> TimeRules {
+        TimeRules {
+            lane_types: Vec::new(),
+            access: Vec::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lane_types.is_empty() && self.access.is_empty()
+    }
+
+    /// What type does the lane at this index act like at some time?
+    pub fn lane_type_at(&self, idx: usize, usual: LaneType, time: Time) -> LaneType {
+        for (i, lt, windows) in &self.lane_types {
+            if *i == idx && windows.iter().any(|w| w.contains(time)) {
+                return *lt;
+            }
+        }
+        usual
+    }
+
+    /// Which access restrictions apply at some time?
+    pub fn access_at<'a>(
+        &'a self,
+        usual: &'a AccessRestrictions,
+        time: Time,
+    ) -> &'a AccessRestrictions {
+        for (access, windows) in &self.access {
+            if windows.iter().any(|w| w.contains(time)) {
+                return access;
+            }
+        }
+        usual
+    }
+
+    /// The first time after `now` that any of these rules starts or stops applying.
+    pub fn next_change(&self, now: Time) -> Option<Time> {
+        self.lane_types
+            .iter()
+            .flat_map(|(_, _, windows)| windows.iter())
+            .chain(self.access.iter().flat_map(|(_, windows)| windows.iter()))
+            .map(|w| w.next_boundary(now))
+            .min()
+    }
+
+    /// Interprets `parking:lane:*:conditional`, `access:conditional`, and
+    /// `motor_vehicle:conditional` tags.
+    pub(crate) fn from_osm(
+        tags: &Tags,
+        lanes_ltr: &Vec<(LaneType, Direction)>,
+        driving_side: DrivingSide,
+    ) -> TimeRules {
+        let mut rules = TimeRules::new();
+
+        // The right side of the way has the forward lanes, unless vehicles drive on the left.
+        let (right, left) = match driving_side {
+            DrivingSide::Right => (Direction::Fwd, Direction::Back),
+            DrivingSide::Left => (Direction::Back, Direction::Fwd),
+        };
+        // Peak-hour parking restrictions usually exist to free up another travel lane. Nothing
+        // will travel in it (see the module docs), but at least nobody parks there.
+        for (key, dirs) in vec![
+            ("parking:lane:right:conditional", vec![right]),
+            ("parking:lane:left:conditional", vec![left]),
+            (
+                "parking:lane:both:conditional",
+                vec![Direction::Fwd, Direction::Back],
+            ),
+        ] {
+            if let Some(value) = tags.get(key) {
+                for (condition, windows) in parse_conditional(value) {
+                    if !vec!["no_parking", "no_stopping", "no"].contains(&condition.as_str()) {
+                        continue;
+                    }
+                    for (idx, (lt, dir)) in lanes_ltr.iter().enumerate() {
+                        if *lt == LaneType::Parking && dirs.contains(dir) {
+                            rules
+                                .lane_types
+                                .push((idx, LaneType::Driving, windows.clone()));
+                        }
+                    }
+                }
+            }
+        }
+
+        for key in vec!["access:conditional", "motor_vehicle:conditional"] {
+            if let Some(value) = tags.get(key) {
+                for (condition, windows) in parse_conditional(value) {
+                    if condition != "no" && condition != "private" {
+                        continue;
+                    }
+                    let mut allow_through_traffic =
+                        PathConstraints::Pedestrian | PathConstraints::Bike;
+                    if key == "access:conditional" && tags.is("psv", "yes") {
+                        allow_through_traffic |= PathConstraints::Bus;
+                    }
+                    if key == "motor_vehicle:conditional" && tags.is("bus", "yes") {
+                        allow_through_traffic |= PathConstraints::Bus;
+                    }
+                    rules.access.push((
+                        AccessRestrictions {
+                            allow_through_traffic,
+                            cap_vehicles_per_hour: None,
+                            banned: EnumSet::new(),
+                            ban_trucks: false,
+                        },
+                        windows,
+                    ));
+                }
+            }
+        }
+
+        rules
+    }
+}
+
+impl std::default::Default for TimeRules {
+    fn default() -> TimeRules {
+        TimeRules::new()
+    }
+}
+
+/// Parses the OSM conditional restriction syntax
+/// (<https://wiki.openstreetmap.org/wiki/Conditional_restrictions>), like
+/// "no @ (Mo-Fr 07:00-09:00,16:00-18:00); yes @ (Sa 10:00-12:00)". Conditions without any time
+/// windows, like "delivery @ (weight < 7.5)", are skipped, and so is any condition with a part
+/// that can't be understood. Since only a typical weekday is simulated, rules that don't apply
+/// every day Monday through Friday are dropped.
+fn parse_conditional(value: &str) -> Vec<(String, Vec<TimeWindow>)> {
+    let mut results = Vec::new();
+    for part in split_outside_parens(value) {
+        let pieces: Vec<&str> = part.splitn(2, '@').collect();
+        if pieces.len() != 2 {
+            continue;
+        }
+        let condition = pieces[1]
+            .trim()
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .replace(", ", ",");
+        // The condition may have several rules, like "Mo-Fr 07:00-09:00; Sa 10:00-12:00"
+        let mut windows = Vec::new();
+        let mut understood = true;
+        for rule in condition.split(';') {
+            match parse_rule(rule) {
+                Some((true, w)) => {
+                    windows.extend(w);
+                }
+                Some((false, _)) => {}
+                None => {
+                    understood = false;
+                    break;
+                }
+            }
+        }
+        if understood && !windows.is_empty() {
+            results.push((pieces[0].trim().to_string(), windows));
+        }
+    }
+    results
+}
+
+/// Splits on semicolons, except for ones inside parentheses.
+fn split_outside_parens(value: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in value.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ';' if depth == 0 => {
+                parts.push(&value[start..idx]);
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&value[start..]);
+    parts
+}
+
+/// Parses one rule like "Mo-Fr 07:00-09:00,16:00-18:00" or "22:00-06:00". Returns whether the
+/// rule applies on a typical weekday, along with its time windows.
+fn parse_rule(rule: &str) -> Option<(bool, Vec<TimeWindow>)> {
+    let tokens: Vec<&str> = rule.split_whitespace().collect();
+    let (weekday, times) = match tokens.len() {
+        1 => (true, tokens[0]),
+        2 => (covers_weekdays(tokens[0])?, tokens[1]),
+        _ => {
+            return None;
+        }
+    };
+    let windows = times
+        .split(',')
+        .map(TimeWindow::parse)
+        .collect::<Option<Vec<_>>>()?;
+    Some((weekday, windows))
+}
+
+/// Parses days like "Mo-Fr", "Sa,Su", or "Mo-We,Fr", and checks if every weekday is included.
+/// Public and school holidays aren't simulated, so they don't count.
+fn covers_weekdays(x: &str) -> Option<bool> {
+    let days = ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"];
+    let day = |d: &str| days.iter().position(|x| *x == d);
+    let mut included = [false; 7];
+    for part in x.split(',') {
+        if part == "PH" || part == "SH" {
+            continue;
+        }
+        let range: Vec<&str> = part.split('-').collect();
+        match range.len() {
+            1 => {
+                included[day(range[0])?] = true;
+            }
+            2 => {
+                let (mut d, end) = (day(range[0])?, day(range[1])?);
+                // Ranges like Fr-Mo wrap around the week
+                loop {
+                    included[d] = true;
+                    if d == end {
+                        break;
+                    }
+                    d = (d + 1) % 7;
+                }
+            }
+            _ => {
+                return None;
+            }
+        }
+    }
+    Some(included[0..5].iter().all(|x| *x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_conditional() {
+        let parsed = parse_conditional("no @ (Mo-Fr 07:00-09:00,16:00-18:30); yes @ (weight>3)");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].0, "no");
+        let windows: Vec<String> = parsed[0].1.iter().map(|w| w.to_string()).collect();
+        assert_eq!(windows, vec!["07:00-09:00", "16:00-18:30"]);
+
+        // Weekend-only rules don't apply, but the rest of the restriction does
+        let parsed =
+            parse_conditional("no @ (Mo-Fr 07:00-09:00; Sa-Su 10:00-12:00); no @ (Sa 08:00-20:00)");
+        assert_eq!(parsed.len(), 1);
+        let windows: Vec<String> = parsed[0].1.iter().map(|w| w.to_string()).collect();
+        assert_eq!(windows, vec!["07:00-09:00"]);
+
+        // Anything that isn't understood throws out the whole condition
+        assert!(parse_conditional("no @ (Mo-Fr 07:00-09:00 AND weight>3)").is_empty());
+        assert!(parse_conditional("no @ (Jan-Mar 07:00-09:00)").is_empty());
+        assert!(parse_conditional("no @ (Mo-Fr 07:00-24:30)").is_empty());
+        assert_eq!(parse_conditional("no @ (Fr-Th, PH 07:00-09:00)").len(), 1);
+        assert!(parse_conditional("no @ (Mo-Th 07:00-09:00)").is_empty());
+
+        assert_eq!(
+            TimeWindow::parse("20:00-24:00").unwrap().to_string(),
+            "20:00-24:00"
+        );
+        assert!(TimeWindow::parse("24:30-01:00").is_none());
+
+        let overnight = TimeWindow::parse("22:00-06:00").unwrap();
+        assert!(overnight.contains(Time::START_OF_DAY + Duration::hours(23)));
+        assert!(overnight.contains(Time::START_OF_DAY + Duration::hours(29)));
+        assert!(!overnight.contains(Time::START_OF_DAY + Duration::hours(12)));
+    }
+
+    #[test]
+    fn test_parking_side() {
+        let tags = Tags::from_pairs(vec![(
+            "parking:lane:right:conditional",
+            "no_parking @ (Mo-Fr 07:00-09:00)",
+        )]);
+        let restricted_lanes = |lanes_ltr: Vec<(LaneType, Direction)>, driving_side| {
+            TimeRules::from_osm(&tags, &lanes_ltr, driving_side)
+                .lane_types
+                .into_iter()
+                .map(|(idx, _, _)| idx)
+                .collect::<Vec<_>>()
+        };
+        // The right side of the way is where the forward lanes are...
+        assert_eq!(
+            restricted_lanes(
+                vec![
+                    (LaneType::Parking, Direction::Back),
+                    (LaneType::Driving, Direction::Back),
+                    (LaneType::Driving, Direction::Fwd),
+                    (LaneType::Parking, Direction::Fwd),
+                ],
+                DrivingSide::Right
+            ),
+            vec![3]
+        );
+        // ...unless vehicles drive on the left, like in London
+        assert_eq!(
+            restricted_lanes(
+                vec![
+                    (LaneType::Parking, Direction::Fwd),
+                    (LaneType::Driving, Direction::Fwd),
+                    (LaneType::Driving, Direction::Back),
+                    (LaneType::Parking, Direction::Back),
+                ],
+                DrivingSide::Left
+            ),
+            vec![3]
+        );
+    }
+}
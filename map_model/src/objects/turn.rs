@@ -3,7 +3,7 @@ use std::fmt;
 
 use serde::{Deserialize, Serialize};
 
-use abstutil::MultiMap;
+use abstutil::{MultiMap, Tags};
 use geom::{Angle, Distance, PolyLine, Pt2D};
 
 use crate::{DirectedRoadID, Direction, IntersectionID, LaneID, Map};
@@ -67,6 +67,31 @@ pub enum TurnPriority {
     Protected,
 }
 
+/// How a crosswalk explicitly mapped in OpenStreetMap is marked, which decides who has the
+/// right-of-way at stop signs and uncontrolled crossings.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum CrossingType {
+    /// Pedestrians get a walk signal
+    Signalized,
+    /// Zebra stripes or similar. Vehicles give way to pedestrians.
+    Marked,
+    /// Pedestrians wait for a gap in traffic.
+    Unmarked,
+}
+
+impl CrossingType {
+    /// Interprets the tags of a `highway=crossing` node or a `footway=crossing` way
+    pub fn from_osm_tags(tags: &Tags) -> CrossingType {
+        if tags.is("crossing", "traffic_signals") {
+            CrossingType::Signalized
+        } else if tags.is("crossing", "unmarked") || tags.is("crossing:markings", "no") {
+            CrossingType::Unmarked
+        } else {
+            CrossingType::Marked
+        }
+    }
+}
+
 /// A Turn leads from the end of one Lane to the start of another. (Except for pedestrians;
 /// sidewalks are bidirectional.)
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
@@ -110,6 +135,18 @@ impl Turn {
         self.geom.first_pt().angle_to(self.geom.last_pt())
     }
 
+    /// Only crosswalks along a footway tagged as a crossing in OpenStreetMap or at a mid-block
+    /// crossing have a type. Other crosswalks are inferred, so nobody knows how they're marked.
+    pub fn crossing_type(&self, map: &Map) -> Option<CrossingType> {
+        if self.turn_type != TurnType::Crosswalk {
+            return None;
+        }
+        map.get_parent(self.id.src)
+            .crossing_type()
+            .or_else(|| map.get_parent(self.id.dst).crossing_type())
+            .or(map.get_i(self.id.parent).crossing)
+    }
+
     pub fn between_sidewalks(&self) -> bool {
         self.turn_type == TurnType::SharedSidewalkCorner || self.turn_type == TurnType::Crosswalk
     }
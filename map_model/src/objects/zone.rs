@@ -4,6 +4,10 @@
 //! 2) Stay Healthy Streets, where most car traffic is banned, except for trips beginning/ending in
 //!    the zone
 //! 3) Congestion capping, where only so many cars per hour can enter the zone
+//! 4) Low-traffic neighborhoods, where cars can only enter to reach somewhere inside, but bikes can
+//!    pass through
+//!
+//! Roads can also ban some modes entirely, which doesn't create a zone.
 
 use std::collections::BTreeSet;
 
@@ -11,7 +15,7 @@ use enumset::EnumSet;
 use petgraph::graphmap::DiGraphMap;
 use serde::{Deserialize, Serialize};
 
-use crate::pathfind::{driving_cost, walking_cost, WalkingNode};
+use crate::pathfind::{driving_cost, sidewalk_costs, walking_cost, WalkingNode};
 use crate::{
     IntersectionID, LaneID, Map, Path, PathConstraints, PathRequest, PathStep, RoadID, TurnID,
 };
@@ -20,6 +24,14 @@ use crate::{
 pub struct AccessRestrictions {
     pub allow_through_traffic: EnumSet<PathConstraints>,
     pub cap_vehicles_per_hour: Option<usize>,
+    /// These can't use the road at all, not even to reach somewhere along it. Edits made before
+    /// this existed don't have it.
+    #[serde(default)]
+    pub banned: EnumSet<PathConstraints>,
+    /// Trucks share car lanes, so they can't be banned through `banned`. Like that, trucks can't
+    /// use the road at all.
+    #[serde(default)]
+    pub ban_trucks: bool,
 }
 
 impl AccessRestrictions {
@@ -27,8 +39,15 @@ impl AccessRestrictions {
         AccessRestrictions {
             allow_through_traffic: EnumSet::all(),
             cap_vehicles_per_hour: None,
+            banned: EnumSet::new(),
+            ban_trucks: false,
         }
     }
+
+    /// Does this create a zone, only allowing some modes to reach places inside it?
+    pub fn is_zone(&self) -> bool {
+        self.allow_through_traffic != EnumSet::all() || self.cap_vehicles_per_hour.is_some()
+    }
 }
 
 /// A contiguous set of roads with access restrictions. This is derived from all the map's roads and
@@ -112,11 +131,11 @@ impl Zone {
             for l in map.get_r(*r).all_lanes() {
                 let l = map.get_l(l);
                 if l.is_walkable() {
-                    let cost = walking_cost(l.length());
+                    let (fwd_cost, back_cost) = sidewalk_costs(l, map);
                     let n1 = WalkingNode::SidewalkEndpoint(l.id, true);
                     let n2 = WalkingNode::SidewalkEndpoint(l.id, false);
-                    graph.add_edge(n1, n2, cost);
-                    graph.add_edge(n2, n1, cost);
+                    graph.add_edge(n1, n2, back_cost);
+                    graph.add_edge(n2, n1, fwd_cost);
 
                     for turn in map.get_turns_for(l.id, PathConstraints::Pedestrian) {
                         if self.members.contains(&map.get_l(turn.id.dst).parent) {
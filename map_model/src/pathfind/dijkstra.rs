@@ -6,9 +6,11 @@ use petgraph::graphmap::DiGraphMap;
 
 use crate::pathfind::driving::driving_cost;
 use crate::pathfind::walking::{
-    one_step_walking_path, walking_cost, walking_path_to_steps, WalkingNode,
+    one_step_walking_path, sidewalk_costs, walking_cost, walking_path_to_steps, WalkingNode,
+};
+use crate::{
+    BikeRoutingProfile, LaneID, Map, Path, PathConstraints, PathRequest, PathStep, TurnID,
 };
-use crate::{LaneID, Map, Path, PathConstraints, PathRequest, PathStep, TurnID};
 
 // TODO These should maybe keep the DiGraphMaps as state. It's cheap to recalculate it for edits.
 
@@ -31,7 +33,10 @@ pub fn pathfind(req: PathRequest, map: &Map) -> Option<Path> {
         }
     }
 
-    calc_path(graph, req, map)
+    let constraints = req.constraints;
+    calc_path(graph, req, map, |turn| {
+        driving_cost(map.get_l(turn.src), map.get_t(turn), constraints, map)
+    })
 }
 
 pub fn pathfind_avoiding_lanes(
@@ -39,7 +44,7 @@ pub fn pathfind_avoiding_lanes(
     avoid: BTreeSet<LaneID>,
     map: &Map,
 ) -> Option<Path> {
-    assert_eq!(req.constraints, PathConstraints::Car);
+    assert!(req.constraints != PathConstraints::Pedestrian);
     let mut graph: DiGraphMap<LaneID, TurnID> = DiGraphMap::new();
     for l in map.all_lanes() {
         if req.constraints.can_use(l, map) && !avoid.contains(&l.id) {
@@ -49,15 +54,63 @@ pub fn pathfind_avoiding_lanes(
         }
     }
 
-    calc_path(graph, req, map)
+    let constraints = req.constraints;
+    calc_path(graph, req, map, |turn| {
+        driving_cost(map.get_l(turn.src), map.get_t(turn), constraints, map)
+    })
+}
+
+/// Like the usual bike pathfinding, but roads are more expensive as they get more stressful.
+/// Private zones that don't allow bikes through can only be used to start or end the trip.
+pub fn pathfind_bike(req: PathRequest, profile: BikeRoutingProfile, map: &Map) -> Option<Path> {
+    assert_eq!(req.constraints, PathConstraints::Bike);
+    let start_r = map.get_l(req.start.lane()).parent;
+    let end_r = map.get_l(req.end.lane()).parent;
+    // Classifying stress is a bit expensive, so do it once per road, not once per turn explored
+    let mut penalty: Vec<f64> = Vec::new();
+    let mut usable: Vec<bool> = Vec::new();
+    for r in map.all_roads() {
+        penalty.push(profile.stress_penalty(r.bike_lts()));
+        usable.push(match r.get_zone(map) {
+            Some(zone) => {
+                zone.restrictions
+                    .allow_through_traffic
+                    .contains(PathConstraints::Bike)
+                    || zone.members.contains(&start_r)
+                    || zone.members.contains(&end_r)
+            }
+            None => true,
+        });
+    }
+
+    let mut graph: DiGraphMap<LaneID, TurnID> = DiGraphMap::new();
+    for l in map.all_lanes() {
+        if req.constraints.can_use(l, map) && usable[l.parent.0] {
+            for turn in map.get_turns_for(l.id, req.constraints) {
+                if usable[map.get_l(turn.id.dst).parent.0] {
+                    graph.add_edge(turn.id.src, turn.id.dst, turn.id);
+                }
+            }
+        }
+    }
+
+    calc_path(graph, req, map, |turn| {
+        let src = map.get_l(turn.src);
+        penalty[src.parent.0] * driving_cost(src, map.get_t(turn), PathConstraints::Bike, map)
+    })
 }
 
-fn calc_path(graph: DiGraphMap<LaneID, TurnID>, req: PathRequest, map: &Map) -> Option<Path> {
+fn calc_path<F: Fn(TurnID) -> f64>(
+    graph: DiGraphMap<LaneID, TurnID>,
+    req: PathRequest,
+    map: &Map,
+    cost: F,
+) -> Option<Path> {
     let (_, path) = petgraph::algo::astar(
         &graph,
         req.start.lane(),
         |l| l == req.end.lane(),
-        |(_, _, turn)| driving_cost(map.get_l(turn.src), map.get_t(*turn), req.constraints, map),
+        |(_, _, turn)| cost(*turn),
         |_| 0.0,
     )?;
     let mut steps = Vec::new();
@@ -79,12 +132,12 @@ fn calc_path(graph: DiGraphMap<LaneID, TurnID>, req: PathRequest, map: &Map) ->
 fn pathfind_walking(req: PathRequest, map: &Map) -> Option<Vec<WalkingNode>> {
     let mut graph: DiGraphMap<WalkingNode, usize> = DiGraphMap::new();
     for l in map.all_lanes() {
-        if l.is_walkable() {
-            let cost = walking_cost(l.length());
+        if PathConstraints::Pedestrian.can_use(l, map) {
+            let (fwd_cost, back_cost) = sidewalk_costs(l, map);
             let n1 = WalkingNode::SidewalkEndpoint(l.id, true);
             let n2 = WalkingNode::SidewalkEndpoint(l.id, false);
-            graph.add_edge(n1, n2, cost);
-            graph.add_edge(n2, n1, cost);
+            graph.add_edge(n1, n2, back_cost);
+            graph.add_edge(n2, n1, fwd_cost);
 
             for turn in map.get_turns_for(l.id, PathConstraints::Pedestrian) {
                 graph.add_edge(
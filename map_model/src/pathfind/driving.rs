@@ -7,10 +7,14 @@ use serde::{Deserialize, Serialize};
 use thread_local::ThreadLocal;
 
 use abstutil::MultiMap;
+use geom::Speed;
 
 use crate::pathfind::node_map::{deserialize_nodemap, NodeMap};
 use crate::pathfind::uber_turns::{IntersectionCluster, UberTurn};
-use crate::{Lane, LaneID, Map, Path, PathConstraints, PathRequest, PathStep, Turn, TurnID};
+use crate::{
+    bike_speed_on_grade, Lane, LaneID, Map, Path, PathConstraints, PathRequest, PathStep, Turn,
+    TurnID,
+};
 
 #[derive(Serialize, Deserialize)]
 pub struct VehiclePathfinder {
@@ -147,11 +151,10 @@ fn make_input_graph(
         // vehicle.
         // TODO Need to test editing lanes inside an IntersectionCluster very carefully. See Mercer
         // and Dexter.
-        if ut
-            .path
-            .iter()
-            .all(|t| constraints.can_use(map.get_l(t.dst), map))
-        {
+        if ut.path.iter().all(|t| {
+            constraints.can_use(map.get_l(t.dst), map)
+                && !map.get_i(t.parent).modal_filter.contains(constraints)
+        }) {
             uber_turn_entrances.insert(ut.entry(), idx);
         } else {
             // Similar to the hack below for unused lanes
@@ -230,8 +233,11 @@ pub fn driving_cost(lane: &Lane, turn: &Turn, constraints: PathConstraints, map:
         }
         PathConstraints::Bike => {
             // Speed limits don't matter, bikes are usually constrained by their own speed limit.
-            let dist = lane.length() + turn.geom.length();
-            // TODO Elevation gain is bad, loss is good.
+            // But climbing is slow, so stretch the lane by how much longer it takes than on flat
+            // ground.
+            let flat = Speed::miles_per_hour(10.0);
+            let grade_penalty = flat / bike_speed_on_grade(flat, lane.percent_grade(map));
+            let dist = lane.length() * grade_penalty + turn.geom.length();
             // TODO If we're on a driving lane, higher speed limit is worse.
             // TODO Bike lanes next to parking is dangerous.
 
@@ -7,14 +7,14 @@ use enumset::EnumSetType;
 use serde::{Deserialize, Serialize};
 
 use abstutil::Timer;
-use geom::{Distance, PolyLine, EPSILON_DIST};
+use geom::{Distance, PolyLine, Time, EPSILON_DIST};
 
 pub use self::ch::ContractionHierarchyPathfinder;
 pub use self::driving::driving_cost;
-pub use self::walking::{walking_cost, WalkingNode};
+pub use self::walking::{sidewalk_costs, walking_cost, WalkingNode};
 use crate::{
-    osm, BusRouteID, BusStopID, Lane, LaneID, LaneType, Map, Position, Traversable, TurnID,
-    UberTurn,
+    osm, BusRouteID, BusStopID, Lane, LaneID, LaneType, LevelOfTrafficStress, Map, Position,
+    RoadID, Traversable, TurnID, UberTurn,
 };
 
 mod ch;
@@ -287,6 +287,51 @@ impl Path {
         }
     }
 
+    /// Find another way from the end of the current lane to the same destination, avoiding some
+    /// lanes, and replace the rest of the path with it. Returns false if there's no other way.
+    pub fn reroute_avoiding(
+        &mut self,
+        mut avoid: BTreeSet<LaneID>,
+        constraints: PathConstraints,
+        map: &Map,
+    ) -> bool {
+        let (current, end) = match (self.current_step(), self.last_step()) {
+            (PathStep::Lane(l1), PathStep::Lane(l2)) => (l1, l2),
+            _ => {
+                return false;
+            }
+        };
+        if current == end || self.currently_inside_ut.is_some() {
+            return false;
+        }
+        avoid.remove(&current);
+        avoid.remove(&end);
+        let req = PathRequest {
+            start: Position::new(current, map.get_l(current).length()),
+            end: Position::new(end, self.end_dist),
+            constraints,
+        };
+        let other = match dijkstra::pathfind_avoiding_lanes(req, avoid, map) {
+            Some(path) => path,
+            None => {
+                return false;
+            }
+        };
+
+        let old_lanes = self
+            .steps
+            .iter()
+            .filter(|s| matches!(s, PathStep::Lane(_) | PathStep::ContraflowLane(_)))
+            .count();
+        self.total_lanes = self.total_lanes - old_lanes + other.total_lanes;
+        // Keep measuring progress from the start of the original path
+        self.total_length = self.crossed_so_far + other.total_length;
+        self.steps = other.steps;
+        self.end_dist = other.end_dist;
+        self.uber_turns = other.uber_turns;
+        true
+    }
+
     pub fn current_step(&self) -> PathStep {
         self.steps[0]
     }
@@ -464,13 +509,61 @@ impl PathConstraints {
 
     // TODO Handle private zones here?
     pub fn can_use(self, l: &Lane, map: &Map) -> bool {
+        self.can_use_type(l.lane_type, l, map)
+    }
+
+    /// Like `can_use`, but lanes may act like a different type at some times of day, like a
+    /// peak-hour bus lane. The contraction hierarchies ignore this; use `lanes_closed_at` to route
+    /// around these lanes during restricted times.
+    pub fn can_use_at(self, l: &Lane, map: &Map, time: Time) -> bool {
+        let road = map.get_r(l.parent);
+        if road.time_rules.is_empty() {
+            return self.can_use(l, map);
+        }
+        self.can_use_type(road.lane_type_at(l.id, time), l, map)
+    }
+
+    /// Lanes that this kind of vehicle can normally use, but time-of-day rules close to it at some
+    /// time. Like a private zone, roads closed to through traffic can still be used to reach a
+    /// destination on `end_road`.
+    pub fn lanes_closed_at(self, map: &Map, time: Time, end_road: RoadID) -> BTreeSet<LaneID> {
+        let mut closed = BTreeSet::new();
+        for r in map.all_roads_with_time_rules() {
+            let road = map.get_r(*r);
+            let access = road.access_restrictions_at(time);
+            let no_through_traffic = road.id != end_road
+                && access != &road.access_restrictions
+                && !access.allow_through_traffic.contains(self);
+            for l in road.all_lanes() {
+                let lane = map.get_l(l);
+                if self.can_use(lane, map)
+                    && (no_through_traffic || !self.can_use_at(lane, map, time))
+                {
+                    closed.insert(l);
+                }
+            }
+        }
+        closed
+    }
+
+    fn can_use_type(self, lt: LaneType, l: &Lane, map: &Map) -> bool {
+        if map
+            .get_r(l.parent)
+            .access_restrictions
+            .banned
+            .contains(self)
+        {
+            return false;
+        }
         match self {
-            PathConstraints::Pedestrian => l.is_walkable(),
-            PathConstraints::Car => l.is_driving(),
+            PathConstraints::Pedestrian => lt == LaneType::Sidewalk || lt == LaneType::Shoulder,
+            PathConstraints::Car => lt == LaneType::Driving,
             PathConstraints::Bike => {
-                if l.is_biking() {
+                if lt == LaneType::Biking {
                     true
-                } else if l.is_driving() || (l.is_bus() && map.config.bikes_can_use_bus_lanes) {
+                } else if lt == LaneType::Driving
+                    || (lt == LaneType::Bus && map.config.bikes_can_use_bus_lanes)
+                {
                     let road = map.get_r(l.parent);
                     !road.osm_tags.is("bicycle", "no")
                         && !road
@@ -480,8 +573,8 @@ impl PathConstraints {
                     false
                 }
             }
-            PathConstraints::Bus => l.is_driving() || l.is_bus(),
-            PathConstraints::Train => l.is_light_rail(),
+            PathConstraints::Bus => lt == LaneType::Driving || lt == LaneType::Bus,
+            PathConstraints::Train => lt == LaneType::LightRail,
         }
     }
 
@@ -502,6 +595,49 @@ impl PathConstraints {
     }
 }
 
+/// How a cyclist trades travel time for calmer streets, judged by each road's
+/// `LevelOfTrafficStress`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BikeRoutingProfile {
+    /// The usual route, which only slightly prefers bike lanes
+    Fastest,
+    /// Willing to go somewhat out of the way to avoid high-stress roads
+    Balanced,
+    /// Sticks to the calmest streets whenever possible
+    LowStress,
+}
+
+impl Default for BikeRoutingProfile {
+    fn default() -> BikeRoutingProfile {
+        BikeRoutingProfile::Fastest
+    }
+}
+
+impl BikeRoutingProfile {
+    pub fn all() -> Vec<BikeRoutingProfile> {
+        vec![
+            BikeRoutingProfile::Fastest,
+            BikeRoutingProfile::Balanced,
+            BikeRoutingProfile::LowStress,
+        ]
+    }
+
+    /// Multiplies the usual cost of biking along a road with this stress level
+    pub fn stress_penalty(self, lts: LevelOfTrafficStress) -> f64 {
+        match (self, lts) {
+            (BikeRoutingProfile::Fastest, _) => 1.0,
+            (BikeRoutingProfile::Balanced, LevelOfTrafficStress::LTS1)
+            | (BikeRoutingProfile::Balanced, LevelOfTrafficStress::LTS2) => 1.0,
+            (BikeRoutingProfile::Balanced, LevelOfTrafficStress::LTS3) => 1.5,
+            (BikeRoutingProfile::Balanced, LevelOfTrafficStress::LTS4) => 3.0,
+            (BikeRoutingProfile::LowStress, LevelOfTrafficStress::LTS1) => 1.0,
+            (BikeRoutingProfile::LowStress, LevelOfTrafficStress::LTS2) => 1.5,
+            (BikeRoutingProfile::LowStress, LevelOfTrafficStress::LTS3) => 4.0,
+            (BikeRoutingProfile::LowStress, LevelOfTrafficStress::LTS4) => 10.0,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
 pub struct PathRequest {
     pub start: Position,
@@ -611,6 +747,17 @@ impl Pathfinder {
     ) -> Option<Path> {
         dijkstra::pathfind_avoiding_lanes(req, avoid, map)
     }
+    pub fn pathfind_bike(
+        &self,
+        req: PathRequest,
+        profile: BikeRoutingProfile,
+        map: &Map,
+    ) -> Option<Path> {
+        if profile == BikeRoutingProfile::Fastest {
+            return self.pathfind(req, map);
+        }
+        dijkstra::pathfind_bike(req, profile, map)
+    }
 
     pub fn should_use_transit(
         &self,
@@ -13,8 +13,8 @@ use geom::{Distance, Speed};
 use crate::pathfind::driving::VehiclePathfinder;
 use crate::pathfind::node_map::{deserialize_nodemap, NodeMap};
 use crate::{
-    BusRoute, BusRouteID, BusStopID, IntersectionID, LaneID, Map, Path, PathConstraints,
-    PathRequest, PathStep, Position,
+    walking_speed_on_grade, BusRoute, BusRouteID, BusStopID, IntersectionID, Lane, LaneID, Map,
+    Path, PathConstraints, PathRequest, PathStep, Position,
 };
 
 #[derive(Serialize, Deserialize)]
@@ -232,27 +232,33 @@ fn make_input_graph(
     let mut input_graph = InputGraph::new();
 
     for l in map.all_lanes() {
-        if l.is_walkable()
+        if PathConstraints::Pedestrian.can_use(l, map)
             && map
                 .get_r(l.parent)
                 .access_restrictions
                 .allow_through_traffic
                 .contains(PathConstraints::Pedestrian)
         {
-            let mut cost = walking_cost(l.length());
+            let (mut fwd_cost, mut back_cost) = sidewalk_costs(l, map);
             // TODO Tune this penalty, along with many others.
             if l.is_shoulder() {
-                cost *= 2;
+                fwd_cost *= 2;
+                back_cost *= 2;
             }
             let n1 = nodes.get(WalkingNode::SidewalkEndpoint(l.id, true));
             let n2 = nodes.get(WalkingNode::SidewalkEndpoint(l.id, false));
-            input_graph.add_edge(n1, n2, cost);
-            input_graph.add_edge(n2, n1, cost);
+            input_graph.add_edge(n1, n2, back_cost);
+            input_graph.add_edge(n2, n1, fwd_cost);
         }
     }
 
     for t in map.all_turns().values() {
-        if t.between_sidewalks() {
+        if t.between_sidewalks()
+            && !map
+                .get_i(t.id.parent)
+                .modal_filter
+                .contains(PathConstraints::Pedestrian)
+        {
             let from =
                 WalkingNode::SidewalkEndpoint(t.id.src, map.get_l(t.id.src).dst_i == t.id.parent);
             let to =
@@ -385,7 +391,21 @@ fn transit_input_graph(
 }
 
 pub fn walking_cost(dist: Distance) -> usize {
-    let walking_speed = Speed::meters_per_second(1.34);
+    walking_cost_on_grade(dist, 0.0)
+}
+
+/// The cost of walking the full length of a sidewalk, first from its start to its end, then in the
+/// opposite direction. Hills make these differ.
+pub fn sidewalk_costs(lane: &Lane, map: &Map) -> (usize, usize) {
+    let grade = lane.percent_grade(map);
+    (
+        walking_cost_on_grade(lane.length(), grade),
+        walking_cost_on_grade(lane.length(), -grade),
+    )
+}
+
+fn walking_cost_on_grade(dist: Distance, grade: f64) -> usize {
+    let walking_speed = walking_speed_on_grade(Speed::meters_per_second(1.34), grade);
     let time = dist / walking_speed;
     (time.inner_seconds().round() as usize).max(1)
 }
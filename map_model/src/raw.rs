@@ -13,7 +13,8 @@ use geom::{Circle, Distance, GPSBounds, PolyLine, Polygon, Pt2D};
 
 use crate::make::initial::lane_specs::get_lane_specs_ltr;
 use crate::{
-    osm, Amenity, AreaType, Direction, DrivingSide, IntersectionType, LaneType, MapConfig,
+    osm, Amenity, AreaType, CrossingType, Direction, DrivingSide, IntersectionType, LaneType,
+    MapConfig,
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -159,6 +160,7 @@ impl RawMap {
             polygon: Circle::new(Pt2D::new(0.0, 0.0), Distance::meters(1.0)).to_polygon(),
             roads: self.roads_per_intersection(id).into_iter().collect(),
             intersection_type: self.intersections[&id].intersection_type,
+            crossing: self.intersections[&id].crossing,
             elevation: self.intersections[&id].elevation,
         };
         let mut roads = BTreeMap::new();
@@ -223,6 +225,73 @@ impl RawMap {
         Some(fixed)
     }
 
+    /// Splits a road at one of its interior points, creating a mid-block crossing there with the
+    /// given ID. Roads involved in turn restrictions can't be split, since it'd be ambiguous which
+    /// half the restriction should use.
+    pub fn split_road_at_crossing(
+        &mut self,
+        id: OriginalRoad,
+        idx: usize,
+        new_i: osm::NodeID,
+        crossing: CrossingType,
+    ) -> Result<(OriginalRoad, OriginalRoad), String> {
+        let road = &self.roads[&id];
+        if idx == 0 || idx >= road.center_points.len() - 1 {
+            return Err(format!("Can't split {} at an endpoint", id));
+        }
+        if !road.turn_restrictions.is_empty()
+            || !road.complicated_turn_restrictions.is_empty()
+            || self.roads.values().any(|r| {
+                r.turn_restrictions.iter().any(|(_, to)| *to == id)
+                    || r.complicated_turn_restrictions
+                        .iter()
+                        .any(|(via, to)| *via == id || *to == id)
+            })
+        {
+            return Err(format!("Can't split {}, it has turn restrictions", id));
+        }
+        if self.intersections.contains_key(&new_i) {
+            return Err(format!("{} already exists", new_i));
+        }
+
+        let mut road1 = self.roads.remove(&id).unwrap();
+        let mut road2 = road1.clone();
+        let point = road1.center_points[idx];
+        road1.center_points.truncate(idx + 1);
+        road1.osm_tags.remove(osm::ENDPT_FWD);
+        road2.center_points.drain(0..idx);
+        road2.osm_tags.remove(osm::ENDPT_BACK);
+
+        self.intersections.insert(
+            new_i,
+            RawIntersection {
+                point,
+                intersection_type: if crossing == CrossingType::Signalized {
+                    IntersectionType::TrafficSignal
+                } else {
+                    IntersectionType::StopSign
+                },
+                crossing: Some(crossing),
+                elevation: (self.intersections[&id.i1].elevation
+                    + self.intersections[&id.i2].elevation)
+                    / 2.0,
+            },
+        );
+        let id1 = OriginalRoad {
+            osm_way_id: id.osm_way_id,
+            i1: id.i1,
+            i2: new_i,
+        };
+        let id2 = OriginalRoad {
+            osm_way_id: id.osm_way_id,
+            i1: new_i,
+            i2: id.i2,
+        };
+        self.roads.insert(id1, road1);
+        self.roads.insert(id2, road2);
+        Ok((id1, id2))
+    }
+
     pub fn closest_intersection(&self, pt: Pt2D) -> osm::NodeID {
         self.intersections
             .iter()
@@ -303,7 +372,8 @@ impl RawRoad {
     }
 
     pub fn is_footway(&self) -> bool {
-        self.osm_tags.is(osm::HIGHWAY, "pedestrian")
+        self.osm_tags
+            .is_any(osm::HIGHWAY, vec!["footway", "path", "pedestrian", "steps"])
     }
 
     pub fn is_service(&self) -> bool {
@@ -317,6 +387,8 @@ pub struct RawIntersection {
     /// RawMap; roads and intersections get merged and deleted.
     pub point: Pt2D,
     pub intersection_type: IntersectionType,
+    /// Mid-block crossings split a road only so pedestrians can cross there.
+    pub crossing: Option<CrossingType>,
     pub elevation: Distance,
 }
 
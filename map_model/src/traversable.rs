@@ -208,6 +208,14 @@ impl Traversable {
         }
     }
 
+    /// Positive is uphill, going the usual direction. Turns are treated as flat.
+    pub fn percent_grade(&self, map: &Map) -> f64 {
+        match *self {
+            Traversable::Lane(id) => map.get_l(id).percent_grade(map),
+            Traversable::Turn(_) => 0.0,
+        }
+    }
+
     pub fn get_zorder(&self, map: &Map) -> isize {
         match *self {
             Traversable::Lane(id) => map.get_parent(id).zorder,
@@ -215,3 +223,73 @@ impl Traversable {
         }
     }
 }
+
+/// The fastest any pedestrian walks on flat ground. The simulation and travel time estimates both
+/// use this.
+pub fn max_walking_speed() -> Speed {
+    Speed::miles_per_hour(3.0)
+}
+
+/// The fastest any cyclist rides on flat ground. The simulation and travel time estimates both use
+/// this.
+pub fn max_biking_speed() -> Speed {
+    Speed::miles_per_hour(10.0)
+}
+
+/// How fast a cyclist rides up or down some grade, given their speed on flat ground. Climbing slows
+/// riders down roughly in proportion to the grade, to about 2/3 speed at 5% and half at 10%.
+/// Downhill, riders speed up, but brake before going 1.5x faster.
+pub fn bike_speed_on_grade(flat: Speed, grade: f64) -> Speed {
+    if grade >= 0.0 {
+        flat * (1.0 / (1.0 + 10.0 * grade))
+    } else {
+        flat * (1.0 - 3.0 * grade).min(1.5)
+    }
+}
+
+/// How fast somebody walks up or down some grade, given their speed on flat ground. This is
+/// Tobler's hiking function, scaled so flat ground is unchanged. People walk fastest down a gentle
+/// 5% slope and slow down on anything steeper, uphill or down.
+pub fn walking_speed_on_grade(flat: Speed, grade: f64) -> Speed {
+    flat * (-3.5 * ((grade + 0.05).abs() - 0.05)).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_ratio(actual: Speed, flat: Speed, expected: f64) {
+        let ratio = actual.inner_meters_per_second() / flat.inner_meters_per_second();
+        assert!(
+            (ratio - expected).abs() < 0.01,
+            "expected {}x flat speed, got {}x",
+            expected,
+            ratio
+        );
+    }
+
+    #[test]
+    fn bike_speeds() {
+        let flat = max_biking_speed();
+        assert_ratio(bike_speed_on_grade(flat, 0.0), flat, 1.0);
+        // Steep climbs are slow
+        assert_ratio(bike_speed_on_grade(flat, 0.1), flat, 0.5);
+        assert_ratio(bike_speed_on_grade(flat, 0.3), flat, 0.25);
+        // Riders speed up downhill, but brake on steep descents
+        assert_ratio(bike_speed_on_grade(flat, -0.1), flat, 1.3);
+        assert_ratio(bike_speed_on_grade(flat, -0.3), flat, 1.5);
+    }
+
+    #[test]
+    fn walking_speeds() {
+        let flat = max_walking_speed();
+        assert_ratio(walking_speed_on_grade(flat, 0.0), flat, 1.0);
+        // A gentle descent is the fastest
+        assert!(walking_speed_on_grade(flat, -0.05) > walking_speed_on_grade(flat, -0.02));
+        assert_ratio(walking_speed_on_grade(flat, -0.05), flat, 1.19);
+        // Steep slopes are slow, uphill or down
+        assert_ratio(walking_speed_on_grade(flat, 0.2), flat, 0.5);
+        assert_ratio(walking_speed_on_grade(flat, -0.3), flat, 0.5);
+        assert!(walking_speed_on_grade(flat, 0.2) < walking_speed_on_grade(flat, -0.2));
+    }
+}
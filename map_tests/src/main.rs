@@ -39,6 +39,8 @@ fn import_map(path: String) -> Map {
             private_offstreet_parking: convert_osm::PrivateOffstreetParking::FixedPerBldg(0),
             elevation: None,
             include_railroads: true,
+            include_footways: false,
+            split_midblock_crossings: false,
         },
         &mut timer,
     );
@@ -84,7 +84,8 @@ impl CapSimState {
     }
 
     /// Before the driving portion of a trip begins, check that the desired path doesn't exceed any
-    /// caps. If so, attempt to reroute around.
+    /// caps, use lanes currently closed by time-of-day rules, or use roads banning trucks. If so,
+    /// attempt to reroute around.
     pub fn validate_path(
         &mut self,
         req: &PathRequest,
@@ -95,6 +96,8 @@ impl CapSimState {
         intersections: &IntersectionSimState,
         map: &Map,
     ) -> Result<Path, String> {
+        let path = avoid_closed_lanes(req, path, now, car.1, map);
+
         if let Some(ref avoid) = self.avoid_congestion {
             if let Some((turn, delay)) = avoid.path_crosses_delay(now, &path, intersections, map) {
                 *capped = true;
@@ -111,7 +114,8 @@ impl CapSimState {
         // TODO Make the responses configurable: cancel the trip, reroute, delay an hour, switch
         // modes. Where should this policy be specified? Is it simulation-wide?
 
-        let mut avoid_lanes: BTreeSet<LaneID> = BTreeSet::new();
+        // Don't wind up on a lane closed by time-of-day rules while going around the cap
+        let mut avoid_lanes = lanes_closed_for(req, now, car.1, map);
         for (l, idx) in &self.lane_to_zone {
             let zone = &self.zones[*idx];
             if zone.entered_in_last_hour.len() >= zone.cap
@@ -133,6 +137,51 @@ impl CapSimState {
     }
 }
 
+/// The contraction hierarchies don't know about time-of-day rules or roads banning trucks. If part
+/// of the path is closed right now, go around it if possible.
+pub fn avoid_closed_lanes(
+    req: &PathRequest,
+    path: Path,
+    now: Time,
+    vehicle_type: VehicleType,
+    map: &Map,
+) -> Path {
+    let closed = lanes_closed_for(req, now, vehicle_type, map);
+    let crosses_closed = path.get_steps().iter().any(|step| match step {
+        PathStep::Lane(l) => closed.contains(l),
+        _ => false,
+    });
+    if !crosses_closed {
+        return path;
+    }
+    map.pathfind_avoiding_lanes(req.clone(), closed)
+        .unwrap_or(path)
+}
+
+/// Lanes this request can't use right now because of time-of-day rules or truck bans. The start
+/// and end lanes are always allowed.
+fn lanes_closed_for(
+    req: &PathRequest,
+    now: Time,
+    vehicle_type: VehicleType,
+    map: &Map,
+) -> BTreeSet<LaneID> {
+    let mut closed = if map.all_roads_with_time_rules().is_empty() {
+        BTreeSet::new()
+    } else {
+        req.constraints
+            .lanes_closed_at(map, now, map.get_l(req.end.lane()).parent)
+    };
+    if vehicle_type == VehicleType::Truck {
+        for r in map.all_roads_banning_trucks() {
+            closed.extend(map.get_r(*r).all_lanes());
+        }
+    }
+    closed.remove(&req.start.lane());
+    closed.remove(&req.end.lane());
+    closed
+}
+
 /// Before the driving portion of a trip begins, check that the desired path doesn't pass through
 /// any road with agents currently experiencing some delay.
 #[derive(Serialize, Deserialize, Clone)]
@@ -17,7 +17,7 @@ use std::fmt;
 use serde::{Deserialize, Serialize};
 
 use abstutil::{deserialize_usize, serialize_usize};
-use geom::{Distance, Pt2D, Speed, Time};
+use geom::{Distance, Duration, Pt2D, Speed, Time};
 use map_model::{
     BuildingID, BusRouteID, BusStopID, DirectedRoadID, IntersectionID, LaneID, Map, ParkingLotID,
     Path, PathConstraints, PathRequest, Position,
@@ -29,6 +29,7 @@ pub use crate::render::{
 };
 
 pub use self::analytics::{Analytics, TripPhase};
+pub use self::cap::avoid_closed_lanes;
 pub(crate) use self::cap::CapSimState;
 pub(crate) use self::events::Event;
 pub use self::events::{AlertLocation, TripPhaseType};
@@ -41,6 +42,7 @@ pub(crate) use self::mechanics::{
     DrivingSimState, IntersectionSimState, ParkingSim, ParkingSimState, WalkingSimState,
 };
 pub(crate) use self::pandemic::PandemicModel;
+pub use self::recording::Recording;
 pub(crate) use self::router::{ActionAtEnd, Router};
 pub(crate) use self::scheduler::{Command, Scheduler};
 pub use self::sim::{AgentProperties, AlertHandler, Sim, SimCallback, SimOptions};
@@ -55,6 +57,7 @@ mod events;
 mod make;
 mod mechanics;
 mod pandemic;
+mod recording;
 mod render;
 mod router;
 mod scheduler;
@@ -69,6 +72,10 @@ pub const MIN_CAR_LENGTH: Distance = Distance::const_meters(4.5);
 pub const MAX_CAR_LENGTH: Distance = Distance::const_meters(6.5);
 // Note this is more than MAX_CAR_LENGTH
 pub const BUS_LENGTH: Distance = Distance::const_meters(12.5);
+// From a large delivery van to a box truck. Also must be < PARKING_SPOT_LENGTH, to fit in loading
+// zones.
+pub const MIN_TRUCK_LENGTH: Distance = Distance::const_meters(6.0);
+pub const MAX_TRUCK_LENGTH: Distance = Distance::const_meters(7.5);
 pub const LIGHT_RAIL_LENGTH: Distance = Distance::const_meters(60.0);
 
 /// At all speeds (including at rest), cars must be at least this far apart, measured from front of
@@ -99,6 +106,7 @@ impl fmt::Display for CarID {
             VehicleType::Bus => write!(f, "Bus #{}", self.0),
             VehicleType::Train => write!(f, "Train #{}", self.0),
             VehicleType::Bike => write!(f, "Bike #{}", self.0),
+            VehicleType::Truck => write!(f, "Truck #{}", self.0),
         }
     }
 }
@@ -141,6 +149,7 @@ impl AgentID {
                 VehicleType::Bike => AgentType::Bike,
                 VehicleType::Bus => AgentType::Bus,
                 VehicleType::Train => AgentType::Train,
+                VehicleType::Truck => AgentType::Truck,
             },
             AgentID::Pedestrian(_) => AgentType::Pedestrian,
             AgentID::BusPassenger(_, _) => AgentType::TransitRider,
@@ -172,6 +181,7 @@ pub enum AgentType {
     Bike,
     Bus,
     Train,
+    Truck,
     Pedestrian,
     TransitRider,
 }
@@ -183,6 +193,7 @@ impl AgentType {
             AgentType::Bike,
             AgentType::Bus,
             AgentType::Train,
+            AgentType::Truck,
             AgentType::Pedestrian,
             AgentType::TransitRider,
         ]
@@ -194,6 +205,7 @@ impl AgentType {
             AgentType::Bike => "Bike",
             AgentType::Bus => "Bus",
             AgentType::Train => "Train",
+            AgentType::Truck => "Truck",
             AgentType::Pedestrian => "Pedestrian",
             AgentType::TransitRider => "Transit rider",
         }
@@ -205,6 +217,7 @@ impl AgentType {
             AgentType::Bike => "bikes",
             AgentType::Bus => "buses",
             AgentType::Train => "trains",
+            AgentType::Truck => "trucks",
             AgentType::Pedestrian => "pedestrians",
             AgentType::TransitRider => "transit riders",
         }
@@ -212,7 +225,7 @@ impl AgentType {
 
     pub fn ongoing_verb(self) -> &'static str {
         match self {
-            AgentType::Car => "driving",
+            AgentType::Car | AgentType::Truck => "driving",
             AgentType::Bike => "biking",
             AgentType::Bus | AgentType::Train => unreachable!(),
             AgentType::Pedestrian => "walking",
@@ -271,6 +284,9 @@ pub enum VehicleType {
     Bus,
     Train,
     Bike,
+    /// Freight and delivery vehicles. They drive like cars, but are longer and slower to get
+    /// moving.
+    Truck,
 }
 
 impl fmt::Display for VehicleType {
@@ -280,6 +296,7 @@ impl fmt::Display for VehicleType {
             VehicleType::Bus => write!(f, "bus"),
             VehicleType::Train => write!(f, "train"),
             VehicleType::Bike => write!(f, "bike"),
+            VehicleType::Truck => write!(f, "truck"),
         }
     }
 }
@@ -291,6 +308,9 @@ impl VehicleType {
             VehicleType::Bus => PathConstraints::Bus,
             VehicleType::Train => PathConstraints::Train,
             VehicleType::Bike => PathConstraints::Bike,
+            // Trucks use the same lanes as cars. The contraction hierarchies don't know about roads
+            // banning trucks, so paths are checked before trucks start driving.
+            VehicleType::Truck => PathConstraints::Car,
         }
     }
 
@@ -300,6 +320,21 @@ impl VehicleType {
             VehicleType::Bus => true,
             VehicleType::Train => true,
             VehicleType::Bike => false,
+            VehicleType::Truck => false,
+        }
+    }
+
+    /// When starting from rest, how much longer this type of vehicle needs to cover some distance,
+    /// compared to instantly reaching `speed`. The rest of the simulation treats acceleration as
+    /// instant, so this is only used to hold back sluggish vehicles.
+    pub(crate) fn startup_delay(self, speed: Speed) -> Duration {
+        match self {
+            // Accelerating at about 1 m/s^2 for a loaded truck, half the time spent getting up to
+            // speed is lost.
+            VehicleType::Truck => Duration::seconds(speed.inner_meters_per_second() / 2.0),
+            VehicleType::Car | VehicleType::Bus | VehicleType::Train | VehicleType::Bike => {
+                Duration::ZERO
+            }
         }
     }
 }
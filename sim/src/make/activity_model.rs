@@ -9,7 +9,7 @@ use rand_xorshift::XorShiftRng;
 
 use abstutil::{prettyprint_usize, Parallelism, Timer};
 use geom::{Distance, Duration, Time};
-use map_model::{BuildingID, BuildingType, Map, PathConstraints, PathRequest};
+use map_model::{BikeRoutingProfile, BuildingID, BuildingType, Map, PathConstraints, PathRequest};
 
 use crate::make::fork_rng;
 use crate::{
@@ -279,6 +279,7 @@ fn create_prole(
         // Fix this outside the parallelism
         id: PersonID(0),
         orig_id: None,
+        bike_profile: BikeRoutingProfile::default(),
         trips: vec![
             IndividTrip::new(depart_am, TripPurpose::Work, goto_work),
             IndividTrip::new(depart_pm, TripPurpose::Home, return_home),
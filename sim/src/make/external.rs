@@ -4,7 +4,7 @@
 use serde::Deserialize;
 
 use geom::{Distance, FindClosest, LonLat, Pt2D, Time};
-use map_model::Map;
+use map_model::{BikeRoutingProfile, Map};
 
 use crate::{IndividTrip, PersonID, PersonSpec, SpawnTrip, TripEndpoint, TripMode, TripPurpose};
 
@@ -55,6 +55,7 @@ impl ExternalPerson {
             let mut spec = PersonSpec {
                 id: PersonID(results.len()),
                 orig_id: None,
+                bike_profile: BikeRoutingProfile::default(),
                 trips: Vec::new(),
             };
             let mut from = lookup_pt(person.origin)?;
@@ -0,0 +1,96 @@
+//! Generates freight deliveries. Trucks enter the map from a border, stop at a building with some
+//! commercial amenities, and leave through another border.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+use rand_xorshift::XorShiftRng;
+
+use abstutil::{prettyprint_usize, Timer};
+use geom::{Duration, Time};
+use map_model::{BikeRoutingProfile, Map, PathConstraints};
+
+use crate::{
+    IndividTrip, PersonID, PersonSpec, Scenario, ScenarioGenerator, SpawnTrip, TripEndpoint,
+    TripPurpose,
+};
+
+impl ScenarioGenerator {
+    /// Each amenity (shop, restaurant, etc) receives one delivery during business hours. Combine
+    /// with other scenarios using `ScenarioModifier::AddExtraTrips`.
+    pub fn deliveries(map: &Map, rng: &mut XorShiftRng, timer: &mut Timer) -> Scenario {
+        let mut s = Scenario::empty(map, "deliveries");
+        // Include all buses/trains
+        s.only_seed_buses = None;
+
+        let incoming: Vec<_> = map
+            .all_incoming_borders()
+            .into_iter()
+            .filter_map(|i| i.some_outgoing_road(map))
+            .filter(|dr| !dr.lanes(PathConstraints::Car, map).is_empty())
+            .collect();
+        let outgoing: Vec<_> = map
+            .all_outgoing_borders()
+            .into_iter()
+            .filter_map(|i| {
+                TripEndpoint::Border(i.id, None).driving_goal(PathConstraints::Car, map)
+            })
+            .collect();
+        if incoming.is_empty() || outgoing.is_empty() {
+            timer.warn(format!(
+                "{} has no borders for trucks to use, so there are no deliveries",
+                map.get_name()
+            ));
+            return s;
+        }
+
+        let mut skipped = 0;
+        timer.start_iter("generate deliveries", map.all_buildings().len());
+        for b in map.all_buildings() {
+            timer.next();
+            if b.amenities.is_empty() {
+                continue;
+            }
+            // Trucks need somewhere to stop
+            if b.driving_connection(map).is_none() {
+                skipped += b.amenities.len();
+                continue;
+            }
+            for _ in &b.amenities {
+                let depart = rand_time(
+                    rng,
+                    Time::START_OF_DAY + Duration::hours(6),
+                    Time::START_OF_DAY + Duration::hours(18),
+                );
+                let dwell = Duration::minutes(rng.gen_range(5, 20));
+                s.people.push(PersonSpec {
+                    id: PersonID(s.people.len()),
+                    orig_id: None,
+                    bike_profile: BikeRoutingProfile::default(),
+                    trips: vec![IndividTrip::new(
+                        depart,
+                        TripPurpose::Delivery,
+                        SpawnTrip::Delivery {
+                            dr: *incoming.choose(rng).unwrap(),
+                            bldg: b.id,
+                            dwell,
+                            goal: outgoing.choose(rng).unwrap().clone(),
+                            origin: None,
+                        },
+                    )],
+                });
+            }
+        }
+
+        timer.note(format!(
+            "{} deliveries generated, {} skipped because buildings aren't reachable by car",
+            prettyprint_usize(s.people.len()),
+            prettyprint_usize(skipped)
+        ));
+        s
+    }
+}
+
+fn rand_time(rng: &mut XorShiftRng, low: Time, high: Time) -> Time {
+    assert!(high > low);
+    Time::START_OF_DAY + Duration::seconds(rng.gen_range(low.inner_seconds(), high.inner_seconds()))
+}
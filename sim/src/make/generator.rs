@@ -10,7 +10,7 @@ use serde::{Deserialize, Serialize};
 
 use abstutil::Timer;
 use geom::{Duration, Time};
-use map_model::{BuildingID, DirectedRoadID, Map, PathConstraints};
+use map_model::{BikeRoutingProfile, BuildingID, DirectedRoadID, Map, PathConstraints};
 
 use crate::{
     DrivingGoal, IndividTrip, PersonID, PersonSpec, Scenario, SidewalkSpot, SpawnTrip, TripPurpose,
@@ -189,6 +189,7 @@ impl SpawnOverTime {
                 scenario.people.push(PersonSpec {
                     id,
                     orig_id: None,
+                    bike_profile: BikeRoutingProfile::default(),
                     trips: vec![IndividTrip::new(
                         depart,
                         TripPurpose::Shopping,
@@ -207,6 +208,7 @@ impl SpawnOverTime {
                 scenario.people.push(PersonSpec {
                     id,
                     orig_id: None,
+                    bike_profile: BikeRoutingProfile::default(),
                     trips: vec![IndividTrip::new(
                         depart,
                         TripPurpose::Shopping,
@@ -233,6 +235,7 @@ impl SpawnOverTime {
                     scenario.people.push(PersonSpec {
                         id,
                         orig_id: None,
+                        bike_profile: BikeRoutingProfile::default(),
                         trips: vec![IndividTrip::new(
                             depart,
                             TripPurpose::Shopping,
@@ -246,6 +249,7 @@ impl SpawnOverTime {
             scenario.people.push(PersonSpec {
                 id,
                 orig_id: None,
+                bike_profile: BikeRoutingProfile::default(),
                 trips: vec![IndividTrip::new(
                     depart,
                     TripPurpose::Shopping,
@@ -296,6 +300,7 @@ impl BorderSpawnOverTime {
                         scenario.people.push(PersonSpec {
                             id,
                             orig_id: None,
+                            bike_profile: BikeRoutingProfile::default(),
                             trips: vec![IndividTrip::new(
                                 depart,
                                 TripPurpose::Shopping,
@@ -315,6 +320,7 @@ impl BorderSpawnOverTime {
                 scenario.people.push(PersonSpec {
                     id,
                     orig_id: None,
+                    bike_profile: BikeRoutingProfile::default(),
                     trips: vec![IndividTrip::new(
                         depart,
                         TripPurpose::Shopping,
@@ -341,6 +347,7 @@ impl BorderSpawnOverTime {
                 scenario.people.push(PersonSpec {
                     id,
                     orig_id: None,
+                    bike_profile: BikeRoutingProfile::default(),
                     trips: vec![IndividTrip::new(
                         depart,
                         TripPurpose::Shopping,
@@ -17,6 +17,7 @@ pub use self::spawner::{TripSpawner, TripSpec};
 
 mod activity_model;
 mod external;
+mod freight;
 mod generator;
 mod load;
 mod modifier;
@@ -9,15 +9,15 @@ use serde::{Deserialize, Serialize};
 use abstutil::{prettyprint_usize, Counter, Timer};
 use geom::{Distance, Duration, LonLat, Speed, Time};
 use map_model::{
-    BuildingID, BusRouteID, BusStopID, DirectedRoadID, Map, OffstreetParking, PathConstraints,
-    Position, RoadID,
+    BikeRoutingProfile, BuildingID, BusRouteID, BusStopID, DirectedRoadID, Map, OffstreetParking,
+    PathConstraints, Position, RoadID,
 };
 
 use crate::make::fork_rng;
 use crate::{
     CarID, DrivingGoal, OrigPersonID, ParkingSpot, PersonID, SidewalkPOI, SidewalkSpot, Sim,
     TripEndpoint, TripMode, TripSpec, Vehicle, VehicleSpec, VehicleType, BIKE_LENGTH,
-    MAX_CAR_LENGTH, MIN_CAR_LENGTH, SPAWN_DIST,
+    MAX_CAR_LENGTH, MAX_TRUCK_LENGTH, MIN_CAR_LENGTH, MIN_TRUCK_LENGTH, SPAWN_DIST,
 };
 
 /// A Scenario describes all the input to a simulation. Usually a scenario covers one day.
@@ -36,6 +36,8 @@ pub struct PersonSpec {
     pub id: PersonID,
     /// Just used for debugging
     pub orig_id: Option<OrigPersonID>,
+    /// How much this person avoids stressful roads when biking
+    pub bike_profile: BikeRoutingProfile,
     pub trips: Vec<IndividTrip>,
 }
 
@@ -86,6 +88,15 @@ pub enum SpawnTrip {
         BusStopID,
         Option<BusStopID>,
     ),
+    /// A truck enters the map, stops at a building to make a delivery, then continues to its goal.
+    /// The goal must be a border.
+    Delivery {
+        dr: DirectedRoadID,
+        bldg: BuildingID,
+        dwell: Duration,
+        goal: DrivingGoal,
+        origin: Option<OffMapLocation>,
+    },
     /// Completely off-map trip. Don't really simulate much of it.
     Remote {
         from: OffMapLocation,
@@ -115,6 +126,8 @@ pub enum TripPurpose {
     Recreation,
     Medical,
     ParkAndRideTransfer,
+    /// A freight vehicle dropping something off
+    Delivery,
 }
 
 impl fmt::Display for TripPurpose {
@@ -135,6 +148,7 @@ impl fmt::Display for TripPurpose {
                 TripPurpose::Recreation => "recreation",
                 TripPurpose::Medical => "medical",
                 TripPurpose::ParkAndRideTransfer => "park-and-ride transfer",
+                TripPurpose::Delivery => "delivery",
             }
         )
     }
@@ -176,6 +190,7 @@ impl Scenario {
                 p.id,
                 p.orig_id,
                 Scenario::rand_ped_speed(rng),
+                p.bike_profile,
                 vehicle_specs,
             );
             let person = sim.get_person(p.id);
@@ -237,6 +252,15 @@ impl Scenario {
         }
     }
 
+    pub fn rand_truck(rng: &mut XorShiftRng) -> VehicleSpec {
+        let length = Scenario::rand_dist(rng, MIN_TRUCK_LENGTH, MAX_TRUCK_LENGTH);
+        VehicleSpec {
+            vehicle_type: VehicleType::Truck,
+            length,
+            max_speed: None,
+        }
+    }
+
     pub fn rand_bike(rng: &mut XorShiftRng) -> VehicleSpec {
         let max_speed = Some(Scenario::rand_speed(
             rng,
@@ -250,7 +274,7 @@ impl Scenario {
         }
     }
     pub fn max_bike_speed() -> Speed {
-        Speed::miles_per_hour(10.0)
+        map_model::max_biking_speed()
     }
 
     pub fn rand_dist(rng: &mut XorShiftRng, low: Distance, high: Distance) -> Distance {
@@ -270,7 +294,7 @@ impl Scenario {
         Scenario::rand_speed(rng, Speed::miles_per_hour(2.0), Speed::miles_per_hour(3.0))
     }
     pub fn max_ped_speed() -> Speed {
-        Speed::miles_per_hour(3.0)
+        map_model::max_walking_speed()
     }
 
     pub fn count_parked_cars_per_bldg(&self) -> Counter<BuildingID> {
@@ -338,6 +362,10 @@ fn seed_parked_cars(
         BTreeMap::new();
     for spot in sim.get_all_parking_spots().1 {
         let (r, restriction) = match spot {
+            // Loading zones are only for deliveries
+            ParkingSpot::Onstreet(l, _) if map.get_l(l).is_loading_zone() => {
+                continue;
+            }
             ParkingSpot::Onstreet(l, _) => (map.get_l(l).parent, None),
             ParkingSpot::Offstreet(b, _) => (
                 map.get_l(map.get_b(b).sidewalk()).parent,
@@ -472,6 +500,33 @@ impl SpawnTrip {
                     }
                 }
             }
+            SpawnTrip::Delivery {
+                dr,
+                bldg,
+                dwell,
+                goal,
+                origin,
+            } => {
+                if let Some(l) = dr.lanes(PathConstraints::Car, map).choose(rng) {
+                    TripSpec::Delivery {
+                        start_pos: Position::new(*l, SPAWN_DIST),
+                        bldg,
+                        dwell,
+                        goal,
+                        use_vehicle: use_vehicle.unwrap(),
+                        retry_if_no_room: true,
+                        origin,
+                    }
+                } else {
+                    TripSpec::NoRoomToSpawn {
+                        i: dr.src_i(map),
+                        goal,
+                        use_vehicle: use_vehicle.unwrap(),
+                        origin,
+                        error: format!("{} has no lanes to spawn a truck", dr.id),
+                    }
+                }
+            }
             SpawnTrip::UsingParkedCar(start_bldg, goal) => TripSpec::UsingParkedCar {
                 start_bldg,
                 goal,
@@ -523,7 +578,7 @@ impl SpawnTrip {
                     TripMode::Drive
                 }
             }
-            SpawnTrip::UsingParkedCar(_, _) => TripMode::Drive,
+            SpawnTrip::UsingParkedCar(_, _) | SpawnTrip::Delivery { .. } => TripMode::Drive,
             SpawnTrip::UsingBike(_, _) => TripMode::Bike,
             SpawnTrip::JustWalking(_, _) => TripMode::Walk,
             SpawnTrip::UsingTransit(_, _, _, _, _) => TripMode::Transit,
@@ -537,7 +592,8 @@ impl SpawnTrip {
             SpawnTrip::VehicleAppearing { ref start, .. } => {
                 TripEndpoint::Border(map.get_l(start.lane()).src_i, None)
             }
-            SpawnTrip::FromBorder { dr, ref origin, .. } => {
+            SpawnTrip::FromBorder { dr, ref origin, .. }
+            | SpawnTrip::Delivery { dr, ref origin, .. } => {
                 TripEndpoint::Border(dr.src_i(map), origin.clone())
             }
             SpawnTrip::UsingParkedCar(b, _) => TripEndpoint::Bldg(*b),
@@ -564,7 +620,8 @@ impl SpawnTrip {
             SpawnTrip::VehicleAppearing { ref goal, .. }
             | SpawnTrip::FromBorder { ref goal, .. }
             | SpawnTrip::UsingParkedCar(_, ref goal)
-            | SpawnTrip::UsingBike(_, ref goal) => match goal {
+            | SpawnTrip::UsingBike(_, ref goal)
+            | SpawnTrip::Delivery { ref goal, .. } => match goal {
                 DrivingGoal::ParkNear(b) => TripEndpoint::Bldg(*b),
                 DrivingGoal::Border(i, _, ref loc) => TripEndpoint::Border(*i, loc.clone()),
             },
@@ -687,6 +744,7 @@ impl PersonSpec {
         let mut vehicle_foreach_trip = Vec::new();
 
         let mut bike_idx = None;
+        let mut truck_idx = None;
         // For each indexed car, is it parked somewhere, or off-map?
         let mut car_locations: Vec<(usize, Option<BuildingID>)> = Vec::new();
 
@@ -770,6 +828,14 @@ impl PersonSpec {
                     }
                     bike_idx
                 }
+                SpawnTrip::Delivery { .. } => {
+                    // Deliveries start and end off-map, so one truck can make all of them
+                    if truck_idx.is_none() {
+                        truck_idx = Some(vehicle_specs.len());
+                        vehicle_specs.push(Scenario::rand_truck(rng));
+                    }
+                    truck_idx
+                }
                 SpawnTrip::JustWalking(_, _) | SpawnTrip::UsingTransit(_, _, _, _, _) => None,
                 SpawnTrip::Remote { .. } => None,
             };
@@ -26,6 +26,18 @@ pub enum TripSpec {
         retry_if_no_room: bool,
         origin: Option<OffMapLocation>,
     },
+    /// A truck appears somewhere, stops near a building to make a delivery, then drives to some
+    /// goal.
+    Delivery {
+        start_pos: Position,
+        bldg: BuildingID,
+        dwell: Duration,
+        goal: DrivingGoal,
+        /// This must be a currently off-map truck owned by the person.
+        use_vehicle: CarID,
+        retry_if_no_room: bool,
+        origin: Option<OffMapLocation>,
+    },
     /// A VehicleAppearing that failed to even pick a start_pos, because of a bug with badly chosen
     /// borders.
     NoRoomToSpawn {
@@ -133,6 +145,37 @@ impl TripSpawner {
                     };
                 }
             }
+            TripSpec::Delivery {
+                start_pos,
+                bldg,
+                goal,
+                use_vehicle,
+                origin,
+                ..
+            } => {
+                if start_pos.dist_along() >= map.get_l(start_pos.lane()).length() {
+                    panic!("Can't spawn at {}; it isn't that long", start_pos);
+                }
+                // Trucks don't fit in normal parking spots
+                if let DrivingGoal::ParkNear(b) = goal {
+                    panic!(
+                        "A delivery to {} must leave via a border, not park near {}",
+                        bldg, b
+                    );
+                }
+
+                if map.get_b(*bldg).driving_connection(map).is_none()
+                    || goal.goal_pos(PathConstraints::Car, map).is_none()
+                {
+                    spec = TripSpec::NoRoomToSpawn {
+                        i: map.get_l(start_pos.lane()).src_i,
+                        goal: goal.clone(),
+                        use_vehicle: use_vehicle.clone(),
+                        origin: origin.clone(),
+                        error: format!("can't drive to {} and then {:?}", bldg, goal),
+                    };
+                }
+            }
             TripSpec::NoRoomToSpawn { .. } => {}
             TripSpec::UsingParkedCar { .. } => {}
             TripSpec::JustWalking { start, goal, .. } => {
@@ -238,6 +281,18 @@ impl TripSpawner {
                         map,
                     )
                 }
+                TripSpec::Delivery {
+                    goal, use_vehicle, ..
+                } => trips.new_trip(
+                    person.id,
+                    start_time,
+                    trip_start,
+                    TripMode::Drive,
+                    purpose,
+                    modified,
+                    vec![TripLeg::Drive(use_vehicle, goal.clone())],
+                    map,
+                ),
                 TripSpec::NoRoomToSpawn {
                     goal, use_vehicle, ..
                 } => {
@@ -390,6 +445,13 @@ impl TripSpec {
                     constraints,
                 })
             }
+            TripSpec::Delivery {
+                start_pos, bldg, ..
+            } => Some(PathRequest {
+                start: *start_pos,
+                end: map.get_b(*bldg).driving_connection(map).unwrap().0,
+                constraints: VehicleType::Truck.to_constraints(),
+            }),
             TripSpec::NoRoomToSpawn { .. } => None,
             // We don't know where the parked car will be
             TripSpec::UsingParkedCar { .. } => None,
@@ -3,14 +3,14 @@ use std::collections::VecDeque;
 use serde::{Deserialize, Serialize};
 
 use geom::{Distance, Duration, PolyLine, Time};
-use map_model::{Direction, Map, Traversable};
+use map_model::{bike_speed_on_grade, Direction, Map, Traversable};
 
 use crate::{
     CarStatus, DistanceInterval, DrawCarInput, ParkingSpot, PersonID, Router, TimeInterval,
     TransitSimState, TripID, Vehicle, VehicleType,
 };
 
-/// Represents a single vehicle. Note "car" is a misnomer; it could also be a bus or bike.
+/// Represents a single vehicle. Note "car" is a misnomer; it could also be a bus, bike, or truck.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Car {
     pub vehicle: Vehicle,
@@ -41,6 +41,24 @@ impl Car {
         self.crossing_state_with_end_dist(dist_int, start_time, map)
     }
 
+    /// Like crossing_state, but the car is starting from rest, so sluggish vehicles take longer.
+    pub fn departing_state(&self, start_dist: Distance, start_time: Time, map: &Map) -> CarState {
+        match self.crossing_state(start_dist, start_time, map) {
+            CarState::Crossing(time_int, dist_int) => {
+                let mut speed = self.router.head().speed_limit(map);
+                if let Some(s) = self.vehicle.max_speed {
+                    speed = speed.min(s);
+                }
+                let delay = self.vehicle.vehicle_type.startup_delay(speed);
+                CarState::Crossing(
+                    TimeInterval::new(time_int.start, time_int.end + delay),
+                    dist_int,
+                )
+            }
+            _ => unreachable!(),
+        }
+    }
+
     pub fn crossing_state_with_end_dist(
         &self,
         dist_int: DistanceInterval,
@@ -52,6 +70,9 @@ impl Car {
         if let Some(s) = self.vehicle.max_speed {
             speed = speed.min(s);
         }
+        if self.vehicle.vehicle_type == VehicleType::Bike {
+            speed = bike_speed_on_grade(speed, on.percent_grade(map)).min(on.speed_limit(map));
+        }
         let dt = (dist_int.end - dist_int.start) / speed;
         CarState::Crossing(TimeInterval::new(start_time, start_time + dt), dist_int)
     }
@@ -217,7 +238,7 @@ impl Car {
                 CarState::Crossing(_, _) => CarStatus::Moving,
                 CarState::Unparking(_, _, _) => CarStatus::Moving,
                 CarState::Parking(_, _, _) => CarStatus::Moving,
-                // Changing color for idling buses is helpful
+                // Changing color for idling buses and double-parked trucks is helpful
                 CarState::IdlingAtStop(_, _) => CarStatus::Parked,
             },
             show_parking_intent: match (self.is_parking(), &self.state) {
@@ -263,6 +284,7 @@ pub enum CarState {
     /// Where's the front of the car while this is happening?
     Unparking(Distance, ParkingSpot, TimeInterval),
     Parking(Distance, ParkingSpot, TimeInterval),
+    /// A bus waiting at a stop, or a truck double-parked to make a delivery
     IdlingAtStop(Distance, TimeInterval),
 }
 
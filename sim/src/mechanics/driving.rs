@@ -4,7 +4,7 @@ use serde::{Deserialize, Serialize};
 
 use abstutil::{deserialize_hashmap, serialize_hashmap, FixedMap, IndexableKey};
 use geom::{Distance, Duration, PolyLine, Speed, Time};
-use map_model::{IntersectionID, LaneID, Map, Path, PathStep, Traversable};
+use map_model::{IntersectionID, LaneID, Map, Path, PathRequest, PathStep, Traversable};
 
 use crate::mechanics::car::{Car, CarState};
 use crate::mechanics::Queue;
@@ -317,15 +317,20 @@ impl DrivingSimState {
                         &mut self.events,
                     );
                 }
-                car.state = car.crossing_state(front, now, ctx.map);
+                car.state = car.departing_state(front, now, ctx.map);
                 ctx.scheduler
                     .push(car.state.get_end_time(), Command::UpdateCar(car.vehicle.id));
             }
             CarState::IdlingAtStop(dist, _) => {
-                car.router = transit.bus_departed_from_stop(car.vehicle.id, ctx.map);
+                car.router = if car.vehicle.vehicle_type.is_transit() {
+                    transit.bus_departed_from_stop(car.vehicle.id, ctx.map)
+                } else {
+                    // A double-parked truck finished its delivery
+                    car.router.after_delivery(ctx.map)
+                };
                 self.events
                     .push(Event::PathAmended(car.router.get_path().clone()));
-                car.state = car.crossing_state(dist, now, ctx.map);
+                car.state = car.departing_state(dist, now, ctx.map);
                 ctx.scheduler
                     .push(car.state.get_end_time(), Command::UpdateCar(car.vehicle.id));
 
@@ -369,6 +374,10 @@ impl DrivingSimState {
             CarState::Queued { .. } => unreachable!(),
             CarState::WaitingToAdvance { blocked_since } => {
                 // 'car' is the leader.
+                if car.router.reroute_around_time_rules(now, ctx.map) {
+                    self.events
+                        .push(Event::PathAmended(car.router.get_path().clone()));
+                }
                 let from = car.router.head();
                 let goto = car.router.next();
                 assert!(from != goto);
@@ -417,7 +426,11 @@ impl DrivingSimState {
                     &mut self.events,
                 );
                 car.total_blocked_time += now - blocked_since;
-                car.state = car.crossing_state(Distance::ZERO, now, ctx.map);
+                car.state = if now > blocked_since {
+                    car.departing_state(Distance::ZERO, now, ctx.map)
+                } else {
+                    car.crossing_state(Distance::ZERO, now, ctx.map)
+                };
                 ctx.scheduler
                     .push(car.state.get_end_time(), Command::UpdateCar(car.vehicle.id));
                 self.events.push(Event::AgentEntersTraversable(
@@ -567,6 +580,14 @@ impl DrivingSimState {
                             false
                         }
                     }
+                    Some(ActionAtEnd::StopToDeliver(dwell)) => {
+                        car.total_blocked_time += now - blocked_since;
+                        car.state =
+                            CarState::IdlingAtStop(our_dist, TimeInterval::new(now, now + dwell));
+                        ctx.scheduler
+                            .push(car.state.get_end_time(), Command::UpdateCar(car.vehicle.id));
+                        true
+                    }
                     None => {
                         ctx.scheduler.push(
                             now + BLIND_RETRY_TO_REACH_END_DIST,
@@ -602,6 +623,38 @@ impl DrivingSimState {
                     spot,
                     parked_since: now,
                 });
+                if let Some(dwell) = car.router.delivery_dwell_time() {
+                    // The truck pulled into a loading zone. After the delivery, it continues the
+                    // same trip.
+                    trips.truck_reached_loading_zone(car.vehicle.id, car.total_blocked_time);
+                    let router = car.router.after_delivery(ctx.map);
+                    let start = ctx.parking.spot_to_driving_pos(spot, &car.vehicle, ctx.map);
+                    let req = PathRequest {
+                        start,
+                        end: car.router.delivery_exit_pos(ctx.map),
+                        constraints: car.vehicle.vehicle_type.to_constraints(),
+                    };
+                    let start_dist = start.dist_along();
+                    let (trip, person) = car.trip_and_person.unwrap();
+                    ctx.scheduler.push(
+                        now + dwell,
+                        Command::SpawnCar(
+                            CreateCar::for_parked_car(
+                                ctx.parking
+                                    .lookup_parked_car(car.vehicle.id)
+                                    .unwrap()
+                                    .clone(),
+                                router,
+                                req,
+                                start_dist,
+                                trip,
+                                person,
+                            ),
+                            true,
+                        ),
+                    );
+                    return false;
+                }
                 trips.car_reached_parking_spot(
                     now,
                     car.vehicle.id,
@@ -5,8 +5,8 @@ use serde::{Deserialize, Serialize};
 use abstutil::{deserialize_btreemap, retain_btreeset, serialize_btreemap, FixedMap};
 use geom::{Duration, Time};
 use map_model::{
-    ControlStopSign, ControlTrafficSignal, IntersectionID, LaneID, Map, PhaseType, Traversable,
-    TurnID, TurnPriority, TurnType,
+    ControlStopSign, ControlTrafficSignal, CrossingType, IntersectionID, LaneID, Map, PathStep,
+    PhaseType, Traversable, TurnID, TurnPriority, TurnType,
 };
 
 use crate::mechanics::car::Car;
@@ -209,6 +209,19 @@ impl IntersectionSimState {
                     TurnPriority::Banned => unreachable!(),
                 }
             }
+        } else if map.get_i(i).is_roundabout() {
+            let roundabout = map.get_i(i);
+            for (req, _) in all {
+                match roundabout.roundabout_priority(req.turn, map) {
+                    TurnPriority::Protected => {
+                        protected.push(req);
+                    }
+                    TurnPriority::Yield => {
+                        yielding.push(req);
+                    }
+                    TurnPriority::Banned => unreachable!(),
+                }
+            }
         } else {
             // This could either be a border intersection or an intersection that was just closed
             // in the middle of simulation. In either case, there shouldn't be any other turns at
@@ -310,6 +323,17 @@ impl IntersectionSimState {
             .entry(req.clone())
             .or_insert(now);
 
+        // Vehicles try to reroute around lanes closed by time-of-day rules before getting here. If
+        // there's no other way, they have to wait until the rules change.
+        if let Some((car, _, _)) = maybe_cars_and_queues.as_ref() {
+            if car.router.get_path().currently_inside_ut().is_none() {
+                if let Some(retry_at) = blocked_by_time_rules(car, turn, now, map) {
+                    scheduler.update(retry_at, Command::update_agent(agent));
+                    return false;
+                }
+            }
+        }
+
         let shared_sidewalk_corner =
             map.get_t(req.turn).turn_type == TurnType::SharedSidewalkCorner;
 
@@ -346,6 +370,8 @@ impl IntersectionSimState {
             self.traffic_signal_policy(&req, map, signal, speed, now, Some(scheduler))
         } else if let Some(ref sign) = map.maybe_get_stop_sign(turn.parent) {
             self.stop_sign_policy(&req, map, sign, now, scheduler)
+        } else if map.get_i(turn.parent).is_roundabout() {
+            self.roundabout_policy(&req, map)
         } else {
             unreachable!()
         };
@@ -583,6 +609,34 @@ impl IntersectionSimState {
     }
 }
 
+/// If time-of-day rules currently prevent this car from making this turn, returns when they next
+/// change.
+fn blocked_by_time_rules(car: &Car, turn: TurnID, now: Time, map: &Map) -> Option<Time> {
+    let lane = map.get_l(turn.dst);
+    let road = map.get_r(lane.parent);
+    if road.time_rules.is_empty() {
+        return None;
+    }
+    let constraints = car.vehicle.vehicle_type.to_constraints();
+    let mut blocked = !constraints.can_use_at(lane, map, now);
+    // Like a private zone, vehicles can still enter if their destination is on the road.
+    let access = road.access_restrictions_at(now);
+    if access != &road.access_restrictions && !access.allow_through_traffic.contains(constraints) {
+        let ends_here = match car.router.get_path().last_step() {
+            PathStep::Lane(l) | PathStep::ContraflowLane(l) => map.get_l(l).parent == road.id,
+            PathStep::Turn(_) => false,
+        };
+        if !ends_here {
+            blocked = true;
+        }
+    }
+    if blocked {
+        road.time_rules.next_change(now)
+    } else {
+        None
+    }
+}
+
 impl IntersectionSimState {
     fn stop_sign_policy(
         &mut self,
@@ -622,9 +676,54 @@ impl IntersectionSimState {
         // TODO Make sure we can optimistically finish this turn before an approaching
         // higher-priority vehicle wants to begin.
 
+        // The one exception to the above: at crossings mapped in OSM, right-of-way between
+        // pedestrians and vehicles depends on the markings. Whoever is giving way will be woken up
+        // when the other agent finishes their turn.
+        if self.gives_way_at_crossing(req, map) {
+            return false;
+        }
+
         true
     }
 
+    /// Traffic entering a roundabout gives way to circulating traffic, but doesn't have to stop
+    /// first. Conflicts with turns already accepted are handled before this.
+    fn roundabout_policy(&self, req: &Request, map: &Map) -> bool {
+        let i = map.get_i(req.turn.parent);
+        if i.roundabout_priority(req.turn, map) == TurnPriority::Protected {
+            return true;
+        }
+        // Whoever is circulating will wake us up after finishing their turn.
+        let turn = map.get_t(req.turn);
+        !self.state[&i.id].waiting.keys().any(|other| {
+            other != req
+                && i.roundabout_priority(other.turn, map) == TurnPriority::Protected
+                && turn.conflicts_with(map.get_t(other.turn))
+        })
+    }
+
+    /// At marked crossings, vehicles give way to pedestrians waiting to cross. At unmarked
+    /// crossings, pedestrians wait for a gap in traffic.
+    fn gives_way_at_crossing(&self, req: &Request, map: &Map) -> bool {
+        let turn = map.get_t(req.turn);
+        let ours = turn.crossing_type(map);
+        self.state[&req.turn.parent].waiting.keys().any(|other| {
+            if other == req {
+                return false;
+            }
+            let other_turn = map.get_t(other.turn);
+            if !turn.conflicts_with(other_turn) {
+                return false;
+            }
+            // Crosswalks never conflict with each other, so whichever turn isn't a crossing belongs
+            // to a vehicle.
+            matches!(
+                (ours, other_turn.crossing_type(map)),
+                (None, Some(CrossingType::Marked)) | (Some(CrossingType::Unmarked), None)
+            )
+        })
+    }
+
     fn traffic_signal_policy(
         &mut self,
         req: &Request,
@@ -7,7 +7,7 @@ use abstutil::{
     deserialize_btreemap, deserialize_multimap, retain_btreemap, serialize_btreemap,
     serialize_multimap, MultiMap, Timer,
 };
-use geom::{Distance, PolyLine, Pt2D};
+use geom::{Distance, PolyLine, Pt2D, Time};
 use map_model::{
     BuildingID, Lane, LaneID, LaneType, Map, OffstreetParking, ParkingLotID, PathConstraints,
     PathStep, Position, Traversable, TurnID,
@@ -24,7 +24,18 @@ pub trait ParkingSim {
     /// Returns any cars that got very abruptly evicted from existence, and also cars actively
     /// moving into a deleted spot.
     fn handle_live_edits(&mut self, map: &Map, timer: &mut Timer) -> (Vec<ParkedCar>, Vec<CarID>);
+    /// Some parking lanes can't be used during parts of the day. Cars already parked there stay,
+    /// but nobody new can park.
+    fn update_time_rules(&mut self, map: &Map, now: Time);
     fn get_free_onstreet_spots(&self, l: LaneID) -> Vec<ParkingSpot>;
+    /// Free spots in loading zones beside a driving lane, and where the vehicle would stop to enter
+    /// them. Only delivery trucks use these.
+    fn get_free_loading_zone_spots(
+        &self,
+        driving_lane: LaneID,
+        vehicle: &Vehicle,
+        map: &Map,
+    ) -> Vec<(ParkingSpot, Position)>;
     fn get_free_offstreet_spots(&self, b: BuildingID) -> Vec<ParkingSpot>;
     fn get_free_lot_spots(&self, pl: ParkingLotID) -> Vec<ParkingSpot>;
     fn reserve_spot(&mut self, spot: ParkingSpot, car: CarID);
@@ -39,7 +50,7 @@ pub trait ParkingSim {
     fn is_free(&self, spot: ParkingSpot) -> bool;
     fn get_car_at_spot(&self, spot: ParkingSpot) -> Option<&ParkedCar>;
     /// The vehicle's front is currently at the given driving_pos. Returns all valid spots and their
-    /// driving position.
+    /// driving position. Loading zones aren't included.
     fn get_all_free_spots(
         &self,
         driving_pos: Position,
@@ -53,7 +64,7 @@ pub trait ParkingSim {
     fn spot_to_sidewalk_pos(&self, spot: ParkingSpot, map: &Map) -> Position;
     fn get_owner_of_car(&self, id: CarID) -> Option<PersonID>;
     fn lookup_parked_car(&self, id: CarID) -> Option<&ParkedCar>;
-    /// (Filled, available)
+    /// (Filled, available). Includes spots in loading zones.
     fn get_all_parking_spots(&self) -> (Vec<ParkingSpot>, Vec<ParkingSpot>);
     /// Unrealistically assumes the driver has knowledge of currently free parking spots, even if
     /// they're far away. Since they don't reserve the spot in advance, somebody else can still beat
@@ -141,6 +152,9 @@ pub struct NormalParkingSimState {
     )]
     driving_to_lots: MultiMap<LaneID, ParkingLotID>,
 
+    /// Parking lanes that currently act like some other lane type, because of time-of-day rules
+    closed_lanes: BTreeSet<LaneID>,
+
     events: Vec<Event>,
 }
 
@@ -158,6 +172,8 @@ impl NormalParkingSimState {
             num_spots_per_lot: BTreeMap::new(),
             driving_to_lots: MultiMap::new(),
 
+            closed_lanes: BTreeSet::new(),
+
             events: Vec::new(),
         };
         for l in map.all_lanes() {
@@ -230,8 +246,21 @@ impl ParkingSim for NormalParkingSimState {
         (evicted, moving_into_deleted_spot)
     }
 
+    fn update_time_rules(&mut self, map: &Map, now: Time) {
+        self.closed_lanes.clear();
+        for l in self.onstreet_lanes.keys() {
+            let lane = map.get_l(*l);
+            if map.get_r(lane.parent).lane_type_at(*l, now) != lane.lane_type {
+                self.closed_lanes.insert(*l);
+            }
+        }
+    }
+
     fn get_free_onstreet_spots(&self, l: LaneID) -> Vec<ParkingSpot> {
         let mut spots: Vec<ParkingSpot> = Vec::new();
+        if self.closed_lanes.contains(&l) {
+            return spots;
+        }
         if let Some(lane) = self.onstreet_lanes.get(&l) {
             for spot in lane.spots() {
                 if self.is_free(spot) {
@@ -242,6 +271,26 @@ impl ParkingSim for NormalParkingSimState {
         spots
     }
 
+    fn get_free_loading_zone_spots(
+        &self,
+        driving_lane: LaneID,
+        vehicle: &Vehicle,
+        map: &Map,
+    ) -> Vec<(ParkingSpot, Position)> {
+        let mut spots = Vec::new();
+        for l in self.driving_to_parking_lanes.get(driving_lane) {
+            if !self.onstreet_lanes[l].loading_zone || self.closed_lanes.contains(l) {
+                continue;
+            }
+            for spot in self.onstreet_lanes[l].spots() {
+                if self.is_free(spot) {
+                    spots.push((spot, self.spot_to_driving_pos(spot, vehicle, map)));
+                }
+            }
+        }
+        spots
+    }
+
     fn get_free_offstreet_spots(&self, b: BuildingID) -> Vec<ParkingSpot> {
         let mut spots: Vec<ParkingSpot> = Vec::new();
         for idx in 0..self.num_spots_per_offstreet.get(&b).cloned().unwrap_or(0) {
@@ -420,6 +469,9 @@ impl ParkingSim for NormalParkingSimState {
         let mut candidates = Vec::new();
 
         for l in self.driving_to_parking_lanes.get(driving_pos.lane()) {
+            if self.onstreet_lanes[l].loading_zone || self.closed_lanes.contains(l) {
+                continue;
+            }
             for spot in self.onstreet_lanes[l].spots() {
                 if self.is_free(spot)
                     && driving_pos.dist_along()
@@ -620,11 +672,13 @@ struct ParkingLane {
     sidewalk: LaneID,
     // The front of the parking spot (farthest along the lane)
     spot_dist_along: Vec<Distance>,
+    /// Reserved for deliveries
+    loading_zone: bool,
 }
 
 impl ParkingLane {
     fn new(lane: &Lane, map: &Map, timer: &mut Timer) -> Option<ParkingLane> {
-        if lane.lane_type != LaneType::Parking {
+        if lane.lane_type != LaneType::Parking && lane.lane_type != LaneType::LoadingZone {
             return None;
         }
 
@@ -655,6 +709,7 @@ impl ParkingLane {
             spot_dist_along: (0..lane.number_parking_spots())
                 .map(|idx| map_model::PARKING_SPOT_LENGTH * (2.0 + idx as f64))
                 .collect(),
+            loading_zone: lane.is_loading_zone(),
         })
     }
 
@@ -786,10 +841,21 @@ impl ParkingSim for InfiniteParkingSimState {
         (Vec::new(), Vec::new())
     }
 
+    fn update_time_rules(&mut self, _: &Map, _: Time) {}
+
     fn get_free_onstreet_spots(&self, _: LaneID) -> Vec<ParkingSpot> {
         Vec::new()
     }
 
+    fn get_free_loading_zone_spots(
+        &self,
+        _: LaneID,
+        _: &Vehicle,
+        _: &Map,
+    ) -> Vec<(ParkingSpot, Position)> {
+        Vec::new()
+    }
+
     fn get_free_offstreet_spots(&self, b: BuildingID) -> Vec<ParkingSpot> {
         // Just returns the next free spot
         vec![self.get_free_bldg_spot(b)]
@@ -5,8 +5,8 @@ use serde::{Deserialize, Serialize};
 use abstutil::{deserialize_multimap, serialize_multimap, FixedMap, IndexableKey, MultiMap};
 use geom::{Distance, Duration, Line, PolyLine, Speed, Time};
 use map_model::{
-    BuildingID, BusRouteID, DrivingSide, Map, ParkingLotID, Path, PathStep, Traversable,
-    SIDEWALK_THICKNESS,
+    walking_speed_on_grade, BuildingID, BusRouteID, DrivingSide, Map, ParkingLotID, Path, PathStep,
+    Traversable, SIDEWALK_THICKNESS,
 };
 
 use crate::sim::Ctx;
@@ -590,7 +590,13 @@ impl Pedestrian {
             }
         };
         let dist_int = DistanceInterval::new_walking(start_dist, end_dist);
-        let time_int = TimeInterval::new(start_time, start_time + dist_int.length() / self.speed);
+        // Walking against the direction of the sidewalk means facing the opposite grade
+        let mut grade = self.path.current_step().as_traversable().percent_grade(map);
+        if end_dist < start_dist {
+            grade = -grade;
+        }
+        let speed = walking_speed_on_grade(self.speed, grade);
+        let time_int = TimeInterval::new(start_time, start_time + dist_int.length() / speed);
         PedState::Crossing(dist_int, time_int)
     }
 
@@ -712,7 +718,10 @@ impl Pedestrian {
                 PedState::WaitingToTurn(_, _) => Some(self.path.next_step().as_turn()),
                 _ => None,
             },
-            preparing_bike: matches!(self.state, PedState::StartingToBike(_, _, _) | PedState::FinishingBiking(_, _, _)),
+            preparing_bike: matches!(
+                self.state,
+                PedState::StartingToBike(_, _, _) | PedState::FinishingBiking(_, _, _)
+            ),
             waiting_for_bus: matches!(self.state, PedState::WaitingForBus(_, _)),
             on,
         }
@@ -0,0 +1,127 @@
+//! Agent positions sampled at a fixed interval during a run, so the run can be played back and
+//! scrubbed through in either direction without re-simulating or loading savestates.
+
+use std::collections::HashMap;
+
+use geom::{Duration, Pt2D, Time};
+use map_model::Map;
+
+use crate::{AgentID, PersonID, Sim, UnzoomedAgent};
+
+/// Positions are rounded to this many meters. Agents are drawn as dots, so nobody will notice.
+const PRECISION_METERS: f64 = 0.1;
+
+pub struct Recording {
+    pub interval: Duration,
+    /// Every agent seen so far. Frames refer to agents by their index here, instead of repeating
+    /// the IDs every time.
+    agents: Vec<(AgentID, Option<PersonID>)>,
+    agent_indices: HashMap<AgentID, u32>,
+    frames: Vec<RecordedFrame>,
+}
+
+struct RecordedFrame {
+    time: Time,
+    agents: Vec<RecordedAgent>,
+}
+
+// A full UnzoomedAgent is several times bigger, and there are many thousands per frame.
+struct RecordedAgent {
+    idx: u32,
+    x: i32,
+    y: i32,
+    parking: bool,
+}
+
+impl Recording {
+    pub fn new(interval: Duration) -> Recording {
+        Recording {
+            interval,
+            agents: Vec::new(),
+            agent_indices: HashMap::new(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Call this as the simulation advances. A frame is captured once at least `interval` has
+    /// passed since the last one. If the simulation was reset to an earlier time, frames after it
+    /// are forgotten.
+    pub fn observe(&mut self, sim: &Sim, map: &Map) {
+        let now = sim.time();
+        while self.frames.last().map(|f| f.time > now).unwrap_or(false) {
+            self.frames.pop();
+        }
+        if let Some(last) = self.frames.last() {
+            if now - last.time < self.interval {
+                return;
+            }
+        }
+
+        let mut agents = Vec::new();
+        for a in sim.get_unzoomed_agents(map) {
+            let idx = match self.agent_indices.get(&a.id) {
+                Some(idx) => *idx,
+                None => {
+                    let idx = self.agents.len() as u32;
+                    self.agents.push((a.id, a.person));
+                    self.agent_indices.insert(a.id, idx);
+                    idx
+                }
+            };
+            agents.push(RecordedAgent {
+                idx,
+                x: (a.pos.x() / PRECISION_METERS).round() as i32,
+                y: (a.pos.y() / PRECISION_METERS).round() as i32,
+                parking: a.parking,
+            });
+        }
+        self.frames.push(RecordedFrame { time: now, agents });
+    }
+
+    /// The time range covered, if anything has been recorded.
+    pub fn time_range(&self) -> Option<(Time, Time)> {
+        Some((self.frames.first()?.time, self.frames.last()?.time))
+    }
+
+    /// The agents from the last frame captured at or before `time`, or the first frame if `time`
+    /// is earlier than everything recorded.
+    pub fn agents_at(&self, time: Time) -> Option<Vec<UnzoomedAgent>> {
+        if self.frames.is_empty() {
+            return None;
+        }
+        let idx = match self.frames.binary_search_by(|f| f.time.cmp(&time)) {
+            Ok(idx) => idx,
+            Err(0) => 0,
+            Err(idx) => idx - 1,
+        };
+        Some(self.expand(&self.frames[idx]))
+    }
+
+    /// The agents from every frame captured in the time range, inclusive.
+    pub fn agents_between(&self, start: Time, end: Time) -> Vec<Vec<UnzoomedAgent>> {
+        self.frames
+            .iter()
+            .filter(|f| f.time >= start && f.time <= end)
+            .map(|f| self.expand(f))
+            .collect()
+    }
+
+    fn expand(&self, frame: &RecordedFrame) -> Vec<UnzoomedAgent> {
+        frame
+            .agents
+            .iter()
+            .map(|a| {
+                let (id, person) = self.agents[a.idx as usize];
+                UnzoomedAgent {
+                    id,
+                    pos: Pt2D::new(
+                        (a.x as f64) * PRECISION_METERS,
+                        (a.y as f64) * PRECISION_METERS,
+                    ),
+                    person,
+                    parking: a.parking,
+                }
+            })
+            .collect()
+    }
+}
@@ -5,7 +5,7 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use geom::Distance;
+use geom::{Distance, Duration, Time};
 use map_model::{
     BuildingID, IntersectionID, LaneID, Map, Path, PathConstraints, PathRequest, PathStep,
     Position, Traversable, TurnID,
@@ -13,8 +13,8 @@ use map_model::{
 
 use crate::mechanics::Queue;
 use crate::{
-    AlertLocation, CarID, Event, ParkingSim, ParkingSimState, ParkingSpot, PersonID, SidewalkSpot,
-    TripID, TripPhaseType, Vehicle, VehicleType,
+    AlertLocation, CarID, DrivingGoal, Event, ParkingSim, ParkingSimState, ParkingSpot, PersonID,
+    SidewalkSpot, TripID, TripPhaseType, Vehicle, VehicleType,
 };
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -33,6 +33,8 @@ pub enum ActionAtEnd {
     StopBiking(SidewalkSpot),
     BusAtStop,
     GiveUpOnParking,
+    /// Double-park in the current lane to make a delivery
+    StopToDeliver(Duration),
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
@@ -55,6 +57,18 @@ enum Goal {
     FollowBusRoute {
         end_dist: Distance,
     },
+    /// Stop near a building for a while, pulling into a loading zone if one's free, otherwise
+    /// double-parking. Afterwards, follow another path to some goal.
+    Deliver {
+        target: BuildingID,
+        /// Where to double-park along the last lane
+        end_dist: Distance,
+        /// Loading zone spot and cached distance along the last lane
+        spot: Option<(ParkingSpot, Distance)>,
+        dwell: Duration,
+        then: DrivingGoal,
+        then_path: Path,
+    },
 }
 
 impl Router {
@@ -111,6 +125,29 @@ impl Router {
         }
     }
 
+    pub fn deliver(
+        owner: CarID,
+        path: Path,
+        target: BuildingID,
+        end_dist: Distance,
+        dwell: Duration,
+        then: DrivingGoal,
+        then_path: Path,
+    ) -> Router {
+        Router {
+            path,
+            goal: Goal::Deliver {
+                target,
+                end_dist,
+                spot: None,
+                dwell,
+                then,
+                then_path,
+            },
+            owner,
+        }
+    }
+
     pub fn head(&self) -> Traversable {
         self.path.current_step().as_traversable()
     }
@@ -139,6 +176,7 @@ impl Router {
             } => stuck_end_dist.unwrap_or_else(|| spot.unwrap().1),
             Goal::BikeThenStop { ref goal } => goal.sidewalk_pos.dist_along(),
             Goal::FollowBusRoute { end_dist } => end_dist,
+            Goal::Deliver { end_dist, spot, .. } => spot.map(|(_, d)| d).unwrap_or(end_dist),
         }
     }
 
@@ -325,6 +363,48 @@ impl Router {
                     None
                 }
             }
+            Goal::Deliver {
+                ref mut end_dist,
+                ref mut spot,
+                dwell,
+                ..
+            } => {
+                if let Some((s, _)) = spot {
+                    if !parking.is_free(*s) {
+                        // Somebody else took the loading zone first. If we already drove past
+                        // where we meant to double-park, just stop here instead.
+                        *spot = None;
+                        *end_dist = (*end_dist).max(front);
+                    }
+                }
+                if spot.is_none() {
+                    let current_lane = self.path.current_step().as_lane();
+                    let target_dist = *end_dist;
+                    *spot = parking
+                        .get_free_loading_zone_spots(current_lane, vehicle, map)
+                        .into_iter()
+                        .filter(|(_, pos)| pos.dist_along() >= front)
+                        .min_by_key(|(_, pos)| (pos.dist_along() - target_dist).abs())
+                        .map(|(s, pos)| (s, pos.dist_along()));
+                }
+
+                match spot {
+                    Some((s, dist)) => {
+                        if *dist == front {
+                            Some(ActionAtEnd::StartParking(*s))
+                        } else {
+                            None
+                        }
+                    }
+                    None => {
+                        if *end_dist == front {
+                            Some(ActionAtEnd::StopToDeliver(dwell))
+                        } else {
+                            None
+                        }
+                    }
+                }
+            }
         }
     }
 
@@ -424,6 +504,33 @@ impl Router {
         self.path.modify_step(3, PathStep::Turn(turn2.id), map);
     }
 
+    /// The contraction hierarchies don't know about time-of-day rules, so the next lane might be
+    /// closed to this vehicle by now. If so, find another way there. Returns true if the path
+    /// changed.
+    pub fn reroute_around_time_rules(&mut self, now: Time, map: &Map) -> bool {
+        let next_lane = match self.path.get_steps().get(2) {
+            Some(PathStep::Lane(l)) => *l,
+            _ => {
+                return false;
+            }
+        };
+        if map.get_parent(next_lane).time_rules.is_empty() {
+            return false;
+        }
+        let end_road = match self.path.last_step() {
+            PathStep::Lane(l) => map.get_l(l).parent,
+            _ => {
+                return false;
+            }
+        };
+        let constraints = self.owner.1.to_constraints();
+        let closed = constraints.lanes_closed_at(map, now, end_road);
+        if !closed.contains(&next_lane) {
+            return false;
+        }
+        self.path.reroute_avoiding(closed, constraints, map)
+    }
+
     pub fn replace_path_for_serialization(&mut self, path: Path) -> Path {
         std::mem::replace(&mut self.path, path)
     }
@@ -439,8 +546,38 @@ impl Router {
 
     pub fn get_parking_spot_goal(&self) -> Option<&ParkingSpot> {
         match self.goal {
-            Goal::ParkNearBuilding { ref spot, .. } => spot.as_ref().map(|(s, _)| s),
+            Goal::ParkNearBuilding { ref spot, .. } | Goal::Deliver { ref spot, .. } => {
+                spot.as_ref().map(|(s, _)| s)
+            }
             _ => None,
         }
     }
+
+    /// If this vehicle is making a delivery, how long does it stop for?
+    pub fn delivery_dwell_time(&self) -> Option<Duration> {
+        match self.goal {
+            Goal::Deliver { dwell, .. } => Some(dwell),
+            _ => None,
+        }
+    }
+
+    /// After making a delivery, head towards the final goal.
+    pub fn after_delivery(&self, map: &Map) -> Router {
+        match self.goal {
+            Goal::Deliver {
+                ref then,
+                ref then_path,
+                ..
+            } => then.make_router(self.owner, then_path.clone(), map),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Where a delivery truck eventually exits the map.
+    pub fn delivery_exit_pos(&self, map: &Map) -> Position {
+        match self.goal {
+            Goal::Deliver { ref then, .. } => then.goal_pos(PathConstraints::Car, map).unwrap(),
+            _ => unreachable!(),
+        }
+    }
 }
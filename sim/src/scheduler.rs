@@ -28,6 +28,8 @@ pub enum Command {
     FinishRemoteTrip(TripID),
     /// The Time is redundant, just used to dedupe commands
     StartBus(BusRouteID, Time),
+    /// Some road's time-of-day rules start or stop applying
+    UpdateTimeRules,
 }
 
 impl Command {
@@ -52,6 +54,7 @@ impl Command {
             Command::Pandemic(ref p) => CommandType::Pandemic(p.clone()),
             Command::FinishRemoteTrip(t) => CommandType::FinishRemoteTrip(*t),
             Command::StartBus(r, t) => CommandType::StartBus(*r, *t),
+            Command::UpdateTimeRules => CommandType::TimeRules,
         }
     }
 
@@ -68,6 +71,7 @@ impl Command {
             Command::Pandemic(_) => SimpleCommandType::Pandemic,
             Command::FinishRemoteTrip(_) => SimpleCommandType::FinishRemoteTrip,
             Command::StartBus(_, _) => SimpleCommandType::StartBus,
+            Command::UpdateTimeRules => SimpleCommandType::TimeRules,
         }
     }
 }
@@ -85,6 +89,7 @@ enum CommandType {
     Pandemic(pandemic::Cmd),
     FinishRemoteTrip(TripID),
     StartBus(BusRouteID, Time),
+    TimeRules,
 }
 
 /// A more compressed form of CommandType, just used for keeping stats on event processing.
@@ -99,6 +104,7 @@ enum SimpleCommandType {
     Pandemic,
     FinishRemoteTrip,
     StartBus,
+    TimeRules,
 }
 
 #[derive(Serialize, Deserialize, PartialEq, Eq, Clone)]
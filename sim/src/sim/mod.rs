@@ -1,8 +1,9 @@
 // This file has a jumbled mess of queries, setup, and mutating methods.
 
-use std::collections::{BTreeSet, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::panic;
 
+use enumset::EnumSet;
 use instant::Instant;
 use rand::SeedableRng;
 use rand_xorshift::XorShiftRng;
@@ -11,8 +12,8 @@ use serde::{Deserialize, Serialize};
 use abstutil::{prettyprint_usize, serialized_size_bytes, CmdArgs, Parallelism, Timer};
 use geom::{Distance, Duration, Speed, Time};
 use map_model::{
-    BuildingID, BusRoute, LaneID, Map, ParkingLotID, Path, PathConstraints, PathRequest, Position,
-    Traversable,
+    BikeRoutingProfile, BuildingID, BusRoute, IntersectionID, LaneID, Map, ParkingLotID, Path,
+    PathConstraints, PathRequest, Position, Traversable,
 };
 
 pub use self::queries::AgentProperties;
@@ -51,6 +52,8 @@ pub struct Sim {
     // TODO Maybe get rid of this, now that savestates aren't used
     run_name: String,
     step_count: usize,
+    // The modal filters as of the last live edits, to notice which ones change
+    modal_filters: BTreeMap<IntersectionID, EnumSet<PathConstraints>>,
 
     // Don't serialize, to reduce prebaked savestate size. Analytics are saved once covering the
     // full day and can be trimmed to any time.
@@ -191,7 +194,7 @@ impl SimOptions {
 impl Sim {
     pub fn new(map: &Map, opts: SimOptions, timer: &mut Timer) -> Sim {
         let mut scheduler = Scheduler::new();
-        Sim {
+        let mut sim = Sim {
             driving: DrivingSimState::new(map, &opts),
             parking: ParkingSimState::new(map, opts.infinite_parking, timer),
             walking: WalkingSimState::new(),
@@ -211,10 +214,13 @@ impl Sim {
             edits_name: map.get_edits().edits_name.clone(),
             run_name: opts.run_name,
             step_count: 0,
+            modal_filters: current_modal_filters(map),
             alerts: opts.alerts,
 
             analytics: Analytics::new(!opts.skip_analytics),
-        }
+        };
+        sim.update_time_rules(map);
+        sim
     }
 
     pub fn make_spawner(&self) -> TripSpawner {
@@ -298,9 +304,11 @@ impl Sim {
         p: PersonID,
         orig_id: Option<OrigPersonID>,
         ped_speed: Speed,
+        bike_profile: BikeRoutingProfile,
         vehicle_specs: Vec<VehicleSpec>,
     ) {
-        self.trips.new_person(p, orig_id, ped_speed, vehicle_specs);
+        self.trips
+            .new_person(p, orig_id, ped_speed, bike_profile, vehicle_specs);
     }
     pub fn random_person(&mut self, ped_speed: Speed, vehicle_specs: Vec<VehicleSpec>) -> &Person {
         self.trips.random_person(ped_speed, vehicle_specs)
@@ -320,6 +328,22 @@ impl Sim {
         }
     }
 
+    /// Apply the time-of-day rules for the current time, then wake up again when they next change.
+    fn update_time_rules(&mut self, map: &Map) {
+        self.parking.update_time_rules(map, self.time);
+        // Only one day is simulated, so don't keep the scheduler alive just for this.
+        if let Some(t) = map
+            .all_roads_with_time_rules()
+            .iter()
+            .filter_map(|r| map.get_r(*r).time_rules.next_change(self.time))
+            .min()
+        {
+            if t <= Time::START_OF_DAY + Duration::hours(24) {
+                self.scheduler.update(t, Command::UpdateTimeRules);
+            }
+        }
+    }
+
     fn start_bus(&mut self, route: &BusRoute, map: &Map) {
         // Spawn one bus for the first leg.
         let (req, path) = self.transit.create_empty_route(route, map);
@@ -437,13 +461,24 @@ impl Sim {
             Command::StartTrip(id, trip_spec) => {
                 self.trips.start_trip(self.time, id, trip_spec, &mut ctx);
             }
-            Command::SpawnCar(create_car, retry_if_no_room) => {
+            Command::SpawnCar(mut create_car, retry_if_no_room) => {
+                // The parked car might've changed since this command was scheduled -- like a
+                // delivery truck dwelling in a loading zone while the map is edited -- so look it
+                // up again.
+                let mut ok = true;
+                if let Some(id) = create_car.maybe_parked_car.as_ref().map(|p| p.vehicle.id) {
+                    create_car.maybe_parked_car = ctx.parking.lookup_parked_car(id).cloned();
+                    ok = create_car.maybe_parked_car.is_some();
+                }
+
                 // If this SpawnCar is being retried and the map was live-edited since the first
                 // attempt, the path might've become invalid. TODO Skip this check
                 // most of the time.
                 let constraints = create_car.vehicle.vehicle_type.to_constraints();
-                let mut ok = true;
                 for step in create_car.router.get_path().get_steps() {
+                    if !ok {
+                        break;
+                    }
                     match step.as_traversable() {
                         Traversable::Lane(l) => {
                             if !constraints.can_use(ctx.map.get_l(l), ctx.map) {
@@ -511,10 +546,10 @@ impl Sim {
                                 trip,
                                 person,
                                 Some(req),
-                                if id.1 == VehicleType::Car {
-                                    TripPhaseType::Driving
-                                } else {
+                                if id.1 == VehicleType::Bike {
                                     TripPhaseType::Biking
+                                } else {
+                                    TripPhaseType::Driving
                                 },
                             ));
                         }
@@ -618,6 +653,9 @@ impl Sim {
             Command::StartBus(r, _) => {
                 self.start_bus(map.get_br(r), map);
             }
+            Command::UpdateTimeRules => {
+                self.update_time_rules(map);
+            }
         }
 
         // Record events at precisely the time they occur.
@@ -956,6 +994,7 @@ impl Sim {
 
         self.driving.handle_live_edits(map);
         self.intersections.handle_live_edits(map);
+        self.update_time_rules(map);
 
         (num_trips_cancelled, num_parked_cars)
     }
@@ -978,7 +1017,32 @@ impl Sim {
                     closed_intersections.insert(*i);
                 }
             }
+            // Paths through a modal filter are only disallowed if this batch of edits started
+            // banning that mode there
+            let modal_filters = current_modal_filters(map);
+            let mut newly_banned: BTreeMap<IntersectionID, EnumSet<PathConstraints>> =
+                BTreeMap::new();
+            for (i, banned) in &modal_filters {
+                let before = self
+                    .modal_filters
+                    .get(i)
+                    .cloned()
+                    .unwrap_or_else(EnumSet::new);
+                let new = *banned - before;
+                if !new.is_empty() {
+                    newly_banned.insert(*i, new);
+                }
+            }
+            self.modal_filters = modal_filters;
+
             for (a, trip) in self.trips.active_agents_and_trips() {
+                let constraints = match a {
+                    AgentID::Car(car) => car.1.to_constraints(),
+                    AgentID::Pedestrian(_) => PathConstraints::Pedestrian,
+                    AgentID::BusPassenger(_, _) => {
+                        continue;
+                    }
+                };
                 if let Some(path) = self.get_path(*a) {
                     if path
                         .get_steps()
@@ -987,6 +1051,10 @@ impl Sim {
                             Traversable::Lane(l) => edited_lanes.contains(&l),
                             Traversable::Turn(t) => {
                                 closed_intersections.contains(&t.parent)
+                                    || newly_banned
+                                        .get(&t.parent)
+                                        .map(|banned| banned.contains(constraints))
+                                        .unwrap_or(false)
                                     || edited_lanes.contains(&t.src)
                                     || edited_lanes.contains(&t.dst)
                             }
@@ -1027,6 +1095,14 @@ impl Sim {
     }
 }
 
+fn current_modal_filters(map: &Map) -> BTreeMap<IntersectionID, EnumSet<PathConstraints>> {
+    map.all_intersections()
+        .iter()
+        .filter(|i| i.has_modal_filter())
+        .map(|i| (i.id, i.modal_filter))
+        .collect()
+}
+
 // Invasive debugging
 impl Sim {
     pub fn delete_car(&mut self, id: CarID, map: &Map) {
@@ -185,6 +185,7 @@ impl Sim {
             VehicleType::Bike,
             VehicleType::Bus,
             VehicleType::Train,
+            VehicleType::Truck,
         ] {
             let id = CarID(idx, *vt);
             if self.driving.does_car_exist(id) {
@@ -192,10 +193,12 @@ impl Sim {
             }
         }
 
-        let id = CarID(idx, VehicleType::Car);
-        // Only cars can be parked.
-        if self.parking.lookup_parked_car(id).is_some() {
-            return Some(id);
+        // Only cars can be parked. Trucks briefly park in loading zones.
+        for vt in &[VehicleType::Car, VehicleType::Truck] {
+            let id = CarID(idx, *vt);
+            if self.parking.lookup_parked_car(id).is_some() {
+                return Some(id);
+            }
         }
 
         None
@@ -5,15 +5,16 @@ use serde::{Deserialize, Serialize};
 use abstutil::{deserialize_btreemap, serialize_btreemap, Counter};
 use geom::{Duration, Speed, Time};
 use map_model::{
-    BuildingID, BusRouteID, BusStopID, IntersectionID, Map, PathConstraints, PathRequest, Position,
+    BikeRoutingProfile, BuildingID, BusRouteID, BusStopID, IntersectionID, Map, PathConstraints,
+    PathRequest, Position,
 };
 
 use crate::sim::Ctx;
 use crate::{
     AgentID, AgentType, AlertLocation, CarID, Command, CreateCar, CreatePedestrian, DrivingGoal,
     Event, IndividTrip, OffMapLocation, OrigPersonID, ParkedCar, ParkingSim, ParkingSpot,
-    PedestrianID, PersonID, PersonSpec, Scenario, Scheduler, SidewalkPOI, SidewalkSpot, SpawnTrip,
-    TransitSimState, TripID, TripPhaseType, TripPurpose, TripSpec, Vehicle, VehicleSpec,
+    PedestrianID, PersonID, PersonSpec, Router, Scenario, Scheduler, SidewalkPOI, SidewalkSpot,
+    SpawnTrip, TransitSimState, TripID, TripPhaseType, TripPurpose, TripSpec, Vehicle, VehicleSpec,
     VehicleType, WalkingSimState,
 };
 
@@ -58,6 +59,7 @@ impl TripManager {
         id: PersonID,
         orig_id: Option<OrigPersonID>,
         ped_speed: Speed,
+        bike_profile: BikeRoutingProfile,
         vehicle_specs: Vec<VehicleSpec>,
     ) {
         assert_eq!(id.0, self.people.len());
@@ -76,6 +78,7 @@ impl TripManager {
             state: PersonState::OffMap,
             ped: PedestrianID(id.0),
             ped_speed,
+            bike_profile,
             vehicles,
             delayed_trips: Vec::new(),
             on_bus: None,
@@ -83,7 +86,13 @@ impl TripManager {
     }
     pub fn random_person(&mut self, ped_speed: Speed, vehicle_specs: Vec<VehicleSpec>) -> &Person {
         let id = PersonID(self.people.len());
-        self.new_person(id, None, ped_speed, vehicle_specs);
+        self.new_person(
+            id,
+            None,
+            ped_speed,
+            BikeRoutingProfile::default(),
+            vehicle_specs,
+        );
         self.get_person(id).unwrap()
     }
 
@@ -240,6 +249,13 @@ impl TripManager {
         }
     }
 
+    /// A delivery truck pulled into a loading zone. It'll leave again later, continuing the same
+    /// trip.
+    pub fn truck_reached_loading_zone(&mut self, car: CarID, blocked_time: Duration) {
+        let trip = &mut self.trips[self.active_trip_mode.remove(&AgentID::Car(car)).unwrap().0];
+        trip.total_blocked_time += blocked_time;
+    }
+
     pub fn ped_reached_parking_spot(
         &mut self,
         now: Time,
@@ -376,8 +392,9 @@ impl TripManager {
             // TODO Convert to a walking trip!
             None
         } else {
+            let profile = self.people[trip.person.0].bike_profile;
             ctx.map
-                .pathfind(req.clone())
+                .pathfind_bike(req.clone(), profile)
                 .map(|path| drive_to.make_router(bike, path, ctx.map))
         };
         if let Some(router) = maybe_router {
@@ -919,6 +936,7 @@ impl TripManager {
             cyclists: 0,
 
             sov_drivers: 0,
+            trucks: 0,
 
             buses,
             trains,
@@ -935,6 +953,9 @@ impl TripManager {
                     VehicleType::Bike => {
                         cnt.cyclists += 1;
                     }
+                    VehicleType::Truck => {
+                        cnt.trucks += 1;
+                    }
                     VehicleType::Bus | VehicleType::Train => unreachable!(),
                 },
                 AgentID::BusPassenger(_, c) => match c.1 {
@@ -944,7 +965,7 @@ impl TripManager {
                     VehicleType::Train => {
                         cnt.train_riders += 1;
                     }
-                    VehicleType::Car | VehicleType::Bike => unreachable!(),
+                    VehicleType::Car | VehicleType::Bike | VehicleType::Truck => unreachable!(),
                 },
                 // These're counted separately
                 AgentID::Pedestrian(_) => {}
@@ -1060,7 +1081,15 @@ impl TripManager {
 
         // Defer calculating the path until now, to handle live map edits.
         let maybe_req = spec.get_pathfinding_request(ctx.map);
-        let maybe_path = maybe_req.clone().and_then(|req| ctx.map.pathfind(req));
+        let maybe_path = maybe_req.clone().and_then(|req| {
+            // Cyclists might prefer calmer streets
+            match spec {
+                TripSpec::VehicleAppearing { .. } if req.constraints == PathConstraints::Bike => {
+                    ctx.map.pathfind_bike(req, person.bike_profile)
+                }
+                _ => ctx.map.pathfind(req),
+            }
+        });
 
         match spec {
             TripSpec::VehicleAppearing {
@@ -1118,6 +1147,90 @@ impl TripManager {
                     }
                 }
             }
+            TripSpec::Delivery {
+                start_pos,
+                bldg,
+                dwell,
+                goal,
+                retry_if_no_room,
+                use_vehicle,
+                origin,
+            } => {
+                assert_eq!(person.state, PersonState::OffMap);
+                self.events.push(Event::PersonEntersMap(
+                    person.id,
+                    AgentID::Car(use_vehicle),
+                    ctx.map.get_l(start_pos.lane()).src_i,
+                    origin,
+                ));
+                person.state = PersonState::Trip(trip);
+
+                let vehicle = person.get_vehicle(use_vehicle);
+                assert!(ctx.parking.lookup_parked_car(vehicle.id).is_none());
+                let req = maybe_req.unwrap();
+                let person = person.id;
+                // Plan the path after the delivery now, so there's no chance of failing later.
+                let constraints = vehicle.vehicle_type.to_constraints();
+                let after_req = PathRequest {
+                    start: req.end,
+                    end: goal.goal_pos(constraints, ctx.map).unwrap(),
+                    constraints,
+                };
+                match maybe_path
+                    .ok_or_else(|| format!("Delivery trip couldn't find the first path: {}", req))
+                    .and_then(|path| {
+                        ctx.cap.validate_path(
+                            &req,
+                            path,
+                            now,
+                            vehicle.id,
+                            &mut self.trips[trip.0].info.capped,
+                            ctx.intersections,
+                            ctx.map,
+                        )
+                    })
+                    .and_then(|path| {
+                        let after_path = ctx.map.pathfind(after_req.clone()).ok_or_else(|| {
+                            format!(
+                                "Delivery trip couldn't find a path after the delivery: {}",
+                                after_req
+                            )
+                        })?;
+                        // The delivery happens later, but the truck ban doesn't depend on time
+                        let after_path = crate::cap::avoid_closed_lanes(
+                            &after_req,
+                            after_path,
+                            now,
+                            vehicle.vehicle_type,
+                            ctx.map,
+                        );
+                        Ok((path, after_path))
+                    }) {
+                    Ok((path, after_path)) => {
+                        let router = Router::deliver(
+                            vehicle.id,
+                            path,
+                            bldg,
+                            req.end.dist_along(),
+                            dwell,
+                            goal,
+                            after_path,
+                        );
+                        ctx.scheduler.push(
+                            now,
+                            Command::SpawnCar(
+                                CreateCar::for_appearing(
+                                    vehicle, start_pos, router, req, trip, person,
+                                ),
+                                retry_if_no_room,
+                            ),
+                        );
+                    }
+                    Err(err) => {
+                        self.cancel_trip(now, trip, err, Some(vehicle), ctx);
+                    }
+                }
+            }
             TripSpec::NoRoomToSpawn {
                 i,
                 use_vehicle,
@@ -1403,6 +1516,7 @@ impl TripManager {
             scenario.people.push(PersonSpec {
                 id: p.id,
                 orig_id: p.orig_id,
+                bike_profile: p.bike_profile,
                 trips: p
                     .trips
                     .iter()
@@ -1695,6 +1809,7 @@ pub struct Person {
 
     pub ped: PedestrianID,
     pub ped_speed: Speed,
+    pub bike_profile: BikeRoutingProfile,
     /// Both cars and bikes
     pub vehicles: Vec<Vehicle>,
 
@@ -1761,6 +1876,7 @@ pub struct CommutersVehiclesCounts {
     pub cyclists: usize,
 
     pub sov_drivers: usize,
+    pub trucks: usize,
 
     pub buses: usize,
     pub trains: usize,
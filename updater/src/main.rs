@@ -183,6 +183,8 @@ fn generate_manifest() -> Manifest {
         if path.contains("system/assets/")
             || path.contains("system/fonts")
             || path.contains("system/proposals")
+            || path.contains("system/themes")
+            || path.contains("system/translations")
             || path.contains("/polygons/")
         {
             continue;
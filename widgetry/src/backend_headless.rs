@@ -0,0 +1,108 @@
+//! A backend that never opens a window or touches the GPU. Uploads and draw calls are only
+//! counted. This is used by `testing::Harness` to exercise states and panels from tests.
+
+use std::cell::Cell;
+use std::marker::PhantomData;
+
+use crate::drawing::Uniforms;
+use crate::{Canvas, Color, GeomBatch, ScreenDims, ScreenRectangle};
+
+/// There's no window to open; use `testing::Harness` instead of `widgetry::run`.
+pub fn setup(_: &str) -> (PrerenderInnards, winit::event_loop::EventLoop<()>) {
+    panic!("widgetry was built with the headless backend, which can't open a window");
+}
+
+// Represents one frame that's gonna be drawn
+pub struct GfxCtxInnards<'a> {
+    current_clip: Option<[i32; 4]>,
+    _lifetime: PhantomData<&'a ()>,
+}
+
+impl<'a> GfxCtxInnards<'a> {
+    pub fn clear(&mut self, _: Color) {}
+
+    pub fn redraw(&mut self, _: &Drawable, _: &Uniforms, prerender: &PrerenderInnards) {
+        prerender
+            .num_draw_calls
+            .set(prerender.num_draw_calls.get() + 1);
+    }
+
+    pub fn enable_clipping(&mut self, rect: ScreenRectangle, scale_factor: f64, canvas: &Canvas) {
+        assert!(self.current_clip.is_none());
+        let left = (rect.x1 * scale_factor) as i32;
+        let bottom = ((canvas.window_height - rect.y2) * scale_factor) as i32;
+        let width = ((rect.x2 - rect.x1) * scale_factor) as i32;
+        let height = ((rect.y2 - rect.y1) * scale_factor) as i32;
+        self.current_clip = Some([left, bottom, width, height]);
+    }
+
+    pub fn disable_clipping(&mut self, _: f64, _: &Canvas) {
+        assert!(self.current_clip.is_some());
+        self.current_clip = None;
+    }
+
+    pub fn take_clip(&mut self, scale_factor: f64, canvas: &Canvas) -> Option<[i32; 4]> {
+        let clip = self.current_clip?;
+        self.disable_clipping(scale_factor, canvas);
+        Some(clip)
+    }
+
+    pub fn restore_clip(&mut self, clip: Option<[i32; 4]>) {
+        self.current_clip = clip;
+    }
+}
+
+// Something that would've been sent to the GPU.
+pub struct Drawable {}
+
+pub struct PrerenderInnards {
+    window_size: Cell<ScreenDims>,
+    pub(crate) num_draw_calls: Cell<usize>,
+    pub(crate) cursor_icon: Cell<winit::window::CursorIcon>,
+
+    pub total_bytes_uploaded: Cell<usize>,
+}
+
+impl PrerenderInnards {
+    pub fn new(window_size: ScreenDims) -> PrerenderInnards {
+        PrerenderInnards {
+            window_size: Cell::new(window_size),
+            num_draw_calls: Cell::new(0),
+            cursor_icon: Cell::new(winit::window::CursorIcon::Default),
+            total_bytes_uploaded: Cell::new(0),
+        }
+    }
+
+    pub fn actually_upload(&self, _: bool, _: GeomBatch) -> Drawable {
+        Drawable {}
+    }
+
+    pub fn request_redraw(&self) {}
+
+    pub fn set_cursor_icon(&self, icon: winit::window::CursorIcon) {
+        self.cursor_icon.set(icon);
+    }
+
+    pub fn draw_new_frame(&self) -> GfxCtxInnards {
+        GfxCtxInnards {
+            current_clip: None,
+            _lifetime: PhantomData,
+        }
+    }
+
+    pub fn window_resized(&self, new_size: ScreenDims, _: f64) {
+        self.window_size.set(new_size);
+    }
+
+    pub fn window_size(&self, _: f64) -> ScreenDims {
+        self.window_size.get()
+    }
+
+    pub fn set_window_icon(&self, _: winit::window::Icon) {}
+
+    pub fn monitor_scale_factor(&self) -> f64 {
+        1.0
+    }
+
+    pub fn draw_finished(&self, _: GfxCtxInnards) {}
+}
@@ -165,7 +165,7 @@ impl LinearGradient {
         Fill::LinearGradient(LinearGradient { line, stops })
     }
 
-    fn interp(&self, pt: Pt2D) -> Color {
+    pub(crate) fn interp(&self, pt: Pt2D) -> Color {
         let pct = self
             .line
             .percent_along_of_point(self.line.project_pt(pt))
@@ -0,0 +1,200 @@
+//! Translate UI strings. Messages are keyed by their English text, gettext-style, so anything
+//! without a translation is just shown in English. `Line` translates automatically, which covers
+//! most labels and buttons -- and since a button's action is still the English label, callers
+//! matching on `Outcome::Clicked` don't change. Messages that depend on a number should use
+//! `tr_n`, so each language can apply its own plural rules.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+thread_local! {
+    static CATALOG: RefCell<Option<Catalog>> = RefCell::new(None);
+}
+
+/// All of the translations for one language.
+#[derive(Serialize, Deserialize)]
+pub struct Catalog {
+    /// A code like "es" or "pt-BR". The part before any '-' picks the plural rules.
+    pub language: String,
+    pub messages: BTreeMap<String, Message>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Message {
+    Simple(String),
+    /// Different forms depending on a number. "other" is required.
+    Plural(BTreeMap<PluralCategory, String>),
+}
+
+/// The CLDR plural categories. Most languages only use a few of these.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PluralCategory {
+    Zero,
+    One,
+    Two,
+    Few,
+    Many,
+    Other,
+}
+
+impl Catalog {
+    pub fn load(path: String) -> Result<Catalog, String> {
+        let catalog: Catalog =
+            abstutil::maybe_read_json(path.clone(), &mut abstutil::Timer::throwaway())
+                .map_err(|err| format!("Couldn't load {}: {}", path, err))?;
+        for (key, msg) in &catalog.messages {
+            if let Message::Plural(ref forms) = msg {
+                if !forms.contains_key(&PluralCategory::Other) {
+                    return Err(format!("{}: {:?} is missing the \"other\" form", path, key));
+                }
+            }
+        }
+        Ok(catalog)
+    }
+}
+
+/// Switches the language for everything built afterwards. None means English. Existing panels
+/// keep their old text until they're rebuilt.
+pub fn set_catalog(catalog: Option<Catalog>) {
+    CATALOG.with(|c| *c.borrow_mut() = catalog);
+}
+
+/// The language of the current catalog, or None for English.
+pub fn current_language() -> Option<String> {
+    CATALOG.with(|c| c.borrow().as_ref().map(|c| c.language.clone()))
+}
+
+/// Translates a message, falling back to the English key.
+pub fn tr(msg: &str) -> String {
+    lookup(msg).unwrap_or_else(|| msg.to_string())
+}
+
+/// Translates a message that depends on a number, like `tr_n("{} trip", "{} trips", n)`. The
+/// plural form is the key. Any `{}` is replaced by the number.
+pub fn tr_n(one: &str, other: &str, n: usize) -> String {
+    let translated = CATALOG.with(|c| {
+        let c = c.borrow();
+        let catalog = c.as_ref()?;
+        match catalog.messages.get(other)? {
+            Message::Simple(x) => Some(x.clone()),
+            Message::Plural(forms) => forms
+                .get(&plural_category(&catalog.language, n))
+                .or_else(|| forms.get(&PluralCategory::Other))
+                .cloned(),
+        }
+    });
+    let msg = translated.unwrap_or_else(|| if n == 1 { one } else { other }.to_string());
+    msg.replace("{}", &abstutil::prettyprint_usize(n))
+}
+
+// Avoids copying when there's no catalog, since every Line calls this.
+pub(crate) fn translate(msg: String) -> String {
+    lookup(&msg).unwrap_or(msg)
+}
+
+fn lookup(msg: &str) -> Option<String> {
+    CATALOG.with(|c| match c.borrow().as_ref()?.messages.get(msg)? {
+        Message::Simple(x) => Some(x.clone()),
+        Message::Plural(forms) => forms.get(&PluralCategory::Other).cloned(),
+    })
+}
+
+/// Simplified from the CLDR rules for integers. Languages not listed use the English rule.
+pub fn plural_category(language: &str, n: usize) -> PluralCategory {
+    let lang = language.split('-').next().unwrap();
+    let (n10, n100) = (n % 10, n % 100);
+    match lang {
+        "ja" | "ko" | "zh" | "vi" | "th" | "id" | "ms" | "tr" => PluralCategory::Other,
+        "fr" => {
+            if n <= 1 {
+                PluralCategory::One
+            } else {
+                PluralCategory::Other
+            }
+        }
+        "pt" if language == "pt-BR" => {
+            if n <= 1 {
+                PluralCategory::One
+            } else {
+                PluralCategory::Other
+            }
+        }
+        "ru" | "uk" | "be" => {
+            if n10 == 1 && n100 != 11 {
+                PluralCategory::One
+            } else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Many
+            }
+        }
+        "pl" => {
+            if n == 1 {
+                PluralCategory::One
+            } else if (2..=4).contains(&n10) && !(12..=14).contains(&n100) {
+                PluralCategory::Few
+            } else {
+                PluralCategory::Many
+            }
+        }
+        "cs" | "sk" => match n {
+            1 => PluralCategory::One,
+            2..=4 => PluralCategory::Few,
+            _ => PluralCategory::Other,
+        },
+        "ar" => match n {
+            0 => PluralCategory::Zero,
+            1 => PluralCategory::One,
+            2 => PluralCategory::Two,
+            _ if (3..=10).contains(&n100) => PluralCategory::Few,
+            _ if n100 >= 11 => PluralCategory::Many,
+            _ => PluralCategory::Other,
+        },
+        _ => {
+            if n == 1 {
+                PluralCategory::One
+            } else {
+                PluralCategory::Other
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plurals() {
+        assert_eq!(tr_n("{} trip", "{} trips", 1), "1 trip");
+        assert_eq!(tr_n("{} trip", "{} trips", 1234), "1,234 trips");
+
+        let mut messages = BTreeMap::new();
+        messages.insert(
+            "Settings".to_string(),
+            Message::Simple("Ustawienia".to_string()),
+        );
+        let mut forms = BTreeMap::new();
+        forms.insert(PluralCategory::One, "{} podróż".to_string());
+        forms.insert(PluralCategory::Few, "{} podróże".to_string());
+        forms.insert(PluralCategory::Many, "{} podróży".to_string());
+        forms.insert(PluralCategory::Other, "{} podróży".to_string());
+        messages.insert("{} trips".to_string(), Message::Plural(forms));
+        set_catalog(Some(Catalog {
+            language: "pl".to_string(),
+            messages,
+        }));
+
+        assert_eq!(tr("Settings"), "Ustawienia");
+        assert_eq!(tr("Untranslated"), "Untranslated");
+        assert_eq!(tr_n("{} trip", "{} trips", 1), "1 podróż");
+        assert_eq!(tr_n("{} trip", "{} trips", 3), "3 podróże");
+        assert_eq!(tr_n("{} trip", "{} trips", 12), "12 podróży");
+        assert_eq!(tr_n("{} trip", "{} trips", 22), "22 podróże");
+        set_catalog(None);
+    }
+}
@@ -35,6 +35,7 @@ pub use crate::drawing::{GfxCtx, Prerender};
 pub use crate::event::{hotkeys, lctrl, Event, Key, MultiKey};
 pub use crate::event_ctx::{EventCtx, UpdateType};
 pub use crate::geom::{GeomBatch, RewriteColor};
+pub use crate::i18n::{tr, tr_n};
 pub use crate::input::UserInput;
 pub use crate::runner::{run, Settings};
 pub use crate::screen_geom::{ScreenDims, ScreenPt, ScreenRectangle};
@@ -53,39 +54,56 @@ pub use crate::widgets::just_draw::DrawWithTooltips;
 pub(crate) use crate::widgets::just_draw::{DeferDraw, JustDraw};
 pub use crate::widgets::line_plot::{LinePlot, PlotOptions, Series};
 pub use crate::widgets::menu::Menu;
+pub use crate::widgets::panel::AccessibleWidget;
 pub use crate::widgets::persistent_split::PersistentSplit;
 pub use crate::widgets::scatter_plot::ScatterPlot;
 pub use crate::widgets::slider::Slider;
 pub use crate::widgets::spinner::Spinner;
+pub use crate::widgets::table::{Col, Filter, Table};
 pub(crate) use crate::widgets::text_box::TextBox;
-pub use crate::widgets::{EdgeInsets, Outcome, Panel, Widget, WidgetImpl, WidgetOutput};
+pub use crate::widgets::{
+    Accessibility, EdgeInsets, Outcome, Panel, Role, Widget, WidgetImpl, WidgetOutput,
+};
 
 mod app_state;
 mod assets;
-#[cfg(any(feature = "glow-backend", feature = "wasm-backend"))]
+#[cfg(all(
+    any(feature = "glow-backend", feature = "wasm-backend"),
+    not(feature = "headless-backend")
+))]
 mod backend_glow;
-#[cfg(feature = "glow-backend")]
+#[cfg(all(feature = "glow-backend", not(feature = "headless-backend")))]
 mod backend_glow_native;
-#[cfg(feature = "wasm-backend")]
+#[cfg(all(feature = "wasm-backend", not(feature = "headless-backend")))]
 mod backend_glow_wasm;
+#[cfg(feature = "headless-backend")]
+mod backend_headless;
 mod canvas;
 mod color;
 mod drawing;
 mod event;
 mod event_ctx;
 mod geom;
+pub mod i18n;
 mod input;
 mod runner;
 mod screen_geom;
 mod style;
 mod svg;
+#[cfg(feature = "headless-backend")]
+pub mod testing;
 mod text;
 mod tools;
 mod widgets;
 
 mod backend {
-    #[cfg(any(feature = "glow-backend", feature = "wasm-backend"))]
+    #[cfg(all(
+        any(feature = "glow-backend", feature = "wasm-backend"),
+        not(feature = "headless-backend")
+    ))]
     pub use crate::backend_glow::*;
+    #[cfg(feature = "headless-backend")]
+    pub use crate::backend_headless::*;
 }
 
 pub struct Choice<T> {
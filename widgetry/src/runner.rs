@@ -21,12 +21,16 @@ const UPDATE_FREQUENCY: std::time::Duration = std::time::Duration::from_millis(1
 pub(crate) struct State<A: SharedAppState> {
     pub(crate) app: App<A>,
     pub(crate) canvas: Canvas,
-    style: Style,
+    pub(crate) style: Style,
 }
 
 impl<A: SharedAppState> State<A> {
     // The bool indicates if the input was actually used.
-    fn event(&mut self, mut ev: Event, prerender: &Prerender) -> (Vec<UpdateType>, bool) {
+    pub(crate) fn event(
+        &mut self,
+        mut ev: Event,
+        prerender: &Prerender,
+    ) -> (Vec<UpdateType>, bool) {
         if let Event::MouseWheelScroll(dx, dy) = ev {
             if self.canvas.invert_scroll {
                 ev = Event::MouseWheelScroll(-dx, -dy);
@@ -1,12 +1,19 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{Color, Text};
 
-#[derive(Clone)]
+/// Colors and sizes shared by all widgets. Apps can load one from a data file; everything except
+/// the loading tips is serialized.
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Style {
     pub outline_thickness: f64,
     pub outline_color: Color,
     pub panel_bg: Color,
     pub hotkey_color: Color,
     pub hovering_color: Color,
+    /// Outlines the widget with keyboard focus
+    pub focus_color: Color,
+    #[serde(skip, default = "Text::new")]
     pub loading_tips: Text,
 }
 
@@ -18,6 +25,7 @@ impl Style {
             panel_bg: Color::grey(0.4),
             hotkey_color: Color::GREEN,
             hovering_color: Color::ORANGE,
+            focus_color: Color::hex("#4CA7E9"),
             loading_tips: Text::new(),
         }
     }
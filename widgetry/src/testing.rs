@@ -0,0 +1,395 @@
+//! Drive widgetry states from tests, without a window or GPU. Build widgetry with
+//! `--no-default-features --features headless-backend`, create a `Harness` the same way you'd
+//! call `widgetry::run`, then feed it synthetic clicks, key presses, and text. Uploads and draw
+//! calls don't do anything, but everything else -- layout, text, hitboxes, transitions -- is real.
+//!
+//! Note that popping the last state still exits the process, just like in a real app.
+//!
+//! The tests here also need the feature; run them with `cd widgetry; cargo test
+//! --no-default-features --features headless-backend`, like CI does.
+
+use std::cell::{Cell, RefCell};
+
+use geom::Duration;
+
+use crate::app_state::App;
+use crate::assets::Assets;
+use crate::backend::PrerenderInnards;
+use crate::runner::State;
+use crate::{
+    Canvas, Event, EventCtx, Key, Outcome, Panel, Prerender, ScreenDims, ScreenPt, SharedAppState,
+    Style, UpdateType, UserInput,
+};
+
+/// Every key that types a character, used to turn text back into key presses.
+const TYPABLE_KEYS: [Key; 46] = [
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+    Key::Num1,
+    Key::Num2,
+    Key::Num3,
+    Key::Num4,
+    Key::Num5,
+    Key::Num6,
+    Key::Num7,
+    Key::Num8,
+    Key::Num9,
+    Key::Num0,
+    Key::LeftBracket,
+    Key::RightBracket,
+    Key::Space,
+    Key::Slash,
+    Key::Dot,
+    Key::Comma,
+    Key::Semicolon,
+    Key::Colon,
+    Key::Equals,
+    Key::SingleQuote,
+];
+
+/// Runs a stack of states against synthetic input.
+pub struct Harness<A: SharedAppState> {
+    state: State<A>,
+    prerender: Prerender,
+}
+
+impl<A: 'static + SharedAppState> Harness<A> {
+    /// Sets up the app just like `widgetry::run`, pretending the window has the given size.
+    pub fn new<F: FnOnce(&mut EventCtx) -> (A, Vec<Box<dyn crate::app_state::State<A>>>)>(
+        window_size: ScreenDims,
+        make_app: F,
+    ) -> Harness<A> {
+        let prerender = Prerender {
+            assets: Assets::new(),
+            num_uploads: Cell::new(0),
+            inner: PrerenderInnards::new(window_size),
+            scale_factor: RefCell::new(1.0),
+        };
+        let mut style = Style::standard();
+        let mut canvas = Canvas::new(window_size);
+
+        let (shared_app_state, states) = make_app(&mut EventCtx {
+            fake_mouseover: true,
+            input: UserInput::new(Event::NoOp, &canvas),
+            canvas: &mut canvas,
+            prerender: &prerender,
+            style: &mut style,
+            updates_requested: vec![],
+        });
+        let mut harness = Harness {
+            state: State {
+                app: App {
+                    shared_app_state,
+                    states,
+                },
+                canvas,
+                style,
+            },
+            prerender,
+        };
+        // Put the cursor somewhere, so hitboxes work from the start
+        harness.event(Event::WindowGainedCursor);
+        harness
+    }
+
+    /// Sends one raw event through the top state, exactly like the real event loop would.
+    pub fn event(&mut self, ev: Event) -> Vec<UpdateType> {
+        self.state.event(ev, &self.prerender).0
+    }
+
+    /// Sends an update event, as if `dt` has passed.
+    pub fn update(&mut self, dt: Duration) -> Vec<UpdateType> {
+        self.event(Event::Update(dt))
+    }
+
+    pub fn move_mouse(&mut self, pt: ScreenPt) {
+        self.event(Event::MouseMovedTo(pt));
+    }
+
+    /// Moves the mouse somewhere, then presses and releases the left button.
+    pub fn click_at(&mut self, pt: ScreenPt) {
+        self.move_mouse(pt);
+        self.event(Event::LeftMouseButtonDown);
+        self.event(Event::LeftMouseButtonUp);
+    }
+
+    /// Clicks the center of a widget. `panel` should find the panel in the current top state,
+    /// which usually means downcasting with `top_state`.
+    pub fn click<F: Fn(&Harness<A>) -> &Panel>(&mut self, panel: F, name: &str) {
+        let pt = panel(self).center_of(name);
+        self.click_at(pt);
+    }
+
+    pub fn press_key(&mut self, key: Key) {
+        self.event(Event::KeyPress(key));
+        self.event(Event::KeyRelease(key));
+    }
+
+    /// Types each character as a key press, holding shift when needed. Panics on characters that
+    /// no key produces.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            let (key, shift) = key_for_char(c).unwrap_or_else(|| panic!("No key types {:?}", c));
+            if shift {
+                self.event(Event::KeyPress(Key::LeftShift));
+            }
+            self.press_key(key);
+            if shift {
+                self.event(Event::KeyRelease(Key::LeftShift));
+            }
+        }
+    }
+
+    /// Draws everything once. Nothing is rendered, but this catches panics in draw code and
+    /// returns the number of draw calls made.
+    pub fn draw(&mut self) -> usize {
+        let before = self.prerender.inner.num_draw_calls.get();
+        self.state.draw(&self.prerender, false);
+        self.prerender.inner.num_draw_calls.get() - before
+    }
+
+    pub fn shared_app_state(&self) -> &A {
+        &self.state.app.shared_app_state
+    }
+
+    pub fn shared_app_state_mut(&mut self) -> &mut A {
+        &mut self.state.app.shared_app_state
+    }
+
+    /// How many states are on the stack.
+    pub fn num_states(&self) -> usize {
+        self.state.app.states.len()
+    }
+
+    /// Returns the top-most state, if it has the expected type.
+    pub fn top_state<S: crate::app_state::State<A>>(&self) -> Option<&S> {
+        self.state.app.states.last().unwrap().downcast_ref::<S>()
+    }
+
+    pub fn top_state_mut<S: crate::app_state::State<A>>(&mut self) -> Option<&mut S> {
+        self.state
+            .app
+            .states
+            .last_mut()
+            .unwrap()
+            .downcast_mut::<S>()
+    }
+
+    pub fn canvas(&self) -> &Canvas {
+        &self.state.canvas
+    }
+
+    /// The cursor the app last asked for, like a hand while hovering on a button.
+    pub fn cursor_icon(&self) -> winit::window::CursorIcon {
+        self.prerender.inner.cursor_icon.get()
+    }
+
+    /// Runs something with a real `EventCtx` outside of any state. Useful to build panels or
+    /// check a single widget in isolation with `click_panel`.
+    pub fn with_ctx<T, F: FnOnce(&mut EventCtx, &mut A) -> T>(&mut self, ev: Event, f: F) -> T {
+        let mut ctx = EventCtx {
+            fake_mouseover: false,
+            input: UserInput::new(ev, &self.state.canvas),
+            canvas: &mut self.state.canvas,
+            prerender: &self.prerender,
+            style: &mut self.state.style,
+            updates_requested: vec![],
+        };
+        f(&mut ctx, &mut self.state.app.shared_app_state)
+    }
+
+    /// Clicks a widget in a panel that isn't owned by any state, returning the panel's outcome.
+    pub fn click_panel(&mut self, panel: &mut Panel, name: &str) -> Outcome {
+        let pt = panel.center_of(name);
+        self.state.canvas.cursor = pt;
+        self.state.canvas.window_has_cursor = true;
+        self.with_ctx(Event::MouseMovedTo(pt), |ctx, _| panel.event(ctx));
+        self.with_ctx(Event::LeftMouseButtonDown, |ctx, _| panel.event(ctx));
+        self.with_ctx(Event::LeftMouseButtonUp, |ctx, _| panel.event(ctx))
+    }
+}
+
+fn key_for_char(c: char) -> Option<(Key, bool)> {
+    for key in TYPABLE_KEYS.iter() {
+        if key.to_char(false) == Some(c) {
+            return Some((*key, false));
+        }
+        if key.to_char(true) == Some(c) {
+            return Some((*key, true));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Btn, GfxCtx, Role, Transition, Widget};
+
+    #[derive(Default)]
+    struct Counter {
+        clicks: usize,
+    }
+
+    impl SharedAppState for Counter {}
+
+    struct Screen {
+        panel: Panel,
+    }
+
+    impl Screen {
+        fn new(ctx: &mut EventCtx) -> Box<dyn crate::State<Counter>> {
+            Box::new(Screen {
+                panel: Panel::new(Widget::col(vec![
+                    Btn::text_fg("increment").build_def(ctx, None),
+                    Btn::text_fg("open").build_def(ctx, None),
+                    Widget::text_entry(ctx, String::new(), true).named("name"),
+                ]))
+                .keyboard_focus()
+                .build(ctx),
+            })
+        }
+    }
+
+    impl crate::State<Counter> for Screen {
+        fn event(&mut self, ctx: &mut EventCtx, app: &mut Counter) -> Transition<Counter> {
+            match self.panel.event(ctx) {
+                Outcome::Clicked(x) => match x.as_ref() {
+                    "increment" => {
+                        app.clicks += 1;
+                    }
+                    "open" => {
+                        return Transition::Push(Screen::new(ctx));
+                    }
+                    _ => unreachable!(),
+                },
+                _ => {}
+            }
+            Transition::Keep
+        }
+
+        fn draw(&self, g: &mut GfxCtx, _: &Counter) {
+            self.panel.draw(g);
+        }
+    }
+
+    fn harness() -> Harness<Counter> {
+        Harness::new(ScreenDims::new(800.0, 600.0), |ctx| {
+            (Counter::default(), vec![Screen::new(ctx)])
+        })
+    }
+
+    #[test]
+    fn click_buttons() {
+        let mut h = harness();
+        let panel = |h: &Harness<Counter>| &h.top_state::<Screen>().unwrap().panel;
+        let pt = panel(&h).center_of("increment");
+        h.move_mouse(pt);
+        assert_eq!(h.cursor_icon(), winit::window::CursorIcon::Hand);
+        h.click(panel, "increment");
+        h.click(panel, "increment");
+        assert_eq!(h.shared_app_state().clicks, 2);
+        assert_eq!(h.num_states(), 1);
+
+        h.click(panel, "open");
+        assert_eq!(h.num_states(), 2);
+        assert!(h.draw() > 0);
+    }
+
+    #[test]
+    fn keyboard_focus() {
+        let mut h = harness();
+        // Focus the first button and activate it
+        h.press_key(Key::Tab);
+        h.press_key(Key::Enter);
+        assert_eq!(h.shared_app_state().clicks, 1);
+
+        // Shift+tab wraps around to the text box
+        h.press_key(Key::Tab);
+        h.event(Event::KeyPress(Key::LeftShift));
+        h.press_key(Key::Tab);
+        h.press_key(Key::Tab);
+        h.event(Event::KeyRelease(Key::LeftShift));
+        let tree = h.top_state::<Screen>().unwrap().panel.accessibility_tree();
+        let labels: Vec<(Role, String, bool)> = tree
+            .into_iter()
+            .map(|w| (w.info.role, w.info.label, w.focused))
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                (Role::Button, "increment".to_string(), false),
+                (Role::Button, "open".to_string(), false),
+                (Role::TextBox, "name".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn tab_hotkey_wins_over_focus() {
+        let mut h = harness();
+        let mut panel = h.with_ctx(Event::NoOp, |ctx, _| {
+            Panel::new(Widget::col(vec![
+                Btn::text_fg("increment").build_def(ctx, None),
+                Btn::text_fg("search").build_def(ctx, Key::Tab),
+            ]))
+            .keyboard_focus()
+            .build(ctx)
+        });
+        let outcome = h.with_ctx(Event::KeyPress(Key::Tab), |ctx, _| panel.event(ctx));
+        assert_eq!(outcome, Outcome::Clicked("search".to_string()));
+    }
+
+    #[test]
+    fn focus_is_opt_in() {
+        let mut h = harness();
+        let (mut minimap, mut top) = h.with_ctx(Event::NoOp, |ctx, _| {
+            (
+                Panel::new(Btn::text_fg("zoom").build_def(ctx, None)).build(ctx),
+                Panel::new(Btn::text_fg("search").build_def(ctx, Key::Tab)).build(ctx),
+            )
+        });
+        // A panel handling events first doesn't steal tab from a hotkey in another panel
+        let outcomes = h.with_ctx(Event::KeyPress(Key::Tab), |ctx, _| {
+            (minimap.event(ctx), top.event(ctx))
+        });
+        assert_eq!(
+            outcomes,
+            (Outcome::Nothing, Outcome::Clicked("search".to_string()))
+        );
+    }
+
+    #[test]
+    fn type_text() {
+        let mut h = harness();
+        h.type_text("Hi, Bob!");
+        assert_eq!(
+            h.top_state::<Screen>().unwrap().panel.text_box("name"),
+            "Hi, Bob!"
+        );
+    }
+}
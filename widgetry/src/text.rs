@@ -6,7 +6,8 @@ use geom::{PolyLine, Polygon};
 
 use crate::assets::Assets;
 use crate::{
-    svg, Color, DeferDraw, EventCtx, GeomBatch, JustDraw, MultiKey, Prerender, ScreenDims, Widget,
+    i18n, svg, Color, DeferDraw, EventCtx, GeomBatch, JustDraw, MultiKey, Prerender, ScreenDims,
+    Widget,
 };
 
 // Same as body()
@@ -137,7 +138,7 @@ impl TextSpan {
 #[allow(non_snake_case)]
 pub fn Line<S: Into<String>>(text: S) -> TextSpan {
     TextSpan {
-        text: text.into(),
+        text: i18n::translate(text.into()),
         fg_color: DEFAULT_FG_COLOR,
         size: DEFAULT_FONT_SIZE,
         font: DEFAULT_FONT,
@@ -1,2 +1,3 @@
+pub mod rasterize;
 pub mod screenshot;
 pub mod warper;
@@ -0,0 +1,446 @@
+//! Render a `GeomBatch` straight to a PNG, SVG, or animated GIF file on the CPU. Unlike
+//! `tools::screenshot`, this doesn't need a window, a GPU context, or any external programs, so it
+//! works from batch tools and CI servers.
+
+use std::fmt::Write;
+
+use geom::{Bounds, Pt2D};
+
+use crate::{Color, Fill, GeomBatch, Texture};
+
+/// Each output pixel averages this many samples in each direction, to smooth out edges.
+const SUPERSAMPLE: u32 = 2;
+/// How many output rows to render at once
+const STRIP_HEIGHT: u32 = 256;
+
+impl GeomBatch {
+    /// Rasterizes everything inside `bounds` into a PNG. The longer side of the image will be
+    /// `max_dim_px` pixels. Textures aren't available without a GPU, so they're approximated with
+    /// a flat color.
+    pub fn render_to_png(
+        &self,
+        bounds: &Bounds,
+        max_dim_px: u32,
+        path: &str,
+    ) -> Result<(), String> {
+        self.rasterize(bounds, max_dim_px)
+            .save(path)
+            .map_err(|err| format!("Couldn't write {}: {}", path, err))
+    }
+
+    /// Like `render_to_png`, but keeps the pixels in memory.
+    pub fn rasterize(&self, bounds: &Bounds, max_dim_px: u32) -> image::RgbaImage {
+        let scale = (max_dim_px as f64) / bounds.width().max(bounds.height());
+        let width = ((bounds.width() * scale).ceil() as u32).max(1);
+        let height = ((bounds.height() * scale).ceil() as u32).max(1);
+        let polygons = self.sorted_by_z();
+
+        // A supersampled canvas for the whole image would be SUPERSAMPLE^2 times bigger than the
+        // output, so render one strip of rows at a time.
+        let mut img = image::RgbaImage::new(width, height);
+        let scale = scale * (SUPERSAMPLE as f64);
+        let mut strip_y = 0;
+        while strip_y < height {
+            let strip_height = STRIP_HEIGHT.min(height - strip_y);
+            let offset_y = (strip_y * SUPERSAMPLE) as f64;
+            let mut canvas = Canvas::new(width * SUPERSAMPLE, strip_height * SUPERSAMPLE);
+            for (fill, poly, _) in &polygons {
+                let (pts, indices) = poly.raw_for_rendering();
+                let pixels: Vec<(f64, f64)> = pts
+                    .iter()
+                    .map(|pt| {
+                        (
+                            (pt.x() - bounds.min_x) * scale,
+                            (pt.y() - bounds.min_y) * scale - offset_y,
+                        )
+                    })
+                    .collect();
+                for tri in indices.chunks(3) {
+                    canvas.fill_triangle(
+                        [
+                            pixels[tri[0] as usize],
+                            pixels[tri[1] as usize],
+                            pixels[tri[2] as usize],
+                        ],
+                        |x, y| {
+                            flat_color(
+                                fill,
+                                Pt2D::new(
+                                    bounds.min_x + x / scale,
+                                    bounds.min_y + (y + offset_y) / scale,
+                                ),
+                            )
+                        },
+                    );
+                }
+            }
+            canvas.downsample_into(&mut img, strip_y);
+            strip_y += strip_height;
+        }
+        img
+    }
+
+    /// Makes an animated GIF, showing each frame for `frame_delay_ms`. The background is drawn
+    /// once and each frame is drawn on top of it. Every frame covers the same `bounds`.
+    pub fn render_to_gif(
+        background: &GeomBatch,
+        frames: Vec<GeomBatch>,
+        bounds: &Bounds,
+        max_dim_px: u32,
+        frame_delay_ms: u32,
+        path: &str,
+    ) -> Result<(), String> {
+        let file = std::fs::File::create(path)
+            .map_err(|err| format!("Couldn't write {}: {}", path, err))?;
+        let mut encoder = image::gif::GifEncoder::new(file);
+        let background = background.rasterize(bounds, max_dim_px);
+        for batch in frames {
+            let frame = image::Frame::from_parts(
+                overlay(&background, batch.rasterize(bounds, max_dim_px)),
+                0,
+                0,
+                image::Delay::from_numer_denom_ms(frame_delay_ms, 1),
+            );
+            encoder
+                .encode_frame(frame)
+                .map_err(|err| format!("Couldn't write {}: {}", path, err))?;
+        }
+        Ok(())
+    }
+
+    /// Like `render_to_gif`, but writes each frame as a numbered PNG in `dir`, for video editors.
+    /// Returns the paths.
+    pub fn render_to_png_sequence(
+        background: &GeomBatch,
+        frames: Vec<GeomBatch>,
+        bounds: &Bounds,
+        max_dim_px: u32,
+        dir: &str,
+    ) -> Result<Vec<String>, String> {
+        std::fs::create_dir_all(dir).map_err(|err| format!("Couldn't create {}: {}", dir, err))?;
+        let background = background.rasterize(bounds, max_dim_px);
+        let mut paths = Vec::new();
+        for (idx, batch) in frames.into_iter().enumerate() {
+            let path = format!("{}/frame_{:05}.png", dir, idx + 1);
+            overlay(&background, batch.rasterize(bounds, max_dim_px))
+                .save(&path)
+                .map_err(|err| format!("Couldn't write {}: {}", path, err))?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
+
+    /// Writes everything inside `bounds` as an SVG, using map-space units. Textures are
+    /// approximated with a flat color.
+    pub fn render_to_svg(&self, bounds: &Bounds, path: &str) -> Result<(), String> {
+        let mut defs = String::new();
+        let mut body = String::new();
+        for (idx, (fill, poly, _)) in self.sorted_by_z().into_iter().enumerate() {
+            let paint = match fill {
+                Fill::LinearGradient(ref lg) => {
+                    let id = format!("gradient{}", idx);
+                    write!(
+                        defs,
+                        r#"<linearGradient id="{}" gradientUnits="userSpaceOnUse" x1="{}" y1="{}" x2="{}" y2="{}">"#,
+                        id,
+                        lg.line.pt1().x(),
+                        lg.line.pt1().y(),
+                        lg.line.pt2().x(),
+                        lg.line.pt2().y()
+                    )
+                    .unwrap();
+                    for (offset, color) in &lg.stops {
+                        write!(
+                            defs,
+                            r#"<stop offset="{}" stop-color="{}" stop-opacity="{}"/>"#,
+                            offset,
+                            color.to_hex(),
+                            color.a
+                        )
+                        .unwrap();
+                    }
+                    defs.push_str("</linearGradient>\n");
+                    format!(r#"fill="url(#{})""#, id)
+                }
+                _ => {
+                    let color = flat_color(fill, poly.center());
+                    format!(r#"fill="{}" fill-opacity="{}""#, color.to_hex(), color.a)
+                }
+            };
+
+            // Use the triangulation, since that's the only representation that handles every
+            // polygon, including ones with holes.
+            let (pts, indices) = poly.raw_for_rendering();
+            let mut d = String::new();
+            for tri in indices.chunks(3) {
+                let (p1, p2, p3) = (
+                    pts[tri[0] as usize],
+                    pts[tri[1] as usize],
+                    pts[tri[2] as usize],
+                );
+                write!(
+                    d,
+                    "M{} {}L{} {}L{} {}Z",
+                    p1.x(),
+                    p1.y(),
+                    p2.x(),
+                    p2.y(),
+                    p3.x(),
+                    p3.y()
+                )
+                .unwrap();
+            }
+            writeln!(body, r#"<path {} d="{}"/>"#, paint, d).unwrap();
+        }
+
+        let mut out = String::new();
+        writeln!(
+            out,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}" width="{}" height="{}" shape-rendering="crispEdges">"#,
+            bounds.min_x,
+            bounds.min_y,
+            bounds.width(),
+            bounds.height(),
+            bounds.width(),
+            bounds.height()
+        )
+        .unwrap();
+        if !defs.is_empty() {
+            writeln!(out, "<defs>\n{}</defs>", defs).unwrap();
+        }
+        out.push_str(&body);
+        out.push_str("</svg>\n");
+        std::fs::write(path, out).map_err(|err| format!("Couldn't write {}: {}", path, err))
+    }
+
+    /// Picks PNG or SVG output based on the file extension.
+    pub fn render_to_file(&self, bounds: &Bounds, path: &str) -> Result<(), String> {
+        if path.ends_with(".svg") {
+            self.render_to_svg(bounds, path)
+        } else if path.ends_with(".png") {
+            self.render_to_png(bounds, 4096, path)
+        } else {
+            Err(format!(
+                "Don't know how to render to {}; use .png or .svg",
+                path
+            ))
+        }
+    }
+
+    /// Painter's algorithm: the GPU draws z offsets closer to -1.0 on top, so draw those last.
+    /// Within the same z offset, draw in the original order.
+    fn sorted_by_z(&self) -> Vec<&(Fill, geom::Polygon, f64)> {
+        let mut list: Vec<&(Fill, geom::Polygon, f64)> = self.list.iter().collect();
+        list.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap());
+        list
+    }
+}
+
+fn overlay(background: &image::RgbaImage, top: image::RgbaImage) -> image::RgbaImage {
+    let mut result = background.clone();
+    image::imageops::overlay(&mut result, &top, 0, 0);
+    result
+}
+
+fn flat_color(fill: &Fill, pt: Pt2D) -> Color {
+    match fill {
+        Fill::Color(c) => *c,
+        Fill::LinearGradient(ref lg) => lg.interp(pt),
+        Fill::Texture(t) => texture_color(*t),
+        Fill::ColoredTexture(c, _) => *c,
+    }
+}
+
+/// Without the texture atlas, just guess the dominant color.
+fn texture_color(t: Texture) -> Color {
+    if t == Texture::GRASS || t == Texture::TREE || t == Texture::PINE_TREE || t == Texture::SHRUB {
+        Color::hex("#84BA3B")
+    } else if t == Texture::STILL_WATER || t == Texture::RUNNING_WATER {
+        Color::hex("#4CA7E9")
+    } else if t == Texture::SAND || t == Texture::DIRT || t == Texture::CACTUS {
+        Color::hex("#D8C89A")
+    } else if t == Texture::SNOW || t == Texture::SNOW_PERSON || t == Texture::NOOP {
+        Color::WHITE
+    } else {
+        Color::grey(0.6)
+    }
+}
+
+/// RGBA pixels with straight (not premultiplied) alpha, all starting transparent. Blending happens
+/// directly on the 8-bit channels, so a full-size render doesn't need a float buffer.
+struct Canvas {
+    width: u32,
+    height: u32,
+    img: image::RgbaImage,
+}
+
+impl Canvas {
+    fn new(width: u32, height: u32) -> Canvas {
+        Canvas {
+            width,
+            height,
+            img: image::RgbaImage::new(width, height),
+        }
+    }
+
+    /// Fills every pixel whose center is inside the triangle. Pixels exactly on a shared edge are
+    /// only claimed by one of the triangles (the "top-left" rule), so translucent polygons don't
+    /// get seams.
+    fn fill_triangle<F: Fn(f64, f64) -> Color>(&mut self, mut pts: [(f64, f64); 3], color: F) {
+        let area = edge(pts[0], pts[1], pts[2]);
+        if area == 0.0 {
+            return;
+        }
+        if area < 0.0 {
+            pts.swap(1, 2);
+        }
+
+        let min_x = pts
+            .iter()
+            .map(|p| p.0)
+            .fold(f64::MAX, f64::min)
+            .floor()
+            .max(0.0) as u32;
+        let min_y = pts
+            .iter()
+            .map(|p| p.1)
+            .fold(f64::MAX, f64::min)
+            .floor()
+            .max(0.0) as u32;
+        let max_x = (pts.iter().map(|p| p.0).fold(f64::MIN, f64::max).ceil() as i64)
+            .min(self.width as i64 - 1);
+        let max_y = (pts.iter().map(|p| p.1).fold(f64::MIN, f64::max).ceil() as i64)
+            .min(self.height as i64 - 1);
+        if max_x < 0 || max_y < 0 {
+            return;
+        }
+
+        let edges = [(pts[1], pts[2]), (pts[2], pts[0]), (pts[0], pts[1])];
+        for y in min_y..=(max_y as u32) {
+            for x in min_x..=(max_x as u32) {
+                let center = ((x as f64) + 0.5, (y as f64) + 0.5);
+                let inside = edges.iter().all(|(a, b)| {
+                    let w = edge(*a, *b, center);
+                    w > 0.0 || (w == 0.0 && is_top_left(*a, *b))
+                });
+                if inside {
+                    self.blend(x, y, color(center.0, center.1));
+                }
+            }
+        }
+    }
+
+    fn blend(&mut self, x: u32, y: u32, src: Color) {
+        let dst = self.img.get_pixel_mut(x, y);
+        let dst_a = (dst[3] as f32) / 255.0;
+        let out_a = src.a + dst_a * (1.0 - src.a);
+        if out_a == 0.0 {
+            return;
+        }
+        for (i, c) in [src.r, src.g, src.b].iter().enumerate() {
+            let old = (dst[i] as f32) / 255.0;
+            dst[i] = to_channel((c * src.a + old * dst_a * (1.0 - src.a)) / out_a);
+        }
+        dst[3] = to_channel(out_a);
+    }
+
+    /// Averages each SUPERSAMPLE x SUPERSAMPLE block into one pixel of `img`, starting at row
+    /// `start_y`. Colors are weighted by alpha, so transparent samples don't darken edges.
+    fn downsample_into(&self, img: &mut image::RgbaImage, start_y: u32) {
+        let samples = (SUPERSAMPLE * SUPERSAMPLE) as f32;
+        for y in 0..(self.height / SUPERSAMPLE) {
+            for x in 0..(self.width / SUPERSAMPLE) {
+                let mut rgb = [0.0; 3];
+                let mut alpha = 0.0;
+                for dy in 0..SUPERSAMPLE {
+                    for dx in 0..SUPERSAMPLE {
+                        let px = self
+                            .img
+                            .get_pixel(x * SUPERSAMPLE + dx, y * SUPERSAMPLE + dy);
+                        let a = (px[3] as f32) / 255.0;
+                        for (sum, c) in rgb.iter_mut().zip(px.0.iter()) {
+                            *sum += (*c as f32) / 255.0 * a;
+                        }
+                        alpha += a;
+                    }
+                }
+                if alpha == 0.0 {
+                    continue;
+                }
+                img.put_pixel(
+                    x,
+                    start_y + y,
+                    image::Rgba([
+                        to_channel(rgb[0] / alpha),
+                        to_channel(rgb[1] / alpha),
+                        to_channel(rgb[2] / alpha),
+                        to_channel(alpha / samples),
+                    ]),
+                );
+            }
+        }
+    }
+}
+
+fn to_channel(x: f32) -> u8 {
+    (x.max(0.0).min(1.0) * 255.0).round() as u8
+}
+
+/// Twice the signed area of the triangle (a, b, c). Positive when c is to one consistent side of
+/// the edge a->b.
+fn edge(a: (f64, f64), b: (f64, f64), c: (f64, f64)) -> f64 {
+    (b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)
+}
+
+fn is_top_left(a: (f64, f64), b: (f64, f64)) -> bool {
+    (a.1 == b.1 && b.0 < a.0) || b.1 < a.1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn covered(canvas: &Canvas) -> usize {
+        canvas.img.pixels().filter(|px| px[3] > 0).count()
+    }
+
+    #[test]
+    fn test_single_triangle() {
+        let mut canvas = Canvas::new(8, 8);
+        canvas.fill_triangle([(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)], |_, _| Color::RED);
+        // The 6 pixels with centers strictly inside. The 4 centers exactly on the diagonal belong
+        // to whatever's on the other side of it.
+        assert_eq!(covered(&canvas), 6);
+        assert_eq!(canvas.img.get_pixel(0, 0).0, [255, 0, 0, 255]);
+        assert_eq!(canvas.img.get_pixel(3, 0).0, [0, 0, 0, 0]);
+        assert_eq!(canvas.img.get_pixel(5, 5).0, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_shared_edge() {
+        // A square split along the diagonal, drawn translucent so any pixel filled twice would
+        // come out more opaque
+        let color = Color::rgba_f(1.0, 0.0, 0.0, 0.5);
+        let mut canvas = Canvas::new(8, 8);
+        canvas.fill_triangle([(0.0, 0.0), (4.0, 0.0), (0.0, 4.0)], |_, _| color);
+        // The winding order shouldn't matter
+        canvas.fill_triangle([(4.0, 0.0), (0.0, 4.0), (4.0, 4.0)], |_, _| color);
+        assert_eq!(covered(&canvas), 16);
+        for px in canvas.img.pixels().filter(|px| px[3] > 0) {
+            assert_eq!(px.0, [255, 0, 0, 128]);
+        }
+    }
+
+    #[test]
+    fn test_blend() {
+        let mut canvas = Canvas::new(1, 1);
+        canvas.blend(0, 0, Color::RED);
+        canvas.blend(0, 0, Color::rgba_f(0.0, 0.0, 1.0, 0.5));
+        assert_eq!(canvas.img.get_pixel(0, 0).0, [128, 0, 128, 255]);
+
+        // Over nothing, the color stays the same and only the alpha reflects the translucency
+        let mut canvas = Canvas::new(1, 1);
+        canvas.blend(0, 0, Color::rgba_f(0.0, 0.0, 1.0, 0.5));
+        assert_eq!(canvas.img.get_pixel(0, 0).0, [0, 0, 255, 128]);
+    }
+}
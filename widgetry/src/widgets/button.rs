@@ -1,8 +1,9 @@
 use geom::{Distance, Polygon};
 
 use crate::{
-    svg, Color, Drawable, EdgeInsets, EventCtx, GeomBatch, GfxCtx, Key, Line, MultiKey, Outcome,
-    RewriteColor, ScreenDims, ScreenPt, ScreenRectangle, Text, Widget, WidgetImpl, WidgetOutput,
+    svg, Accessibility, Color, Drawable, EdgeInsets, EventCtx, GeomBatch, GfxCtx, Key, Line,
+    MultiKey, Outcome, RewriteColor, Role, ScreenDims, ScreenPt, ScreenRectangle, Text, Widget,
+    WidgetImpl, WidgetOutput,
 };
 
 pub struct Button {
@@ -25,6 +26,15 @@ pub struct Button {
 }
 
 impl Button {
+    /// True if pressing this key (without ctrl) activates the button.
+    pub(crate) fn has_hotkey(&self, key: Key) -> bool {
+        match self.hotkey {
+            Some(MultiKey::Normal(k)) => k == key,
+            Some(MultiKey::Any(ref keys)) => keys.contains(&key),
+            Some(MultiKey::LCtrl(_)) | None => false,
+        }
+    }
+
     fn new(
         ctx: &EventCtx,
         normal: GeomBatch,
@@ -97,6 +107,19 @@ impl WidgetImpl for Button {
         }
     }
 
+    fn accessibility(&self) -> Option<Accessibility> {
+        Some(Accessibility::new(Role::Button, &self.action))
+    }
+
+    fn focused_key(&mut self, _: &mut EventCtx, key: Key, output: &mut WidgetOutput) -> bool {
+        // Not space, which is often a hotkey in another panel
+        if key == Key::Enter {
+            output.outcome = Outcome::Clicked(self.action.clone());
+            return true;
+        }
+        false
+    }
+
     fn draw(&self, g: &mut GfxCtx) {
         if self.hovering {
             g.redraw_at(self.top_left, &self.draw_hovered);
@@ -1,6 +1,7 @@
 use crate::{
-    Btn, Button, Color, EventCtx, GeomBatch, GfxCtx, Line, MultiKey, Outcome, RewriteColor,
-    ScreenDims, ScreenPt, Text, TextExt, TextSpan, Widget, WidgetImpl, WidgetOutput,
+    Accessibility, Btn, Button, Color, EventCtx, GeomBatch, GfxCtx, Key, Line, MultiKey, Outcome,
+    RewriteColor, Role, ScreenDims, ScreenPt, Text, TextExt, TextSpan, Widget, WidgetImpl,
+    WidgetOutput,
 };
 
 pub struct Checkbox {
@@ -191,6 +192,14 @@ impl Checkbox {
             right_label.draw_text(ctx),
         ])
     }
+
+    fn toggle(&mut self, output: &mut WidgetOutput) {
+        output.outcome = Outcome::Changed;
+        std::mem::swap(&mut self.btn, &mut self.other_btn);
+        self.btn.set_pos(self.other_btn.top_left);
+        self.enabled = !self.enabled;
+        output.redo_layout = true;
+    }
 }
 
 impl WidgetImpl for Checkbox {
@@ -205,12 +214,27 @@ impl WidgetImpl for Checkbox {
     fn event(&mut self, ctx: &mut EventCtx, output: &mut WidgetOutput) {
         self.btn.event(ctx, output);
         if let Outcome::Clicked(_) = output.outcome {
-            output.outcome = Outcome::Changed;
-            std::mem::swap(&mut self.btn, &mut self.other_btn);
-            self.btn.set_pos(self.other_btn.top_left);
-            self.enabled = !self.enabled;
-            output.redo_layout = true;
+            self.toggle(output);
+        }
+    }
+
+    fn accessibility(&self) -> Option<Accessibility> {
+        Some(
+            Accessibility::new(Role::Checkbox, &self.btn.action).value(if self.enabled {
+                "on"
+            } else {
+                "off"
+            }),
+        )
+    }
+
+    fn focused_key(&mut self, _: &mut EventCtx, key: Key, output: &mut WidgetOutput) -> bool {
+        // Not space, which is often a hotkey in another panel
+        if key == Key::Enter {
+            self.toggle(output);
+            return true;
         }
+        false
     }
 
     fn draw(&self, g: &mut GfxCtx) {
@@ -1,8 +1,8 @@
 use geom::{Distance, Polygon, Pt2D};
 
 use crate::{
-    Btn, Button, Choice, Color, EventCtx, GeomBatch, GfxCtx, Menu, Outcome, ScreenDims, ScreenPt,
-    ScreenRectangle, WidgetImpl, WidgetOutput,
+    Accessibility, Btn, Button, Choice, Color, EventCtx, GeomBatch, GfxCtx, Key, Menu, Outcome,
+    Role, ScreenDims, ScreenPt, ScreenRectangle, WidgetImpl, WidgetOutput,
 };
 
 pub struct Dropdown<T: Clone> {
@@ -127,6 +127,29 @@ impl<T: 'static + Clone> WidgetImpl for Dropdown<T> {
         }
     }
 
+    fn accessibility(&self) -> Option<Accessibility> {
+        Some(
+            Accessibility::new(Role::Dropdown, &self.label)
+                .value(&self.choices[self.current_idx].label),
+        )
+    }
+
+    fn focused_key(&mut self, ctx: &mut EventCtx, key: Key, _: &mut WidgetOutput) -> bool {
+        // Once the menu is open, it handles the arrow keys and Enter
+        if self.menu.is_some() {
+            if key == Key::Escape {
+                self.menu = None;
+                return true;
+            }
+            return false;
+        }
+        if key == Key::Enter || key == Key::DownArrow {
+            self.open_menu(ctx);
+            return true;
+        }
+        false
+    }
+
     fn draw(&self, g: &mut GfxCtx) {
         self.btn.draw(g);
         if let Some(ref m) = self.menu {
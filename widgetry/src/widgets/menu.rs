@@ -1,8 +1,8 @@
 use geom::Pt2D;
 
 use crate::{
-    text, Choice, EventCtx, GfxCtx, Key, Line, Outcome, ScreenDims, ScreenPt, ScreenRectangle,
-    Style, Text, Widget, WidgetImpl, WidgetOutput,
+    text, Accessibility, Choice, EventCtx, GfxCtx, Key, Line, Outcome, Role, ScreenDims, ScreenPt,
+    ScreenRectangle, Style, Text, Widget, WidgetImpl, WidgetOutput,
 };
 
 pub struct Menu<T> {
@@ -155,6 +155,16 @@ impl<T: 'static> WidgetImpl for Menu<T> {
         }
     }
 
+    // The menu already handles the arrow keys and Enter, focused or not
+    fn accessibility(&self) -> Option<Accessibility> {
+        let a = Accessibility::new(Role::Menu, "");
+        Some(if let Some(choice) = self.choices.get(self.current_idx) {
+            a.value(&choice.label)
+        } else {
+            a
+        })
+    }
+
     fn draw(&self, g: &mut GfxCtx) {
         if self.choices.is_empty() {
             return;
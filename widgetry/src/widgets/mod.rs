@@ -7,14 +7,16 @@ use stretch::style::{
     AlignItems, Dimension, FlexDirection, FlexWrap, JustifyContent, PositionType, Style,
 };
 
+use serde::Serialize;
+
 use geom::{Distance, Percent, Polygon};
 
 use crate::widgets::containers::{Container, Nothing};
 pub use crate::widgets::panel::Panel;
 use crate::{
     Button, Checkbox, Choice, Color, DeferDraw, DrawWithTooltips, Drawable, Dropdown, EventCtx,
-    GeomBatch, GfxCtx, JustDraw, Menu, RewriteColor, ScreenDims, ScreenPt, ScreenRectangle, Text,
-    TextBox,
+    GeomBatch, GfxCtx, JustDraw, Key, Menu, RewriteColor, ScreenDims, ScreenPt, ScreenRectangle,
+    Text, TextBox,
 };
 
 pub mod autocomplete;
@@ -33,6 +35,7 @@ pub mod persistent_split;
 pub mod scatter_plot;
 pub mod slider;
 pub mod spinner;
+pub mod table;
 pub mod text_box;
 
 /// Create a new widget by implementing this trait. You can instantiate your widget by calling
@@ -58,6 +61,18 @@ pub trait WidgetImpl: downcast_rs::Downcast {
     fn restore(&mut self, _: &mut EventCtx, _prev: &Box<dyn WidgetImpl>) {
         unreachable!()
     }
+    /// Interactive widgets should describe themselves for assistive tools. Widgets returning
+    /// something here can also receive keyboard focus.
+    fn accessibility(&self) -> Option<Accessibility> {
+        None
+    }
+    /// Keyboard focus moved to or away from this widget.
+    fn set_focus(&mut self, _: bool) {}
+    /// This widget has keyboard focus and a key was pressed. Return true if the key was used. By
+    /// convention, Enter activates the widget. Space doesn't, since it's often a hotkey elsewhere.
+    fn focused_key(&mut self, _: &mut EventCtx, _: Key, _: &mut WidgetOutput) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, PartialEq)]
@@ -90,6 +105,43 @@ impl WidgetOutput {
 
 downcast_rs::impl_downcast!(WidgetImpl);
 
+/// Describes a widget for screen readers and other assistive tools.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Accessibility {
+    pub role: Role,
+    pub label: String,
+    /// The current value, for widgets that have one
+    pub value: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Role {
+    Button,
+    Checkbox,
+    Dropdown,
+    Menu,
+    Slider,
+    Spinner,
+    TextBox,
+    /// Something non-interactive that was given an explicit label
+    Label,
+}
+
+impl Accessibility {
+    pub fn new<I: Into<String>>(role: Role, label: I) -> Accessibility {
+        Accessibility {
+            role,
+            label: label.into(),
+            value: None,
+        }
+    }
+
+    pub fn value<I: Into<String>>(mut self, value: I) -> Accessibility {
+        self.value = Some(value.into());
+        self
+    }
+}
+
 pub struct Widget {
     // TODO pub just for Container. Just move that here?
     pub(crate) widget: Box<dyn WidgetImpl>,
@@ -99,6 +151,7 @@ pub struct Widget {
     // to_geom forces this one to happen
     bg_batch: Option<GeomBatch>,
     id: Option<String>,
+    accessible_label: Option<String>,
 }
 
 struct LayoutStyle {
@@ -305,6 +358,13 @@ impl Widget {
         self.id = Some(id.into());
         self
     }
+
+    /// Overrides how this widget is described to screen readers. Non-interactive widgets are only
+    /// described if they're given a label.
+    pub fn accessible_label<I: Into<String>>(mut self, label: I) -> Widget {
+        self.accessible_label = Some(label.into());
+        self
+    }
 }
 
 // Convenient?? constructors
@@ -324,6 +384,7 @@ impl Widget {
             bg: None,
             bg_batch: None,
             id: None,
+            accessible_label: None,
         }
     }
 
@@ -613,6 +674,64 @@ impl Widget {
         }
     }
 
+    fn is_scrollbar(&self) -> bool {
+        self.id == Some("horiz scrollbar".to_string())
+            || self.id == Some("vert scrollbar".to_string())
+    }
+
+    /// Everything that can receive keyboard focus, in traversal order.
+    pub(crate) fn collect_focusable<'a>(&'a self, widgets: &mut Vec<&'a Widget>) {
+        if let Some(container) = self.widget.downcast_ref::<Container>() {
+            for w in &container.members {
+                w.collect_focusable(widgets);
+            }
+        } else if self.widget.accessibility().is_some() && !self.is_scrollbar() {
+            widgets.push(self);
+        }
+    }
+
+    pub(crate) fn collect_focusable_mut<'a>(&'a mut self, widgets: &mut Vec<&'a mut Widget>) {
+        if self.widget.is::<Container>() {
+            let container = self.widget.downcast_mut::<Container>().unwrap();
+            for w in &mut container.members {
+                w.collect_focusable_mut(widgets);
+            }
+        } else if self.widget.accessibility().is_some() && !self.is_scrollbar() {
+            widgets.push(self);
+        }
+    }
+
+    /// Interactive widgets and anything with an explicit label, in traversal order.
+    pub(crate) fn collect_accessible<'a>(&'a self, out: &mut Vec<(&'a Widget, Accessibility)>) {
+        if let Some(container) = self.widget.downcast_ref::<Container>() {
+            if let Some(ref label) = self.accessible_label {
+                out.push((self, Accessibility::new(Role::Label, label.clone())));
+            }
+            for w in &container.members {
+                w.collect_accessible(out);
+            }
+            return;
+        }
+        if self.is_scrollbar() {
+            return;
+        }
+        match self.widget.accessibility() {
+            Some(mut a) => {
+                if let Some(ref label) = self.accessible_label {
+                    a.label = label.clone();
+                } else if a.label.is_empty() {
+                    a.label = self.id.clone().unwrap_or_default();
+                }
+                out.push((self, a));
+            }
+            None => {
+                if let Some(ref label) = self.accessible_label {
+                    out.push((self, Accessibility::new(Role::Label, label.clone())));
+                }
+            }
+        }
+    }
+
     fn currently_hovering(&self) -> Option<&String> {
         if let Some(btn) = self.widget.downcast_ref::<Button>() {
             if btn.hovering {
@@ -5,13 +5,15 @@ use stretch::node::Stretch;
 use stretch::number::Number;
 use stretch::style::{Dimension, Style};
 
-use geom::{Percent, Polygon};
+use serde::Serialize;
+
+use geom::{Distance, Percent, Polygon};
 
 use crate::widgets::Container;
 use crate::{
-    Autocomplete, Checkbox, Color, Dropdown, EventCtx, GfxCtx, HorizontalAlignment, Menu, Outcome,
-    PersistentSplit, ScreenDims, ScreenPt, ScreenRectangle, Slider, Spinner, TextBox,
-    VerticalAlignment, Widget, WidgetImpl, WidgetOutput,
+    Accessibility, Autocomplete, Button, Checkbox, Color, Dropdown, Event, EventCtx, GfxCtx,
+    HorizontalAlignment, Key, Menu, Outcome, PersistentSplit, ScreenDims, ScreenPt,
+    ScreenRectangle, Slider, Spinner, TextBox, VerticalAlignment, Widget, WidgetImpl, WidgetOutput,
 };
 
 pub struct Panel {
@@ -25,6 +27,20 @@ pub struct Panel {
     contents_dims: ScreenDims,
     container_dims: ScreenDims,
     clip_rect: Option<ScreenRectangle>,
+
+    /// Does tab move keyboard focus between widgets?
+    keyboard_focus: bool,
+    /// Index into the interactive widgets, in traversal order, that has keyboard focus
+    focused: Option<usize>,
+}
+
+/// One entry in `Panel::accessibility_tree`.
+#[derive(Clone, Debug, Serialize)]
+pub struct AccessibleWidget {
+    #[serde(flatten)]
+    pub info: Accessibility,
+    pub rect: ScreenRectangle,
+    pub focused: bool,
 }
 
 impl Panel {
@@ -34,6 +50,7 @@ impl Panel {
             horiz: HorizontalAlignment::Center,
             vert: VerticalAlignment::Center,
             dims: Dims::MaxPercent(Percent::int(100), Percent::int(100)),
+            keyboard_focus: false,
         }
     }
 
@@ -264,6 +281,10 @@ impl Panel {
             self.recompute_layout(ctx, false);
         }
 
+        if let Some(outcome) = self.keyboard_focus_event(ctx) {
+            return outcome;
+        }
+
         let before = self.scroll_offset();
         let mut output = WidgetOutput::new();
         self.top_level.widget.event(ctx, &mut output);
@@ -274,6 +295,126 @@ impl Panel {
         output.outcome
     }
 
+    /// If the panel opted in, tab and shift+tab move keyboard focus between interactive widgets.
+    /// Enter goes to the focused widget first. Using the mouse drops focus.
+    fn keyboard_focus_event(&mut self, ctx: &mut EventCtx) -> Option<Outcome> {
+        if !self.keyboard_focus {
+            return None;
+        }
+        if ctx.input.left_mouse_button_pressed() {
+            self.set_focus(None);
+            return None;
+        }
+        if ctx.input.pressed(Key::Tab) {
+            let (num, tab_is_hotkey) = {
+                let mut widgets = Vec::new();
+                self.top_level.collect_focusable(&mut widgets);
+                let tab_is_hotkey = widgets.iter().any(|w| {
+                    w.widget
+                        .downcast_ref::<Button>()
+                        .map(|btn| btn.has_hotkey(Key::Tab))
+                        .unwrap_or(false)
+                });
+                (widgets.len(), tab_is_hotkey)
+            };
+            // A button bound to tab wins over focus traversal
+            if num == 0 || tab_is_hotkey {
+                ctx.input.unconsume_event();
+                return None;
+            }
+            let backwards = ctx.canvas.lshift_held;
+            let idx = match self.focused {
+                None if backwards => num - 1,
+                None => 0,
+                Some(idx) if backwards => (idx + num - 1) % num,
+                Some(idx) => (idx + 1) % num,
+            };
+            self.set_focus(Some(idx));
+            self.scroll_to_focus(ctx);
+            return Some(Outcome::Nothing);
+        }
+
+        let key = match ctx.input.event {
+            Event::KeyPress(key) if !ctx.input.has_been_consumed() => key,
+            _ => {
+                return None;
+            }
+        };
+        let idx = self.focused?;
+        let mut output = WidgetOutput::new();
+        let used = {
+            let mut widgets = Vec::new();
+            self.top_level.collect_focusable_mut(&mut widgets);
+            widgets[idx].widget.focused_key(ctx, key, &mut output)
+        };
+        if !used {
+            return None;
+        }
+        ctx.input.consume_event();
+        if output.redo_layout {
+            self.recompute_layout(ctx, true);
+        }
+        Some(output.outcome)
+    }
+
+    fn set_focus(&mut self, focused: Option<usize>) {
+        if self.focused == focused {
+            return;
+        }
+        let mut widgets = Vec::new();
+        self.top_level.collect_focusable_mut(&mut widgets);
+        if let Some(idx) = self.focused {
+            if let Some(w) = widgets.get_mut(idx) {
+                w.widget.set_focus(false);
+            }
+        }
+        self.focused = None;
+        if let Some(idx) = focused {
+            if let Some(w) = widgets.get_mut(idx) {
+                w.widget.set_focus(true);
+                self.focused = Some(idx);
+            }
+        }
+    }
+
+    fn focused_rect(&self) -> Option<ScreenRectangle> {
+        let mut widgets = Vec::new();
+        self.top_level.collect_focusable(&mut widgets);
+        widgets.get(self.focused?).map(|w| w.rect.clone())
+    }
+
+    fn scroll_to_focus(&mut self, ctx: &EventCtx) {
+        if !self.scrollable_y {
+            return;
+        }
+        if let (Some(rect), Some(clip)) = (self.focused_rect(), self.clip_rect.clone()) {
+            let offset = self.scroll_offset();
+            if rect.y1 < clip.y1 {
+                self.set_scroll_offset(ctx, (offset.0, offset.1 - (clip.y1 - rect.y1)));
+            } else if rect.y2 > clip.y2 {
+                self.set_scroll_offset(ctx, (offset.0, offset.1 + (rect.y2 - clip.y2)));
+            }
+        }
+    }
+
+    /// Describes every interactive widget, plus anything given an `accessible_label`, in
+    /// traversal order. Screen readers and other tools can consume this.
+    pub fn accessibility_tree(&self) -> Vec<AccessibleWidget> {
+        let mut focusable = Vec::new();
+        self.top_level.collect_focusable(&mut focusable);
+        let focused: Option<&Widget> = self.focused.and_then(|idx| focusable.get(idx).cloned());
+
+        let mut out = Vec::new();
+        self.top_level.collect_accessible(&mut out);
+        out.into_iter()
+            .map(|(w, info)| AccessibleWidget {
+                info,
+                rect: w.rect.clone(),
+                focused: focused.map(|f| std::ptr::eq(f, w)).unwrap_or(false),
+            })
+            .collect()
+    }
+
     pub fn draw(&self, g: &mut GfxCtx) {
         if let Some(ref rect) = self.clip_rect {
             g.enable_clipping(rect.clone());
@@ -299,6 +440,13 @@ impl Panel {
         }
 
         self.top_level.draw(g);
+        if let Some(rect) = self.focused_rect() {
+            if let Ok(ring) = rect.to_polygon().to_outline(Distance::meters(3.0)) {
+                g.fork_screenspace();
+                g.draw_polygon(g.style().focus_color, ring);
+                g.unfork();
+            }
+        }
         if self.scrollable_x || self.scrollable_y {
             g.disable_clipping();
 
@@ -323,6 +471,7 @@ impl Panel {
         self.set_scroll_offset(ctx, prev.scroll_offset());
 
         self.top_level.restore(ctx, &prev);
+        self.set_focus(prev.focused);
 
         // Since we just moved things around, let all widgets respond to the mouse being somewhere
         ctx.no_op_event(true, |ctx| assert_eq!(self.event(ctx), Outcome::Nothing));
@@ -483,6 +632,7 @@ pub struct PanelBuilder {
     horiz: HorizontalAlignment,
     vert: VerticalAlignment,
     dims: Dims,
+    keyboard_focus: bool,
 }
 
 enum Dims {
@@ -509,6 +659,8 @@ impl PanelBuilder {
             contents_dims: ScreenDims::new(0.0, 0.0),
             container_dims: ScreenDims::new(0.0, 0.0),
             clip_rect: None,
+            keyboard_focus: self.keyboard_focus,
+            focused: None,
         };
         if let Dims::ExactPercent(w, h) = panel.dims {
             // Don't set size, because then scrolling breaks -- the actual size has to be based on
@@ -543,6 +695,13 @@ impl PanelBuilder {
         panel
     }
 
+    /// Let tab and shift+tab move keyboard focus between this panel's widgets. Only use this when
+    /// no other panel on screen needs tab, since panels can't see each other's hotkeys.
+    pub fn keyboard_focus(mut self) -> PanelBuilder {
+        self.keyboard_focus = true;
+        self
+    }
+
     pub fn aligned(mut self, horiz: HorizontalAlignment, vert: VerticalAlignment) -> PanelBuilder {
         self.horiz = horiz;
         self.vert = vert;
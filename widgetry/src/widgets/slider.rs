@@ -1,8 +1,8 @@
 use geom::{Circle, Distance, Polygon, Pt2D};
 
 use crate::{
-    Color, Drawable, EventCtx, GeomBatch, GfxCtx, ScreenDims, ScreenPt, ScreenRectangle, Widget,
-    WidgetImpl, WidgetOutput,
+    Accessibility, Color, Drawable, EventCtx, GeomBatch, GfxCtx, Key, Role, ScreenDims, ScreenPt,
+    ScreenRectangle, Widget, WidgetImpl, WidgetOutput,
 };
 
 pub struct Slider {
@@ -184,7 +184,7 @@ impl Slider {
         (self.current_percent * (num_items as f64 - 1.0)) as usize
     }
 
-    pub(crate) fn set_percent(&mut self, ctx: &EventCtx, percent: f64) {
+    pub fn set_percent(&mut self, ctx: &EventCtx, percent: f64) {
         assert!(percent >= 0.0 && percent <= 1.0);
         self.current_percent = percent;
         self.recalc(ctx);
@@ -267,6 +267,26 @@ impl WidgetImpl for Slider {
         }
     }
 
+    fn accessibility(&self) -> Option<Accessibility> {
+        Some(
+            Accessibility::new(Role::Slider, "")
+                .value(format!("{}%", (self.current_percent * 100.0).round())),
+        )
+    }
+
+    fn focused_key(&mut self, ctx: &mut EventCtx, key: Key, _: &mut WidgetOutput) -> bool {
+        let delta = match key {
+            Key::LeftArrow | Key::DownArrow => -0.05,
+            Key::RightArrow | Key::UpArrow => 0.05,
+            _ => {
+                return false;
+            }
+        };
+        self.current_percent = (self.current_percent + delta).min(1.0).max(0.0);
+        self.recalc(ctx);
+        true
+    }
+
     fn draw(&self, g: &mut GfxCtx) {
         g.redraw_at(self.top_left, &self.draw);
         // TODO Since the sliders in Panels are scrollbars outside of the clipping rectangle,
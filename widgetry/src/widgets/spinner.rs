@@ -1,8 +1,8 @@
 use geom::{Polygon, Pt2D};
 
 use crate::{
-    text, Btn, Button, EventCtx, GeomBatch, GfxCtx, Line, Outcome, ScreenDims, ScreenPt,
-    ScreenRectangle, Text, Widget, WidgetImpl, WidgetOutput,
+    text, Accessibility, Btn, Button, EventCtx, GeomBatch, GfxCtx, Key, Line, Outcome, Role,
+    ScreenDims, ScreenPt, ScreenRectangle, Text, Widget, WidgetImpl, WidgetOutput,
 };
 
 // TODO MAX_CHAR_WIDTH is a hardcoded nonsense value
@@ -113,6 +113,25 @@ impl WidgetImpl for Spinner {
         }
     }
 
+    fn accessibility(&self) -> Option<Accessibility> {
+        Some(Accessibility::new(Role::Spinner, "").value(self.current.to_string()))
+    }
+
+    fn focused_key(&mut self, _: &mut EventCtx, key: Key, output: &mut WidgetOutput) -> bool {
+        let before = self.current;
+        match key {
+            Key::UpArrow | Key::RightArrow => self.modify(1),
+            Key::DownArrow | Key::LeftArrow => self.modify(-1),
+            _ => {
+                return false;
+            }
+        }
+        if self.current != before {
+            output.outcome = Outcome::Changed;
+        }
+        true
+    }
+
     fn draw(&self, g: &mut GfxCtx) {
         // TODO Cache
         let mut batch = GeomBatch::from(vec![(
@@ -0,0 +1,584 @@
+use std::cmp::Ordering;
+
+use abstutil::{csv_escape, prettyprint_usize};
+use geom::Polygon;
+
+use crate::{Btn, Color, EventCtx, GeomBatch, Key, Line, Panel, Text, TextBox, TextExt, Widget};
+
+const DEFAULT_ROWS_PER_PAGE: usize = 8;
+/// Clicking a new header keeps the previous sort columns as tie-breakers, up to this many.
+const MAX_SORT_COLUMNS: usize = 3;
+
+/// A paginated table of rows of type `T`. `A` is whatever app state the columns need to render,
+/// and `F` is the state of an optional custom filter. Columns can be sorted by clicking their
+/// header; each row is a button whose action is `label_per_row`.
+///
+/// The table doesn't own a panel. Put `render` somewhere in a panel, then pass `Outcome::Clicked`
+/// actions to `clicked` and call `panel_changed` on `Outcome::Changed`. When either says so,
+/// rebuild the panel. Clicks on rows can go to `select_row`, which runs the `on_select` callback.
+pub struct Table<A, T, F> {
+    data: Vec<T>,
+    label_per_row: Box<dyn Fn(&T) -> String>,
+    on_select: Option<Box<dyn Fn(&mut EventCtx, &mut A, &T)>>,
+    columns: Vec<Column<A, T>>,
+    filter: Filter<A, T, F>,
+    range_filters: Vec<RangeFilter<T>>,
+    search: String,
+
+    /// The column names to sort by, and if each is descending. The first has highest priority.
+    sort_by: Vec<(String, bool)>,
+    skip: usize,
+    rows_per_page: usize,
+}
+
+pub enum Col<T> {
+    Static,
+    Sortable(Box<dyn Fn(&T, &T) -> Ordering>),
+}
+
+impl<T: 'static> Col<T> {
+    /// Sort by some key of each row.
+    pub fn sortable_by_key<K: Ord, G: 'static + Fn(&T) -> K>(key: G) -> Col<T> {
+        Col::Sortable(Box::new(move |a, b| key(a).cmp(&key(b))))
+    }
+}
+
+struct Column<A, T> {
+    name: String,
+    render: Box<dyn Fn(&EventCtx, &A, &T) -> GeomBatch>,
+    /// Plain text columns can be searched and exported
+    to_text: Option<Box<dyn Fn(&A, &T) -> String>>,
+    col: Col<T>,
+}
+
+pub struct Filter<A, T, F> {
+    pub state: F,
+    pub to_controls: Box<dyn Fn(&mut EventCtx, &A, &F) -> Widget>,
+    pub from_controls: Box<dyn Fn(&Panel) -> F>,
+    pub apply: Box<dyn Fn(&F, &T) -> bool>,
+}
+
+impl<A, T> Filter<A, T, ()> {
+    /// No custom filter controls.
+    pub fn empty() -> Filter<A, T, ()> {
+        Filter {
+            state: (),
+            to_controls: Box::new(|_, _, _| Widget::nothing()),
+            from_controls: Box::new(|_| ()),
+            apply: Box::new(|_, _| true),
+        }
+    }
+}
+
+/// Keeps rows whose value is between an optional minimum and maximum, both inclusive.
+struct RangeFilter<T> {
+    name: String,
+    value: Box<dyn Fn(&T) -> f64>,
+    min: Option<f64>,
+    max: Option<f64>,
+}
+
+impl<A, T, F> Table<A, T, F> {
+    pub fn new(
+        data: Vec<T>,
+        label_per_row: Box<dyn Fn(&T) -> String>,
+        default_sort_by: &str,
+        filter: Filter<A, T, F>,
+    ) -> Table<A, T, F> {
+        Table {
+            data,
+            label_per_row,
+            on_select: None,
+            columns: Vec::new(),
+            filter,
+            range_filters: Vec::new(),
+            search: String::new(),
+
+            sort_by: vec![(default_sort_by.to_string(), true)],
+            skip: 0,
+            rows_per_page: DEFAULT_ROWS_PER_PAGE,
+        }
+    }
+
+    pub fn rows_per_page(&mut self, n: usize) {
+        assert!(n > 0);
+        self.rows_per_page = n;
+    }
+
+    /// Called with the row whenever `select_row` sees a click on it.
+    pub fn on_select(&mut self, cb: Box<dyn Fn(&mut EventCtx, &mut A, &T)>) {
+        self.on_select = Some(cb);
+    }
+
+    /// Replaces every row, keeping the sort order and filters, and goes back to the first page.
+    pub fn replace_data(&mut self, data: Vec<T>) {
+        self.data = data;
+        self.skip = 0;
+    }
+
+    /// A column drawn by `render`. It won't be searched or exported to CSV.
+    pub fn column(
+        &mut self,
+        name: &str,
+        render: Box<dyn Fn(&EventCtx, &A, &T) -> GeomBatch>,
+        col: Col<T>,
+    ) {
+        self.columns.push(Column {
+            name: name.to_string(),
+            render,
+            to_text: None,
+            col,
+        });
+    }
+
+    /// Adds a filter on some numeric value of each row, with text boxes to enter the minimum and
+    /// maximum.
+    pub fn range_filter(&mut self, name: &str, value: Box<dyn Fn(&T) -> f64>) {
+        self.range_filters.push(RangeFilter {
+            name: name.to_string(),
+            value,
+            min: None,
+            max: None,
+        });
+    }
+
+    pub fn render(&self, ctx: &mut EventCtx, app: &A) -> Widget {
+        let data = self.filtered_and_sorted(app);
+        let num_filtered = data.len();
+
+        // Render the headers
+        let headers = self
+            .columns
+            .iter()
+            .map(|col| {
+                if let Some(idx) = self.sort_by.iter().position(|(name, _)| name == &col.name) {
+                    let arrow = if self.sort_by[idx].1 { "↓" } else { "↑" };
+                    Btn::text_bg2(if idx == 0 {
+                        format!("{} {}", col.name, arrow)
+                    } else {
+                        format!("{} {}{}", col.name, arrow, idx + 1)
+                    })
+                    .build(ctx, &col.name, None)
+                } else if let Col::Sortable(_) = col.col {
+                    Btn::text_bg2(&col.name).build_def(ctx, None)
+                } else {
+                    Line(&col.name).draw(ctx).centered_vert()
+                }
+            })
+            .collect();
+
+        // Render data
+        let mut rows = Vec::new();
+        for row in data.into_iter().skip(self.skip).take(self.rows_per_page) {
+            rows.push((
+                (self.label_per_row)(row),
+                self.columns
+                    .iter()
+                    .map(|col| (col.render)(ctx, app, row))
+                    .collect(),
+            ));
+        }
+
+        // Put together the UI
+        Widget::col(vec![
+            (self.filter.to_controls)(ctx, app, &self.filter.state),
+            self.builtin_filter_controls(ctx),
+            make_table(ctx, headers, rows, 0.88 * ctx.canvas.window_width),
+            make_pagination(ctx, num_filtered, self.skip, self.rows_per_page),
+        ])
+    }
+
+    fn builtin_filter_controls(&self, ctx: &mut EventCtx) -> Widget {
+        let mut row = Vec::new();
+        if self.columns.iter().any(|col| col.to_text.is_some()) {
+            row.push("Search:".draw_text(ctx).centered_vert());
+            row.push(text_box(ctx, 20, self.search.clone(), "search"));
+        }
+        for range in &self.range_filters {
+            row.push(format!("{}:", range.name).draw_text(ctx).centered_vert());
+            row.push(text_box(
+                ctx,
+                8,
+                range.min.map(|x| x.to_string()).unwrap_or_default(),
+                &format!("{} min", range.name),
+            ));
+            row.push("to".draw_text(ctx).centered_vert());
+            row.push(text_box(
+                ctx,
+                8,
+                range.max.map(|x| x.to_string()).unwrap_or_default(),
+                &format!("{} max", range.name),
+            ));
+        }
+        if self.columns.iter().any(|col| col.to_text.is_some()) {
+            row.push(Btn::text_bg2("Export to CSV").build_def(ctx, None));
+        }
+        if row.is_empty() {
+            Widget::nothing()
+        } else {
+            Widget::row(row)
+        }
+    }
+
+    /// Every row passing the filters, in sorted order. Pagination isn't applied.
+    fn filtered_and_sorted(&self, app: &A) -> Vec<&T> {
+        let search = self.search.to_lowercase();
+        let mut data: Vec<&T> = self
+            .data
+            .iter()
+            .filter(|row| {
+                let row: &T = *row;
+                if !(self.filter.apply)(&self.filter.state, row) {
+                    return false;
+                }
+                for range in &self.range_filters {
+                    let value = (range.value)(row);
+                    if range.min.map(|min| value < min).unwrap_or(false)
+                        || range.max.map(|max| value > max).unwrap_or(false)
+                    {
+                        return false;
+                    }
+                }
+                search.is_empty()
+                    || self.columns.iter().any(|col| {
+                        col.to_text
+                            .as_ref()
+                            .map(|f| f(app, row).to_lowercase().contains(&search))
+                            .unwrap_or(false)
+                    })
+            })
+            .collect();
+
+        data.sort_by(|a, b| {
+            for (name, descending) in &self.sort_by {
+                if let Some(Col::Sortable(ref cmp)) = self
+                    .columns
+                    .iter()
+                    .find(|col| &col.name == name)
+                    .map(|col| &col.col)
+                {
+                    let ord = cmp(*a, *b);
+                    let ord = if *descending { ord.reverse() } else { ord };
+                    if ord != Ordering::Equal {
+                        return ord;
+                    }
+                }
+            }
+            Ordering::Equal
+        });
+        data
+    }
+
+    // Recalculate if true
+    pub fn clicked(&mut self, action: &str) -> bool {
+        if action == "previous" {
+            self.skip -= self.rows_per_page;
+            return true;
+        }
+        if action == "next" {
+            self.skip += self.rows_per_page;
+            return true;
+        }
+        for col in &self.columns {
+            if col.name == action {
+                self.skip = 0;
+                if self.sort_by[0].0 == action {
+                    self.sort_by[0].1 = !self.sort_by[0].1;
+                } else {
+                    // The previous sort columns break ties
+                    self.sort_by.retain(|(name, _)| name != action);
+                    self.sort_by.insert(0, (action.to_string(), true));
+                    self.sort_by.truncate(MAX_SORT_COLUMNS);
+                }
+                return true;
+            }
+        }
+        false
+    }
+
+    /// If the action was a click on a row, returns that row.
+    pub fn clicked_row(&self, action: &str) -> Option<&T> {
+        self.data
+            .iter()
+            .find(|row| (self.label_per_row)(row) == action)
+    }
+
+    /// If the action was a click on a row, runs the `on_select` callback and returns true.
+    pub fn select_row(&self, ctx: &mut EventCtx, app: &mut A, action: &str) -> bool {
+        match self.clicked_row(action) {
+            Some(row) => {
+                if let Some(ref cb) = self.on_select {
+                    cb(ctx, app, row);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn panel_changed(&mut self, panel: &Panel) {
+        self.filter.state = (self.filter.from_controls)(panel);
+        if panel.has_widget("search") {
+            self.search = panel.text_box("search");
+        }
+        for range in &mut self.range_filters {
+            range.min = panel
+                .text_box(&format!("{} min", range.name))
+                .trim()
+                .parse()
+                .ok();
+            range.max = panel
+                .text_box(&format!("{} max", range.name))
+                .trim()
+                .parse()
+                .ok();
+        }
+        self.skip = 0;
+    }
+
+    /// The names of the text columns, and every row passing the filters in the current order,
+    /// with the text of those columns.
+    pub fn export_rows(&self, app: &A) -> (Vec<String>, Vec<(&T, Vec<String>)>) {
+        let columns: Vec<&Column<A, T>> = self
+            .columns
+            .iter()
+            .filter(|col| col.to_text.is_some())
+            .collect();
+        let rows = self
+            .filtered_and_sorted(app)
+            .into_iter()
+            .map(|row| {
+                (
+                    row,
+                    columns
+                        .iter()
+                        .map(|col| (col.to_text.as_ref().unwrap())(app, row))
+                        .collect(),
+                )
+            })
+            .collect();
+        (
+            columns.into_iter().map(|col| col.name.clone()).collect(),
+            rows,
+        )
+    }
+
+    /// All rows passing the filters, in the current order, as CSV. Only text columns are
+    /// included.
+    pub fn to_csv(&self, app: &A) -> String {
+        let (columns, rows) = self.export_rows(app);
+        let mut out = columns
+            .iter()
+            .map(|x| csv_escape(x))
+            .collect::<Vec<_>>()
+            .join(",");
+        out.push('\n');
+        for (_, values) in rows {
+            out.push_str(
+                &values
+                    .iter()
+                    .map(|x| csv_escape(x))
+                    .collect::<Vec<_>>()
+                    .join(","),
+            );
+            out.push('\n');
+        }
+        out
+    }
+}
+
+// Simpler wrappers than column(). The more generic case exists to allow for icons and non-text
+// things.
+impl<A: 'static, T: 'static, F> Table<A, T, F> {
+    /// A plain text column, which is searchable and exported to CSV.
+    pub fn text_col(&mut self, name: &str, to_text: Box<dyn Fn(&A, &T) -> String>, col: Col<T>) {
+        let to_text: std::rc::Rc<dyn Fn(&A, &T) -> String> = to_text.into();
+        let render = to_text.clone();
+        self.columns.push(Column {
+            name: name.to_string(),
+            render: Box::new(move |ctx, app, x| Text::from(Line((render)(app, x))).render(ctx)),
+            to_text: Some(Box::new(move |app, x| (to_text)(app, x))),
+            col,
+        });
+    }
+
+    pub fn static_col(&mut self, name: &str, to_str: Box<dyn Fn(&T) -> String>) {
+        self.text_col(name, Box::new(move |_, x| (to_str)(x)), Col::Static);
+    }
+}
+
+fn text_box(ctx: &EventCtx, max_chars: usize, prefilled: String, name: &str) -> Widget {
+    Widget::new(Box::new(TextBox::new(ctx, max_chars, prefilled, false)))
+        .named(name)
+        .centered_vert()
+}
+
+fn make_pagination(ctx: &mut EventCtx, total: usize, skip: usize, rows_per_page: usize) -> Widget {
+    Widget::row(vec![
+        if skip > 0 {
+            Btn::plaintext("<").build(ctx, "previous", Key::LeftArrow)
+        } else {
+            Btn::plaintext("<").inactive(ctx)
+        },
+        format!(
+            "{}-{} of {}",
+            if total > 0 {
+                prettyprint_usize(skip + 1)
+            } else {
+                "0".to_string()
+            },
+            prettyprint_usize((skip + rows_per_page).min(total)),
+            prettyprint_usize(total)
+        )
+        .draw_text(ctx)
+        .centered_vert(),
+        if skip + rows_per_page < total {
+            Btn::plaintext(">").build(ctx, "next", Key::RightArrow)
+        } else {
+            Btn::plaintext(">").inactive(ctx)
+        },
+    ])
+}
+
+fn make_table(
+    ctx: &mut EventCtx,
+    headers: Vec<Widget>,
+    rows: Vec<(String, Vec<GeomBatch>)>,
+    total_width: f64,
+) -> Widget {
+    let mut width_per_col: Vec<f64> = headers.iter().map(|w| w.get_width_for_forcing()).collect();
+    for (_, row) in &rows {
+        for (col, width) in row.iter().zip(width_per_col.iter_mut()) {
+            *width = width.max(col.get_dims().width);
+        }
+    }
+    let extra_margin = ((total_width - width_per_col.clone().into_iter().sum::<f64>())
+        / (width_per_col.len().max(2) - 1) as f64)
+        .max(0.0);
+
+    let mut col = vec![Widget::custom_row(
+        headers
+            .into_iter()
+            .enumerate()
+            .map(|(idx, w)| {
+                let margin = extra_margin + width_per_col[idx] - w.get_width_for_forcing();
+                if idx == width_per_col.len() - 1 {
+                    w.margin_right((margin - extra_margin) as usize)
+                } else {
+                    w.margin_right(margin as usize)
+                }
+            })
+            .collect(),
+    )];
+
+    // TODO Maybe can do this now simpler with to_geom
+    for (label, row) in rows {
+        let mut batch = GeomBatch::new();
+        batch.autocrop_dims = false;
+        let mut x1 = 0.0;
+        for (col, width) in row.into_iter().zip(width_per_col.iter()) {
+            batch.append(col.translate(x1, 0.0));
+            x1 += *width + extra_margin;
+        }
+
+        let rect = Polygon::rectangle(total_width, batch.get_dims().height);
+        let mut hovered = GeomBatch::new();
+        hovered.push(Color::hex("#7C7C7C"), rect.clone());
+        hovered.append(batch.clone());
+
+        col.push(
+            Btn::custom(batch, hovered, rect, None)
+                .tooltip(Text::new())
+                .build(ctx, label, None),
+        );
+    }
+
+    Widget::custom_col(col)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Row {
+        name: &'static str,
+        category: usize,
+        value: usize,
+    }
+
+    fn table() -> Table<(), Row, ()> {
+        let mut table = Table::new(
+            vec![
+                Row {
+                    name: "a",
+                    category: 1,
+                    value: 3,
+                },
+                Row {
+                    name: "b",
+                    category: 2,
+                    value: 1,
+                },
+                Row {
+                    name: "c, d",
+                    category: 1,
+                    value: 1,
+                },
+            ],
+            Box::new(|x| x.name.to_string()),
+            "Value",
+            Filter::empty(),
+        );
+        table.static_col("Name", Box::new(|x| x.name.to_string()));
+        table.text_col(
+            "Category",
+            Box::new(|_, x| x.category.to_string()),
+            Col::sortable_by_key(|x| x.category),
+        );
+        table.text_col(
+            "Value",
+            Box::new(|_, x| x.value.to_string()),
+            Col::sortable_by_key(|x| x.value),
+        );
+        table
+    }
+
+    fn names(table: &Table<(), Row, ()>) -> Vec<&'static str> {
+        table
+            .filtered_and_sorted(&())
+            .into_iter()
+            .map(|x| x.name)
+            .collect()
+    }
+
+    #[test]
+    fn multi_column_sort() {
+        let mut table = table();
+        // Ties keep the original order
+        assert_eq!(names(&table), vec!["a", "b", "c, d"]);
+
+        // Category ascending, then value descending
+        assert!(table.clicked("Category"));
+        assert!(table.clicked("Category"));
+        assert_eq!(names(&table), vec!["a", "c, d", "b"]);
+
+        // Value is now the primary, and category breaks ties
+        assert!(table.clicked("Value"));
+        assert_eq!(names(&table), vec!["a", "c, d", "b"]);
+    }
+
+    #[test]
+    fn filters_and_csv() {
+        let mut table = table();
+        table.range_filter("Value", Box::new(|x| x.value as f64));
+        table.range_filters[0].max = Some(2.0);
+        assert_eq!(
+            table.to_csv(&()),
+            "Name,Category,Value\nb,2,1\n\"c, d\",1,1\n"
+        );
+
+        table.search = "C".to_string();
+        assert_eq!(names(&table), vec!["c, d"]);
+        assert_eq!(table.clicked_row("b").map(|x| x.value), Some(1));
+    }
+}
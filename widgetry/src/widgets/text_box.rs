@@ -1,8 +1,8 @@
 use geom::Polygon;
 
 use crate::{
-    text, EventCtx, GeomBatch, GfxCtx, Key, Line, Outcome, ScreenDims, ScreenPt, ScreenRectangle,
-    Text, WidgetImpl, WidgetOutput,
+    text, Accessibility, EventCtx, GeomBatch, GfxCtx, Key, Line, Outcome, Role, ScreenDims,
+    ScreenPt, ScreenRectangle, Text, WidgetImpl, WidgetOutput,
 };
 
 // TODO right now, only a single line
@@ -113,6 +113,26 @@ impl WidgetImpl for TextBox {
         }
     }
 
+    fn accessibility(&self) -> Option<Accessibility> {
+        Some(Accessibility::new(Role::TextBox, "").value(&self.line))
+    }
+
+    // Typing is handled by the normal event, since has_focus is set
+    fn set_focus(&mut self, focused: bool) {
+        self.has_focus = focused;
+    }
+
+    fn can_restore(&self) -> bool {
+        true
+    }
+    // Keep typing after the panel is rebuilt
+    fn restore(&mut self, _: &mut EventCtx, prev: &Box<dyn WidgetImpl>) {
+        let prev = prev.downcast_ref::<TextBox>().unwrap();
+        self.line = prev.line.clone();
+        self.cursor_x = prev.cursor_x;
+        self.has_focus = prev.has_focus;
+    }
+
     fn draw(&self, g: &mut GfxCtx) {
         // TODO Cache
         let mut batch = GeomBatch::from(vec![(